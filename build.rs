@@ -0,0 +1,25 @@
+// Build script
+// Records the commit the binary is built from, which `--version` prints.
+// Builds outside a git checkout, like from a release tarball, go without.
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if !Path::new(".git").exists() {
+        return;
+    }
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+
+    let hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    if let Some(hash) = hash.filter(|hash| !hash.is_empty()) {
+        println!("cargo:rustc-env=R_HYPRCONFIG_GIT_HASH={hash}");
+    }
+}
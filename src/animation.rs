@@ -0,0 +1,317 @@
+// Animation lines
+// `animation = NAME, ONOFF, SPEED, CURVE[, STYLE]`. The Animations panel keys
+// each line by its name, like `animation.windows`, and keeps the rest as the
+// item value.
+
+use crate::ui::ConfigItem;
+
+/// Every animation Hyprland knows, in the order of its animation tree
+#[rustfmt::skip]
+pub const ANIMATION_NAMES: &[&str] = &[
+    "global",
+    "windows", "windowsIn", "windowsOut", "windowsMove",
+    "layers", "layersIn", "layersOut",
+    "fade", "fadeIn", "fadeOut", "fadeSwitch", "fadeShadow", "fadeDim",
+    "fadeLayers", "fadeLayersIn", "fadeLayersOut",
+    "fadePopups", "fadePopupsIn", "fadePopupsOut", "fadeDpms",
+    "border", "borderangle",
+    "workspaces", "workspacesIn", "workspacesOut",
+    "specialWorkspace", "specialWorkspaceIn", "specialWorkspaceOut",
+    "zoomFactor", "monitorAdded",
+];
+
+/// The curve every animation can use without defining a bezier
+pub const DEFAULT_CURVE: &str = "default";
+/// Curves Hyprland defines itself
+pub const BUILTIN_CURVES: [&str; 2] = [DEFAULT_CURVE, "linear"];
+
+/// Fields of the animation editor, in the order Tab visits them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationField {
+    Name,
+    Enabled,
+    Speed,
+    Curve,
+    Style,
+}
+
+impl AnimationField {
+    pub const ALL: [AnimationField; 5] = [
+        AnimationField::Name,
+        AnimationField::Enabled,
+        AnimationField::Speed,
+        AnimationField::Curve,
+        AnimationField::Style,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let index = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AnimationField::Name => "Name",
+            AnimationField::Enabled => "Enabled",
+            AnimationField::Speed => "Speed",
+            AnimationField::Curve => "Curve",
+            AnimationField::Style => "Style",
+        }
+    }
+}
+
+/// A single `animation` line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    pub name: String,
+    pub enabled: bool,
+    /// Duration in deciseconds
+    pub speed: u32,
+    pub curve: String,
+    /// Such as `slide`, `popin 80%` or `slidefadevert 20%`; empty for none
+    pub style: String,
+}
+
+impl Animation {
+    /// Parse the value of an `animation.NAME` item. A disabled animation may
+    /// be just `0`.
+    pub fn parse(name: &str, value: &str) -> Option<Self> {
+        let fields: Vec<&str> = value.split(',').map(str::trim).collect();
+        let enabled = match fields[0] {
+            "1" => true,
+            "0" => false,
+            _ => return None,
+        };
+        let (speed, curve, style) = match fields[1..] {
+            [] if !enabled => (0, DEFAULT_CURVE, String::new()),
+            [speed, curve] => (speed.parse().ok()?, curve, String::new()),
+            [speed, curve, ref style @ ..] => {
+                // Styles never contain commas, but keep whatever is there
+                (speed.parse().ok()?, curve, style.join(", "))
+            }
+            _ => return None,
+        };
+        if name.trim().is_empty() || curve.is_empty() {
+            return None;
+        }
+        Some(Animation {
+            name: name.trim().to_string(),
+            enabled,
+            speed,
+            curve: curve.to_string(),
+            style,
+        })
+    }
+
+    /// The item value, `ONOFF, SPEED, CURVE[, STYLE]`. A disabled animation
+    /// without a speed stays a bare `0`.
+    pub fn to_value(&self) -> String {
+        let onoff = if self.enabled { "1" } else { "0" };
+        if !self.enabled && self.speed == 0 {
+            return onoff.to_string();
+        }
+        let mut value = format!("{onoff}, {}, {}", self.speed, self.curve);
+        if !self.style.trim().is_empty() {
+            value.push_str(", ");
+            value.push_str(self.style.trim());
+        }
+        value
+    }
+
+    /// The whole config line
+    pub fn to_line(&self) -> String {
+        format!("animation = {}, {}", self.name, self.to_value())
+    }
+
+    /// Display value of a field for the editor
+    pub fn field_value(&self, field: AnimationField) -> String {
+        match field {
+            AnimationField::Name => self.name.clone(),
+            AnimationField::Enabled => if self.enabled { "on" } else { "off" }.to_string(),
+            AnimationField::Speed => self.speed.to_string(),
+            AnimationField::Curve => self.curve.clone(),
+            AnimationField::Style => self.style.clone(),
+        }
+    }
+
+    /// Add a typed digit to the speed
+    pub fn push_speed_digit(&mut self, digit: u32) {
+        self.speed = self.speed.saturating_mul(10).saturating_add(digit).min(999);
+    }
+
+    /// Step the name through the known animations
+    pub fn cycle_name(&mut self, forward: bool) {
+        self.name = cycle(ANIMATION_NAMES, &self.name, forward).to_string();
+    }
+
+    /// Step the curve through the builtin curves and the defined beziers
+    pub fn cycle_curve(&mut self, curves: &[String], forward: bool) {
+        let curves: Vec<&str> = curves.iter().map(String::as_str).collect();
+        self.curve = cycle(&curves, &self.curve, forward).to_string();
+    }
+
+    /// Check the line against the curves the config defines. `curves`
+    /// includes the builtin ones.
+    pub fn validate(&self, curves: &[String]) -> Result<(), String> {
+        if !ANIMATION_NAMES.contains(&self.name.as_str()) {
+            return Err(format!("'{}' is not a Hyprland animation", self.name));
+        }
+        if self.enabled && self.speed == 0 {
+            return Err("speed must be at least 1".to_string());
+        }
+        if !curves.contains(&self.curve) {
+            return Err(format!("no bezier named '{}' is defined", self.curve));
+        }
+        Ok(())
+    }
+}
+
+/// The builtin curves followed by the beziers defined among the Animations
+/// panel items, which are keyed `bezier.NAME`
+pub fn defined_curves(items: &[ConfigItem]) -> Vec<String> {
+    let beziers = items
+        .iter()
+        .filter_map(|item| item.key.strip_prefix("bezier."))
+        .map(str::to_string);
+    BUILTIN_CURVES
+        .iter()
+        .map(|curve| curve.to_string())
+        .chain(beziers)
+        .collect()
+}
+
+/// Keys of the animation items whose curve no bezier defines. Hyprland
+/// ignores those lines without a word.
+pub fn undefined_curve_keys(items: &[ConfigItem]) -> Vec<String> {
+    let curves = defined_curves(items);
+    items
+        .iter()
+        .filter(|item| {
+            item.key
+                .strip_prefix("animation.")
+                .and_then(|name| Animation::parse(name, &item.value))
+                .is_some_and(|animation| !curves.contains(&animation.curve))
+        })
+        .map(|item| item.key.clone())
+        .collect()
+}
+
+/// The entry after or before `current`, or the first one if it isn't listed
+fn cycle<'a>(options: &[&'a str], current: &str, forward: bool) -> &'a str {
+    let count = options.len();
+    match options.iter().position(|option| *option == current) {
+        Some(index) if forward => options[(index + 1) % count],
+        Some(index) => options[(index + count - 1) % count],
+        None => options[0],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::ConfigDataType;
+
+    fn item(key: &str, value: &str) -> ConfigItem {
+        ConfigItem::new(
+            key.to_string(),
+            value.to_string(),
+            String::new(),
+            ConfigDataType::String,
+        )
+    }
+
+    fn curves() -> Vec<String> {
+        vec![DEFAULT_CURVE.to_string(), "myBezier".to_string()]
+    }
+
+    #[test]
+    fn test_parse_and_format_round_trip() {
+        let animation = Animation::parse("windows", "1, 7, myBezier").unwrap();
+        assert!(animation.enabled);
+        assert_eq!(animation.speed, 7);
+        assert_eq!(animation.curve, "myBezier");
+        assert_eq!(animation.to_value(), "1, 7, myBezier");
+        assert_eq!(animation.to_line(), "animation = windows, 1, 7, myBezier");
+
+        let animation = Animation::parse("windowsOut", "1,7,default,popin 80%").unwrap();
+        assert_eq!(animation.style, "popin 80%");
+        assert_eq!(animation.to_value(), "1, 7, default, popin 80%");
+
+        let animation = Animation::parse("fade", "0").unwrap();
+        assert!(!animation.enabled);
+        assert_eq!(animation.to_value(), "0");
+
+        for bad in ["", "2, 7, default", "1, fast, default", "1, 7", "1"] {
+            assert!(Animation::parse("fade", bad).is_none(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_disabling_keeps_the_other_fields() {
+        let mut animation = Animation::parse("workspaces", "1, 6, default, slide").unwrap();
+        animation.enabled = false;
+        assert_eq!(animation.to_value(), "0, 6, default, slide");
+    }
+
+    #[test]
+    fn test_names_and_curves_cycle() {
+        let mut animation = Animation::parse("windows", "1, 7, default").unwrap();
+        animation.cycle_name(true);
+        assert_eq!(animation.name, "windowsIn");
+        animation.cycle_name(false);
+        animation.cycle_name(false);
+        assert_eq!(animation.name, "global");
+
+        animation.cycle_curve(&curves(), true);
+        assert_eq!(animation.curve, "myBezier");
+        animation.cycle_curve(&curves(), true);
+        assert_eq!(animation.curve, DEFAULT_CURVE);
+    }
+
+    #[test]
+    fn test_undefined_curves_are_found_across_the_panel() {
+        let items = vec![
+            item("animation.windows", "1, 7, myBezier"),
+            item("animation.fade", "1, 7, default"),
+            item("animation.border", "1, 10, linear"),
+            item("animation.workspaces", "1, 6, typo, slide"),
+            item("animation.layers", "0"),
+            // Defined after its first use, which Hyprland allows
+            item("bezier.myBezier", "0.05, 0.9, 0.1, 1.05"),
+            item("animations.enabled", "true"),
+        ];
+        assert_eq!(
+            defined_curves(&items),
+            vec!["default", "linear", "myBezier"]
+        );
+        assert_eq!(undefined_curve_keys(&items), vec!["animation.workspaces"]);
+        assert_eq!(
+            undefined_curve_keys(&items[..4]),
+            vec!["animation.windows", "animation.workspaces"]
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        let animation = Animation::parse("windows", "1, 7, myBezier").unwrap();
+        assert!(animation.validate(&curves()).is_ok());
+        assert_eq!(
+            animation.validate(&[DEFAULT_CURVE.to_string()]),
+            Err("no bezier named 'myBezier' is defined".to_string())
+        );
+
+        let mut animation = Animation::parse("fade", "1, 3, default").unwrap();
+        animation.speed = 0;
+        assert!(animation.validate(&curves()).is_err());
+        animation.enabled = false;
+        assert!(animation.validate(&curves()).is_ok());
+
+        let animation = Animation::parse("wobble", "1, 3, default").unwrap();
+        assert!(animation.validate(&curves()).is_err());
+    }
+}
@@ -1,6 +1,9 @@
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -14,17 +17,27 @@ use std::{
 };
 
 use crate::{
+    animation::AnimationField,
     batch::BatchManager,
-    commands::CommandDispatcher,
-    config::Config,
+    commands::{CommandContext, CommandDispatcher},
+    config::{Config, KeybindMode},
+    environment::EnvField,
     errors::{ConfigError, ConfigResult, HyprConfigError, HyprctlError},
     hyprctl::HyprCtl,
+    import_export::download::{download_config, UrlConfig},
+    import_export::github::{GitHubClient, RepoDownload, RepoSpec},
+    import_export::import::FolderMerge,
+    keymap::KeyAction,
     memory::{get_common_pools, get_interner_stats},
+    nixos::parser::{HyprlandNixSettings, NixParser},
+    plugins::is_plugin_option,
     state::StateManager,
     ui::UI,
-    undo::{ConfigSnapshot, UndoManager},
 };
 
+/// Time to focus the window the window rule editor grabs with F2
+const WINDOW_GRAB_DELAY: Duration = Duration::from_secs(3);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppState {
     Running,
@@ -38,10 +51,22 @@ pub enum FocusedPanel {
     Decoration,
     Animations,
     Gestures,
+    /// Options of the dwindle and master layouts
+    Layouts,
+    /// Options of window groups and their tab bar
+    Groups,
     Binds,
     WindowRules,
     LayerRules,
+    Workspaces,
+    Environment,
+    Plugins,
+    Autostart,
+    /// `$name = value` definitions
+    Variables,
     Misc,
+    /// Options under a category no other panel takes, so none are lost
+    Other,
     Import,
     Export,
 }
@@ -53,11 +78,19 @@ impl FocusedPanel {
             FocusedPanel::Input => FocusedPanel::Decoration,
             FocusedPanel::Decoration => FocusedPanel::Animations,
             FocusedPanel::Animations => FocusedPanel::Gestures,
-            FocusedPanel::Gestures => FocusedPanel::Binds,
+            FocusedPanel::Gestures => FocusedPanel::Layouts,
+            FocusedPanel::Layouts => FocusedPanel::Groups,
+            FocusedPanel::Groups => FocusedPanel::Binds,
             FocusedPanel::Binds => FocusedPanel::WindowRules,
             FocusedPanel::WindowRules => FocusedPanel::LayerRules,
-            FocusedPanel::LayerRules => FocusedPanel::Misc,
-            FocusedPanel::Misc => FocusedPanel::Import,
+            FocusedPanel::LayerRules => FocusedPanel::Workspaces,
+            FocusedPanel::Workspaces => FocusedPanel::Environment,
+            FocusedPanel::Environment => FocusedPanel::Plugins,
+            FocusedPanel::Plugins => FocusedPanel::Autostart,
+            FocusedPanel::Autostart => FocusedPanel::Variables,
+            FocusedPanel::Variables => FocusedPanel::Misc,
+            FocusedPanel::Misc => FocusedPanel::Other,
+            FocusedPanel::Other => FocusedPanel::Import,
             FocusedPanel::Import => FocusedPanel::Export,
             FocusedPanel::Export => FocusedPanel::General,
         }
@@ -70,16 +103,23 @@ impl FocusedPanel {
             FocusedPanel::Decoration => FocusedPanel::Input,
             FocusedPanel::Animations => FocusedPanel::Decoration,
             FocusedPanel::Gestures => FocusedPanel::Animations,
-            FocusedPanel::Binds => FocusedPanel::Gestures,
+            FocusedPanel::Layouts => FocusedPanel::Gestures,
+            FocusedPanel::Groups => FocusedPanel::Layouts,
+            FocusedPanel::Binds => FocusedPanel::Groups,
             FocusedPanel::WindowRules => FocusedPanel::Binds,
             FocusedPanel::LayerRules => FocusedPanel::WindowRules,
-            FocusedPanel::Misc => FocusedPanel::LayerRules,
-            FocusedPanel::Import => FocusedPanel::Misc,
+            FocusedPanel::Workspaces => FocusedPanel::LayerRules,
+            FocusedPanel::Environment => FocusedPanel::Workspaces,
+            FocusedPanel::Plugins => FocusedPanel::Environment,
+            FocusedPanel::Autostart => FocusedPanel::Plugins,
+            FocusedPanel::Variables => FocusedPanel::Autostart,
+            FocusedPanel::Misc => FocusedPanel::Variables,
+            FocusedPanel::Other => FocusedPanel::Misc,
+            FocusedPanel::Import => FocusedPanel::Other,
             FocusedPanel::Export => FocusedPanel::Import,
         }
     }
 
-    #[allow(dead_code)]
     pub fn as_str(self) -> &'static str {
         match self {
             FocusedPanel::General => "General",
@@ -87,14 +127,47 @@ impl FocusedPanel {
             FocusedPanel::Decoration => "Decoration",
             FocusedPanel::Animations => "Animations",
             FocusedPanel::Gestures => "Gestures",
+            FocusedPanel::Layouts => "Layouts",
+            FocusedPanel::Groups => "Groups",
             FocusedPanel::Binds => "Binds",
             FocusedPanel::WindowRules => "Window Rules",
             FocusedPanel::LayerRules => "Layer Rules",
+            FocusedPanel::Workspaces => "Workspaces",
+            FocusedPanel::Environment => "Environment",
+            FocusedPanel::Plugins => "Plugins",
+            FocusedPanel::Autostart => "Autostart",
+            FocusedPanel::Variables => "Variables",
             FocusedPanel::Misc => "Misc",
+            FocusedPanel::Other => "Other",
             FocusedPanel::Import => "Import",
             FocusedPanel::Export => "Export",
         }
     }
+
+    /// Whether the panel's items are lines that can be deleted, rather than
+    /// options
+    pub fn has_deletable_items(self) -> bool {
+        matches!(
+            self,
+            FocusedPanel::Binds
+                | FocusedPanel::WindowRules
+                | FocusedPanel::LayerRules
+                | FocusedPanel::Workspaces
+                | FocusedPanel::Environment
+                | FocusedPanel::Plugins
+                | FocusedPanel::Autostart
+                | FocusedPanel::Variables
+        )
+    }
+}
+
+/// A GitHub download running in the background, so the import dialog can
+/// show its progress
+struct GitHubImportTask {
+    /// `owner/repo @ branch` and the folder, for the preview
+    label: String,
+    handle: tokio::task::JoinHandle<Result<RepoDownload>>,
+    progress: tokio::sync::watch::Receiver<String>,
 }
 
 pub struct App {
@@ -105,7 +178,6 @@ pub struct App {
     pub hyprctl: HyprCtl,
     pub ui: UI,
     pub batch_manager: BatchManager,
-    pub undo_manager: UndoManager,
     pub last_tick: Instant,
     pub tick_rate: Duration,
     
@@ -114,6 +186,22 @@ pub struct App {
     
     // Command dispatcher for event handling
     pub command_dispatcher: CommandDispatcher,
+
+    github_task: Option<GitHubImportTask>,
+    github_download: Option<RepoDownload>,
+    url_task: Option<tokio::task::JoinHandle<Result<UrlConfig>>>,
+    url_download: Option<UrlConfig>,
+    /// A gist upload started from the export dialog, resolving to its URL
+    gist_task: Option<tokio::task::JoinHandle<Result<String>>>,
+    folder_merge: Option<FolderMerge>,
+    /// The conflict selected in the folder import preview
+    folder_conflict: usize,
+    /// Hyprland reloading its config, from its event socket
+    config_changes: Option<tokio::sync::mpsc::UnboundedReceiver<()>>,
+    /// A reload happened while a dialog was open, refresh once it closes
+    config_change_pending: bool,
+    /// In vim mode, a `g` or `d` waiting to be pressed again
+    pending_vim_key: Option<char>,
 }
 
 impl App {
@@ -166,11 +254,21 @@ impl App {
         let keybinds = self.ui.collect_keybinds();
         let window_rules = self.ui.collect_window_rules();
         let layer_rules = self.ui.collect_layer_rules();
+        let workspace_rules = self.ui.collect_workspace_rules();
+        let env_vars = self.ui.collect_env_vars();
+        let plugins = self.ui.collect_plugins();
+        let autostart = self.ui.collect_autostart();
+        let variables = self.ui.collect_variables();
 
         eprintln!("Config changes: {}", config_changes.len());
         eprintln!("Keybinds: {}", keybinds.len());
         eprintln!("Window rules: {}", window_rules.len());
         eprintln!("Layer rules: {}", layer_rules.len());
+        eprintln!("Workspace rules: {}", workspace_rules.len());
+        eprintln!("Environment variables: {}", env_vars.len());
+        eprintln!("Plugins: {}", plugins.len());
+        eprintln!("Autostart commands: {}", autostart.len());
+        eprintln!("Variables: {}", variables.len());
 
         // Test the save without actually writing to avoid modifying user's config
         eprintln!("=== Save functionality test complete ===");
@@ -180,19 +278,60 @@ impl App {
     pub async fn new(debug: bool) -> Result<Self> {
         let config = Config::load().await?;
 
-        // Try to initialize hyprctl, but don't fail if it's not available
-        let hyprctl = match HyprCtl::new().await {
-            Ok(hyprctl) => hyprctl,
-            Err(e) => {
-                eprintln!("Warning: Failed to initialize hyprctl: {e}");
-                eprintln!("Will try to load configuration from config file instead.");
-                HyprCtl::new_disconnected()
+        // Try to initialize hyprctl, but don't fail if it's not available.
+        // In file-only mode Hyprland isn't even asked.
+        let hyprctl = if config.is_file_only() {
+            HyprCtl::new_file_only()
+        } else {
+            match HyprCtl::new().await {
+                Ok(hyprctl) => hyprctl,
+                Err(e) => {
+                    crate::stderr_log::warn(format!(
+                        "Failed to initialize hyprctl, loading the config file instead: {e}"
+                    ));
+                    HyprCtl::new_disconnected()
+                }
             }
         };
 
         let mut ui = UI::new();
+        ui.hyprland_config_path = config.hyprland_config_path.clone();
+        ui.hyprland_status = hyprctl.status();
+
+        // Declarative distributions generate hyprland.conf, so saving to it
+        // can't work and only live changes apply
+        let declarative = crate::platform::DistributionDetector::detect()
+            .is_ok_and(|info| info.distribution_type.has_declarative_config());
+        if declarative
+            && !config.nixos_mode
+            && crate::config::is_nix_managed(&config.hyprland_config_path)
+        {
+            crate::stderr_log::warn(format!(
+                "{} is generated by Nix, so saves to it will fail. \
+                 Enable nixos_mode or export a Nix module with N.",
+                config.hyprland_config_path.display()
+            ));
+        }
 
+        if let Ok(themes_dir) = Config::themes_dir() {
+            let (themes, warnings) = crate::theme::load_custom_themes(&themes_dir);
+            for warning in warnings {
+                crate::stderr_log::warn(format!("theme {warning}"));
+            }
+            ui.custom_themes = themes;
+        }
+        if let crate::theme::ColorScheme::Custom(name) = &config.theme {
+            if !ui.custom_themes.contains_key(name) {
+                crate::stderr_log::warn(format!(
+                    "no custom theme is named {name}, using the default theme"
+                ));
+            }
+        }
         ui.set_theme(config.theme.clone());
+        ui.change_history.set_max_depth(config.undo_depth);
+        ui.search_fuzzy_threshold = config.search_threshold;
+        ui.keybind_mode = config.keybind_mode;
+        ui.reload_after_save = config.reload_after_save;
 
         // Initialize common strings in the string interner for memory optimization
         if debug {
@@ -201,8 +340,9 @@ impl App {
             eprintln!("Initializing structured state management...");
         }
         if let Err(e) = ui.load_current_config(&hyprctl).await {
-            eprintln!("Warning: Failed to load current configuration: {e}");
-            eprintln!("Using default placeholder values.");
+            crate::stderr_log::warn(format!(
+                "Failed to load current configuration, using placeholder values: {e}"
+            ));
         }
 
         // Initialize batch manager
@@ -210,6 +350,12 @@ impl App {
             .ok_or_else(|| anyhow::anyhow!("Cannot determine config directory"))?
             .join("r-hyprconfig");
         let batch_manager = BatchManager::new(config_dir).await?;
+        let file_only = hyprctl.is_file_only();
+        if !config.first_run_completed {
+            ui.setup_wizard = Some(crate::setup::SetupWizard::new(
+                crate::setup::Environment::detect(&config, hyprctl.status()),
+            ));
+        }
 
         Ok(Self {
             state: AppState::Running,
@@ -219,11 +365,20 @@ impl App {
             hyprctl,
             ui,
             batch_manager,
-            undo_manager: UndoManager::default(),
             last_tick: Instant::now(),
             tick_rate: Duration::from_millis(50), // Faster tick rate for responsive preview
             state_manager: StateManager::new(),
             command_dispatcher: CommandDispatcher::new(),
+            github_task: None,
+            github_download: None,
+            url_task: None,
+            url_download: None,
+            gist_task: None,
+            folder_merge: None,
+            folder_conflict: 0,
+            config_changes: crate::hyprctl::watch_config_changes().filter(|_| !file_only),
+            config_change_pending: false,
+            pending_vim_key: None,
         })
     }
 
@@ -235,7 +390,12 @@ impl App {
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
 
+        // Warnings would draw over the interface, so log them until it closes
+        let stderr_redirect = crate::stderr_log::log_file_path()
+            .and_then(|path| crate::stderr_log::StderrRedirect::to_file(&path))
+            .ok();
         let result = self.run_app(&mut terminal).await;
+        drop(stderr_redirect);
 
         // restore terminal
         disable_raw_mode()?;
@@ -256,25 +416,28 @@ impl App {
 
             let timeout = self.tick_rate.saturating_sub(self.last_tick.elapsed());
             if crossterm::event::poll(timeout)? {
-                if let Event::Key(key) = event::read()? {
+                let event = event::read()?;
+                if let Event::Mouse(mouse) = event {
+                    self.handle_mouse_event(mouse).await?;
+                } else if let Event::Key(key) = event {
                     if key.kind == KeyEventKind::Press {
-                        // Check for Ctrl+Z (undo)
-                        if key.code == KeyCode::Char('z')
-                            && key
-                                .modifiers
-                                .contains(crossterm::event::KeyModifiers::CONTROL)
-                        {
-                            self.handle_undo().await?;
-                        }
-                        // Check for Ctrl+Y (redo)
-                        else if key.code == KeyCode::Char('y')
-                            && key
-                                .modifiers
-                                .contains(crossterm::event::KeyModifiers::CONTROL)
-                        {
-                            self.handle_redo().await?;
-                        } else {
-                            self.handle_key_event(key.code).await?;
+                        // Chords like Ctrl+Z are looked up here, before the
+                        // modifiers are dropped; plain keys go to the dispatcher
+                        let chord = crate::keymap::is_chord(key.code, key.modifiers)
+                            .then(|| {
+                                let modal_open =
+                                    CommandContext::from_app(self, key.code).has_modal_open();
+                                crate::keymap::action_for(
+                                    key.code,
+                                    key.modifiers,
+                                    self.ui.current_tab,
+                                    modal_open,
+                                )
+                            })
+                            .flatten();
+                        match chord {
+                            Some(action) => self.run_key_action(action).await?,
+                            None => self.handle_key_event(key.code).await?,
                         }
                     }
                 }
@@ -322,22 +485,367 @@ impl App {
         Ok(())
     }
 
+    /// What a plain key does in the panel list, with the configured keys
+    pub fn list_action_for(&mut self, key: KeyCode, modal_open: bool) -> Option<KeyAction> {
+        let (modifiers, panel) = (crossterm::event::KeyModifiers::NONE, self.ui.current_tab);
+        match self.config.keybind_mode {
+            KeybindMode::Normal => crate::keymap::action_for(key, modifiers, panel, modal_open),
+            KeybindMode::Vim => crate::keymap::vim_action_for(
+                key,
+                modifiers,
+                panel,
+                modal_open,
+                &mut self.pending_vim_key,
+            ),
+        }
+    }
+
+    /// Do what a key in the key map is bound to
+    pub async fn run_key_action(&mut self, action: KeyAction) -> Result<()> {
+        match action {
+            KeyAction::NextPanel => {
+                self.ui.next_tab();
+                self.focused_panel = self.ui.current_tab;
+            }
+            KeyAction::PreviousPanel => {
+                self.ui.previous_tab();
+                self.focused_panel = self.ui.current_tab;
+            }
+            KeyAction::SelectPrevious | KeyAction::SelectNext => {
+                if action == KeyAction::SelectPrevious {
+                    self.ui.scroll_up();
+                } else {
+                    self.ui.scroll_down();
+                }
+                if self.ui.is_preview_mode() {
+                    if let Some(item) = self.ui.get_selected_item() {
+                        let item_key = item.key.clone();
+                        let item_value = item.value.clone();
+                        if let Err(e) = self
+                            .ui
+                            .handle_preview_change(&item_key, &item_value, &self.hyprctl)
+                            .await
+                        {
+                            crate::stderr_log::warn(format!("Preview error: {}", e));
+                        }
+                    }
+                }
+            }
+            KeyAction::PreviousPage => self.ui.prev_page(),
+            KeyAction::NextPage => self.ui.next_page(),
+            KeyAction::SelectFirst => self.ui.select_first_item(),
+            KeyAction::SelectLast => self.ui.select_last_item(),
+            KeyAction::HalfPageUp => self.ui.scroll_half_page(false),
+            KeyAction::HalfPageDown => self.ui.scroll_half_page(true),
+            KeyAction::Search => self.ui.start_search_debounced(),
+            KeyAction::SearchAllPanels => self.open_global_search(),
+            KeyAction::Quit => self.request_quit(),
+            KeyAction::Edit => {
+                if self.ui.get_selected_item().is_some() {
+                    self.start_editing().await?;
+                }
+            }
+            KeyAction::ToggleBoolean => self.toggle_selected_boolean().await,
+            KeyAction::StepUp => self.step_selected_number(true).await,
+            KeyAction::StepDown => self.step_selected_number(false).await,
+            KeyAction::AddItem => self.show_add_item_dialog().await,
+            KeyAction::DeleteItem => self.show_delete_item_dialog().await,
+            KeyAction::MoveItemUp => self.move_selected_item(false),
+            KeyAction::MoveItemDown => self.move_selected_item(true),
+            KeyAction::ResetToDefault => self.reset_selected_option().await,
+            KeyAction::Undo => self.handle_undo().await?,
+            KeyAction::Redo => self.handle_redo().await?,
+            KeyAction::ToggleMark => {
+                self.ui.toggle_mark_selected();
+            }
+            KeyAction::ClearMarks => {
+                let cleared = self.ui.clear_marks();
+                if cleared > 0 {
+                    self.ui.show_toast(format!("Unmarked {cleared} items"));
+                }
+            }
+            KeyAction::RestoreDeleted => {
+                self.ui.show_popup = true;
+                self.ui.popup_message = match self.ui.restore_deleted_item() {
+                    Some(change) => format!("Restored {}", change.key),
+                    None => "No deleted items to restore".to_string(),
+                };
+            }
+            KeyAction::CopyValue => self.copy_value_to_clipboard(),
+            KeyAction::PasteValue => self.paste_from_clipboard().await,
+            KeyAction::TogglePlugin => self.toggle_selected_plugin().await,
+            KeyAction::ToggleExec => self.toggle_selected_exec(),
+            KeyAction::NextKeybindConflict => {
+                if !self.ui.select_next_keybind_conflict() {
+                    self.ui.show_popup = true;
+                    self.ui.popup_message = "No keybinds share a key combination".to_string();
+                }
+            }
+            KeyAction::SwitchLayout => {
+                if !self.ui.select_other_layout() {
+                    self.ui.show_popup = true;
+                    self.ui.popup_message = "Only one layout has options here".to_string();
+                }
+            }
+            KeyAction::Save => self.ui.show_save_dialog = true,
+            KeyAction::SaveItem => self.confirm_item_save(),
+            KeyAction::Reload => self.ui.show_reload_dialog = true,
+            KeyAction::RestoreBackup => self.show_restore_dialog().await,
+            KeyAction::PreviewSetting => self.show_enhanced_preview().await,
+            KeyAction::LivePreview => self.toggle_live_preview().await,
+            KeyAction::Export => self.show_export_dialog().await,
+            KeyAction::Import => self.show_import_dialog().await,
+            KeyAction::Batch => self.show_batch_dialog().await,
+            KeyAction::NextTheme => self.cycle_theme().await,
+            KeyAction::ThemeFromHyprland => self.theme_from_hyprland().await,
+            KeyAction::Help => self.ui.toggle_help(),
+            KeyAction::ToggleWarnings => self.ui.toggle_warnings(),
+            KeyAction::ToggleResolvedVariables => self.ui.toggle_resolved_variables(),
+            KeyAction::CommandPalette => self.ui.open_command_palette(),
+            KeyAction::ExportAs(format) => {
+                self.show_export_dialog().await;
+                self.handle_export_dialog_key(KeyCode::Char(format.dialog_key()))
+                    .await?;
+            }
+            KeyAction::ImportFrom(source) => {
+                self.show_import_dialog().await;
+                self.handle_import_dialog_key(KeyCode::Char(source.dialog_key()))
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::collapsible_match)]
+    pub async fn handle_command_palette_key(&mut self, key: KeyCode) -> Result<()> {
+        match key {
+            KeyCode::Up => {
+                self.ui.palette_selected = self.ui.palette_selected.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                if self.ui.palette_selected + 1 < self.ui.palette_results().len() {
+                    self.ui.palette_selected += 1;
+                }
+            }
+            KeyCode::Enter => {
+                let Some(entry) = self
+                    .ui
+                    .palette_results()
+                    .get(self.ui.palette_selected)
+                    .cloned()
+                else {
+                    return Ok(());
+                };
+                self.ui.show_command_palette = false;
+                self.run_key_action(entry.action).await?;
+            }
+            KeyCode::Esc => {
+                self.ui.show_command_palette = false;
+            }
+            KeyCode::Backspace => {
+                self.ui.palette_query.pop();
+                self.ui.palette_selected = 0;
+            }
+            KeyCode::Char(c) => {
+                self.ui.palette_query.push(c);
+                self.ui.palette_selected = 0;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Clicks pick tabs and list rows, a double click edits the row and the
+    /// wheel moves the selection like the arrow keys. The mouse does nothing
+    /// while a dialog or the editor is open.
+    pub async fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<()> {
+        if CommandContext::from_app(self, KeyCode::Null).has_modal_open() {
+            return Ok(());
+        }
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(panel) = self.ui.tab_at(mouse.column, mouse.row) {
+                    self.ui.current_tab = panel;
+                    self.focused_panel = panel;
+                } else if let Some(index) = self.ui.list_row_at(mouse.column, mouse.row) {
+                    if self.ui.click_list_row(index, Instant::now()) {
+                        self.handle_key_event(KeyCode::Enter).await?;
+                    }
+                }
+            }
+            MouseEventKind::ScrollUp => self.handle_key_event(KeyCode::Up).await?,
+            MouseEventKind::ScrollDown => self.handle_key_event(KeyCode::Down).await?,
+            _ => {}
+        }
+        Ok(())
+    }
+
     pub async fn handle_save_dialog_key(&mut self, key: KeyCode) -> Result<()> {
         match key {
             KeyCode::Char('y') | KeyCode::Char('Y') => {
-                self.ui.show_save_dialog = false;
-                self.save_config().await?;
-                self.ui.show_popup = true;
-                self.ui.popup_message = "Configuration saved successfully!".to_string();
+                self.confirm_save().await?;
             }
             KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
                 self.ui.show_save_dialog = false;
+                self.ui.save_validation_errors.clear();
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                self.config.reload_after_save = !self.config.reload_after_save;
+                self.ui.reload_after_save = self.config.reload_after_save;
+                if let Err(e) = self.config.save().await {
+                    crate::stderr_log::warn(format!("Failed to remember the reload setting: {e}"));
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                if self.config.is_nixos_mode() {
+                    self.ui.show_popup = true;
+                    self.ui.popup_message =
+                        "Diff preview is not available in NixOS mode".to_string();
+                    return Ok(());
+                }
+
+                let diff = self.pending_config_diff().await?;
+                if diff.is_empty() {
+                    self.ui.show_popup = true;
+                    self.ui.popup_message = "No changes to save".to_string();
+                } else {
+                    self.ui.show_save_dialog = false;
+                    self.ui
+                        .show_diff_preview("Pending configuration changes".to_string(), diff);
+                }
             }
             _ => {}
         }
         Ok(())
     }
 
+    /// Save once Hyprland's own parser accepts the new config. If it reports
+    /// errors they are listed in the save dialog, where Y saves anyway.
+    async fn confirm_save(&mut self) -> Result<()> {
+        if self.ui.save_validation_errors.is_empty() {
+            let errors = self.hyprland_config_errors().await?;
+            if !errors.is_empty() {
+                self.ui.save_validation_errors = errors;
+                self.ui.show_save_dialog = true;
+                return Ok(());
+            }
+        }
+
+        self.ui.show_save_dialog = false;
+        self.ui.save_validation_errors.clear();
+        self.config.save().await?;
+        self.save_and_reload().await
+    }
+
+    /// Write the changes to the config files, then reload Hyprland if the
+    /// setting asks for it
+    async fn save_and_reload(&mut self) -> Result<()> {
+        self.save_config().await?;
+        self.ui.show_popup = true;
+        self.ui.popup_message = if self.should_reload_after_save() {
+            self.reload_after_save("Configuration saved").await
+        } else {
+            "Configuration saved successfully!".to_string()
+        };
+        Ok(())
+    }
+
+    /// Whether a save is followed by a reload: the setting is on and there is
+    /// a Hyprland to reload
+    fn should_reload_after_save(&self) -> bool {
+        self.config.reload_after_save && self.ui.hyprland_status.is_connected()
+    }
+
+    /// Reload Hyprland so the saved keybinds and rules take effect, and say
+    /// whether it found errors in the saved config. `saved` starts the message.
+    async fn reload_after_save(&mut self, saved: &str) -> String {
+        if let Err(e) = self.hyprctl.reload_config().await {
+            return format!("{saved}, but reloading Hyprland failed: {e}");
+        }
+        match self.hyprctl.config_errors().await {
+            Ok(errors) if errors.is_empty() => {
+                format!("{saved} and Hyprland reloaded it without errors")
+            }
+            Ok(errors) => {
+                let mut message = format!(
+                    "{saved}, but Hyprland reported {} error{} after reloading:\n",
+                    errors.len(),
+                    if errors.len() == 1 { "" } else { "s" }
+                );
+                for error in errors {
+                    message.push_str(&format!("\n• {error}"));
+                }
+                message
+            }
+            Err(e) => format!("{saved} and Hyprland reloaded, errors unknown: {e}"),
+        }
+    }
+
+    /// Errors Hyprland reports for the hyprland.conf a save would write. The
+    /// check is skipped, with a warning, when Hyprland can't be run.
+    async fn hyprland_config_errors(&self) -> Result<Vec<String>> {
+        if self.config.is_nixos_mode() {
+            return Ok(Vec::new());
+        }
+
+        let (_, _, updated) = self.render_pending_configs().await?.swap_remove(0);
+        let config_dir = self
+            .config
+            .hyprland_config_path
+            .parent()
+            .map(std::path::Path::to_path_buf)
+            .unwrap_or_else(std::env::temp_dir);
+        match self.hyprctl.validate_config_in(&updated, &config_dir).await {
+            Ok(errors) => Ok(errors),
+            Err(e) => {
+                crate::stderr_log::warn(format!("Could not validate config with Hyprland: {e}"));
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Current and updated content of every file a save would write,
+    /// hyprland.conf first and then each sourced file
+    async fn render_pending_configs(&self) -> Result<Vec<(std::path::PathBuf, String, String)>> {
+        let config_changes = self.ui.collect_config_changes_to_save();
+        let mut lines = self.ui.collect_config_lines();
+        self.config.route_new_lines(&mut lines).await?;
+
+        let (current, updated) = self
+            .config
+            .render_hyprland_config_with_rules(
+                &config_changes,
+                &lines.keybinds,
+                &lines.window_rules,
+                &lines.layer_rules,
+                &lines.workspace_rules,
+                &lines.env_vars,
+                &lines.plugins,
+                &lines.autostart,
+                &lines.variables,
+            )
+            .await?;
+        let mut files = vec![(self.config.hyprland_config_path.clone(), current, updated)];
+        files.extend(self.config.render_included_files(&lines.included).await?);
+        Ok(files)
+    }
+
+    /// Unified diff between the config files on disk and what saving would
+    /// write to them
+    async fn pending_config_diff(&self) -> Result<Vec<crate::diff::DiffLine>> {
+        use crate::diff::{unified_diff, DiffLine};
+
+        let mut diff = Vec::new();
+        for (path, current, updated) in self.render_pending_configs().await? {
+            let hunks = unified_diff(&current, &updated, 3);
+            if !hunks.is_empty() {
+                diff.push(DiffLine::File(path.display().to_string()));
+                diff.extend(hunks);
+            }
+        }
+        Ok(diff)
+    }
+
     pub async fn handle_nixos_export_dialog_key(&mut self, key: KeyCode) -> Result<()> {
         match key {
             KeyCode::Char('1') => {
@@ -356,6 +864,10 @@ impl App {
                 self.ui.nixos_export_config_type = crate::nixos::NixConfigType::FlakeSystem;
                 self.update_nixos_export_preview().await;
             }
+            KeyCode::Char('f') | KeyCode::Char('F') => {
+                self.ui.nixos_export_use_formatter = !self.ui.nixos_export_use_formatter;
+                self.update_nixos_export_preview().await;
+            }
             KeyCode::Enter => {
                 // Export with selected config type
                 self.ui.show_nixos_export_dialog = false;
@@ -365,7 +877,7 @@ impl App {
                 self.ui.show_nixos_export_dialog = false;
                 self.ui.nixos_export_preview = None;
             }
-            _ => {}
+            key => self.ui.scroll_nixos_export_preview(key),
         }
         Ok(())
     }
@@ -386,131 +898,1178 @@ impl App {
         Ok(())
     }
 
-    pub async fn handle_popup_key(&mut self, key: KeyCode) -> Result<()> {
-        // Check if this is a deletion confirmation popup
-        if let Some((panel, item_key)) = &self.ui.pending_deletion {
-            match key {
-                KeyCode::Char('y') | KeyCode::Char('Y') => {
-                    // Confirm deletion
-                    let panel_clone = *panel;
-                    let key_clone = item_key.clone();
-                    if self.ui.delete_item(&panel_clone, &key_clone) {
-                        self.ui.show_popup = true;
-                        self.ui.popup_message = "Item deleted successfully!".to_string();
-                    } else {
+    /// Quit, first asking to save or discard any unsaved changes
+    pub fn request_quit(&mut self) {
+        if self.ui.dirty_count() > 0 {
+            self.ui.show_quit_dialog = true;
+        } else {
+            self.state = AppState::Quitting;
+        }
+    }
+
+    pub async fn handle_quit_dialog_key(&mut self, key: KeyCode) -> Result<()> {
+        match key {
+            KeyCode::Char('s') | KeyCode::Char('S') => {
+                self.ui.show_quit_dialog = false;
+                // Errors Hyprland finds reopen the save dialog instead
+                match self.confirm_save().await {
+                    Ok(()) if !self.ui.show_save_dialog => self.state = AppState::Quitting,
+                    Ok(()) => {}
+                    Err(e) => {
                         self.ui.show_popup = true;
-                        self.ui.popup_message =
-                            "Failed to delete item - item not found.".to_string();
+                        self.ui.popup_message = format!("Failed to save, not quitting: {e}");
                     }
-                    self.ui.pending_deletion = None;
-                }
-                _ => {
-                    // Cancel deletion on any other key
-                    self.ui.pending_deletion = None;
-                    self.ui.show_popup = false;
-                    self.ui.popup_message.clear();
                 }
             }
-        } else {
-            // Normal popup handling
-            match key {
-                KeyCode::Enter | KeyCode::Esc => {
-                    self.ui.show_popup = false;
-                    self.ui.popup_message.clear();
-                }
-                _ => {}
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                self.ui.show_quit_dialog = false;
+                self.state = AppState::Quitting;
             }
+            KeyCode::Char('c') | KeyCode::Char('C') | KeyCode::Esc => {
+                self.ui.show_quit_dialog = false;
+            }
+            _ => {}
         }
         Ok(())
     }
 
-    pub async fn handle_edit_key(&mut self, key: KeyCode) -> Result<()> {
-        use crate::ui::EditMode;
+    /// Open the backup restore dialog with the current list of backups
+    pub async fn show_restore_dialog(&mut self) {
+        self.ui.restore_backups = self.config.list_backups().unwrap_or_default();
+        self.ui.restore_selected = 0;
+        self.ui.show_restore_dialog = true;
+    }
 
-        // Check preview mode and get editing key before match to avoid borrowing issues
-        let preview_enabled = self.ui.is_preview_mode();
-        let editing_key = self.ui.editing_item.as_ref().map(|(_, key)| key.clone());
+    /// Open the setup wizard, as `--setup` does
+    pub fn start_setup_wizard(&mut self) {
+        self.ui.setup_wizard = Some(crate::setup::SetupWizard::new(
+            crate::setup::Environment::detect(&self.config, self.ui.hyprland_status),
+        ));
+    }
 
-        // Handle preview trigger after making changes
-        let mut should_trigger_preview = false;
-        let mut preview_value = String::new();
+    pub async fn handle_setup_wizard_key(&mut self, key: KeyCode) -> Result<()> {
+        use crate::setup::SetupStep;
 
-        match &mut self.ui.edit_mode {
-            // ---- TEXT INPUT EDITING ----
-            EditMode::Text {
-                current_value,
-                cursor_pos,
-            } => {
-                match key {
-                    KeyCode::Enter => {
-                        match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
-                            Ok(()) => {
-                                self.ui.show_popup = true;
-                                self.ui.popup_message = "Value updated successfully!".to_string();
-                            }
-                            Err(_) => {
-                                // Error message already set in apply_edit_with_hyprctl
-                            }
-                        }
-                    }
-                    KeyCode::Esc => {
-                        // Cancel any pending preview changes
-                        if self.ui.is_preview_mode() {
-                            if let Err(e) = self.ui.cancel_preview(&self.hyprctl).await {
-                                eprintln!("Error canceling preview: {}", e);
-                            }
-                        }
-                        self.ui.cancel_edit();
-                    }
-                    KeyCode::Char(c) => {
-                        current_value.insert(*cursor_pos, c);
-                        *cursor_pos += 1;
+        let Some(wizard) = &mut self.ui.setup_wizard else {
+            return Ok(());
+        };
+        match key {
+            KeyCode::Enter => match wizard.step.next() {
+                Some(next) => wizard.step = next,
+                None => self.finish_setup_wizard().await,
+            },
+            KeyCode::Backspace | KeyCode::BackTab => {
+                if let Some(previous) = wizard.step.previous() {
+                    wizard.step = previous;
+                }
+            }
+            KeyCode::Esc => self.finish_setup_wizard().await,
+            KeyCode::Char('c') | KeyCode::Char('C')
+                if wizard.step == SetupStep::ConfigFile && !wizard.environment.config_exists =>
+            {
+                self.create_hyprland_config().await?;
+            }
+            KeyCode::Right | KeyCode::Char('t') | KeyCode::Char('T')
+                if wizard.step == SetupStep::Theme =>
+            {
+                self.ui.next_theme();
+            }
+            KeyCode::Left if wizard.step == SetupStep::Theme => {
+                self.ui.previous_theme();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
 
-                        // Set up for preview trigger
-                        if preview_enabled {
-                            should_trigger_preview = true;
-                            preview_value = current_value.clone();
-                        }
-                    }
-                    KeyCode::Backspace => {
-                        if *cursor_pos > 0 {
-                            *cursor_pos -= 1;
-                            current_value.remove(*cursor_pos);
+    /// Create hyprland.conf from the starter config and load it
+    async fn create_hyprland_config(&mut self) -> Result<()> {
+        if let Err(e) = self.config.create_default_hyprland_config().await {
+            self.ui.show_popup = true;
+            self.ui.popup_message = format!("Couldn't create hyprland.conf: {e}");
+            return Ok(());
+        }
+        self.reload_config().await?;
+        if let Some(wizard) = &mut self.ui.setup_wizard {
+            wizard.environment.config_exists = self.config.hyprland_config_path.is_file();
+        }
+        self.ui.show_toast("Created hyprland.conf");
+        Ok(())
+    }
 
-                            // Set up for preview trigger
-                            if preview_enabled {
-                                should_trigger_preview = true;
-                                preview_value = current_value.clone();
-                            }
-                        }
-                    }
-                    KeyCode::Left => {
+    /// Close the wizard for good, keeping the theme picked in it
+    async fn finish_setup_wizard(&mut self) {
+        self.ui.setup_wizard = None;
+        self.config.theme = self.ui.theme.scheme.clone();
+        self.config.first_run_completed = true;
+        if let Err(e) = self.config.save().await {
+            crate::stderr_log::warn(format!("Failed to remember that setup is done: {e}"));
+        }
+    }
+
+    pub async fn handle_deprecations_dialog_key(&mut self, key: KeyCode) -> Result<()> {
+        match key {
+            KeyCode::Char('m') | KeyCode::Char('M') => {
+                self.ui.show_deprecations_dialog = false;
+                self.migrate_deprecated_options().await?;
+            }
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                self.ui.show_deprecations_dialog = false;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Rewrite the deprecated options in the config files and reload them.
+    /// Reloading would drop unsaved changes, so those have to go first.
+    async fn migrate_deprecated_options(&mut self) -> Result<()> {
+        self.ui.show_popup = true;
+        if self.ui.dirty_count() > 0 {
+            self.ui.popup_message =
+                "Save or reload your changes before migrating deprecated options".to_string();
+            return Ok(());
+        }
+        let count = self.ui.deprecated_options.len();
+        match self.config.migrate_deprecated_options().await {
+            Ok(true) => {
+                self.reload_config().await?;
+                self.ui.show_popup = true;
+                self.ui.popup_message = format!(
+                    "Migrated {count} deprecated option{}, the old config is in a backup",
+                    if count == 1 { "" } else { "s" }
+                );
+            }
+            Ok(false) => {
+                self.ui.popup_message = "No deprecated options left to migrate".to_string();
+            }
+            Err(e) => {
+                self.ui.popup_message = format!("Migration failed: {e}");
+            }
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::collapsible_match)]
+    pub async fn handle_restore_dialog_key(&mut self, key: KeyCode) -> Result<()> {
+        match key {
+            KeyCode::Up => {
+                self.ui.restore_selected = self.ui.restore_selected.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                if self.ui.restore_selected + 1 < self.ui.restore_backups.len() {
+                    self.ui.restore_selected += 1;
+                }
+            }
+            KeyCode::Enter => {
+                let Some(backup) = self.ui.restore_backups.get(self.ui.restore_selected).cloned()
+                else {
+                    self.ui.show_restore_dialog = false;
+                    return Ok(());
+                };
+                self.ui.show_restore_dialog = false;
+
+                match self.config.restore_backup(&backup).await {
+                    Ok(()) => {
+                        self.reload_config().await?;
+                        self.ui.show_popup = true;
+                        self.ui.popup_message = format!(
+                            "Restored configuration from {}",
+                            backup.display()
+                        );
+                    }
+                    Err(e) => {
+                        self.ui.show_popup = true;
+                        self.ui.popup_message = format!("Restore failed: {e}");
+                    }
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.ui.show_restore_dialog = false;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Reset the selected option to Hyprland's default: drop it from the config
+    /// files, reload, and read back the value Hyprland falls back to
+    pub async fn reset_selected_option(&mut self) {
+        if self.ui.edit_mode != crate::ui::EditMode::None {
+            return;
+        }
+        let panel = self.ui.current_tab;
+        let Some(item) = self.ui.get_selected_item().cloned() else {
+            return;
+        };
+        let Some(hypr_key) = self.ui.get_hyprctl_key(&panel, &item.key) else {
+            self.ui.show_popup = true;
+            self.ui.popup_message = format!("'{}' is not an option with a default", item.key);
+            return;
+        };
+        if self.config.is_nixos_mode() {
+            self.ui.show_popup = true;
+            self.ui.popup_message =
+                "Options can't be reset in NixOS mode, edit your NixOS configuration".to_string();
+            return;
+        }
+        if self.hyprctl.is_file_only() {
+            self.ui.show_popup = true;
+            self.ui.popup_message =
+                "Defaults come from Hyprland, which file-only mode doesn't use".to_string();
+            return;
+        }
+        if !self.hyprctl.is_hyprland_running().await {
+            self.ui.show_popup = true;
+            self.ui.popup_message =
+                "Hyprland is not running, can't look up the default value".to_string();
+            return;
+        }
+
+        self.ui.popup_message = match self.reset_option(&hypr_key).await {
+            Ok(default) => {
+                self.ui.mark_option_default(panel, &item.key, &default);
+                format!("Reset {hypr_key} to its default: {default}")
+            }
+            Err(e) => format!("Failed to reset {hypr_key}: {e}"),
+        };
+        self.ui.show_popup = true;
+    }
+
+    /// Ask before writing just the selected item to the config file
+    pub fn confirm_item_save(&mut self) {
+        if self.ui.edit_mode != crate::ui::EditMode::None || self.ui.show_popup {
+            return;
+        }
+        self.ui.show_popup = true;
+        if self.config.is_nixos_mode() {
+            self.ui.popup_message =
+                "Single items can't be saved in NixOS mode, edit your NixOS configuration"
+                    .to_string();
+            return;
+        }
+        match self.ui.selected_item_save() {
+            Ok(save) => {
+                self.ui.popup_message = format!(
+                    "Save only '{}' to the config file? Nothing else is written - Press 'Y' to save, any other key to cancel",
+                    save.describe()
+                );
+                self.ui.pending_item_save = Some(save);
+            }
+            Err(e) => self.ui.popup_message = format!("Can't save this item on its own: {e}"),
+        }
+    }
+
+    /// Write one item to the config file, leaving every other line as it is
+    async fn save_item(&mut self, save: crate::ui::ItemSave) {
+        let result = match &save {
+            crate::ui::ItemSave::Option { option, value } => {
+                let options = std::collections::HashMap::from([(option.clone(), value.clone())]);
+                self.config.save_hyprland_config(&options).await.map(|()| {
+                    self.ui.mark_options_saved(options.keys());
+                    true
+                })
+            }
+            crate::ui::ItemSave::Line {
+                file,
+                old_lines,
+                line,
+            } => {
+                self.config
+                    .save_config_line(file.as_deref(), old_lines, line)
+                    .await
+            }
+        };
+
+        // The confirmation popup kept the saved item selected
+        if result.is_ok() {
+            if let Some(key) = self.ui.get_selected_item().map(|item| item.key.clone()) {
+                self.ui.mark_item_saved(self.ui.current_tab, &key);
+            }
+        }
+
+        self.ui.show_popup = true;
+        self.ui.popup_message = match result {
+            Ok(false) => format!("'{}' is already in the config file", save.describe()),
+            Ok(true) if self.should_reload_after_save() => {
+                self.reload_after_save(&format!("Saved '{}'", save.describe()))
+                    .await
+            }
+            Ok(true) => format!("Saved '{}'", save.describe()),
+            Err(e) => format!("Failed to save '{}': {e}", save.describe()),
+        };
+    }
+
+    /// Enable or disable the selected plugin line. While Hyprland runs the
+    /// plugin is loaded or unloaded right away; the line changes on save.
+    pub async fn toggle_selected_plugin(&mut self) {
+        let Some(plugin) = self.ui.toggle_selected_plugin() else {
+            self.ui.show_popup = true;
+            self.ui.popup_message = "Select a plugin line to enable or disable it".to_string();
+            return;
+        };
+        let name = plugin.name();
+        let state = if plugin.enabled {
+            "Enabled"
+        } else {
+            "Disabled"
+        };
+
+        self.ui.show_popup = true;
+        self.ui.popup_message = if !self.hyprctl.status().is_connected() {
+            format!("{state} {name}. Save to write it to the config.")
+        } else if plugin.enabled {
+            match self.hyprctl.load_plugin(&plugin.path).await {
+                Ok(()) => format!("{state} and loaded {name}. Save to keep it."),
+                Err(e) => format!("{state} {name}, but Hyprland could not load it: {e}"),
+            }
+        } else {
+            match self.hyprctl.unload_plugin(&plugin.path).await {
+                Ok(()) => format!("{state} and unloaded {name}. Save to keep it."),
+                Err(e) => format!("{state} {name}, but Hyprland could not unload it: {e}"),
+            }
+        };
+    }
+
+    /// Move the selected keybind, rule or command one place up, or down when
+    /// `later` is set. The new order is written on save.
+    pub fn move_selected_item(&mut self, later: bool) {
+        if self.ui.move_selected_item(later) {
+            return;
+        }
+        if !UI::is_line_panel(self.ui.current_tab) {
+            self.ui.show_popup = true;
+            self.ui.popup_message =
+                "Only keybinds, rules, environment variables, plugins, autostart commands and variables can be reordered"
+                    .to_string();
+        } else if !self.ui.search_query.is_empty() {
+            self.ui.show_popup = true;
+            self.ui.popup_message = "Clear the search to reorder items".to_string();
+        }
+    }
+
+    /// Comment out the selected autostart command, or uncomment it. Hyprland
+    /// only runs the commands while it reads the config, so nothing runs now.
+    pub fn toggle_selected_exec(&mut self) {
+        self.ui.show_popup = true;
+        self.ui.popup_message = match self.ui.toggle_selected_exec() {
+            Some(exec) => {
+                let state = if exec.enabled { "Enabled" } else { "Disabled" };
+                format!(
+                    "{state} '{}'. Save to write it to the config.",
+                    exec.command
+                )
+            }
+            None => "Select an autostart command to enable or disable it".to_string(),
+        };
+    }
+
+    /// Flip the selected true/false option without opening the editor. In
+    /// live preview it is set in Hyprland right away, like a kept preview;
+    /// otherwise it is written on save. Other items ignore the key.
+    pub async fn toggle_selected_boolean(&mut self) {
+        if !self.ui.marked_keys(self.ui.current_tab).is_empty() {
+            return self.set_marked_booleans().await;
+        }
+        if let Some((key, value)) = self.ui.toggle_selected_boolean() {
+            self.apply_in_live_preview(&key, &value).await;
+        }
+    }
+
+    /// Turn the marked true/false options all on or all off, as Space does
+    /// while items are marked
+    async fn set_marked_booleans(&mut self) {
+        let changes = self.ui.set_marked_booleans();
+        let Some((_, first)) = changes.first() else {
+            self.ui.show_toast("No marked option to turn on or off");
+            return;
+        };
+        let state = if crate::ui::is_on(first) { "on" } else { "off" };
+        self.ui
+            .show_toast(format!("Turned {} marked options {state}", changes.len()));
+        self.apply_all_in_live_preview(&changes).await;
+    }
+
+    /// Step the selected number up or down without opening the slider, the
+    /// same way a boolean is toggled. Other items ignore the key.
+    pub async fn step_selected_number(&mut self, up: bool) {
+        if let Some((key, value)) = self.ui.step_selected_number(up) {
+            self.apply_in_live_preview(&key, &value).await;
+        }
+    }
+
+    /// Set an option changed in the list in Hyprland, while live preview is on
+    async fn apply_in_live_preview(&mut self, key: &str, value: &str) {
+        if !self.ui.is_preview_mode() {
+            return;
+        }
+        let Some(hypr_key) = self.ui.get_hyprctl_key(&self.ui.current_tab, key) else {
+            return;
+        };
+        // A preview of another value that wasn't kept is reverted first
+        if let Err(e) = self.ui.cancel_preview(&self.hyprctl).await {
+            crate::stderr_log::warn(format!("Error canceling preview: {}", e));
+        }
+        if let Err(e) = self.hyprctl.set_option(&hypr_key, value).await {
+            self.ui.show_popup = true;
+            self.ui.popup_message = format!("Failed to apply {hypr_key} = {value}: {e}");
+        }
+    }
+
+    /// Set several options changed in the list in Hyprland while live
+    /// preview is on, in one `hyprctl --batch` call when there are more than
+    /// a few of them
+    async fn apply_all_in_live_preview(&mut self, changes: &[(String, String)]) {
+        if !self.ui.is_preview_mode() {
+            return;
+        }
+        let options: std::collections::HashMap<String, String> = changes
+            .iter()
+            .filter_map(|(key, value)| {
+                let hypr_key = self.ui.get_hyprctl_key(&self.ui.current_tab, key)?;
+                Some((hypr_key, value.clone()))
+            })
+            .collect();
+        if options.len() <= crate::hyprctl::BATCH_THRESHOLD {
+            for (key, value) in changes {
+                self.apply_in_live_preview(key, value).await;
+            }
+            return;
+        }
+
+        if let Err(e) = self.ui.cancel_preview(&self.hyprctl).await {
+            crate::stderr_log::warn(format!("Error canceling preview: {}", e));
+        }
+        let failed = match self.hyprctl.set_options_batch(&options).await {
+            Ok(outcome) => outcome.failed,
+            Err(e) => {
+                let mut keys: Vec<&String> = options.keys().collect();
+                keys.sort();
+                keys.into_iter()
+                    .map(|key| (key.clone(), e.to_string()))
+                    .collect()
+            }
+        };
+        if !failed.is_empty() {
+            let mut message = format!(
+                "Failed to apply {} of {} options:",
+                failed.len(),
+                options.len()
+            );
+            for (key, error) in failed {
+                message.push_str(&format!("\n  {key}: {error}"));
+            }
+            self.ui.show_popup = true;
+            self.ui.popup_message = message;
+        }
+    }
+
+    async fn reset_option(&self, hypr_key: &str) -> Result<String> {
+        self.config.remove_option(hypr_key).await?;
+        self.hyprctl.reload_config().await?;
+        let value = self.hyprctl.get_option_value(hypr_key).await?;
+        Ok(value.value())
+    }
+
+    /// Copy the value being edited, or the selected item's value
+    pub fn copy_value_to_clipboard(&mut self) {
+        let Some(value) = self.ui.clipboard_value() else {
+            return;
+        };
+        self.ui.popup_message = match crate::clipboard::copy(&value) {
+            Ok(method) => format!("Copied '{value}' to the clipboard via {}", method.label()),
+            Err(e) => e.to_string(),
+        };
+        self.ui.show_popup = true;
+    }
+
+    /// Paste the clipboard into the open text edit at the cursor
+    pub async fn paste_from_clipboard(&mut self) {
+        if self.ui.show_import_dialog
+            && (self.ui.github_import_form.is_some() || self.ui.import_input.is_some())
+        {
+            return self.paste_into_import_form();
+        }
+        if !matches!(self.ui.edit_mode, crate::ui::EditMode::Text { .. }) {
+            return;
+        }
+        let text = match crate::clipboard::paste() {
+            Ok(text) => text,
+            Err(e) => {
+                self.ui.show_popup = true;
+                self.ui.popup_message = e.to_string();
+                return;
+            }
+        };
+        let Some(value) = self.ui.paste_into_edit(&text) else {
+            return;
+        };
+
+        if self.ui.is_preview_mode() && self.ui.edit_error.is_none() {
+            if let Some((_, key)) = self.ui.editing_item.clone() {
+                if let Err(e) = self
+                    .ui
+                    .handle_preview_change(&key, &value, &self.hyprctl)
+                    .await
+                {
+                    crate::stderr_log::warn(format!("Preview error: {}", e));
+                }
+            }
+        }
+    }
+
+    /// Paste a URL or folder into the GitHub, URL or folder import form
+    fn paste_into_import_form(&mut self) {
+        use crate::ui::GitHubImportField;
+
+        let text = match crate::clipboard::paste() {
+            Ok(text) => crate::clipboard::single_line(&text),
+            Err(e) => {
+                self.ui.show_popup = true;
+                self.ui.popup_message = e.to_string();
+                return;
+            }
+        };
+        if let Some(form) = self.ui.github_import_form.as_mut() {
+            match form.field {
+                GitHubImportField::Repository => {
+                    form.repository.push_str(text.trim());
+                    form.branches.clear();
+                }
+                GitHubImportField::Folder => form.folder.push_str(text.trim()),
+                GitHubImportField::Branch => {}
+            }
+        }
+        if let Some(input) = self.ui.import_input.as_mut() {
+            input.push_str(text.trim());
+        }
+    }
+
+    /// Open the search-all-panels dialog with a fresh query
+    pub fn open_global_search(&mut self) {
+        if self.ui.edit_mode != crate::ui::EditMode::None {
+            return;
+        }
+        self.ui.show_global_search = true;
+        self.ui.global_search_query.clear();
+        self.ui.global_search_results.clear();
+        self.ui.global_search_selected = 0;
+    }
+
+    #[allow(clippy::collapsible_match)]
+    pub async fn handle_global_search_key(&mut self, key: KeyCode) -> Result<()> {
+        match key {
+            KeyCode::Up => {
+                self.ui.global_search_selected = self.ui.global_search_selected.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                if self.ui.global_search_selected + 1 < self.ui.global_search_results.len() {
+                    self.ui.global_search_selected += 1;
+                }
+            }
+            KeyCode::Enter => {
+                let Some((panel, item)) = self
+                    .ui
+                    .global_search_results
+                    .get(self.ui.global_search_selected)
+                    .cloned()
+                else {
+                    return Ok(());
+                };
+                self.ui.show_global_search = false;
+
+                // Drop any panel filter so the item is actually in the list
+                self.ui.search_mode = false;
+                self.ui.search_query.clear();
+                self.ui.search_cursor = 0;
+
+                self.focused_panel = panel;
+                self.ui.current_tab = panel;
+                if self.ui.select_item_by_key(panel, &item.key) {
+                    self.start_editing().await?;
+                }
+            }
+            KeyCode::Esc => {
+                self.ui.show_global_search = false;
+            }
+            KeyCode::Backspace => {
+                self.ui.global_search_query.pop();
+                self.ui.update_global_search();
+            }
+            KeyCode::Char(c) => {
+                self.ui.global_search_query.push(c);
+                self.ui.update_global_search();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Start editing the selected item. Window rules also get the open windows,
+    /// offered as patterns.
+    pub async fn start_editing(&mut self) -> Result<()> {
+        self.ui.start_editing().await?;
+        if matches!(
+            self.ui.edit_mode,
+            crate::ui::EditMode::Rule {
+                rule_type: crate::ui::RuleType::Window,
+                ..
+            }
+        ) {
+            // Without a running Hyprland there are no windows to offer
+            self.ui.window_clients = self.hyprctl.get_clients().await.unwrap_or_default();
+        }
+        Ok(())
+    }
+
+    pub async fn handle_popup_key(&mut self, key: KeyCode) -> Result<()> {
+        // A destructive dispatcher from the keybind editor waits for 'Y'
+        if let Some((dispatcher, args)) = self.ui.pending_dispatch.take() {
+            if matches!(key, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.test_keybind_dispatch(&dispatcher, &args).await;
+            } else {
+                self.ui.show_popup = false;
+                self.ui.popup_message.clear();
+            }
+        } else if let Some(save) = self.ui.pending_item_save.take() {
+            if matches!(key, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.save_item(save).await;
+            } else {
+                self.ui.show_popup = false;
+                self.ui.popup_message.clear();
+            }
+        } else if let Some((panel, item_key)) = &self.ui.pending_deletion {
+            // Deletion confirmation popup
+            match key {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    // Confirm deletion
+                    let panel_clone = *panel;
+                    let key_clone = item_key.clone();
+                    if self.ui.delete_item(&panel_clone, &key_clone) {
+                        self.ui.show_popup = true;
+                        self.ui.popup_message =
+                            "Item deleted successfully! U restores it until you save.".to_string();
+                    } else {
+                        self.ui.show_popup = true;
+                        self.ui.popup_message =
+                            "Failed to delete item - item not found.".to_string();
+                    }
+                    self.ui.pending_deletion = None;
+                }
+                _ => {
+                    // Cancel deletion on any other key
+                    self.ui.pending_deletion = None;
+                    self.ui.show_popup = false;
+                    self.ui.popup_message.clear();
+                }
+            }
+        } else if let Some((panel, keys)) = self.ui.pending_bulk_deletion.take() {
+            if matches!(key, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                let deleted = self.ui.delete_items(panel, &keys);
+                self.ui.popup_message = format!(
+                    "Deleted {deleted} items! U restores them one at a time until you save."
+                );
+            } else {
+                self.ui.show_popup = false;
+                self.ui.popup_message.clear();
+            }
+        } else {
+            // Normal popup handling
+            match key {
+                KeyCode::Enter | KeyCode::Esc => {
+                    self.ui.show_popup = false;
+                    self.ui.popup_message.clear();
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Run the keybind editor's action through hyprctl without saving the bind
+    async fn test_keybind_dispatch(&mut self, dispatcher: &str, args: &str) {
+        let command = format!("{dispatcher} {args}");
+        self.ui.show_popup = true;
+        self.ui.popup_message = match self.hyprctl.dispatch(dispatcher, args).await {
+            Ok(()) => format!("Ran: hyprctl dispatch {}", command.trim()),
+            Err(e) => format!("Test failed: {e}"),
+        };
+    }
+
+    #[allow(clippy::collapsible_match)]
+    pub async fn handle_edit_key(&mut self, key: KeyCode) -> Result<()> {
+        use crate::ui::EditMode;
+
+        // Check preview mode and get editing key before match to avoid borrowing issues
+        let preview_enabled = self.ui.is_preview_mode();
+        let editing_key = self.ui.editing_item.as_ref().map(|(_, key)| key.clone());
+
+        // Handle preview trigger after making changes
+        let mut should_trigger_preview = false;
+        let mut preview_value = String::new();
+
+        let text_completions = self.ui.text_completions();
+        let animation_curves = self.ui.animation_curves();
+        match &mut self.ui.edit_mode {
+            // ---- TEXT INPUT EDITING ----
+            EditMode::Text {
+                current_value,
+                cursor_pos,
+            } => {
+                match key {
+                    KeyCode::Up | KeyCode::Down if !text_completions.is_empty() => {
+                        let count = text_completions.len();
+                        let current = self.ui.completion_index.min(count - 1);
+                        self.ui.completion_index = if key == KeyCode::Down {
+                            (current + 1) % count
+                        } else {
+                            (current + count - 1) % count
+                        };
+                    }
+                    KeyCode::Tab if !text_completions.is_empty() => {
+                        let index = self.ui.completion_index.min(text_completions.len() - 1);
+                        *current_value = text_completions[index].clone();
+                        *cursor_pos = current_value.len();
+                        self.ui.completion_index = 0;
+                    }
+                    // Invalid values are never sent to hyprctl; the popup shows why
+                    KeyCode::Enter if self.ui.edit_error.is_some() => {}
+                    KeyCode::Enter => {
+                        match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
+                            Ok(()) => {
+                                self.ui.show_popup = true;
+                                self.ui.popup_message = "Value updated successfully!".to_string();
+                            }
+                            Err(_) => {
+                                // Error message already set in apply_edit_with_hyprctl
+                            }
+                        }
+                    }
+                    KeyCode::Esc => {
+                        // Cancel any pending preview changes
+                        if self.ui.is_preview_mode() {
+                            if let Err(e) = self.ui.cancel_preview(&self.hyprctl).await {
+                                crate::stderr_log::warn(format!("Error canceling preview: {}", e));
+                            }
+                        }
+                        self.ui.cancel_edit();
+                    }
+                    KeyCode::Char(c) => {
+                        current_value.insert(*cursor_pos, c);
+                        *cursor_pos += 1;
+                        self.ui.completion_index = 0;
+
+                        // Set up for preview trigger
+                        if preview_enabled {
+                            should_trigger_preview = true;
+                            preview_value = current_value.clone();
+                        }
+                    }
+                    KeyCode::Backspace => {
+                        if *cursor_pos > 0 {
+                            *cursor_pos -= 1;
+                            current_value.remove(*cursor_pos);
+                            self.ui.completion_index = 0;
+
+                            // Set up for preview trigger
+                            if preview_enabled {
+                                should_trigger_preview = true;
+                                preview_value = current_value.clone();
+                            }
+                        }
+                    }
+                    KeyCode::Left => {
                         if *cursor_pos > 0 {
                             *cursor_pos -= 1;
                         }
                     }
-                    KeyCode::Right => {
-                        if *cursor_pos < current_value.len() {
-                            *cursor_pos += 1;
+                    KeyCode::Right => {
+                        if *cursor_pos < current_value.len() {
+                            *cursor_pos += 1;
+                        }
+                    }
+                    KeyCode::Home => {
+                        *cursor_pos = 0;
+                    }
+                    KeyCode::End => {
+                        *cursor_pos = current_value.len();
+                    }
+                    _ => {}
+                }
+            }
+            // ---- BOOLEAN TOGGLE EDITING ----
+            EditMode::Boolean { current_value } => {
+                match key {
+                    KeyCode::Enter => {
+                        match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
+                            Ok(()) => {
+                                self.ui.show_popup = true;
+                                self.ui.popup_message = "Value updated successfully!".to_string();
+                            }
+                            Err(_) => {
+                                // Error message already set in apply_edit_with_hyprctl
+                            }
+                        }
+                    }
+                    KeyCode::Esc => {
+                        self.ui.cancel_edit();
+                    }
+                    KeyCode::Char(' ') => {
+                        *current_value = !*current_value;
+
+                        if preview_enabled {
+                            should_trigger_preview = true;
+                            preview_value = current_value.to_string();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // ---- SELECTION FROM OPTIONS ----
+            EditMode::Select { options, selected } => {
+                match key {
+                    KeyCode::Enter => {
+                        match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
+                            Ok(()) => {
+                                self.ui.show_popup = true;
+                                self.ui.popup_message = "Value updated successfully!".to_string();
+                            }
+                            Err(_) => {
+                                // Error message already set in apply_edit_with_hyprctl
+                            }
+                        }
+                    }
+                    KeyCode::Esc => {
+                        self.ui.cancel_edit();
+                    }
+                    KeyCode::Up => {
+                        if *selected > 0 {
+                            *selected -= 1;
+                        } else {
+                            *selected = options.len() - 1;
+                        }
+
+                        if preview_enabled {
+                            should_trigger_preview = true;
+                            preview_value = options[*selected].clone();
+                        }
+                    }
+                    KeyCode::Down => {
+                        if *selected < options.len() - 1 {
+                            *selected += 1;
+                        } else {
+                            *selected = 0;
+                        }
+
+                        if preview_enabled {
+                            should_trigger_preview = true;
+                            preview_value = options[*selected].clone();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // ---- SLIDER/NUMERIC INPUT ----
+            EditMode::Slider {
+                current_value,
+                min,
+                max,
+                step,
+            } => {
+                match key {
+                    KeyCode::Enter => {
+                        match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
+                            Ok(()) => {
+                                self.ui.show_popup = true;
+                                self.ui.popup_message = "Value updated successfully!".to_string();
+                            }
+                            Err(_) => {
+                                // Error message already set in apply_edit_with_hyprctl
+                            }
+                        }
+                    }
+                    KeyCode::Esc => {
+                        self.ui.cancel_edit();
+                    }
+                    KeyCode::Left => {
+                        *current_value = (*current_value - *step).max(*min);
+
+                        if preview_enabled {
+                            should_trigger_preview = true;
+                            preview_value = if current_value.fract() == 0.0 {
+                                (*current_value as i32).to_string()
+                            } else {
+                                format!("{current_value:.2}")
+                            };
+                        }
+                    }
+                    KeyCode::Right => {
+                        *current_value = (*current_value + *step).min(*max);
+
+                        if preview_enabled {
+                            should_trigger_preview = true;
+                            preview_value = if current_value.fract() == 0.0 {
+                                (*current_value as i32).to_string()
+                            } else {
+                                format!("{current_value:.2}")
+                            };
+                        }
+                    }
+                    KeyCode::Home => {
+                        *current_value = *min;
+
+                        if preview_enabled {
+                            should_trigger_preview = true;
+                            preview_value = if current_value.fract() == 0.0 {
+                                (*current_value as i32).to_string()
+                            } else {
+                                format!("{current_value:.2}")
+                            };
+                        }
+                    }
+                    KeyCode::End => {
+                        *current_value = *max;
+
+                        if preview_enabled {
+                            should_trigger_preview = true;
+                            preview_value = if current_value.fract() == 0.0 {
+                                (*current_value as i32).to_string()
+                            } else {
+                                format!("{current_value:.2}")
+                            };
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // ---- KEYBIND CONFIGURATION ----
+            EditMode::Keybind {
+                modifiers,
+                key: key_field,
+                dispatcher,
+                args,
+                editing_field,
+                ..
+            } => {
+                match key {
+                    KeyCode::Enter => {
+                        match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
+                            Ok(()) => {
+                                self.ui.show_popup = true;
+                                self.ui.popup_message = "Keybind updated successfully!".to_string();
+                            }
+                            Err(_) => {
+                                // Error message already set in apply_edit_with_hyprctl
+                            }
+                        }
+                    }
+                    KeyCode::Esc => {
+                        self.ui.cancel_edit();
+                    }
+                    KeyCode::F(5) => {
+                        let dispatcher = dispatcher.trim().to_string();
+                        let args = args.trim().to_string();
+                        if dispatcher.is_empty() {
+                            self.ui.show_popup = true;
+                            self.ui.popup_message = "Enter an action to test first".to_string();
+                        } else if crate::keybinds::is_destructive_dispatcher(&dispatcher) {
+                            self.ui.show_popup = true;
+                            self.ui.popup_message = format!(
+                                "Run '{dispatcher}' now? This acts on your session immediately - Press 'Y' to run it, any other key to cancel"
+                            );
+                            self.ui.pending_dispatch = Some((dispatcher, args));
+                        } else {
+                            self.test_keybind_dispatch(&dispatcher, &args).await;
+                        }
+                    }
+                    KeyCode::Up | KeyCode::Down
+                        if *editing_field == crate::ui::KeybindField::Dispatcher =>
+                    {
+                        let count = crate::keybinds::dispatcher_completions(dispatcher).len();
+                        if count > 0 {
+                            let current = self.ui.completion_index.min(count - 1);
+                            self.ui.completion_index = if key == KeyCode::Down {
+                                (current + 1) % count
+                            } else {
+                                (current + count - 1) % count
+                            };
+                        }
+                    }
+                    // Tab accepts the highlighted action, or moves on once it is typed out
+                    KeyCode::Tab
+                        if *editing_field == crate::ui::KeybindField::Dispatcher
+                            && crate::keybinds::dispatcher_completions(dispatcher)
+                                .get(self.ui.completion_index)
+                                .is_some_and(|name| name != dispatcher) =>
+                    {
+                        let completions = crate::keybinds::dispatcher_completions(dispatcher);
+                        *dispatcher = completions[self.ui.completion_index].to_string();
+                        self.ui.completion_index = 0;
+                    }
+                    KeyCode::Tab => {
+                        // Cycle through editing fields
+                        *editing_field = match editing_field {
+                            crate::ui::KeybindField::Modifiers => crate::ui::KeybindField::Key,
+                            crate::ui::KeybindField::Key => crate::ui::KeybindField::Dispatcher,
+                            crate::ui::KeybindField::Dispatcher => crate::ui::KeybindField::Args,
+                            crate::ui::KeybindField::Args => crate::ui::KeybindField::Modifiers,
+                        };
+                    }
+                    KeyCode::Char(c) => {
+                        match editing_field {
+                            crate::ui::KeybindField::Key => {
+                                // Keys can be multi-character keysyms such as code:28
+                                key_field.push(c);
+                            }
+                            crate::ui::KeybindField::Dispatcher => {
+                                dispatcher.push(c);
+                                self.ui.completion_index = 0;
+                            }
+                            crate::ui::KeybindField::Args => {
+                                args.push(c);
+                            }
+                            crate::ui::KeybindField::Modifiers => {
+                                // Handle modifier addition
+                                let mod_string = match c {
+                                    's' => "SUPER",
+                                    'a' => "ALT",
+                                    'c' => "CTRL",
+                                    'h' => "SHIFT",
+                                    _ => return Ok(()),
+                                };
+
+                                if !modifiers.contains(&mod_string.to_string()) {
+                                    modifiers.push(mod_string.to_string());
+                                }
+                            }
                         }
                     }
-                    KeyCode::Home => {
-                        *cursor_pos = 0;
+                    KeyCode::Backspace => match editing_field {
+                        crate::ui::KeybindField::Key => {
+                            key_field.pop();
+                        }
+                        crate::ui::KeybindField::Dispatcher => {
+                            dispatcher.pop();
+                            self.ui.completion_index = 0;
+                        }
+                        crate::ui::KeybindField::Args => {
+                            args.pop();
+                        }
+                        crate::ui::KeybindField::Modifiers => {
+                            modifiers.pop();
+                        }
+                    },
+                    _ => {}
+                }
+            }
+            // ---- RULE CONFIGURATION ----
+            EditMode::Rule {
+                rule_type,
+                pattern,
+                action,
+                editing_field,
+            } => {
+                let completions = if *rule_type == crate::ui::RuleType::Window
+                    && *editing_field == crate::ui::RuleField::Pattern
+                {
+                    crate::window_rules::client_completions(&self.ui.window_clients, pattern)
+                } else {
+                    Vec::new()
+                };
+                match key {
+                    KeyCode::Enter if self.ui.edit_error.is_some() => {}
+                    KeyCode::Enter => {
+                        match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
+                            Ok(()) => {
+                                self.ui.show_popup = true;
+                                self.ui.popup_message = "Rule updated successfully!".to_string();
+                            }
+                            Err(_) => {
+                                // Error message already set in apply_edit_with_hyprctl
+                            }
+                        }
                     }
-                    KeyCode::End => {
-                        *cursor_pos = current_value.len();
+                    KeyCode::Esc => {
+                        self.ui.cancel_edit();
+                    }
+                    KeyCode::F(2) if *rule_type == crate::ui::RuleType::Window => {
+                        self.ui.window_grab_deadline = Some(Instant::now() + WINDOW_GRAB_DELAY);
+                    }
+                    KeyCode::Up | KeyCode::Down if !completions.is_empty() => {
+                        let count = completions.len();
+                        let current = self.ui.completion_index.min(count - 1);
+                        self.ui.completion_index = if key == KeyCode::Down {
+                            (current + 1) % count
+                        } else {
+                            (current + count - 1) % count
+                        };
+                    }
+                    // Tab takes the highlighted window's pattern, already escaped
+                    KeyCode::Tab
+                        if completions
+                            .get(self.ui.completion_index)
+                            .is_some_and(|completion| completion != pattern) =>
+                    {
+                        *pattern = completions[self.ui.completion_index].clone();
+                        self.ui.completion_index = 0;
+                    }
+                    KeyCode::Tab => {
+                        // Cycle through the pattern, the action and the
+                        // values of an opacity, size or move action
+                        *editing_field = editing_field.next(action);
                     }
+                    KeyCode::Char(c) => match editing_field {
+                        crate::ui::RuleField::Pattern => {
+                            pattern.push(c);
+                            self.ui.completion_index = 0;
+                        }
+                        crate::ui::RuleField::Action => {
+                            action.push(c);
+                        }
+                        crate::ui::RuleField::Opacity(field) => {
+                            let field = *field;
+                            crate::opacity::edit_action(action, |opacity| {
+                                opacity.type_char(field, c)
+                            });
+                        }
+                        crate::ui::RuleField::Placement(field) => {
+                            let field = *field;
+                            crate::placement::edit_action(action, |rule| rule.type_char(field, c));
+                        }
+                    },
+                    KeyCode::Backspace => match editing_field {
+                        crate::ui::RuleField::Pattern => {
+                            pattern.pop();
+                            self.ui.completion_index = 0;
+                        }
+                        crate::ui::RuleField::Action => {
+                            action.pop();
+                        }
+                        crate::ui::RuleField::Opacity(field) => {
+                            let field = *field;
+                            crate::opacity::edit_action(action, |opacity| opacity.backspace(field));
+                        }
+                        crate::ui::RuleField::Placement(field) => {
+                            let field = *field;
+                            crate::placement::edit_action(action, |rule| rule.backspace(field));
+                        }
+                    },
                     _ => {}
                 }
             }
-            // ---- BOOLEAN TOGGLE EDITING ----
-            EditMode::Boolean { current_value } => {
+            // ---- WORKSPACE RULE EDITING ----
+            EditMode::Workspace {
+                rule,
+                editing_field,
+            } => {
                 match key {
+                    KeyCode::Enter if self.ui.edit_error.is_some() => {}
                     KeyCode::Enter => {
                         match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
                             Ok(()) => {
                                 self.ui.show_popup = true;
-                                self.ui.popup_message = "Value updated successfully!".to_string();
+                                self.ui.popup_message =
+                                    "Workspace rule updated successfully!".to_string();
                             }
                             Err(_) => {
                                 // Error message already set in apply_edit_with_hyprctl
@@ -520,25 +2079,38 @@ impl App {
                     KeyCode::Esc => {
                         self.ui.cancel_edit();
                     }
-                    KeyCode::Char(' ') => {
-                        *current_value = !*current_value;
-
-                        if preview_enabled {
-                            should_trigger_preview = true;
-                            preview_value = current_value.to_string();
+                    KeyCode::Tab | KeyCode::Down => {
+                        *editing_field = editing_field.next();
+                    }
+                    KeyCode::BackTab | KeyCode::Up => {
+                        *editing_field = editing_field.prev();
+                    }
+                    KeyCode::Char(' ') if editing_field.is_toggle() => {
+                        rule.toggle(*editing_field);
+                    }
+                    KeyCode::Char(c) => {
+                        if let Some(text) = rule.text_field_mut(*editing_field) {
+                            text.push(c);
+                        }
+                    }
+                    KeyCode::Backspace => {
+                        if let Some(text) = rule.text_field_mut(*editing_field) {
+                            text.pop();
                         }
                     }
                     _ => {}
                 }
             }
-            // ---- SELECTION FROM OPTIONS ----
-            EditMode::Select { options, selected } => {
+            // ---- ENVIRONMENT VARIABLE EDITING ----
+            EditMode::Environment { var, editing_field } => {
                 match key {
+                    KeyCode::Enter if self.ui.edit_error.is_some() => {}
                     KeyCode::Enter => {
                         match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
                             Ok(()) => {
                                 self.ui.show_popup = true;
-                                self.ui.popup_message = "Value updated successfully!".to_string();
+                                self.ui.popup_message =
+                                    "Environment variable updated successfully!".to_string();
                             }
                             Err(_) => {
                                 // Error message already set in apply_edit_with_hyprctl
@@ -548,46 +2120,99 @@ impl App {
                     KeyCode::Esc => {
                         self.ui.cancel_edit();
                     }
-                    KeyCode::Up => {
-                        if *selected > 0 {
-                            *selected -= 1;
-                        } else {
-                            *selected = options.len() - 1;
+                    KeyCode::Tab | KeyCode::Down => {
+                        *editing_field = editing_field.next();
+                    }
+                    KeyCode::BackTab | KeyCode::Up => {
+                        *editing_field = editing_field.prev();
+                    }
+                    KeyCode::Char(' ') if *editing_field == EnvField::Kind => {
+                        var.kind = var.kind.toggle();
+                    }
+                    KeyCode::Char(c) => {
+                        if let Some(text) = var.text_field_mut(*editing_field) {
+                            text.push(c);
                         }
-
-                        if preview_enabled {
-                            should_trigger_preview = true;
-                            preview_value = options[*selected].clone();
+                    }
+                    KeyCode::Backspace => {
+                        if let Some(text) = var.text_field_mut(*editing_field) {
+                            text.pop();
                         }
                     }
-                    KeyCode::Down => {
-                        if *selected < options.len() - 1 {
-                            *selected += 1;
+                    _ => {}
+                }
+            }
+            // ---- KEYBOARD LAYOUT LIST ----
+            EditMode::Layouts { list } => {
+                match key {
+                    KeyCode::Enter if self.ui.edit_error.is_some() => {}
+                    KeyCode::Enter => {
+                        match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
+                            Ok(()) => {
+                                self.ui.show_popup = true;
+                                self.ui.popup_message =
+                                    "Keyboard layouts updated successfully!".to_string();
+                            }
+                            Err(_) => {
+                                // Error message already set in apply_edit_with_hyprctl
+                            }
+                        }
+                    }
+                    KeyCode::Esc => {
+                        self.ui.cancel_edit();
+                    }
+                    KeyCode::Up | KeyCode::Down if !text_completions.is_empty() => {
+                        let count = text_completions.len();
+                        let current = self.ui.completion_index.min(count - 1);
+                        self.ui.completion_index = if key == KeyCode::Down {
+                            (current + 1) % count
                         } else {
-                            *selected = 0;
+                            (current + count - 1) % count
+                        };
+                    }
+                    KeyCode::Up => {
+                        list.selected = list.selected.saturating_sub(1);
+                    }
+                    KeyCode::Down => {
+                        if list.selected + 1 < list.layouts.len() {
+                            list.selected += 1;
                         }
-
-                        if preview_enabled {
-                            should_trigger_preview = true;
-                            preview_value = options[*selected].clone();
+                    }
+                    KeyCode::Left => list.move_selected(false),
+                    KeyCode::Right => list.move_selected(true),
+                    KeyCode::Tab => {
+                        let layout = text_completions
+                            .get(self.ui.completion_index)
+                            .cloned()
+                            .unwrap_or_else(|| list.adding.clone());
+                        list.add(&layout);
+                        list.adding.clear();
+                        self.ui.completion_index = 0;
+                    }
+                    KeyCode::Delete => list.remove_selected(),
+                    KeyCode::Backspace => {
+                        if list.adding.pop().is_none() {
+                            list.remove_selected();
                         }
+                        self.ui.completion_index = 0;
+                    }
+                    // Layouts are added one at a time, so no separators
+                    KeyCode::Char(c) if c != ',' && !c.is_whitespace() => {
+                        list.adding.push(c);
+                        self.ui.completion_index = 0;
                     }
                     _ => {}
                 }
             }
-            // ---- SLIDER/NUMERIC INPUT ----
-            EditMode::Slider {
-                current_value,
-                min,
-                max,
-                step,
-            } => {
+            // ---- KEYBOARD OPTION CHECKBOXES ----
+            EditMode::KbOptions { flags } => {
                 match key {
                     KeyCode::Enter => {
                         match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
                             Ok(()) => {
                                 self.ui.show_popup = true;
-                                self.ui.popup_message = "Value updated successfully!".to_string();
+                                self.ui.popup_message =
+                                    "Keyboard options updated successfully!".to_string();
                             }
                             Err(_) => {
                                 // Error message already set in apply_edit_with_hyprctl
@@ -597,71 +2222,95 @@ impl App {
                     KeyCode::Esc => {
                         self.ui.cancel_edit();
                     }
-                    KeyCode::Left => {
-                        *current_value = (*current_value - *step).max(*min);
-
-                        if preview_enabled {
-                            should_trigger_preview = true;
-                            preview_value = if current_value.fract() == 0.0 {
-                                (*current_value as i32).to_string()
-                            } else {
-                                format!("{current_value:.2}")
-                            };
+                    KeyCode::Up => {
+                        flags.selected = flags.selected.saturating_sub(1);
+                    }
+                    KeyCode::Down => {
+                        if flags.selected + 1 < flags.visible().len() {
+                            flags.selected += 1;
                         }
                     }
-                    KeyCode::Right => {
-                        *current_value = (*current_value + *step).min(*max);
-
-                        if preview_enabled {
-                            should_trigger_preview = true;
-                            preview_value = if current_value.fract() == 0.0 {
-                                (*current_value as i32).to_string()
-                            } else {
-                                format!("{current_value:.2}")
-                            };
+                    KeyCode::Char(' ') => flags.toggle_selected(),
+                    KeyCode::Char(c) => {
+                        flags.filter.push(c);
+                        flags.selected = 0;
+                    }
+                    KeyCode::Backspace => {
+                        flags.filter.pop();
+                        flags.selected = 0;
+                    }
+                    _ => {}
+                }
+            }
+            // ---- ANIMATION LINE ----
+            EditMode::Animation {
+                animation,
+                editing_field,
+            } => {
+                match key {
+                    KeyCode::Enter if self.ui.edit_error.is_some() => {}
+                    KeyCode::Enter => {
+                        match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
+                            Ok(()) => {
+                                self.ui.show_popup = true;
+                                self.ui.popup_message =
+                                    "Animation updated successfully!".to_string();
+                            }
+                            Err(_) => {
+                                // Error message already set in apply_edit_with_hyprctl
+                            }
                         }
                     }
-                    KeyCode::Home => {
-                        *current_value = *min;
-
-                        if preview_enabled {
-                            should_trigger_preview = true;
-                            preview_value = if current_value.fract() == 0.0 {
-                                (*current_value as i32).to_string()
-                            } else {
-                                format!("{current_value:.2}")
-                            };
+                    KeyCode::Esc => {
+                        self.ui.cancel_edit();
+                    }
+                    KeyCode::Tab | KeyCode::Down => {
+                        *editing_field = editing_field.next();
+                    }
+                    KeyCode::BackTab | KeyCode::Up => {
+                        *editing_field = editing_field.prev();
+                    }
+                    KeyCode::Left | KeyCode::Right => {
+                        let forward = key == KeyCode::Right;
+                        match editing_field {
+                            AnimationField::Name => animation.cycle_name(forward),
+                            AnimationField::Curve => {
+                                animation.cycle_curve(&animation_curves, forward)
+                            }
+                            AnimationField::Enabled => animation.enabled = !animation.enabled,
+                            _ => {}
                         }
                     }
-                    KeyCode::End => {
-                        *current_value = *max;
-
-                        if preview_enabled {
-                            should_trigger_preview = true;
-                            preview_value = if current_value.fract() == 0.0 {
-                                (*current_value as i32).to_string()
-                            } else {
-                                format!("{current_value:.2}")
-                            };
+                    KeyCode::Char(' ') if *editing_field == AnimationField::Enabled => {
+                        animation.enabled = !animation.enabled;
+                    }
+                    KeyCode::Char(c) if *editing_field == AnimationField::Speed => {
+                        if let Some(digit) = c.to_digit(10) {
+                            animation.push_speed_digit(digit);
                         }
                     }
+                    KeyCode::Char(c) if *editing_field == AnimationField::Style => {
+                        animation.style.push(c);
+                    }
+                    KeyCode::Backspace => match editing_field {
+                        AnimationField::Speed => animation.speed /= 10,
+                        AnimationField::Style => {
+                            animation.style.pop();
+                        }
+                        _ => {}
+                    },
                     _ => {}
                 }
             }
-            // ---- KEYBIND CONFIGURATION ----
-            EditMode::Keybind {
-                modifiers,
-                key: key_field,
-                dispatcher,
-                args,
-                editing_field,
-            } => {
+            // ---- BEZIER CURVE ----
+            EditMode::Bezier { curve, point } => {
+                use crate::bezier::NUDGE_STEP;
                 match key {
                     KeyCode::Enter => {
                         match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
                             Ok(()) => {
                                 self.ui.show_popup = true;
-                                self.ui.popup_message = "Keybind updated successfully!".to_string();
+                                self.ui.popup_message = "Curve updated successfully!".to_string();
                             }
                             Err(_) => {
                                 // Error message already set in apply_edit_with_hyprctl
@@ -671,72 +2320,26 @@ impl App {
                     KeyCode::Esc => {
                         self.ui.cancel_edit();
                     }
-                    KeyCode::Tab => {
-                        // Cycle through editing fields
-                        *editing_field = match editing_field {
-                            crate::ui::KeybindField::Modifiers => crate::ui::KeybindField::Key,
-                            crate::ui::KeybindField::Key => crate::ui::KeybindField::Dispatcher,
-                            crate::ui::KeybindField::Dispatcher => crate::ui::KeybindField::Args,
-                            crate::ui::KeybindField::Args => crate::ui::KeybindField::Modifiers,
-                        };
-                    }
-                    KeyCode::Char(c) => {
-                        match editing_field {
-                            crate::ui::KeybindField::Key => {
-                                *key_field = c.to_string();
-                            }
-                            crate::ui::KeybindField::Dispatcher => {
-                                dispatcher.push(c);
-                            }
-                            crate::ui::KeybindField::Args => {
-                                args.push(c);
-                            }
-                            crate::ui::KeybindField::Modifiers => {
-                                // Handle modifier addition
-                                let mod_string = match c {
-                                    's' => "SUPER",
-                                    'a' => "ALT",
-                                    'c' => "CTRL",
-                                    'h' => "SHIFT",
-                                    _ => return Ok(()),
-                                };
-
-                                if !modifiers.contains(&mod_string.to_string()) {
-                                    modifiers.push(mod_string.to_string());
-                                }
-                            }
-                        }
-                    }
-                    KeyCode::Backspace => match editing_field {
-                        crate::ui::KeybindField::Key => {
-                            key_field.clear();
-                        }
-                        crate::ui::KeybindField::Dispatcher => {
-                            dispatcher.pop();
-                        }
-                        crate::ui::KeybindField::Args => {
-                            args.pop();
-                        }
-                        crate::ui::KeybindField::Modifiers => {
-                            modifiers.pop();
-                        }
-                    },
+                    KeyCode::Tab | KeyCode::BackTab => *point = 1 - *point,
+                    KeyCode::Left => curve.nudge(*point, -NUDGE_STEP, 0.0),
+                    KeyCode::Right => curve.nudge(*point, NUDGE_STEP, 0.0),
+                    KeyCode::Up => curve.nudge(*point, 0.0, NUDGE_STEP),
+                    KeyCode::Down => curve.nudge(*point, 0.0, -NUDGE_STEP),
                     _ => {}
                 }
             }
-            // ---- RULE CONFIGURATION ----
-            EditMode::Rule {
-                rule_type: _,
-                pattern,
-                action,
-                editing_field,
+            // ---- COLOR / GRADIENT PICKER ----
+            EditMode::Color {
+                gradient,
+                stop,
+                channel,
             } => {
                 match key {
                     KeyCode::Enter => {
                         match self.ui.apply_edit_with_hyprctl(&self.hyprctl).await {
                             Ok(()) => {
                                 self.ui.show_popup = true;
-                                self.ui.popup_message = "Rule updated successfully!".to_string();
+                                self.ui.popup_message = "Color updated successfully!".to_string();
                             }
                             Err(_) => {
                                 // Error message already set in apply_edit_with_hyprctl
@@ -744,32 +2347,84 @@ impl App {
                         }
                     }
                     KeyCode::Esc => {
+                        if self.ui.is_preview_mode() {
+                            if let Err(e) = self.ui.cancel_preview(&self.hyprctl).await {
+                                crate::stderr_log::warn(format!("Error canceling preview: {}", e));
+                            }
+                        }
                         self.ui.cancel_edit();
                     }
+                    KeyCode::Up => {
+                        *channel = channel.prev();
+                    }
+                    KeyCode::Down => {
+                        *channel = channel.next();
+                    }
                     KeyCode::Tab => {
-                        // Cycle between pattern and action editing
-                        *editing_field = match editing_field {
-                            crate::ui::RuleField::Pattern => crate::ui::RuleField::Action,
-                            crate::ui::RuleField::Action => crate::ui::RuleField::Pattern,
-                        };
+                        *stop = (*stop + 1) % gradient.stops.len();
                     }
-                    KeyCode::Char(c) => match editing_field {
-                        crate::ui::RuleField::Pattern => {
-                            pattern.push(c);
-                        }
-                        crate::ui::RuleField::Action => {
-                            action.push(c);
-                        }
-                    },
-                    KeyCode::Backspace => match editing_field {
-                        crate::ui::RuleField::Pattern => {
-                            pattern.pop();
-                        }
-                        crate::ui::RuleField::Action => {
-                            action.pop();
+                    KeyCode::BackTab => {
+                        *stop = (*stop + gradient.stops.len() - 1) % gradient.stops.len();
+                    }
+                    _ => {
+                        let current = &mut gradient.stops[*stop];
+                        let changed = match key {
+                            KeyCode::Left => {
+                                current.color.adjust_channel(*channel, -1);
+                                true
+                            }
+                            KeyCode::Right => {
+                                current.color.adjust_channel(*channel, 1);
+                                true
+                            }
+                            KeyCode::PageDown => {
+                                current.color.adjust_channel(*channel, -16);
+                                true
+                            }
+                            KeyCode::PageUp => {
+                                current.color.adjust_channel(*channel, 16);
+                                true
+                            }
+                            KeyCode::Home => {
+                                current.color.set_channel(*channel, 0);
+                                true
+                            }
+                            KeyCode::End => {
+                                current.color.set_channel(*channel, 255);
+                                true
+                            }
+                            KeyCode::Char('f') => {
+                                current.format = current.format.next();
+                                true
+                            }
+                            KeyCode::Char('+') => match gradient.duplicate_stop(*stop) {
+                                Some(new_stop) => {
+                                    *stop = new_stop;
+                                    true
+                                }
+                                None => false,
+                            },
+                            KeyCode::Char('-') => {
+                                let removed = gradient.remove_stop(*stop);
+                                *stop = (*stop).min(gradient.stops.len() - 1);
+                                removed
+                            }
+                            KeyCode::Char('[') => {
+                                gradient.rotate(-5);
+                                true
+                            }
+                            KeyCode::Char(']') => {
+                                gradient.rotate(5);
+                                true
+                            }
+                            _ => false,
+                        };
+
+                        if changed && preview_enabled {
+                            should_trigger_preview = true;
+                            preview_value = gradient.to_string();
                         }
-                    },
-                    _ => {}
+                    }
                 }
             }
             // TODO: Re-implement AddingItem edit mode
@@ -779,15 +2434,18 @@ impl App {
             }
         }
 
+        // Re-check the typed value so the popup can flag it as you type
+        self.ui.edit_error = self.ui.validate_edit().err();
+
         // Handle preview after the match to avoid borrowing issues
-        if should_trigger_preview {
+        if should_trigger_preview && self.ui.edit_error.is_none() {
             if let Some(key) = editing_key {
                 if let Err(e) = self
                     .ui
                     .handle_preview_change(&key, &preview_value, &self.hyprctl)
                     .await
                 {
-                    eprintln!("Preview error: {}", e);
+                    crate::stderr_log::warn(format!("Preview error: {}", e));
                 }
             }
         }
@@ -796,7 +2454,23 @@ impl App {
     }
 
     pub async fn handle_help_key(&mut self, key: KeyCode) -> Result<()> {
+        if self.ui.help_search_active {
+            match key {
+                KeyCode::Esc => self.ui.finish_help_search(true),
+                KeyCode::Enter => self.ui.finish_help_search(false),
+                KeyCode::Backspace => self.ui.help_search_backspace(),
+                KeyCode::Char(c) => self.ui.help_search_input(c),
+                KeyCode::Up => self.ui.scroll_help_up(),
+                KeyCode::Down => self.ui.scroll_help_down(),
+                _ => {}
+            }
+            return Ok(());
+        }
+
         match key {
+            KeyCode::Char('/') => {
+                self.ui.start_help_search();
+            }
             KeyCode::Esc | KeyCode::F(1) | KeyCode::Char('?') => {
                 self.ui.toggle_help();
             }
@@ -861,10 +2535,209 @@ impl App {
             // This will be handled automatically in the next render cycle
         }
 
+        self.poll_github_download().await;
+        self.poll_url_download().await;
+        self.poll_gist_upload().await;
+        self.poll_window_grab().await;
+        self.poll_preview_revert().await;
+        self.poll_config_changes().await;
+
         // Process pending preview changes
         if self.ui.has_pending_preview() {
             if let Err(e) = self.ui.apply_pending_preview(&self.hyprctl).await {
-                eprintln!("Preview application error: {}", e);
+                crate::stderr_log::warn(format!("Preview application error: {}", e));
+            }
+        }
+    }
+
+    /// Refresh the panels when Hyprland reloads its config, keeping unsaved
+    /// edits. Waits while a dialog is open, which may be editing an item.
+    async fn poll_config_changes(&mut self) {
+        if let Some(changes) = &mut self.config_changes {
+            loop {
+                match changes.try_recv() {
+                    Ok(()) => self.config_change_pending = true,
+                    Err(tokio::sync::mpsc::error::TryRecvError::Empty) => break,
+                    Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => {
+                        self.config_changes = None;
+                        break;
+                    }
+                }
+            }
+        }
+        if !self.config_change_pending
+            || CommandContext::from_app(self, KeyCode::Null).has_modal_open()
+        {
+            return;
+        }
+        self.config_change_pending = false;
+
+        // Cached values are from before the reload
+        self.hyprctl.clear_cache();
+        match self.ui.refresh_keeping_edits(&self.hyprctl).await {
+            // Our own saves trigger a reload too, but change nothing
+            Ok(true) => self.ui.show_toast("Config reloaded externally"),
+            Ok(false) => {}
+            Err(e) => {
+                crate::stderr_log::warn(format!("Failed to refresh after Hyprland reloaded: {e}"))
+            }
+        }
+    }
+
+    /// Switch to the next theme and remember it in config.toml
+    pub async fn cycle_theme(&mut self) {
+        self.config.theme = self.ui.next_theme();
+        if let Err(e) = self.config.save().await {
+            self.ui.show_popup = true;
+            self.ui.popup_message = format!(
+                "Switched to {}, but couldn't save it: {e}",
+                self.config.theme
+            );
+        }
+    }
+
+    /// Make a theme from Hyprland's border, shadow and background colors,
+    /// save it to the themes folder so T can come back to it, and switch to it
+    pub async fn theme_from_hyprland(&mut self) {
+        use crate::theme::{ColorScheme, Theme, HYPRLAND_THEME_COLORS, HYPRLAND_THEME_NAME};
+
+        let mut values = std::collections::HashMap::new();
+        for (option, _) in HYPRLAND_THEME_COLORS {
+            if let Ok(value) = self.hyprctl.get_option(option).await {
+                values.insert(option.to_string(), value);
+            }
+        }
+        let colors = crate::theme::hyprland_theme_colors(&values);
+        if colors.is_empty() {
+            self.ui.show_popup = true;
+            self.ui.popup_message =
+                "Couldn't read any colors from Hyprland. Is it running?".to_string();
+            return;
+        }
+
+        let content = crate::theme::custom_theme_file(HYPRLAND_THEME_NAME, &colors);
+        let theme = match Theme::parse_custom(HYPRLAND_THEME_NAME, &content) {
+            Ok((theme, _)) => theme,
+            Err(e) => {
+                self.ui.show_popup = true;
+                self.ui.popup_message = format!("Couldn't make a theme from Hyprland: {e}");
+                return;
+            }
+        };
+        self.ui
+            .custom_themes
+            .insert(HYPRLAND_THEME_NAME.to_string(), theme);
+        let scheme = ColorScheme::Custom(HYPRLAND_THEME_NAME.to_string());
+        self.ui.set_theme(scheme.clone());
+        self.config.theme = scheme;
+
+        let saved = async {
+            let dir = Config::themes_dir()?;
+            std::fs::create_dir_all(&dir)?;
+            let path = dir.join("hyprland.toml");
+            std::fs::write(&path, &content)?;
+            self.config.save().await?;
+            anyhow::Ok(path)
+        }
+        .await;
+        self.ui.show_popup = true;
+        self.ui.popup_message = match saved {
+            Ok(path) => format!(
+                "Switched to a theme made from {} of Hyprland's colors, saved as {}",
+                values.len(),
+                path.display()
+            ),
+            Err(e) => format!(
+                "Switched to a theme made from Hyprland's colors, but couldn't save it: {e}"
+            ),
+        };
+    }
+
+    /// Revert a live preview that wasn't kept with Enter before its countdown
+    /// ran out
+    async fn poll_preview_revert(&mut self) {
+        let Some(deadline) = self.ui.preview_revert_deadline else {
+            return;
+        };
+        if Instant::now() < deadline {
+            return;
+        }
+
+        let key = self.ui.preview_key.clone().unwrap_or_default();
+        let original = self.ui.preview_original_value.clone().unwrap_or_default();
+        if let Err(e) = self.ui.cancel_preview(&self.hyprctl).await {
+            crate::stderr_log::warn(format!("Error reverting preview: {}", e));
+        }
+        // cancel_preview reports a failed restore itself
+        if !self.ui.show_popup {
+            self.ui.show_popup = true;
+            self.ui.popup_message = format!(
+                "{key} was reverted to {original} because the preview wasn't kept within {} seconds",
+                crate::ui::PREVIEW_CONFIRM_TIMEOUT.as_secs()
+            );
+        }
+    }
+
+    /// Turn live preview on or off. Turning it off reverts a preview that
+    /// hasn't been kept.
+    pub async fn toggle_live_preview(&mut self) {
+        if self.hyprctl.is_file_only() {
+            self.ui.show_popup = true;
+            self.ui.popup_message =
+                "Live preview is off in file-only mode, changes only go to the config file"
+                    .to_string();
+            return;
+        }
+        if self.ui.is_preview_mode() {
+            if let Err(e) = self.ui.cancel_preview(&self.hyprctl).await {
+                crate::stderr_log::warn(format!("Error canceling preview: {}", e));
+            }
+        }
+        self.ui.toggle_preview_mode();
+
+        // The first time, show which edits it will apply right away
+        if self.ui.is_preview_mode() && !self.ui.preview_summary_shown {
+            self.ui.preview_summary_shown = true;
+            self.ui.show_popup = true;
+            self.ui.popup_message = self.ui.live_preview_summary_message();
+        }
+    }
+
+    /// Once the grab countdown is over, fill the window rule being edited with
+    /// the class and title of whatever window has focus
+    async fn poll_window_grab(&mut self) {
+        let Some(deadline) = self.ui.window_grab_deadline else {
+            return;
+        };
+        if Instant::now() < deadline {
+            return;
+        }
+        self.ui.window_grab_deadline = None;
+
+        let active = self.hyprctl.get_active_window().await;
+        let crate::ui::EditMode::Rule {
+            rule_type: crate::ui::RuleType::Window,
+            pattern,
+            ..
+        } = &mut self.ui.edit_mode
+        else {
+            // The editor was closed during the countdown
+            return;
+        };
+        match active {
+            Ok(Some(window)) => {
+                *pattern = crate::window_rules::window_pattern(&window);
+                self.ui.edit_error = self.ui.validate_edit().err();
+            }
+            Ok(None) => {
+                self.ui.show_popup = true;
+                self.ui.popup_message =
+                    "No window had focus when the countdown ended. Press F2 and focus a window to grab it."
+                        .to_string();
+            }
+            Err(e) => {
+                self.ui.show_popup = true;
+                self.ui.popup_message = format!("Could not grab the window: {e}");
             }
         }
     }
@@ -873,22 +2746,39 @@ impl App {
         // Reload the application's own config
         self.config = Config::load().await?;
 
+        // Hyprland may have been started or stopped since the last look
+        self.ui.hyprland_status = self.hyprctl.refresh_status().await;
+
         // If Hyprland is running, try to reload its configuration first
-        if self.hyprctl.is_hyprland_running().await {
+        if self.ui.hyprland_status.is_connected() {
             match self.hyprctl.reload_config().await {
                 Ok(()) => {
-                    eprintln!("Hyprland configuration reloaded from file");
+                    crate::stderr_log::note("Hyprland configuration reloaded from file");
                 }
                 Err(e) => {
-                    eprintln!("Warning: Failed to reload Hyprland configuration: {e}");
+                    crate::stderr_log::warn(format!(
+                        "Failed to reload Hyprland configuration: {e}"
+                    ));
                 }
             }
         }
 
-        // Reload current configuration into UI (from hyprctl or config file)
+        // Reload current configuration into UI (from hyprctl or config file),
+        // keeping each panel's selection on the same item
+        let selection = self.ui.selected_keys();
         if let Err(e) = self.ui.load_current_config(&self.hyprctl).await {
-            eprintln!("Warning: Failed to reload UI configuration: {e}");
+            crate::stderr_log::warn(format!("Failed to reload UI configuration: {e}"));
         }
+        self.ui.refresh_all_panels(&selection);
+
+        // Recorded changes and marks no longer match the reloaded items
+        self.ui.change_history.clear();
+        self.ui.marked_items.clear();
+        self.ui.change_history.set_max_depth(self.config.undo_depth);
+        self.ui.search_fuzzy_threshold = self.config.search_threshold;
+        self.ui.keybind_mode = self.config.keybind_mode;
+        self.ui.reload_after_save = self.config.reload_after_save;
+        self.ui.clear_search_cache();
 
         Ok(())
     }
@@ -897,19 +2787,22 @@ impl App {
     // CONFIGURATION MANAGEMENT
     // ================================
 
-    /// Save current configuration changes to disk
+    /// Save current configuration changes to disk. Hyprland is reloaded
+    /// afterwards by `save_and_reload`, if at all.
     async fn save_config(&mut self) -> Result<()> {
-        self.config.save().await?;
-
         // Collect all configuration changes from the UI
-        let config_changes = self.ui.collect_all_config_changes();
-        let keybinds = self.ui.collect_keybinds();
-        let window_rules = self.ui.collect_window_rules();
-        let layer_rules = self.ui.collect_layer_rules();
+        let config_changes = self.ui.collect_config_changes_to_save();
+        let mut lines = self.ui.collect_config_lines();
+        self.config.route_new_lines(&mut lines).await?;
 
         // Validate configuration changes before saving
         if let Err(validation_error) = self
-            .validate_config_changes(&config_changes, &keybinds, &window_rules, &layer_rules)
+            .validate_config_changes(
+                &config_changes,
+                &self.ui.collect_keybinds(),
+                &self.ui.collect_window_rules(),
+                &self.ui.collect_layer_rules(),
+            )
             .await
         {
             self.ui.show_popup = true;
@@ -918,46 +2811,44 @@ impl App {
         }
 
         // Check if we have any changes to save
-        let has_changes = !config_changes.is_empty()
-            || !keybinds.is_empty()
-            || !window_rules.is_empty()
-            || !layer_rules.is_empty();
+        let has_changes = !config_changes.is_empty() || !lines.is_empty();
 
         if has_changes {
             // Save changes to the actual Hyprland config file
             self.config
                 .save_hyprland_config_with_rules(
                     &config_changes,
-                    &keybinds,
-                    &window_rules,
-                    &layer_rules,
+                    &lines.keybinds,
+                    &lines.window_rules,
+                    &lines.layer_rules,
+                    &lines.workspace_rules,
+                    &lines.env_vars,
+                    &lines.plugins,
+                    &lines.autostart,
+                    &lines.variables,
                 )
                 .await?;
-
-            eprintln!(
-                "Saved {} config options, {} keybinds, {} window rules, {} layer rules",
+            // Items from sourced files are updated in place in those files
+            self.config.save_included_files(&lines.included).await?;
+            self.ui.mark_options_saved(config_changes.keys());
+            self.ui.record_saved_values();
+            self.ui.change_history.empty_trash();
+
+            crate::stderr_log::note(format!(
+                "Saved {} config options, {} keybinds, {} window rules, {} layer rules, {} workspace rules, {} environment variables, {} plugins, {} autostart commands, {} variables, {} sourced files",
                 config_changes.len(),
-                keybinds.len(),
-                window_rules.len(),
-                layer_rules.len()
-            );
-
-            // If Hyprland is running, try to reload the configuration
-            if self.hyprctl.is_hyprland_running().await {
-                match self.hyprctl.reload_config().await {
-                    Ok(()) => {
-                        eprintln!("Hyprland configuration reloaded successfully");
-                    }
-                    Err(e) => {
-                        eprintln!("Warning: Failed to reload Hyprland configuration: {e}");
-                        eprintln!("Changes saved to config file but may require manual restart");
-                    }
-                }
-            } else {
-                eprintln!("Hyprland not running - changes saved to config file");
-            }
+                lines.keybinds.len(),
+                lines.window_rules.len(),
+                lines.layer_rules.len(),
+                lines.workspace_rules.len(),
+                lines.env_vars.len(),
+                lines.plugins.len(),
+                lines.autostart.len(),
+                lines.variables.len(),
+                lines.included.len()
+            ));
         } else {
-            eprintln!("No configuration changes to save");
+            crate::stderr_log::note("No configuration changes to save");
         }
 
         Ok(())
@@ -983,6 +2874,11 @@ impl App {
         let keybinds = self.ui.collect_keybinds();
         let window_rules = self.ui.collect_window_rules();
         let layer_rules = self.ui.collect_layer_rules();
+        let workspace_rules = self.ui.collect_workspace_rules();
+        let env_vars = self.ui.collect_env_vars();
+        let plugins = self.ui.collect_plugins();
+        let autostart = self.ui.collect_autostart();
+        let variables = self.ui.collect_variables();
 
         // Create export data structure
         let export_data = toml::Table::from_iter([
@@ -1024,6 +2920,31 @@ impl App {
                 "layer_rules".to_string(),
                 toml::Value::Array(layer_rules.into_iter().map(toml::Value::String).collect()),
             ),
+            (
+                "workspace_rules".to_string(),
+                toml::Value::Array(
+                    workspace_rules
+                        .into_iter()
+                        .map(toml::Value::String)
+                        .collect(),
+                ),
+            ),
+            (
+                "environment".to_string(),
+                toml::Value::Array(env_vars.into_iter().map(toml::Value::String).collect()),
+            ),
+            (
+                "plugins".to_string(),
+                toml::Value::Array(plugins.into_iter().map(toml::Value::String).collect()),
+            ),
+            (
+                "autostart".to_string(),
+                toml::Value::Array(autostart.into_iter().map(toml::Value::String).collect()),
+            ),
+            (
+                "variables".to_string(),
+                toml::Value::Array(variables.into_iter().map(toml::Value::String).collect()),
+            ),
         ]);
 
         // Write to file
@@ -1081,6 +3002,7 @@ impl App {
         let export_data: toml::Table = toml::from_str(&content)?;
 
         let mut imported_count = 0;
+        let selection = self.ui.selected_keys();
 
         // Import theme if present
         if let Some(metadata) = export_data.get("metadata").and_then(|v| v.as_table()) {
@@ -1114,29 +3036,119 @@ impl App {
             }
         }
 
-        // Import window rules
-        if let Some(rules) = export_data.get("window_rules").and_then(|v| v.as_array()) {
-            for rule in rules {
-                if let Some(rule_str) = rule.as_str() {
-                    self.ui.add_imported_window_rule(rule_str);
+        // Import window rules
+        if let Some(rules) = export_data.get("window_rules").and_then(|v| v.as_array()) {
+            for rule in rules {
+                if let Some(rule_str) = rule.as_str() {
+                    self.ui.add_imported_window_rule(rule_str);
+                    imported_count += 1;
+                }
+            }
+        }
+
+        // Import layer rules
+        if let Some(rules) = export_data.get("layer_rules").and_then(|v| v.as_array()) {
+            for rule in rules {
+                if let Some(rule_str) = rule.as_str() {
+                    self.ui.add_imported_layer_rule(rule_str);
+                    imported_count += 1;
+                }
+            }
+        }
+
+        // Import workspace rules
+        if let Some(rules) = export_data
+            .get("workspace_rules")
+            .and_then(|v| v.as_array())
+        {
+            for rule in rules {
+                if let Some(rule_str) = rule.as_str() {
+                    self.ui.add_imported_workspace_rule(rule_str);
+                    imported_count += 1;
+                }
+            }
+        }
+
+        // Import environment variables
+        if let Some(vars) = export_data.get("environment").and_then(|v| v.as_array()) {
+            for var in vars {
+                if let Some(line) = var.as_str() {
+                    self.ui.add_imported_env_var(line);
+                    imported_count += 1;
+                }
+            }
+        }
+
+        // Import plugins
+        if let Some(plugins) = export_data.get("plugins").and_then(|v| v.as_array()) {
+            for plugin in plugins {
+                if let Some(line) = plugin.as_str() {
+                    self.ui.add_imported_plugin(line);
+                    imported_count += 1;
+                }
+            }
+        }
+
+        // Import autostart commands
+        if let Some(commands) = export_data.get("autostart").and_then(|v| v.as_array()) {
+            for command in commands {
+                if let Some(line) = command.as_str() {
+                    self.ui.add_imported_exec(line);
                     imported_count += 1;
                 }
             }
         }
 
-        // Import layer rules
-        if let Some(rules) = export_data.get("layer_rules").and_then(|v| v.as_array()) {
-            for rule in rules {
-                if let Some(rule_str) = rule.as_str() {
-                    self.ui.add_imported_layer_rule(rule_str);
+        // Import variables
+        if let Some(variables) = export_data.get("variables").and_then(|v| v.as_array()) {
+            for variable in variables {
+                if let Some(line) = variable.as_str() {
+                    self.ui.add_imported_variable(line);
                     imported_count += 1;
                 }
             }
         }
 
         // Refresh the UI to show imported data
-        self.ui.refresh_all_panels();
+        self.ui.refresh_all_panels(&selection);
+
+        Ok(imported_count)
+    }
+
+    /// Read the Hyprland settings from the NixOS config file, the one set in
+    /// the app config or else the one detected on this system
+    fn read_nixos_hyprland_settings(&self) -> Result<(std::path::PathBuf, HyprlandNixSettings)> {
+        let path = self
+            .config
+            .nixos_config_path
+            .clone()
+            .or_else(|| {
+                self.ui
+                    .nixos_env
+                    .get_primary_config_location()
+                    .map(|location| location.path.clone())
+            })
+            .ok_or_else(|| anyhow::anyhow!("No NixOS configuration file found"))?;
+
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+        let settings = NixParser::new()
+            .parse_expression(&content)?
+            .hyprland_settings()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No wayland.windowManager.hyprland settings in {}",
+                    path.display()
+                )
+            })?;
+        Ok((path, settings))
+    }
 
+    fn import_nixos_config(&mut self) -> Result<usize> {
+        let (_, settings) = self.read_nixos_hyprland_settings()?;
+        let selection = self.ui.selected_keys();
+        let imported_count = self.ui.import_hyprland_settings(&settings)?;
+        self.ui.refresh_all_panels(&selection);
         Ok(imported_count)
     }
 
@@ -1157,22 +3169,20 @@ impl App {
             self.ui.nixos_export_config_type.clone(),
         ) {
             Ok(config) => {
-                // Truncate preview if too long
-                let preview = if config.len() > 2000 {
-                    format!(
-                        "{}...\n\n[Output truncated - {} more characters]",
-                        &config[..2000],
-                        config.len() - 2000
-                    )
-                } else {
-                    config
+                let (config, format_error) =
+                    crate::nixos::formatter::format_nix(config, self.nixos_export_formatter());
+                let config = match format_error {
+                    Some(e) => format!("# {e}, showing the built-in formatting\n\n{config}"),
+                    None => config,
                 };
-                self.ui.nixos_export_preview = Some(preview);
+                // The whole module is shown, the preview scrolls
+                self.ui.nixos_export_preview = Some(config);
             }
             Err(e) => {
                 self.ui.nixos_export_preview = Some(format!("Error generating preview: {e}"));
             }
         }
+        self.ui.nixos_export_scroll = 0;
     }
 
     async fn export_nixos_config(&mut self) {
@@ -1223,10 +3233,24 @@ impl App {
             target_type,
         )?;
 
+        let (nixos_config, format_error) =
+            crate::nixos::formatter::format_nix(nixos_config, self.nixos_export_formatter());
+
         // Write to file
         fs::write(&export_path, nixos_config)?;
 
-        Ok(export_path.to_string_lossy().to_string())
+        let path = export_path.to_string_lossy().to_string();
+        Ok(match format_error {
+            Some(e) => format!("{path}\n\n{e}, so it was written with the built-in formatting"),
+            None => path,
+        })
+    }
+
+    /// The external formatter to run exports through, if enabled and found
+    fn nixos_export_formatter(&self) -> Option<crate::nixos::formatter::NixFormatter> {
+        self.ui
+            .nixos_export_formatter
+            .filter(|_| self.ui.nixos_export_use_formatter)
     }
 
     // ================================
@@ -1239,9 +3263,20 @@ impl App {
         self.ui.batch_dialog_mode = crate::ui::BatchDialogMode::ManageProfiles;
         self.ui.batch_selected_profile = None;
         self.ui.batch_operation_type = crate::batch::BatchOperationType::Apply;
+        self.ui.batch_name_input = None;
+        self.refresh_batch_profiles();
         self.ui.show_batch_dialog = true;
     }
 
+    /// Reload the profile list, keeping the highlight in range
+    fn refresh_batch_profiles(&mut self) {
+        self.ui.batch_profiles = self.batch_manager.list_snapshots();
+        self.ui.batch_profile_index = self
+            .ui
+            .batch_profile_index
+            .min(self.ui.batch_profiles.len().saturating_sub(1));
+    }
+
     pub async fn handle_batch_dialog_key(&mut self, key: KeyCode) -> Result<()> {
         match self.ui.batch_dialog_mode {
             crate::ui::BatchDialogMode::ManageProfiles => {
@@ -1256,20 +3291,66 @@ impl App {
         }
     }
 
+    #[allow(clippy::collapsible_match)]
     pub async fn handle_batch_manage_profiles_key(&mut self, key: KeyCode) -> Result<()> {
+        // Typing the name of a new profile
+        if let Some(name) = &mut self.ui.batch_name_input {
+            match key {
+                KeyCode::Enter => {
+                    let name = name.trim().to_string();
+                    self.create_batch_profile(name).await;
+                }
+                KeyCode::Esc => {
+                    self.ui.batch_name_input = None;
+                }
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Char(c) => {
+                    name.push(c);
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
         match key {
             KeyCode::Char('1') => {
-                // Create new profile
-                self.create_batch_profile().await;
-            }
-            KeyCode::Char('2') => {
-                // Select existing profile
-                self.ui.batch_dialog_mode = crate::ui::BatchDialogMode::SelectOperation;
+                // Create new profile, starting from a timestamped name
+                let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
+                self.ui.batch_name_input = Some(format!("profile_{timestamp}"));
+            }
+            KeyCode::Char('2') | KeyCode::Enter => {
+                // Select the highlighted profile
+                match self.ui.batch_profiles.get(self.ui.batch_profile_index) {
+                    Some(name) => {
+                        self.ui.batch_selected_profile = Some(name.clone());
+                        self.ui.batch_dialog_mode = crate::ui::BatchDialogMode::SelectOperation;
+                    }
+                    None => {
+                        self.ui.show_popup = true;
+                        self.ui.popup_message =
+                            "No profiles yet - press 1 to create one".to_string();
+                    }
+                }
             }
             KeyCode::Char('3') => {
-                // Delete profile
+                // Delete the highlighted profile
+                self.ui.batch_selected_profile = self
+                    .ui
+                    .batch_profiles
+                    .get(self.ui.batch_profile_index)
+                    .cloned();
                 self.delete_batch_profile().await;
             }
+            KeyCode::Up => {
+                self.ui.batch_profile_index = self.ui.batch_profile_index.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                if self.ui.batch_profile_index + 1 < self.ui.batch_profiles.len() {
+                    self.ui.batch_profile_index += 1;
+                }
+            }
             KeyCode::Esc => {
                 self.ui.show_batch_dialog = false;
             }
@@ -1318,24 +3399,17 @@ impl App {
         Ok(())
     }
 
-    async fn create_batch_profile(&mut self) {
-        // Create profile with timestamp
-        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-        let profile_name = format!("profile_{timestamp}");
-
-        // For now, create an empty profile with no config paths
-        // TODO: In the future, implement actual config file path collection
-        let config_paths = Vec::new();
-
-        match self.batch_manager.create_profile(
-            profile_name.clone(),
-            Some("Auto-generated profile".to_string()),
-            config_paths,
-        ).await {
-            Ok(_) => {
+    async fn create_batch_profile(&mut self, name: String) {
+        let snapshot = self.ui.profile_snapshot(None);
+        match self.batch_manager.create_snapshot(&name, &snapshot).await {
+            Ok(path) => {
+                self.ui.batch_name_input = None;
+                self.refresh_batch_profiles();
+                if let Some(index) = self.ui.batch_profiles.iter().position(|p| *p == name) {
+                    self.ui.batch_profile_index = index;
+                }
                 self.ui.show_popup = true;
-                self.ui.popup_message = format!("Profile '{profile_name}' created successfully!");
-                self.ui.show_batch_dialog = false;
+                self.ui.popup_message = format!("Profile '{name}' saved to {}", path.display());
             }
             Err(e) => {
                 self.ui.show_popup = true;
@@ -1345,14 +3419,13 @@ impl App {
     }
 
     async fn delete_batch_profile(&mut self) {
-        if let Some(profile_name) = &self.ui.batch_selected_profile {
-            match self.batch_manager.delete_profile(profile_name).await {
+        if let Some(profile_name) = self.ui.batch_selected_profile.take() {
+            match self.batch_manager.delete_snapshot(&profile_name).await {
                 Ok(_) => {
+                    self.refresh_batch_profiles();
                     self.ui.show_popup = true;
                     self.ui.popup_message =
                         format!("Profile '{profile_name}' deleted successfully!");
-                    self.ui.batch_selected_profile = None;
-                    self.ui.show_batch_dialog = false;
                 }
                 Err(e) => {
                     self.ui.show_popup = true;
@@ -1366,48 +3439,47 @@ impl App {
     }
 
     async fn execute_batch_operation(&mut self) {
-        if let Some(profile_name) = &self.ui.batch_selected_profile {
-            // Collect current configuration for the operation
-            let config_changes = self.ui.collect_all_config_changes();
-            let keybinds = self.ui.collect_keybinds();
-            let window_rules = self.ui.collect_window_rules();
-            let layer_rules = self.ui.collect_layer_rules();
-
-            let operation = crate::batch::BatchOperation {
-                operation_type: self.ui.batch_operation_type.clone(),
-                settings: config_changes,
-                keybinds,
-                window_rules,
-                layer_rules,
-                target_profiles: vec![profile_name.clone()],
-            };
-
-            match self
-                .batch_manager
-                .execute_batch_operation(&operation, &self.hyprctl)
-                .await
-            {
-                Ok(results) => {
-                    let success_count = results.iter().filter(|r| r.success).count();
-                    self.ui.show_popup = true;
-                    self.ui.popup_message = format!(
-                        "Batch operation '{:?}' completed! {success_count}/{} profiles succeeded.",
-                        self.ui.batch_operation_type,
-                        results.len()
-                    );
-                    self.ui.show_batch_dialog = false;
-                }
-                Err(e) => {
-                    self.ui.show_popup = true;
-                    self.ui.popup_message = format!("Batch operation failed: {e}");
-                }
-            }
-        } else {
+        let Some(profile_name) = self.ui.batch_selected_profile.clone() else {
             self.ui.show_popup = true;
             self.ui.popup_message = "No profile selected for operation".to_string();
+            return;
+        };
+
+        match self.run_profile_operation(&profile_name).await {
+            Ok(message) => {
+                self.ui.show_popup = true;
+                self.ui.popup_message = message;
+                self.ui.show_batch_dialog = false;
+            }
+            Err(e) => {
+                self.ui.show_popup = true;
+                self.ui.popup_message = format!(
+                    "Batch operation '{:?}' failed: {e}",
+                    self.ui.batch_operation_type
+                );
+            }
         }
     }
 
+    /// Run the selected operation with a profile and describe the outcome
+    async fn run_profile_operation(&mut self, profile_name: &str) -> Result<String> {
+        let operation = self.ui.batch_operation_type.clone();
+        let selection = self.ui.selected_keys();
+        let outcome = self
+            .batch_manager
+            .execute(&operation, profile_name, &mut self.ui, &self.hyprctl)
+            .await?;
+
+        self.refresh_batch_profiles();
+        self.ui.refresh_all_panels(&selection);
+        // Recorded changes and marks no longer match the replaced items
+        self.ui.change_history.clear();
+        self.ui.marked_items.clear();
+        self.ui.clear_search_cache();
+
+        Ok(outcome.summary(&operation, profile_name))
+    }
+
     // ================================
     // PREVIEW FUNCTIONALITY
     // ================================
@@ -1422,10 +3494,18 @@ impl App {
             FocusedPanel::Decoration => self.ui.decoration_list_state.selected(),
             FocusedPanel::Animations => self.ui.animations_list_state.selected(),
             FocusedPanel::Gestures => self.ui.gestures_list_state.selected(),
+            FocusedPanel::Layouts => self.ui.layouts_list_state.selected(),
+            FocusedPanel::Groups => self.ui.groups_list_state.selected(),
             FocusedPanel::Binds => self.ui.binds_list_state.selected(),
             FocusedPanel::WindowRules => self.ui.window_rules_list_state.selected(),
             FocusedPanel::LayerRules => self.ui.layer_rules_list_state.selected(),
+            FocusedPanel::Workspaces => self.ui.workspaces_list_state.selected(),
+            FocusedPanel::Environment => self.ui.environment_list_state.selected(),
+            FocusedPanel::Plugins => self.ui.plugins_list_state.selected(),
+            FocusedPanel::Autostart => self.ui.autostart_list_state.selected(),
+            FocusedPanel::Variables => self.ui.variables_list_state.selected(),
             FocusedPanel::Misc => self.ui.misc_list_state.selected(),
+            FocusedPanel::Other => self.ui.other_list_state.selected(),
             FocusedPanel::Import => self.ui.import_list_state.selected(),
             FocusedPanel::Export => self.ui.export_list_state.selected(),
         };
@@ -1514,6 +3594,15 @@ impl App {
 
     pub async fn handle_preview_dialog_key(&mut self, key: KeyCode) -> Result<()> {
         match key {
+            // The save diff goes back to the save dialog or saves
+            KeyCode::Esc if self.ui.preview_diff.is_some() => {
+                self.ui.close_preview_dialog();
+                self.ui.show_save_dialog = true;
+            }
+            KeyCode::Enter if self.ui.preview_diff.is_some() => {
+                self.ui.close_preview_dialog();
+                self.confirm_save().await?;
+            }
             KeyCode::Esc => {
                 self.ui.close_preview_dialog();
             }
@@ -1702,62 +3791,10 @@ impl App {
     }
 
     async fn validate_keybind(&self, keybind: &str) -> Result<()> {
-        let trimmed = keybind.trim();
-
-        // Skip empty lines or comments
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            return Ok(());
-        }
-
-        // Check if it's a bind-related directive
-        let bind_prefixes = ["bind", "binde", "bindm", "bindr", "bindl"];
-        let is_bind = bind_prefixes.iter().any(|prefix| {
-            trimmed.starts_with(prefix)
-                && (trimmed.len() == prefix.len()
-                    || trimmed
-                        .chars()
-                        .nth(prefix.len())
-                        .map(|c| c == '=' || c.is_whitespace())
-                        .unwrap_or(false))
-        });
-
-        if !is_bind {
-            return Err(anyhow::anyhow!(
-                "must start with a bind directive (bind, binde, bindm, bindr, bindl)"
-            ));
-        }
-
-        // Find the = separator
-        if let Some(eq_pos) = trimmed.find('=') {
-            let after_eq = &trimmed[eq_pos + 1..].trim();
-
-            // Split by comma to get components
-            let parts: Vec<&str> = after_eq.split(',').map(|s| s.trim()).collect();
-
-            // More flexible validation - just check we have at least modifiers and key
-            if parts.len() < 2 {
-                return Err(anyhow::anyhow!(
-                    "must have at least: MODIFIERS, KEY after '='"
-                ));
-            }
-
-            // Check that key (second part) isn't empty
-            if parts.get(1).unwrap_or(&"").trim().is_empty() {
-                return Err(anyhow::anyhow!("key cannot be empty"));
-            }
-
-            // If there's a dispatcher (third part), check it's not empty
-            if parts.len() >= 3 {
-                let dispatcher = parts.get(2).unwrap_or(&"").trim();
-                if dispatcher.is_empty() {
-                    return Err(anyhow::anyhow!("dispatcher cannot be empty"));
-                }
-            }
-        } else {
-            return Err(anyhow::anyhow!("must contain '=' separator"));
-        }
-
-        Ok(())
+        // Checked as Hyprland reads it, with its variables resolved
+        let keybind = self.ui.variables().resolve(keybind);
+        crate::keybinds::validate_bind_line(&keybind, self.config.strict_dispatchers)
+            .map_err(anyhow::Error::msg)
     }
 
     async fn validate_window_rule(&self, rule: &str) -> Result<()> {
@@ -1826,20 +3863,16 @@ impl App {
     // UNDO/REDO FUNCTIONALITY
     // ================================
 
-    /// Handle undo operation - restore previous configuration state
+    /// Handle undo operation - revert the most recent item change
     pub async fn handle_undo(&mut self) -> Result<()> {
-        if let Some(snapshot) = self.undo_manager.undo() {
-            // Restore the configuration state from the snapshot
-            self.ui.config_items = snapshot.config_items;
-
-            // Show feedback to user
-            let description = snapshot.description.unwrap_or_else(|| "Change".to_string());
+        if let Some(change) = self.ui.undo() {
             self.ui.show_popup = true;
-            self.ui.popup_message = format!("Undone: {}", description);
+            self.ui.popup_message = format!("Undone: {}", change.description());
 
-            // Apply the restored configuration if live preview is enabled
+            // Re-issue the reverted value if live preview is enabled
             if self.ui.is_preview_mode() {
-                self.apply_current_configuration().await?;
+                self.apply_change_value(&change, change.undone_value())
+                    .await;
             }
         } else {
             // No undo available
@@ -1850,18 +3883,14 @@ impl App {
     }
 
     pub async fn handle_redo(&mut self) -> Result<()> {
-        if let Some(snapshot) = self.undo_manager.redo() {
-            // Restore the configuration state from the snapshot
-            self.ui.config_items = snapshot.config_items;
-
-            // Show feedback to user
-            let description = snapshot.description.unwrap_or_else(|| "Change".to_string());
+        if let Some(change) = self.ui.redo() {
             self.ui.show_popup = true;
-            self.ui.popup_message = format!("Redone: {}", description);
+            self.ui.popup_message = format!("Redone: {}", change.description());
 
-            // Apply the restored configuration if live preview is enabled
+            // Re-issue the restored value if live preview is enabled
             if self.ui.is_preview_mode() {
-                self.apply_current_configuration().await?;
+                self.apply_change_value(&change, change.redone_value())
+                    .await;
             }
         } else {
             // No redo available
@@ -1871,24 +3900,22 @@ impl App {
         Ok(())
     }
 
-    /// Take a snapshot before making changes
-    pub fn take_config_snapshot(&mut self, description: &str) {
-        let snapshot = ConfigSnapshot::from_ui(&self.ui, Some(description.to_string()));
-        self.undo_manager.take_snapshot(snapshot);
-    }
-
-    /// Apply current configuration state to Hyprland (for live preview)
-    async fn apply_current_configuration(&mut self) -> Result<()> {
-        let config_changes = self.ui.collect_all_config_changes();
+    /// Push an undone/redone value to Hyprland for options that map to a hyprctl keyword
+    async fn apply_change_value(
+        &mut self,
+        change: &crate::undo::ChangeRecord,
+        value: Option<&str>,
+    ) {
+        let (Some(hypr_key), Some(value)) =
+            (self.ui.get_hyprctl_key(&change.panel, &change.key), value)
+        else {
+            return;
+        };
 
-        // Apply each configuration change
-        for (key, value) in config_changes {
-            if let Err(e) = self.hyprctl.set_option(&key, &value).await {
-                eprintln!("Failed to apply config change {key}={value}: {e}");
-            }
+        if let Err(e) = self.hyprctl.set_option(&hypr_key, value).await {
+            self.ui.popup_message =
+                format!("{}\nFailed to apply {hypr_key}: {e}", self.ui.popup_message);
         }
-
-        Ok(())
     }
 
     // ================================
@@ -1901,6 +3928,9 @@ impl App {
         self.ui.import_export_mode = crate::ui::ImportExportMode::SelectSource;
         self.ui.selected_import_source = crate::ui::ImportSourceType::LocalFile;
         self.ui.import_preview = None;
+        self.ui.github_import_form = None;
+        self.ui.import_input = None;
+        self.folder_merge = None;
         self.ui.import_list_state.select(Some(0));
     }
 
@@ -1915,7 +3945,18 @@ impl App {
     pub async fn handle_import_dialog_key(&mut self, key: KeyCode) -> Result<()> {
         use crate::ui::{ImportExportMode, ImportSourceType};
 
+        if self.ui.import_export_mode == ImportExportMode::Preview
+            && self.handle_folder_conflict_key(key)
+        {
+            return Ok(());
+        }
         match self.ui.import_export_mode {
+            ImportExportMode::SelectSource if self.ui.github_import_form.is_some() => {
+                return self.handle_github_form_key(key).await;
+            }
+            ImportExportMode::SelectSource if self.ui.import_input.is_some() => {
+                self.handle_import_input_key(key);
+            }
             ImportExportMode::SelectSource => match key {
                 KeyCode::Char('1') => {
                     self.ui.selected_import_source = ImportSourceType::LocalFile;
@@ -1924,16 +3965,23 @@ impl App {
                 }
                 KeyCode::Char('2') => {
                     self.ui.selected_import_source = ImportSourceType::LocalFolder;
-                    self.ui.import_export_mode = ImportExportMode::Preview;
-                    self.generate_import_preview().await;
+                    let config_dir = self.config.hyprland_config_path.parent();
+                    self.ui.import_input = Some(
+                        config_dir
+                            .map(|dir| dir.to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                    );
                 }
                 KeyCode::Char('3') => {
                     self.ui.selected_import_source = ImportSourceType::GitHubRepository;
-                    self.ui.import_export_mode = ImportExportMode::Preview;
-                    self.generate_import_preview().await;
+                    self.ui.github_import_form = Some(Default::default());
                 }
                 KeyCode::Char('4') => {
                     self.ui.selected_import_source = ImportSourceType::UrlDownload;
+                    self.ui.import_input = Some(String::new());
+                }
+                KeyCode::Char('5') => {
+                    self.ui.selected_import_source = ImportSourceType::NixHomeManager;
                     self.ui.import_export_mode = ImportExportMode::Preview;
                     self.generate_import_preview().await;
                 }
@@ -1944,23 +3992,23 @@ impl App {
                 _ => {}
             },
             ImportExportMode::Preview => match key {
+                // Wait for the download to finish
+                KeyCode::Enter if self.github_task.is_some() || self.url_task.is_some() => {}
                 KeyCode::Enter => {
                     self.ui.import_export_mode = ImportExportMode::Execute;
                     self.execute_import().await;
                 }
                 KeyCode::Esc => {
+                    if let Some(task) = self.github_task.take() {
+                        task.handle.abort();
+                    }
+                    if let Some(task) = self.url_task.take() {
+                        task.abort();
+                    }
                     self.ui.import_export_mode = ImportExportMode::SelectSource;
                     self.ui.import_preview = None;
                 }
-                KeyCode::Up => {
-                    if self.ui.import_export_scroll > 0 {
-                        self.ui.import_export_scroll -= 1;
-                    }
-                }
-                KeyCode::Down => {
-                    self.ui.import_export_scroll += 1;
-                }
-                _ => {}
+                key => self.ui.scroll_import_export_preview(key),
             },
             ImportExportMode::Execute => match key {
                 KeyCode::Enter | KeyCode::Esc => {
@@ -2027,19 +4075,14 @@ impl App {
                     self.ui.import_export_mode = ImportExportMode::Execute;
                     self.execute_export().await;
                 }
+                KeyCode::Char('c' | 'C') => self.copy_export_to_clipboard().await,
+                KeyCode::Char('g' | 'G') => self.share_export_as_gist(false).await,
+                KeyCode::Char('p' | 'P') => self.share_export_as_gist(true).await,
                 KeyCode::Esc => {
                     self.ui.import_export_mode = ImportExportMode::SelectFormat;
                     self.ui.export_preview = None;
                 }
-                KeyCode::Up => {
-                    if self.ui.import_export_scroll > 0 {
-                        self.ui.import_export_scroll -= 1;
-                    }
-                }
-                KeyCode::Down => {
-                    self.ui.import_export_scroll += 1;
-                }
-                _ => {}
+                key => self.ui.scroll_import_export_preview(key),
             },
             ImportExportMode::Execute => match key {
                 KeyCode::Enter | KeyCode::Esc => {
@@ -2063,10 +4106,18 @@ impl App {
             FocusedPanel::Decoration => self.ui.decoration_list_state.selected(),
             FocusedPanel::Animations => self.ui.animations_list_state.selected(),
             FocusedPanel::Gestures => self.ui.gestures_list_state.selected(),
+            FocusedPanel::Layouts => self.ui.layouts_list_state.selected(),
+            FocusedPanel::Groups => self.ui.groups_list_state.selected(),
             FocusedPanel::Binds => self.ui.binds_list_state.selected(),
             FocusedPanel::WindowRules => self.ui.window_rules_list_state.selected(),
             FocusedPanel::LayerRules => self.ui.layer_rules_list_state.selected(),
+            FocusedPanel::Workspaces => self.ui.workspaces_list_state.selected(),
+            FocusedPanel::Environment => self.ui.environment_list_state.selected(),
+            FocusedPanel::Plugins => self.ui.plugins_list_state.selected(),
+            FocusedPanel::Autostart => self.ui.autostart_list_state.selected(),
+            FocusedPanel::Variables => self.ui.variables_list_state.selected(),
             FocusedPanel::Misc => self.ui.misc_list_state.selected(),
+            FocusedPanel::Other => self.ui.other_list_state.selected(),
             FocusedPanel::Import => {
                 // Show import dialog instead
                 self.show_import_dialog().await;
@@ -2091,62 +4142,505 @@ impl App {
                         item.suggestions.join(", ")
                     );
 
-                    // Generate preview of what the setting would look like with a different value
-                    let after_text = self.generate_setting_preview_text(item);
+                    // Generate preview of what the setting would look like with a different value
+                    let after_text = self.generate_setting_preview_text(item);
+
+                    self.ui
+                        .show_setting_preview(setting_name, before_text, after_text);
+                } else {
+                    self.ui.show_popup = true;
+                    self.ui.popup_message = "No setting selected for preview".to_string();
+                }
+            } else {
+                self.ui.show_popup = true;
+                self.ui.popup_message = "No configuration items available for preview".to_string();
+            }
+        } else {
+            self.ui.show_popup = true;
+            self.ui.popup_message =
+                "No setting selected. Use ↑↓ to select a setting first".to_string();
+        }
+    }
+
+    fn generate_setting_preview_text(&self, item: &crate::ui::ConfigItem) -> String {
+        match &item.data_type {
+            crate::ui::ConfigDataType::Boolean => {
+                let new_value = if item.value == "true" { "false" } else { "true" };
+                format!("Preview Value: {}\n\nThis would toggle the current boolean setting.", new_value)
+            }
+            crate::ui::ConfigDataType::Integer { min, max } => {
+                let current_val = item.value.parse::<i32>().unwrap_or(0);
+                let new_val = match (min, max) {
+                    (Some(min_val), Some(max_val)) => (current_val + 5).clamp(*min_val, *max_val),
+                    _ => current_val + 5,
+                };
+                format!("Preview Value: {}\n\nThis would increase the current value by 5 (within valid range).", new_val)
+            }
+            crate::ui::ConfigDataType::Float { min, max } => {
+                let current_val = item.value.parse::<f32>().unwrap_or(0.0);
+                let new_val = match (min, max) {
+                    (Some(min_val), Some(max_val)) => (current_val + 0.1).clamp(*min_val, *max_val),
+                    _ => current_val + 0.1,
+                };
+                format!("Preview Value: {:.2}\n\nThis would increase the current value by 0.1 (within valid range).", new_val)
+            }
+            crate::ui::ConfigDataType::Keyword { options } => {
+                let next_option = options.iter()
+                    .find(|&opt| opt != &item.value)
+                    .unwrap_or(&options[0]);
+                format!("Preview Value: {}\n\nThis would cycle to the next available option.\nAll options: {}", 
+                    next_option, options.join(", "))
+            }
+            crate::ui::ConfigDataType::Color => {
+                "Preview Value: #FF5555\n\nThis would set a new color value. Use the color picker to select.".to_string()
+            }
+            crate::ui::ConfigDataType::String => {
+                format!("Preview Value: {} (modified)\n\nThis would append '(modified)' to the current string value.", item.value)
+            }
+        }
+    }
+
+    /// Keys of the GitHub import form: typing edits the repository or
+    /// folder, ←→ picks the branch and Enter looks up branches or downloads
+    async fn handle_github_form_key(&mut self, key: KeyCode) -> Result<()> {
+        use crate::ui::GitHubImportField;
+
+        let Some(form) = self.ui.github_import_form.as_mut() else {
+            return Ok(());
+        };
+        match key {
+            KeyCode::Esc => self.ui.github_import_form = None,
+            KeyCode::Tab | KeyCode::Down => form.next_field(),
+            KeyCode::Left | KeyCode::Right if form.field == GitHubImportField::Branch => {
+                let count = form.branches.len().max(1);
+                form.branch_index = if key == KeyCode::Right {
+                    (form.branch_index + 1) % count
+                } else {
+                    (form.branch_index + count - 1) % count
+                };
+            }
+            KeyCode::Backspace => match form.field {
+                GitHubImportField::Repository => {
+                    form.repository.pop();
+                    form.branches.clear();
+                }
+                GitHubImportField::Folder => {
+                    form.folder.pop();
+                }
+                GitHubImportField::Branch => {}
+            },
+            KeyCode::Char(c) => match form.field {
+                GitHubImportField::Repository => {
+                    form.repository.push(c);
+                    form.branches.clear();
+                }
+                GitHubImportField::Folder => form.folder.push(c),
+                GitHubImportField::Branch => {}
+            },
+            KeyCode::Enter => {
+                if form.field == GitHubImportField::Repository || form.branches.is_empty() {
+                    self.look_up_github_branches().await;
+                } else {
+                    self.start_github_download();
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Fill in the branches of the repository in the form, with the default
+    /// branch first. A branch or folder from a pasted URL is selected.
+    async fn look_up_github_branches(&mut self) {
+        let Some(form) = &self.ui.github_import_form else {
+            return;
+        };
+        let result = async {
+            let repo = RepoSpec::parse(&form.repository)?;
+            let client = GitHubClient::new()?;
+            let default_branch = client.default_branch(&repo).await?;
+            let mut branches = client.branches(&repo).await?;
+            branches.retain(|branch| *branch != default_branch);
+            branches.insert(0, default_branch);
+            anyhow::Ok((repo, branches))
+        }
+        .await;
+
+        let Some(form) = self.ui.github_import_form.as_mut() else {
+            return;
+        };
+        match result {
+            Ok((repo, mut branches)) => {
+                // A URL can name a tag or commit, which isn't in the list
+                if let Some(branch) = &repo.branch {
+                    if !branches.contains(branch) {
+                        branches.insert(0, branch.clone());
+                    }
+                }
+                form.branch_index = repo
+                    .branch
+                    .as_ref()
+                    .and_then(|branch| branches.iter().position(|b| b == branch))
+                    .unwrap_or(0);
+                form.branches = branches;
+                form.repository = repo.full_name();
+                if form.folder.is_empty() {
+                    form.folder = repo.subpath.unwrap_or_default();
+                }
+                form.field = crate::ui::GitHubImportField::Branch;
+            }
+            Err(e) => {
+                self.ui.show_popup = true;
+                self.ui.popup_message = format!("Could not open the repository: {e}");
+            }
+        }
+    }
+
+    /// Download the configs of the form's repository in the background and
+    /// switch the dialog to the preview, which follows the progress
+    fn start_github_download(&mut self) {
+        let Some(form) = &self.ui.github_import_form else {
+            return;
+        };
+        let (repo, branch) = match (RepoSpec::parse(&form.repository), form.selected_branch()) {
+            (Ok(repo), Some(branch)) => (repo, branch.to_string()),
+            _ => return,
+        };
+        let folder = form.folder.trim().trim_matches('/').to_string();
+        let label = format!(
+            "{} @ {branch}, folder {}",
+            repo.full_name(),
+            if folder.is_empty() { "/" } else { &folder }
+        );
+
+        let (sender, progress) =
+            tokio::sync::watch::channel("Listing the files of the repository...".to_string());
+        let handle = tokio::spawn(async move {
+            GitHubClient::new()?
+                .download_configs(&repo, &branch, &folder, |index, total, path| {
+                    let _ = sender.send(format!("Downloading {index}/{total}: {path}"));
+                })
+                .await
+        });
+
+        if let Some(task) = self.github_task.replace(GitHubImportTask {
+            label,
+            handle,
+            progress,
+        }) {
+            task.handle.abort();
+        }
+        self.github_download = None;
+        self.ui.import_export_scroll = 0;
+        self.ui.import_export_mode = crate::ui::ImportExportMode::Preview;
+        self.show_github_progress();
+    }
+
+    fn show_github_progress(&mut self) {
+        if let Some(task) = &self.github_task {
+            self.ui.import_preview = Some(format!(
+                "Import from GitHub\n{}\n\n{}",
+                task.label,
+                *task.progress.borrow()
+            ));
+        }
+    }
+
+    /// Follow the background GitHub download, showing its progress and then
+    /// the preview of what it found
+    async fn poll_github_download(&mut self) {
+        match &self.github_task {
+            Some(task) if task.handle.is_finished() => {}
+            Some(_) => return self.show_github_progress(),
+            None => return,
+        }
+        let Some(task) = self.github_task.take() else {
+            return;
+        };
 
-                    self.ui
-                        .show_setting_preview(setting_name, before_text, after_text);
-                } else {
-                    self.ui.show_popup = true;
-                    self.ui.popup_message = "No setting selected for preview".to_string();
-                }
+        let preview = match task.handle.await {
+            Ok(Ok(download)) => {
+                let preview = Self::github_import_preview(&task.label, &download);
+                self.github_download = Some(download);
+                preview
+            }
+            Ok(Err(e)) => format!("Import from GitHub\n{}\n\n{e}", task.label),
+            Err(e) => format!(
+                "Import from GitHub\n{}\n\nThe download stopped: {e}",
+                task.label
+            ),
+        };
+        self.ui.import_preview = Some(preview);
+    }
+
+    fn github_import_preview(label: &str, download: &RepoDownload) -> String {
+        let (content, used) = download.combined_config();
+        let settings = HyprlandNixSettings::from_hyprland_conf(&content);
+
+        let mut preview = format!("Import from GitHub\n{label}\n\nFiles:\n");
+        for path in download.files.keys() {
+            if used.contains(path) {
+                preview.push_str(&format!("  ✓ {path}\n"));
             } else {
-                self.ui.show_popup = true;
-                self.ui.popup_message = "No configuration items available for preview".to_string();
+                preview.push_str(&format!("  – {path} (not sourced by hyprland.conf)\n"));
             }
-        } else {
-            self.ui.show_popup = true;
-            self.ui.popup_message =
-                "No setting selected. Use ↑↓ to select a setting first".to_string();
         }
+        for skipped in &download.skipped {
+            preview.push_str(&format!("  – {skipped}\n"));
+        }
+        preview.push_str(&format!(
+            "\nThis will import:\n\
+            • {} options\n\
+            • {} keybind, rule and variable lines",
+            settings.options.len(),
+            settings.lines.len()
+        ));
+        preview
     }
 
-    fn generate_setting_preview_text(&self, item: &crate::ui::ConfigItem) -> String {
-        match &item.data_type {
-            crate::ui::ConfigDataType::Boolean => {
-                let new_value = if item.value == "true" { "false" } else { "true" };
-                format!("Preview Value: {}\n\nThis would toggle the current boolean setting.", new_value)
+    /// Add the downloaded GitHub configs to the panels
+    fn import_github_download(&mut self) -> Result<usize> {
+        let download = self
+            .github_download
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Nothing has been downloaded from GitHub yet"))?;
+        let (content, _) = download.combined_config();
+        let settings = HyprlandNixSettings::from_hyprland_conf(&content);
+        let selection = self.ui.selected_keys();
+        let imported_count = self.ui.import_hyprland_settings(&settings)?;
+        self.ui.refresh_all_panels(&selection);
+        Ok(imported_count)
+    }
+
+    /// Edit the URL or folder to import from
+    fn handle_import_input_key(&mut self, key: KeyCode) {
+        let Some(input) = self.ui.import_input.as_mut() else {
+            return;
+        };
+        match key {
+            KeyCode::Esc => self.ui.import_input = None,
+            KeyCode::Backspace => {
+                input.pop();
             }
-            crate::ui::ConfigDataType::Integer { min, max } => {
-                let current_val = item.value.parse::<i32>().unwrap_or(0);
-                let new_val = match (min, max) {
-                    (Some(min_val), Some(max_val)) => (current_val + 5).clamp(*min_val, *max_val),
-                    _ => current_val + 5,
-                };
-                format!("Preview Value: {}\n\nThis would increase the current value by 5 (within valid range).", new_val)
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Enter if !input.trim().is_empty() => {
+                if self.ui.selected_import_source == crate::ui::ImportSourceType::LocalFolder {
+                    self.scan_import_folder();
+                } else {
+                    self.start_url_download();
+                }
             }
-            crate::ui::ConfigDataType::Float { min, max } => {
-                let current_val = item.value.parse::<f32>().unwrap_or(0.0);
-                let new_val = match (min, max) {
-                    (Some(min_val), Some(max_val)) => (current_val + 0.1).clamp(*min_val, *max_val),
-                    _ => current_val + 0.1,
-                };
-                format!("Preview Value: {:.2}\n\nThis would increase the current value by 0.1 (within valid range).", new_val)
+            _ => {}
+        }
+    }
+
+    /// Download the config at the entered URL in the background and switch
+    /// the dialog to the preview
+    fn start_url_download(&mut self) {
+        let Some(url) = self.ui.import_input.clone() else {
+            return;
+        };
+        let handle = tokio::spawn(async move { download_config(&url).await });
+        if let Some(task) = self.url_task.replace(handle) {
+            task.abort();
+        }
+        self.url_download = None;
+        self.ui.import_export_scroll = 0;
+        self.ui.import_export_mode = crate::ui::ImportExportMode::Preview;
+        self.ui.import_preview = Some(format!(
+            "Import from URL\n{}\n\nDownloading...",
+            self.ui.import_input.as_deref().unwrap_or_default()
+        ));
+    }
+
+    /// Show the parsed settings once the URL download has finished
+    async fn poll_url_download(&mut self) {
+        if !self
+            .url_task
+            .as_ref()
+            .is_some_and(|task| task.is_finished())
+        {
+            return;
+        }
+        let Some(task) = self.url_task.take() else {
+            return;
+        };
+        let url = self.ui.import_input.clone().unwrap_or_default();
+
+        let preview = match task.await {
+            Ok(Ok(download)) => match download.settings() {
+                Ok(settings) => {
+                    let preview = Self::url_import_preview(&download, &settings);
+                    self.url_download = Some(download);
+                    preview
+                }
+                Err(e) => format!(
+                    "Import from URL\n{}\n\nDetected format: {}\n\n{e:#}",
+                    download.url,
+                    download.format.description()
+                ),
+            },
+            Ok(Err(e)) => format!("Import from URL\n{url}\n\n{e}"),
+            Err(e) => format!("Import from URL\n{url}\n\nThe download stopped: {e}"),
+        };
+        self.ui.import_preview = Some(preview);
+    }
+
+    fn url_import_preview(download: &UrlConfig, settings: &HyprlandNixSettings) -> String {
+        let mut preview = format!(
+            "Import from URL\n{}\n\n\
+            Detected format: {} ({} lines)\n\n\
+            This will import:\n\
+            • {} options\n\
+            • {} keybind, rule and variable lines\n",
+            download.url,
+            download.format.description(),
+            download.content.lines().count(),
+            settings.options.len(),
+            settings.lines.len()
+        );
+        if !settings.options.is_empty() {
+            preview.push_str("\nOptions:\n");
+            for (key, value) in &settings.options {
+                preview.push_str(&format!("  {key} = {value}\n"));
             }
-            crate::ui::ConfigDataType::Keyword { options } => {
-                let next_option = options.iter()
-                    .find(|&opt| opt != &item.value)
-                    .unwrap_or(&options[0]);
-                format!("Preview Value: {}\n\nThis would cycle to the next available option.\nAll options: {}", 
-                    next_option, options.join(", "))
+        }
+        if !settings.lines.is_empty() {
+            preview.push_str("\nLines:\n");
+            for line in &settings.lines {
+                preview.push_str(&format!("  {line}\n"));
             }
-            crate::ui::ConfigDataType::Color => {
-                "Preview Value: #FF5555\n\nThis would set a new color value. Use the color picker to select.".to_string()
+        }
+        preview
+    }
+
+    /// Add the settings of the downloaded URL config to the panels
+    fn import_url_download(&mut self) -> Result<usize> {
+        let download = self
+            .url_download
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("Nothing has been downloaded from the URL"))?;
+        let settings = download.settings()?;
+        let selection = self.ui.selected_keys();
+        let imported_count = self.ui.import_hyprland_settings(&settings)?;
+        self.ui.refresh_all_panels(&selection);
+        Ok(imported_count)
+    }
+
+    /// Scan the entered folder for config fragments and preview the merge
+    fn scan_import_folder(&mut self) {
+        let Some(input) = &self.ui.import_input else {
+            return;
+        };
+        let folder = std::path::PathBuf::from(shellexpand::tilde(input.trim()).into_owned());
+
+        self.ui.import_export_scroll = 0;
+        self.ui.import_export_mode = crate::ui::ImportExportMode::Preview;
+        match FolderMerge::scan(&folder) {
+            Ok(merge) => {
+                self.folder_merge = Some(merge);
+                self.folder_conflict = 0;
+                self.show_folder_preview();
             }
-            crate::ui::ConfigDataType::String => {
-                format!("Preview Value: {} (modified)\n\nThis would append '(modified)' to the current string value.", item.value)
+            Err(e) => {
+                self.folder_merge = None;
+                self.ui.import_preview = Some(format!("Import from Local Folder\n\n{e}"));
+            }
+        }
+    }
+
+    /// Handle Tab and ←→ in the folder preview, which pick the file that
+    /// wins each conflict. Returns whether the key was used.
+    fn handle_folder_conflict_key(&mut self, key: KeyCode) -> bool {
+        if self.ui.selected_import_source != crate::ui::ImportSourceType::LocalFolder {
+            return false;
+        }
+        let Some(merge) = self.folder_merge.as_mut() else {
+            return false;
+        };
+        if merge.conflicts.is_empty() {
+            return false;
+        }
+        let selected = self.folder_conflict.min(merge.conflicts.len() - 1);
+        match key {
+            KeyCode::Tab => self.folder_conflict = (selected + 1) % merge.conflicts.len(),
+            KeyCode::BackTab => {
+                self.folder_conflict =
+                    (selected + merge.conflicts.len() - 1) % merge.conflicts.len()
+            }
+            KeyCode::Right => merge.conflicts[selected].choose_next(),
+            KeyCode::Left => merge.conflicts[selected].choose_previous(),
+            _ => return false,
+        }
+        self.show_folder_preview();
+        true
+    }
+
+    fn show_folder_preview(&mut self) {
+        let Some(merge) = &self.folder_merge else {
+            return;
+        };
+        let merged = merge.merged();
+
+        let mut preview = format!(
+            "Import from Local Folder\n{}\n\n\
+            This will import:\n\
+            • {} options\n\
+            • {} keybind, rule and variable lines\n",
+            merge.root.display(),
+            merged.options.len(),
+            merged.lines.len()
+        );
+        if merge.conflicts.is_empty() {
+            preview.push_str("\nNo file sets an option differently from another.\n");
+        } else {
+            preview.push_str(&format!(
+                "\n{} conflicting options (Tab selects one, ←→ picks the file that wins):\n",
+                merge.conflicts.len()
+            ));
+            for (index, conflict) in merge.conflicts.iter().enumerate() {
+                let marker = if index == self.folder_conflict {
+                    "▶"
+                } else {
+                    " "
+                };
+                preview.push_str(&format!("{marker} {}\n", conflict.key));
+                for (choice, (path, value)) in conflict.values.iter().enumerate() {
+                    let chosen = if choice == conflict.chosen {
+                        "✓"
+                    } else {
+                        " "
+                    };
+                    preview.push_str(&format!(
+                        "    [{chosen}] {} = {value}  ({})\n",
+                        conflict.key,
+                        path.display()
+                    ));
+                }
             }
         }
+
+        preview.push_str("\nFiles:\n");
+        for fragment in &merge.fragments {
+            preview.push_str(&format!(
+                "  {} ({} options, {} lines)\n",
+                fragment.path.display(),
+                fragment.settings.options.len(),
+                fragment.settings.lines.len()
+            ));
+        }
+        self.ui.import_preview = Some(preview);
+    }
+    /// Add the merged settings of the scanned folder to the panels
+    fn import_folder_merge(&mut self) -> Result<usize> {
+        let merge = self
+            .folder_merge
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("No folder has been scanned"))?;
+        let selection = self.ui.selected_keys();
+        let imported_count = self.ui.import_hyprland_settings(&merge.merged())?;
+        self.ui.refresh_all_panels(&selection);
+        Ok(imported_count)
     }
 
     async fn generate_import_preview(&mut self) {
@@ -2183,6 +4677,21 @@ impl App {
                 • Show preview before importing\n\
                 • Support for pastebin, gists, and direct links"
                 .to_string(),
+            ImportSourceType::NixHomeManager => match self.read_nixos_hyprland_settings() {
+                Ok((path, settings)) => format!(
+                    "Import from NixOS Configuration\n\n\
+                    File: {}\n\n\
+                    This will import:\n\
+                    • {} options from wayland.windowManager.hyprland.settings\n\
+                    • {} keybind, rule and variable lines from settings and extraConfig\n\
+                    • {} settings are Nix expressions and will be left out",
+                    path.display(),
+                    settings.options.len(),
+                    settings.lines.len(),
+                    settings.skipped.len()
+                ),
+                Err(e) => format!("Import from NixOS Configuration\n\n{e}"),
+            },
         };
 
         self.ui.import_preview = Some(preview_text);
@@ -2274,11 +4783,39 @@ impl App {
             }
         };
 
-        self.ui.export_preview = Some(preview_text);
+        self.ui.import_export_scroll = 0;
+
+        // Show what would be written, or why it can't be generated
+        let format = self.ui.selected_export_format.to_config_format();
+        let rendered = match crate::import_export::ConfigExporter::new() {
+            Ok(exporter) => exporter.render_current_config(format, &self.hyprctl).await,
+            Err(e) => Err(e),
+        };
+        match rendered {
+            Ok(code) => {
+                self.ui.export_preview = Some(preview_text);
+                self.ui.export_preview_code = Some(code);
+            }
+            Err(e) => {
+                self.ui.export_preview = Some(format!(
+                    "{preview_text}\n\nThe generated config can't be shown: {e}"
+                ));
+                self.ui.export_preview_code = None;
+            }
+        }
     }
 
     async fn execute_import(&mut self) {
-        match self.import_config_from_file().await {
+        use crate::ui::ImportSourceType;
+
+        let result = match self.ui.selected_import_source {
+            ImportSourceType::NixHomeManager => self.import_nixos_config(),
+            ImportSourceType::GitHubRepository => self.import_github_download(),
+            ImportSourceType::UrlDownload => self.import_url_download(),
+            ImportSourceType::LocalFolder => self.import_folder_merge(),
+            _ => self.import_config_from_file().await,
+        };
+        match result {
             Ok(imported_count) => {
                 self.ui.show_popup = true;
                 self.ui.popup_message = format!(
@@ -2292,6 +4829,8 @@ impl App {
             }
         }
         self.ui.show_import_dialog = false;
+        self.ui.github_import_form = None;
+        self.ui.import_input = None;
     }
 
     async fn execute_export(&mut self) {
@@ -2308,6 +4847,85 @@ impl App {
         self.ui.show_export_dialog = false;
     }
 
+    /// Render the config in the format chosen in the export dialog, and
+    /// close the dialog since the result is shown in a popup
+    async fn render_selected_export(&mut self) -> Result<String> {
+        let format = self.ui.selected_export_format.to_config_format();
+        let rendered = match crate::import_export::ConfigExporter::new() {
+            Ok(exporter) => exporter.render_current_config(format, &self.hyprctl).await,
+            Err(e) => Err(e),
+        };
+
+        self.ui.show_export_dialog = false;
+        self.ui.export_preview = None;
+        self.ui.show_popup = true;
+        rendered
+    }
+
+    /// Copy the selected export format to the clipboard instead of a file
+    async fn copy_export_to_clipboard(&mut self) {
+        let format = self.ui.selected_export_format.to_config_format();
+        self.ui.popup_message = match self.render_selected_export().await {
+            Ok(content) => match crate::clipboard::copy(&content) {
+                Ok(method) => format!(
+                    "Copied the config ({}, {} bytes) to the clipboard via {}",
+                    format.description(),
+                    content.len(),
+                    method.label()
+                ),
+                Err(e) => format!("Copy failed: {e}"),
+            },
+            Err(e) => format!("Copy failed: {e}"),
+        };
+    }
+
+    /// Render the selected export format and upload it as a gist in the
+    /// background, the URL is shown once GitHub has answered
+    async fn share_export_as_gist(&mut self, public: bool) {
+        if self.gist_task.is_some() {
+            return;
+        }
+        let content = match self.render_selected_export().await {
+            Ok(content) => content,
+            Err(e) => {
+                self.ui.popup_message = format!("Sharing failed: {e}");
+                return;
+            }
+        };
+        let format = self.ui.selected_export_format.to_config_format();
+        let visibility = if public { "public" } else { "secret" };
+        self.ui.popup_message = format!("Uploading the config to a {visibility} gist...");
+
+        let filename = format!("hyprland.{}", format.extension());
+        let description = "Hyprland config shared from r-hyprconfig";
+        self.gist_task = Some(tokio::spawn(async move {
+            GitHubClient::new()?
+                .create_gist(&filename, &content, description, public)
+                .await
+        }));
+    }
+
+    /// Show the gist URL once the upload has finished
+    async fn poll_gist_upload(&mut self) {
+        if !self
+            .gist_task
+            .as_ref()
+            .is_some_and(|task| task.is_finished())
+        {
+            return;
+        }
+        let Some(task) = self.gist_task.take() else {
+            return;
+        };
+
+        self.ui.show_popup = true;
+        self.ui.popup_message = match task.await {
+            Ok(Ok(url)) => format!("Config shared as a gist: {url}"),
+            Ok(Err(e)) => format!("Sharing failed: {e}"),
+            Err(e) => format!("Sharing failed, the upload stopped: {e}"),
+        };
+    }
+
     pub async fn show_add_item_dialog(&mut self) {
         // Show dialog to add new configuration items based on current panel
         match self.ui.current_tab {
@@ -2330,14 +4948,64 @@ impl App {
                 // Start editing mode for adding new layer rule
                 self.ui.start_add_layer_rule();
             }
+            crate::app::FocusedPanel::Workspaces => {
+                self.ui.show_popup = true;
+                self.ui.popup_message =
+                    "Add Workspace Rule: Press Enter to edit the new workspace rule".to_string();
+                self.ui.start_add_workspace_rule();
+            }
+            crate::app::FocusedPanel::Environment => {
+                self.ui.show_popup = true;
+                self.ui.popup_message =
+                    "Add Environment Variable: Press Enter to edit the new variable".to_string();
+                self.ui.start_add_env_var();
+            }
+            crate::app::FocusedPanel::Plugins => {
+                self.ui.show_popup = true;
+                self.ui.popup_message =
+                    "Add Plugin: Press Enter to enter the path of the plugin's .so file"
+                        .to_string();
+                self.ui.start_add_plugin();
+            }
+            crate::app::FocusedPanel::Autostart => {
+                self.ui.show_popup = true;
+                self.ui.popup_message =
+                    "Add Autostart Command: Press Enter to edit the new command".to_string();
+                self.ui.start_add_exec();
+            }
+            crate::app::FocusedPanel::Variables => {
+                self.ui.show_popup = true;
+                self.ui.popup_message =
+                    "Add Variable: Press Enter to name the new variable and give it a value"
+                        .to_string();
+                self.ui.start_add_variable();
+            }
             _ => {
                 self.ui.show_popup = true;
-                self.ui.popup_message = "Add Item: Not available for this panel. Use 'I' key in Binds, Window Rules, or Layer Rules panels.".to_string();
+                self.ui.popup_message = "Add Item: Not available for this panel. Use 'I' key in Binds, Window Rules, Layer Rules, Workspaces, Environment, Plugins, Autostart, or Variables panels.".to_string();
             }
         }
     }
 
     pub async fn show_delete_item_dialog(&mut self) {
+        // Marked items are deleted together, after one confirmation
+        let panel = self.ui.current_tab;
+        let marked: Vec<String> = self
+            .ui
+            .marked_keys(panel)
+            .into_iter()
+            .filter(|key| !is_plugin_option(key))
+            .collect();
+        if !marked.is_empty() && panel.has_deletable_items() {
+            self.ui.show_popup = true;
+            self.ui.popup_message = format!(
+                "Delete {} marked items? - Press 'Y' to confirm, any other key to cancel",
+                marked.len()
+            );
+            self.ui.pending_bulk_deletion = Some((panel, marked));
+            return;
+        }
+
         // Show dialog to delete the currently selected item
         match self.ui.current_tab {
             crate::app::FocusedPanel::Binds => {
@@ -2377,9 +5045,78 @@ impl App {
                     self.ui.pending_deletion = Some((self.ui.current_tab, key));
                 }
             }
+            crate::app::FocusedPanel::Workspaces => {
+                if let Some(selected) = self.ui.get_selected_item() {
+                    let value = selected.value.clone();
+                    let key = selected.key.clone();
+                    self.ui.show_popup = true;
+                    self.ui.popup_message = format!(
+                        "Delete Workspace Rule: '{}' - Press 'Y' to confirm, any other key to cancel",
+                        value
+                    );
+                    self.ui.pending_deletion = Some((self.ui.current_tab, key));
+                }
+            }
+            crate::app::FocusedPanel::Environment => {
+                if let Some(selected) = self.ui.get_selected_item() {
+                    let value = selected.value.clone();
+                    let key = selected.key.clone();
+                    self.ui.show_popup = true;
+                    self.ui.popup_message = format!(
+                        "Delete Environment Variable: '{}' - Press 'Y' to confirm, any other key to cancel",
+                        value
+                    );
+                    self.ui.pending_deletion = Some((self.ui.current_tab, key));
+                }
+            }
+            // Plugin options go away with their plugin, only its line can be deleted
+            crate::app::FocusedPanel::Plugins => {
+                if let Some(selected) = self
+                    .ui
+                    .get_selected_item()
+                    .filter(|item| !is_plugin_option(&item.key))
+                {
+                    let value = selected.value.clone();
+                    let key = selected.key.clone();
+                    self.ui.show_popup = true;
+                    self.ui.popup_message = format!(
+                        "Delete Plugin: '{}' - Press 'Y' to confirm, any other key to cancel",
+                        value
+                    );
+                    self.ui.pending_deletion = Some((self.ui.current_tab, key));
+                }
+            }
+            crate::app::FocusedPanel::Autostart => {
+                if let Some(selected) = self.ui.get_selected_item() {
+                    let value = selected.value.clone();
+                    let key = selected.key.clone();
+                    self.ui.show_popup = true;
+                    self.ui.popup_message = format!(
+                        "Delete Autostart Command: '{}' - Press 'Y' to confirm, any other key to cancel",
+                        value
+                    );
+                    self.ui.pending_deletion = Some((self.ui.current_tab, key));
+                }
+            }
+            crate::app::FocusedPanel::Variables => {
+                if let Some(selected) = self.ui.get_selected_item() {
+                    let value = selected.value.clone();
+                    let key = selected.key.clone();
+                    let in_use = match self.ui.variable_uses(&value) {
+                        0 => String::new(),
+                        1 => " (1 line still uses it)".to_string(),
+                        n => format!(" ({n} lines still use it)"),
+                    };
+                    self.ui.show_popup = true;
+                    self.ui.popup_message = format!(
+                        "Delete Variable: '{value}'{in_use} - Press 'Y' to confirm, any other key to cancel"
+                    );
+                    self.ui.pending_deletion = Some((self.ui.current_tab, key));
+                }
+            }
             _ => {
                 self.ui.show_popup = true;
-                self.ui.popup_message = "Delete Item: Not available for this panel. Use 'D' key in Binds, Window Rules, or Layer Rules panels.".to_string();
+                self.ui.popup_message = "Delete Item: Not available for this panel. Use 'D' key in Binds, Window Rules, Layer Rules, Workspaces, Environment, Plugins, Autostart, or Variables panels.".to_string();
             }
         }
     }
@@ -2438,6 +5175,7 @@ impl App {
     }
 
     /// Handle error with automatic recovery attempts
+    #[allow(clippy::collapsible_match)]
     pub async fn handle_error_with_recovery(&mut self, error: crate::errors::HyprConfigError, operation: &str) {
         use crate::errors::{HyprConfigError, RecoveryContext, RecoveryStrategy};
         
@@ -2468,7 +5206,7 @@ impl App {
                 // Try creating parent directories if it's a write operation
                 if op.contains("write") || op.contains("create") {
                     if let Some(parent) = path.parent() {
-                        if let Err(_) = tokio::fs::create_dir_all(parent).await {
+                        if tokio::fs::create_dir_all(parent).await.is_err() {
                             // Directory creation failed, show original error
                         } else {
                             self.ui.show_popup = true;
@@ -2487,3 +5225,167 @@ impl App {
         self.display_error_with_recovery(&error);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An app editing `config`, with a hyprctl that never runs anything
+    async fn test_app(config: Config, batch_dir: &std::path::Path) -> App {
+        App {
+            state: AppState::Running,
+            debug: false,
+            focused_panel: FocusedPanel::General,
+            config,
+            hyprctl: HyprCtl::new_file_only(),
+            ui: UI::new(),
+            batch_manager: BatchManager::new(batch_dir.to_path_buf()).await.unwrap(),
+            last_tick: Instant::now(),
+            tick_rate: Duration::from_millis(50),
+            state_manager: StateManager::new(),
+            command_dispatcher: CommandDispatcher::new(),
+            github_task: None,
+            github_download: None,
+            url_task: None,
+            url_download: None,
+            gist_task: None,
+            folder_merge: None,
+            folder_conflict: 0,
+            config_changes: None,
+            config_change_pending: false,
+            pending_vim_key: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_saving_reloads_hyprland_only_when_asked() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            hyprland_config_path: temp_dir.path().join("hyprland.conf"),
+            reload_after_save: false,
+            ..Config::default()
+        };
+        let mut app = test_app(config, temp_dir.path()).await;
+        app.ui.hyprland_status = crate::hyprctl::HyprlandStatus::Connected;
+        app.ui.config_items.clear();
+        app.ui.add_imported_exec("exec-once = waybar");
+
+        app.save_and_reload().await.unwrap();
+        let saved = std::fs::read_to_string(temp_dir.path().join("hyprland.conf")).unwrap();
+        assert!(saved.contains("exec-once = waybar"));
+        assert_eq!(app.hyprctl.reload_count(), 0);
+        assert_eq!(app.ui.popup_message, "Configuration saved successfully!");
+
+        // Ctrl+S on one item follows the same setting
+        let save_line = |line: &str| crate::ui::ItemSave::Line {
+            file: None,
+            old_lines: Vec::new(),
+            line: line.to_string(),
+        };
+        app.save_item(save_line("exec-once = mako")).await;
+        assert_eq!(app.hyprctl.reload_count(), 0);
+        assert_eq!(app.ui.popup_message, "Saved 'exec-once = mako'");
+
+        // With the setting on, Hyprland is reloaded once per save
+        app.config.reload_after_save = true;
+        app.save_and_reload().await.unwrap();
+        assert_eq!(app.hyprctl.reload_count(), 1);
+        app.save_item(save_line("exec-once = dunst")).await;
+        assert_eq!(app.hyprctl.reload_count(), 2);
+        assert!(app
+            .ui
+            .popup_message
+            .starts_with("Saved 'exec-once = dunst', but reloading Hyprland failed"));
+    }
+
+    #[tokio::test]
+    async fn test_options_keep_the_variables_they_use_when_one_is_edited() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("hyprland.conf");
+        let content = "$gap = 5\n$accent = rgba(33ccffee)\n\ngeneral {\n    gaps_in = $gap\n    col.active_border = $accent\n}\n";
+        std::fs::write(&path, content).unwrap();
+        let config = Config {
+            hyprland_config_path: path.clone(),
+            ..Config::default()
+        };
+        let mut app = test_app(config, temp_dir.path()).await;
+
+        // Loaded with the options as hyprctl reports them, variables resolved
+        let option = |key: &str, value: &str, data_type| {
+            crate::ui::ConfigItem::new(key.to_string(), value.to_string(), String::new(), data_type)
+        };
+        app.ui.config_items.clear();
+        app.ui.config_items.insert(
+            FocusedPanel::General,
+            vec![
+                option(
+                    "general:gaps_in",
+                    "5",
+                    crate::ui::ConfigDataType::Integer {
+                        min: None,
+                        max: None,
+                    },
+                ),
+                option(
+                    "general:col.active_border",
+                    "ee33ccff",
+                    crate::ui::ConfigDataType::Color,
+                ),
+            ],
+        );
+        for line in ["$gap = 5", "$accent = rgba(33ccffee)"] {
+            app.ui.add_imported_variable(line);
+        }
+        app.ui.record_saved_values();
+
+        let key = app.ui.config_items[&FocusedPanel::Variables][0].key.clone();
+        app.ui
+            .set_item_value(FocusedPanel::Variables, &key, "$gap = 8");
+        app.save_and_reload().await.unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(saved, content.replace("$gap = 5", "$gap = 8"));
+    }
+
+    #[tokio::test]
+    async fn test_marked_booleans_report_options_that_failed_to_apply() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut app = test_app(Config::default(), temp_dir.path()).await;
+        let keys = [
+            "misc:vfr",
+            "misc:vrr",
+            "misc:disable_autoreload",
+            "misc:focus_on_activate",
+        ];
+        let items = keys
+            .iter()
+            .map(|key| {
+                crate::ui::ConfigItem::new(
+                    key.to_string(),
+                    "false".to_string(),
+                    String::new(),
+                    crate::ui::ConfigDataType::Boolean,
+                )
+            })
+            .collect();
+        app.ui.config_items.insert(FocusedPanel::General, items);
+        app.ui.current_tab = FocusedPanel::General;
+        app.ui.general_list_state.select(Some(0));
+        for _ in keys {
+            app.ui.toggle_mark_selected();
+        }
+        app.ui.toggle_preview_mode();
+
+        // More options than the batch threshold go out together, and the
+        // file-only hyprctl fails every one of them
+        app.toggle_selected_boolean().await;
+        assert!(app.ui.show_popup);
+        assert!(app
+            .ui
+            .popup_message
+            .starts_with("Failed to apply 4 of 4 options:"));
+        for key in keys {
+            assert!(app.ui.popup_message.contains(&format!("\n  {key}: ")));
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// Autostart
+// `exec-once = COMMAND` runs a command when Hyprland starts and `exec =
+// COMMAND` on every reload as well. The Autostart panel keeps a disabled
+// command as a commented-out line, `# exec-once = COMMAND`, the way the
+// Plugins panel does, and Hyprland runs the lines in the order they appear.
+
+use crate::ui::ConfigItem;
+
+/// Keywords that run a command. The `execr` ones run it without the window
+/// rules in front of it, `exec-shutdown` runs it when Hyprland exits.
+pub const EXEC_KEYWORDS: [&str; 5] = ["exec-once", "exec", "execr-once", "execr", "exec-shutdown"];
+
+/// A single `exec-once`, `exec` or related line
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecLine {
+    pub keyword: &'static str,
+    pub command: String,
+    /// Disabled commands are commented out
+    pub enabled: bool,
+}
+
+impl ExecLine {
+    /// Parse `exec-once = COMMAND`, or `# exec-once = COMMAND` for a
+    /// disabled command
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let (enabled, body) = match line.strip_prefix('#') {
+            Some(rest) => (false, rest.trim_start()),
+            None => (true, line),
+        };
+        let (keyword, command) = body.split_once('=')?;
+        let keyword = EXEC_KEYWORDS
+            .into_iter()
+            .find(|known| *known == keyword.trim())?;
+        let command = strip_comment(command).trim();
+        if command.is_empty() {
+            return None;
+        }
+        Some(ExecLine {
+            keyword,
+            command: command.to_string(),
+            enabled,
+        })
+    }
+
+    /// The whole config line
+    pub fn to_line(&self) -> String {
+        if self.enabled {
+            format!("{} = {}", self.keyword, self.command)
+        } else {
+            format!("# {} = {}", self.keyword, self.command)
+        }
+    }
+
+    /// When Hyprland runs the command
+    pub fn describe(&self) -> &'static str {
+        match self.keyword {
+            "exec-once" | "execr-once" => "Runs once when Hyprland starts",
+            "exec-shutdown" => "Runs when Hyprland exits",
+            _ => "Runs on start and on every config reload",
+        }
+    }
+
+    /// The program the command starts, after any `[rules]` for its window
+    /// and `NAME=value` assignments. `None` when it is a Hyprland variable
+    /// like `$terminal`, which only Hyprland can resolve.
+    pub fn program(&self) -> Option<&str> {
+        let mut command = self.command.trim();
+        if let Some(rest) = command.strip_prefix('[') {
+            command = rest.split_once(']')?.1;
+        }
+        command
+            .split_whitespace()
+            .find(|token| !is_assignment(token))
+            .filter(|program| !program.starts_with('$'))
+    }
+
+    /// The program, if it can't be found on PATH
+    pub fn missing_program(&self) -> Option<&str> {
+        self.program().filter(|program| !is_on_path(program))
+    }
+}
+
+/// `text` up to its comment. `##` is an escaped `#`, not a comment.
+fn strip_comment(text: &str) -> &str {
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '#' && chars.next_if(|(_, next)| *next == '#').is_none() {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// Whether a token sets an environment variable for the command, like `GDK_SCALE=2`
+fn is_assignment(token: &str) -> bool {
+    token.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// Whether a program can be run: a path to it exists, or it is on PATH
+pub fn is_on_path(program: &str) -> bool {
+    which::which(shellexpand::tilde(program).as_ref()).is_ok()
+}
+
+/// Whether a config line runs a command, or would if it weren't commented out
+pub fn is_exec_line(line: &str) -> bool {
+    ExecLine::parse(line).is_some()
+}
+
+/// Keys of the enabled Autostart items whose program isn't on PATH. Hyprland
+/// runs them anyway and they fail without a word.
+pub fn missing_program_keys(items: &[ConfigItem]) -> Vec<String> {
+    items
+        .iter()
+        .filter(|item| {
+            ExecLine::parse(&item.value)
+                .is_some_and(|exec| exec.enabled && exec.missing_program().is_some())
+        })
+        .map(|item| item.key.clone())
+        .collect()
+}
+
+/// Check an edited autostart line
+pub fn validate_line(line: &str) -> Result<(), String> {
+    ExecLine::parse(line)
+        .map(|_| ())
+        .ok_or_else(|| "expected exec-once = COMMAND or exec = COMMAND".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::ConfigDataType;
+
+    fn item(key: &str, value: &str) -> ConfigItem {
+        ConfigItem::new(
+            key.to_string(),
+            value.to_string(),
+            String::new(),
+            ConfigDataType::String,
+        )
+    }
+
+    #[test]
+    fn test_parse_enabled_and_disabled_lines() {
+        let exec = ExecLine::parse("exec-once = waybar & hyprpaper").unwrap();
+        assert!(exec.enabled);
+        assert_eq!(exec.keyword, "exec-once");
+        assert_eq!(exec.command, "waybar & hyprpaper");
+
+        let exec = ExecLine::parse("  #exec=notify-send 'Issue ##42' # old").unwrap();
+        assert!(!exec.enabled);
+        assert_eq!(exec.keyword, "exec");
+        assert_eq!(exec.command, "notify-send 'Issue ##42'");
+        assert_eq!(exec.to_line(), "# exec = notify-send 'Issue ##42'");
+
+        for other in [
+            "exec-once =",
+            "execute = a",
+            "# start the bar",
+            "bind = SUPER, Q, exec, kitty",
+        ] {
+            assert!(!is_exec_line(other), "{other}");
+        }
+    }
+
+    #[test]
+    fn test_toggling_round_trips() {
+        let mut exec = ExecLine::parse("exec-once = hypridle").unwrap();
+        exec.enabled = false;
+        let disabled = exec.to_line();
+        assert_eq!(disabled, "# exec-once = hypridle");
+        let mut exec = ExecLine::parse(&disabled).unwrap();
+        exec.enabled = true;
+        assert_eq!(exec.to_line(), "exec-once = hypridle");
+    }
+
+    #[test]
+    fn test_program_skips_rules_and_assignments() {
+        let program = |line: &str| ExecLine::parse(line).unwrap().program().map(str::to_string);
+        assert_eq!(
+            program("exec-once = waybar -c ~/bar"),
+            Some("waybar".to_string())
+        );
+        assert_eq!(
+            program("exec-once = [workspace 2 silent] firefox"),
+            Some("firefox".to_string())
+        );
+        assert_eq!(
+            program("exec = GDK_SCALE=2 QT_QPA_PLATFORM=wayland app --flag=1"),
+            Some("app".to_string())
+        );
+        assert_eq!(program("exec-once = $terminal"), None);
+    }
+
+    #[test]
+    fn test_missing_programs_are_found() {
+        assert!(is_on_path("sh"));
+        assert!(!is_on_path("r-hyprconfig-no-such-program"));
+        assert!(!is_on_path("/nonexistent/bin/waybar"));
+
+        let items = vec![
+            item("exec_0", "exec-once = sh -c true"),
+            item("exec_1", "exec-once = r-hyprconfig-no-such-program"),
+            item("exec_2", "# exec-once = r-hyprconfig-no-such-program"),
+            item("exec_3", "exec = $terminal"),
+        ];
+        assert_eq!(missing_program_keys(&items), vec!["exec_1"]);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(validate_line("exec-once = waybar").is_ok());
+        assert!(validate_line("# exec = swaync").is_ok());
+        assert!(validate_line("exec-once = ").is_err());
+        assert!(validate_line("plugin = /a.so").is_err());
+    }
+}
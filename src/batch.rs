@@ -9,6 +9,11 @@ use std::path::{Path, PathBuf};
 use crate::config::Config;
 use crate::file_io::{FileOperations, FileUtils};
 use crate::hyprctl::HyprCtl;
+use crate::ui::UI;
+use crate::undo::ConfigSnapshot;
+
+/// Extension of snapshot profile files
+pub const PROFILE_EXTENSION: &str = "rhypr";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchProfile {
@@ -37,6 +42,38 @@ pub enum BatchOperationType {
     Backup,  // Create backups of all targets
 }
 
+/// What running a profile operation did
+#[derive(Debug, Clone, Default)]
+pub struct ProfileOutcome {
+    /// Options pushed to Hyprland
+    pub applied: Vec<String>,
+    /// Options Hyprland rejected, with the reason
+    pub failed: Vec<(String, String)>,
+    /// The profile the previous config was snapshotted to
+    pub backup_profile: String,
+}
+
+impl ProfileOutcome {
+    /// One-line summary for the popup
+    pub fn summary(&self, operation: &BatchOperationType, profile: &str) -> String {
+        let mut summary = match operation {
+            BatchOperationType::Backup => {
+                format!("Current config saved as profile '{}'", self.backup_profile)
+            }
+            _ => format!(
+                "{operation:?} with profile '{profile}': {} applied, {} failed. Previous config saved as profile '{}'",
+                self.applied.len(),
+                self.failed.len(),
+                self.backup_profile
+            ),
+        };
+        if let Some((key, error)) = self.failed.first() {
+            summary.push_str(&format!(" (first failure: {key}: {error})"));
+        }
+        summary
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BatchExecutionResult {
     #[allow(dead_code)]
@@ -51,12 +88,15 @@ pub struct BatchExecutionResult {
 pub struct BatchManager {
     profiles: HashMap<String, BatchProfile>,
     config_dir: PathBuf,
+    /// Snapshot profiles live here, one `<name>.rhypr` file each
+    profiles_dir: PathBuf,
 }
 
 impl BatchManager {
     pub async fn new(config_dir: PathBuf) -> Result<Self> {
         let mut manager = Self {
             profiles: HashMap::new(),
+            profiles_dir: config_dir.join("profiles"),
             config_dir,
         };
 
@@ -91,8 +131,8 @@ impl BatchManager {
             name: name.clone(),
             description,
             config_paths,
-            created_at: now.into(),
-            last_modified: now.into(),
+            created_at: now,
+            last_modified: now,
         };
 
         self.profiles.insert(name, profile);
@@ -122,6 +162,114 @@ impl BatchManager {
         self.profiles.get(name)
     }
 
+    /// Save `snapshot` as the named profile
+    pub async fn create_snapshot(&self, name: &str, snapshot: &ConfigSnapshot) -> Result<PathBuf> {
+        validate_profile_name(name)?;
+        let path = self.snapshot_path(name);
+        if path.exists() {
+            return Err(anyhow!("Profile '{}' already exists", name));
+        }
+
+        FileUtils::ensure_directory(&self.profiles_dir)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create profiles directory: {}", e))?;
+        FileUtils::safe_write(&path, &snapshot_to_rhypr(name, snapshot)?)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to write profile: {}", e))?;
+
+        Ok(path)
+    }
+
+    /// Names of the saved snapshot profiles, sorted
+    pub fn list_snapshots(&self) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(&self.profiles_dir) else {
+            return Vec::new();
+        };
+
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == PROFILE_EXTENSION))
+            .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub async fn load_snapshot(&self, name: &str) -> Result<ConfigSnapshot> {
+        let content = FileUtils::resilient_read(self.snapshot_path(name))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read profile '{}': {}", name, e))?;
+        snapshot_from_rhypr(&content)
+    }
+
+    pub async fn delete_snapshot(&self, name: &str) -> Result<()> {
+        let path = self.snapshot_path(name);
+        if !path.exists() {
+            return Err(anyhow!("Profile '{}' does not exist", name));
+        }
+
+        tokio::fs::remove_file(&path).await?;
+        Ok(())
+    }
+
+    /// Run `operation` with the named profile against the running session.
+    /// The current config is always snapshotted to a `backup_*` profile first,
+    /// which is all Backup does. Apply pushes every option in the profile
+    /// through hyprctl, Merge only the ones whose value differs from the
+    /// current one, and Replace clears the current items and applies the
+    /// profile. The UI's items are updated to match.
+    pub async fn execute(
+        &self,
+        operation: &BatchOperationType,
+        profile: &str,
+        ui: &mut UI,
+        hyprctl: &HyprCtl,
+    ) -> Result<ProfileOutcome> {
+        let backup_profile = format!("backup_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S_%3f"));
+        let backup = ui.profile_snapshot(Some(format!("Before {operation:?} with '{profile}'")));
+        self.create_snapshot(&backup_profile, &backup).await?;
+
+        let mut outcome = ProfileOutcome {
+            backup_profile,
+            ..Default::default()
+        };
+        if *operation == BatchOperationType::Backup {
+            return Ok(outcome);
+        }
+
+        let snapshot = self.load_snapshot(profile).await?;
+        let profile_options = ui.snapshot_options(&snapshot);
+        let options = match operation {
+            BatchOperationType::Merge => {
+                changed_options(&ui.collect_all_config_changes(), &profile_options)
+            }
+            _ => profile_options,
+        };
+        ui.apply_profile(&snapshot, operation);
+
+        match hyprctl.set_options_batch(&options).await {
+            Ok(result) => {
+                outcome.applied = result.applied;
+                outcome.failed = result.failed;
+            }
+            Err(e) => {
+                let mut keys: Vec<&String> = options.keys().collect();
+                keys.sort();
+                outcome.failed = keys
+                    .into_iter()
+                    .map(|key| (key.clone(), e.to_string()))
+                    .collect();
+            }
+        }
+        Ok(outcome)
+    }
+
+    fn snapshot_path(&self, name: &str) -> PathBuf {
+        self.profiles_dir
+            .join(format!("{name}.{PROFILE_EXTENSION}"))
+    }
+
     pub async fn execute_batch_operation(
         &self,
         operation: &BatchOperation,
@@ -380,6 +528,55 @@ impl BatchManager {
     }
 }
 
+/// The profile options whose value differs from the current one, including
+/// options that aren't set at all yet
+pub fn changed_options(
+    current: &HashMap<String, String>,
+    profile: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    profile
+        .iter()
+        .filter(|(key, value)| current.get(*key).map(|v| v.trim()) != Some(value.trim()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Profile names become file names, so path separators and leading dots are
+/// rejected
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        return Err(anyhow!("Profile name cannot be empty"));
+    }
+    if name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(anyhow!("Profile name '{}' is not a valid file name", name));
+    }
+    Ok(())
+}
+
+/// Format a snapshot the way `.rhypr` exports are written: a commented
+/// header followed by the JSON content
+pub fn snapshot_to_rhypr(name: &str, snapshot: &ConfigSnapshot) -> Result<String> {
+    let mut content = String::new();
+    content.push_str("# r-hyprconfig profile\n");
+    content.push_str(&format!("# Name: {name}\n"));
+    if let Some(description) = &snapshot.description {
+        content.push_str(&format!("# Description: {description}\n"));
+    }
+    content.push_str(&format!(
+        "# Created: {}\n\n",
+        snapshot.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+    ));
+    content.push_str(&serde_json::to_string_pretty(snapshot)?);
+    Ok(content)
+}
+
+pub fn snapshot_from_rhypr(content: &str) -> Result<ConfigSnapshot> {
+    let json_start = content
+        .find('{')
+        .ok_or_else(|| anyhow!("Invalid profile: no JSON content found"))?;
+    Ok(serde_json::from_str(&content[json_start..])?)
+}
+
 // Helper trait to extend Config with batch-related methods
 pub trait ConfigBatchExt {
     fn set_value(&mut self, key: &str, value: &str) -> Result<()>;
@@ -480,6 +677,104 @@ mod tests {
         assert!(manager.get_profile("test_profile").is_some());
     }
 
+    #[tokio::test]
+    async fn test_snapshot_profiles_round_trip() {
+        use crate::app::FocusedPanel;
+        use crate::ui::{ConfigDataType, ConfigItem};
+
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BatchManager::new(temp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+
+        let mut items = HashMap::new();
+        items.insert(
+            FocusedPanel::General,
+            vec![ConfigItem {
+                key: "gaps_in".to_string(),
+                value: "8".to_string(),
+                description: String::new(),
+                data_type: ConfigDataType::Integer {
+                    min: Some(0),
+                    max: None,
+                },
+                suggestions: Vec::new(),
+                source_file: None,
+            }],
+        );
+        let snapshot = ConfigSnapshot::new(items, Some("Laptop".to_string()));
+
+        let path = manager.create_snapshot("laptop", &snapshot).await.unwrap();
+        assert_eq!(path, temp_dir.path().join("profiles").join("laptop.rhypr"));
+        assert!(manager.create_snapshot("laptop", &snapshot).await.is_err());
+        assert!(manager
+            .create_snapshot("../escape", &snapshot)
+            .await
+            .is_err());
+        assert_eq!(manager.list_snapshots(), vec!["laptop".to_string()]);
+
+        let loaded = manager.load_snapshot("laptop").await.unwrap();
+        assert_eq!(loaded.description.as_deref(), Some("Laptop"));
+        assert_eq!(loaded.config_items[&FocusedPanel::General][0].value, "8");
+
+        manager.delete_snapshot("laptop").await.unwrap();
+        assert!(manager.list_snapshots().is_empty());
+    }
+
+    #[test]
+    fn test_merge_only_sets_changed_keys() {
+        let current = HashMap::from([
+            ("general:gaps_in".to_string(), "5".to_string()),
+            ("general:gaps_out".to_string(), "20".to_string()),
+            ("decoration:rounding".to_string(), "8".to_string()),
+        ]);
+        let profile = HashMap::from([
+            ("general:gaps_in".to_string(), "10".to_string()),
+            ("general:gaps_out".to_string(), " 20 ".to_string()),
+            ("input:kb_layout".to_string(), "de".to_string()),
+        ]);
+
+        let changed = changed_options(&current, &profile);
+        assert_eq!(
+            changed,
+            HashMap::from([
+                ("general:gaps_in".to_string(), "10".to_string()),
+                ("input:kb_layout".to_string(), "de".to_string()),
+            ])
+        );
+        assert!(changed_options(&profile, &profile).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_backup_snapshots_the_current_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = BatchManager::new(temp_dir.path().to_path_buf())
+            .await
+            .unwrap();
+        let mut ui = UI::new();
+        let hyprctl = HyprCtl::new_disconnected();
+
+        let outcome = manager
+            .execute(&BatchOperationType::Backup, "unused", &mut ui, &hyprctl)
+            .await
+            .unwrap();
+        assert!(outcome.backup_profile.starts_with("backup_"));
+        assert!(outcome.applied.is_empty() && outcome.failed.is_empty());
+        assert_eq!(
+            manager.list_snapshots(),
+            vec![outcome.backup_profile.clone()]
+        );
+
+        let backup = manager
+            .load_snapshot(&outcome.backup_profile)
+            .await
+            .unwrap();
+        assert_eq!(
+            backup.config_items.len(),
+            ui.profile_snapshot(None).config_items.len()
+        );
+    }
+
     #[test]
     fn test_batch_operation_creation() {
         let mut settings = HashMap::new();
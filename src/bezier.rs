@@ -0,0 +1,172 @@
+// Bezier curves for animations
+// `bezier = NAME, X0, Y0, X1, Y1` sets the two control points of a cubic
+// curve from (0, 0) to (1, 1). X is time and must stay within 0..1; Y is
+// progress and may overshoot for a bounce.
+
+/// Control point coordinates outside these ranges make no sense for an animation
+const X_RANGE: (f32, f32) = (0.0, 1.0);
+const Y_RANGE: (f32, f32) = (-1.0, 2.0);
+/// How far one arrow key press moves a control point
+pub const NUDGE_STEP: f32 = 0.05;
+
+/// The two control points of a curve, `[x0, y0, x1, y1]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bezier {
+    pub points: [f32; 4],
+}
+
+impl Bezier {
+    /// Parse the four comma-separated numbers of a bezier value, clamped to
+    /// sane ranges
+    pub fn parse(value: &str) -> Option<Self> {
+        let numbers: Vec<f32> = value
+            .split(',')
+            .map(|part| part.trim().parse::<f32>())
+            .collect::<Result<_, _>>()
+            .ok()?;
+        let points: [f32; 4] = numbers.try_into().ok()?;
+        if points.iter().any(|p| !p.is_finite()) {
+            return None;
+        }
+        let mut curve = Self { points };
+        for (index, value) in points.into_iter().enumerate() {
+            curve.set(index, value);
+        }
+        Some(curve)
+    }
+
+    /// The value in the format it was read in, like `0.05, 0.9, 0.1, 1.05`
+    pub fn to_value(&self) -> String {
+        self.points
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Set one coordinate, clamped and rounded to two decimals so repeated
+    /// nudges don't accumulate float noise
+    pub fn set(&mut self, index: usize, value: f32) {
+        let (min, max) = if index.is_multiple_of(2) {
+            X_RANGE
+        } else {
+            Y_RANGE
+        };
+        self.points[index] = ((value.clamp(min, max) * 100.0).round() / 100.0).clamp(min, max);
+    }
+
+    /// Move control point `point` (0 or 1) by `dx`, `dy`
+    pub fn nudge(&mut self, point: usize, dx: f32, dy: f32) {
+        let (x, y) = (point * 2, point * 2 + 1);
+        self.set(x, self.points[x] + dx);
+        self.set(y, self.points[y] + dy);
+    }
+
+    /// The point of the curve at `t` in 0..1
+    pub fn at(&self, t: f32) -> (f32, f32) {
+        let [x0, y0, x1, y1] = self.points;
+        let u = 1.0 - t;
+        let blend = |p0: f32, p1: f32| 3.0 * u * u * t * p0 + 3.0 * u * t * t * p1 + t * t * t;
+        (blend(x0, x1), blend(y0, y1))
+    }
+
+    /// The curve drawn with braille characters, `width` by `height` cells of
+    /// 2 by 4 dots each, top row first. The plot covers 0..1 in both
+    /// directions, stretched to fit any overshoot.
+    pub fn plot(&self, width: usize, height: usize) -> Vec<String> {
+        let (dots_x, dots_y) = (width * 2, height * 4);
+        let mut dots = vec![vec![false; dots_x]; dots_y];
+
+        let samples = dots_x * dots_y;
+        let curve: Vec<(f32, f32)> = (0..=samples)
+            .map(|i| self.at(i as f32 / samples as f32))
+            .collect();
+        let low = curve.iter().map(|p| p.1).fold(0.0, f32::min);
+        let high = curve.iter().map(|p| p.1).fold(1.0, f32::max);
+        for (x, y) in curve {
+            let column = (x * (dots_x - 1) as f32).round() as usize;
+            let row = ((high - y) / (high - low) * (dots_y - 1) as f32).round() as usize;
+            dots[row.min(dots_y - 1)][column.min(dots_x - 1)] = true;
+        }
+
+        // Dot bits of a braille cell, by row and column
+        const BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+        (0..height)
+            .map(|cell_row| {
+                (0..width)
+                    .map(|cell_column| {
+                        let mut bits = 0;
+                        for (dy, row_bits) in BITS.iter().enumerate() {
+                            for (dx, bit) in row_bits.iter().enumerate() {
+                                if dots[cell_row * 4 + dy][cell_column * 2 + dx] {
+                                    bits |= bit;
+                                }
+                            }
+                        }
+                        char::from_u32(0x2800 + bits).unwrap_or(' ')
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_round_trip_in_the_same_format() {
+        for value in [
+            "0.05, 0.9, 0.1, 1.05",
+            "0.25, 0.46, 0.45, 0.94",
+            "0.16, 1, 0.3, 1",
+        ] {
+            assert_eq!(Bezier::parse(value).unwrap().to_value(), value);
+        }
+        assert_eq!(
+            Bezier::parse("0.05,0.9,0.1,1.05").unwrap().to_value(),
+            "0.05, 0.9, 0.1, 1.05"
+        );
+        assert!(Bezier::parse("0.05, 0.9, 0.1").is_none());
+        assert!(Bezier::parse("0.05, 0.9, 0.1, fast").is_none());
+        assert!(Bezier::parse("myBezier, 0.05, 0.9, 0.1, 1.05").is_none());
+    }
+
+    #[test]
+    fn test_control_points_are_clamped() {
+        assert_eq!(
+            Bezier::parse("-0.5, 3, 1.5, -4").unwrap().points,
+            [0.0, 2.0, 1.0, -1.0]
+        );
+
+        let mut curve = Bezier::parse("0.05, 0.9, 0.1, 1.05").unwrap();
+        curve.nudge(0, -NUDGE_STEP, NUDGE_STEP);
+        assert_eq!(curve.to_value(), "0, 0.95, 0.1, 1.05");
+        curve.nudge(0, -NUDGE_STEP, 0.0);
+        assert_eq!(curve.points[0], 0.0);
+        for _ in 0..3 {
+            curve.nudge(1, NUDGE_STEP, NUDGE_STEP);
+        }
+        assert_eq!(curve.to_value(), "0, 0.95, 0.25, 1.2");
+    }
+
+    #[test]
+    fn test_plot_runs_corner_to_corner() {
+        let linear = Bezier::parse("0, 0, 1, 1").unwrap();
+        assert_eq!(linear.at(0.0), (0.0, 0.0));
+        assert_eq!(linear.at(1.0), (1.0, 1.0));
+
+        let plot = linear.plot(10, 4);
+        assert_eq!(plot.len(), 4);
+        assert!(plot.iter().all(|row| row.chars().count() == 10));
+        // Starts at the bottom left, ends at the top right
+        let bottom_left = plot[3].chars().next().unwrap() as u32 - 0x2800;
+        let top_right = plot[0].chars().last().unwrap() as u32 - 0x2800;
+        assert_ne!(bottom_left & 0x40, 0);
+        assert_ne!(top_right & 0x08, 0);
+        // Nothing in the opposite corners
+        assert_eq!(plot[0].chars().next(), Some('\u{2800}'));
+        assert_eq!(plot[3].chars().last(), Some('\u{2800}'));
+    }
+}
@@ -0,0 +1,141 @@
+// Clipboard access
+// Copies through `wl-copy` on Wayland, falling back to the OSC 52 escape
+// sequence that many terminals turn into a clipboard write. Pasting needs
+// `wl-paste`, since terminals rarely allow reading the clipboard.
+
+use anyhow::Result;
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// How a copy reached the clipboard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClipboardMethod {
+    WlCopy,
+    Osc52,
+}
+
+impl ClipboardMethod {
+    pub fn label(self) -> &'static str {
+        match self {
+            ClipboardMethod::WlCopy => "wl-copy",
+            ClipboardMethod::Osc52 => "terminal (OSC 52)",
+        }
+    }
+}
+
+/// Copy `text` to the clipboard, trying `wl-copy` first
+pub fn copy(text: &str) -> Result<ClipboardMethod> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() && wl_copy(text).is_ok() {
+        return Ok(ClipboardMethod::WlCopy);
+    }
+    if osc52_supported() {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(osc52_sequence(text).as_bytes())?;
+        stdout.flush()?;
+        return Ok(ClipboardMethod::Osc52);
+    }
+    anyhow::bail!(
+        "No clipboard available. Install wl-clipboard, or use a terminal that supports OSC 52."
+    )
+}
+
+/// Read the clipboard with `wl-paste`
+pub fn paste() -> Result<String> {
+    let output = Command::new("wl-paste")
+        .arg("--no-newline")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|_| {
+            anyhow::anyhow!("Pasting needs wl-paste. Install wl-clipboard to paste from here.")
+        })?;
+    if !output.status.success() {
+        anyhow::bail!("The clipboard is empty or holds no text");
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn wl_copy(text: &str) -> Result<()> {
+    // wl-copy forks to serve the clipboard, so don't keep its output pipes
+    let mut child = Command::new("wl-copy")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("wl-copy exited with {status}");
+    }
+    Ok(())
+}
+
+/// OSC 52 only works on a terminal, and not on the Linux console
+fn osc52_supported() -> bool {
+    let term = std::env::var("TERM").unwrap_or_default();
+    std::io::stdout().is_terminal() && !matches!(term.as_str(), "" | "dumb" | "linux")
+}
+
+/// The escape sequence asking the terminal to set the clipboard to `text`
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+}
+
+/// Config values are single lines, so pasted line breaks become spaces
+pub fn single_line(text: &str) -> String {
+    text.trim_end_matches(['\r', '\n'])
+        .split(['\r', '\n'])
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_padding() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"rgba(33ccffee)"), "cmdiYSgzM2NjZmZlZSk=");
+    }
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("10"), "\x1b]52;c;MTA=\x07");
+    }
+
+    #[test]
+    fn test_pasted_text_is_one_line() {
+        assert_eq!(single_line("rgba(33ccffee)\n"), "rgba(33ccffee)");
+        assert_eq!(single_line("a\r\nb\n\nc"), "a b c");
+        assert_eq!(single_line(" 5 "), " 5 ");
+    }
+}
@@ -0,0 +1,425 @@
+// Hyprland color values
+// Parses and formats the rgba(), rgb() and 0xAARRGGBB color notations,
+// and gradients made of several colors plus an optional angle
+
+use ratatui::style::Color;
+
+/// Notation a Hyprland color is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFormat {
+    /// rgba(rrggbbaa)
+    Rgba,
+    /// rgb(rrggbb)
+    Rgb,
+    /// 0xAARRGGBB
+    Hex,
+}
+
+impl ColorFormat {
+    /// Cycle to the next notation
+    pub fn next(self) -> Self {
+        match self {
+            ColorFormat::Rgba => ColorFormat::Rgb,
+            ColorFormat::Rgb => ColorFormat::Hex,
+            ColorFormat::Hex => ColorFormat::Rgba,
+        }
+    }
+}
+
+/// A single color channel, used to drive the color editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChannel {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+impl ColorChannel {
+    pub const ALL: [ColorChannel; 4] = [
+        ColorChannel::Red,
+        ColorChannel::Green,
+        ColorChannel::Blue,
+        ColorChannel::Alpha,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            ColorChannel::Red => ColorChannel::Green,
+            ColorChannel::Green => ColorChannel::Blue,
+            ColorChannel::Blue => ColorChannel::Alpha,
+            ColorChannel::Alpha => ColorChannel::Red,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            ColorChannel::Red => ColorChannel::Alpha,
+            ColorChannel::Green => ColorChannel::Red,
+            ColorChannel::Blue => ColorChannel::Green,
+            ColorChannel::Alpha => ColorChannel::Blue,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorChannel::Red => "R",
+            ColorChannel::Green => "G",
+            ColorChannel::Blue => "B",
+            ColorChannel::Alpha => "A",
+        }
+    }
+}
+
+/// An RGBA color as Hyprland understands it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HyprColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl HyprColor {
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Parse a color in any supported notation, returning the notation it was written in
+    pub fn parse(value: &str) -> Option<(Self, ColorFormat)> {
+        let value = value.trim();
+        let lower = value.to_ascii_lowercase();
+
+        if let Some(hex) = lower
+            .strip_prefix("rgba(")
+            .and_then(|v| v.strip_suffix(')'))
+        {
+            let [r, g, b, a] = parse_hex_bytes::<4>(hex.trim())?;
+            return Some((Self::new(r, g, b, a), ColorFormat::Rgba));
+        }
+
+        if let Some(hex) = lower.strip_prefix("rgb(").and_then(|v| v.strip_suffix(')')) {
+            let [r, g, b] = parse_hex_bytes::<3>(hex.trim())?;
+            return Some((Self::new(r, g, b, 0xff), ColorFormat::Rgb));
+        }
+
+        if let Some(hex) = lower.strip_prefix("0x") {
+            let [a, r, g, b] = parse_hex_bytes::<4>(hex)?;
+            return Some((Self::new(r, g, b, a), ColorFormat::Hex));
+        }
+
+        None
+    }
+
+    /// Format the color in the given notation. `rgb()` has no alpha channel,
+    /// so alpha is dropped when writing that form.
+    pub fn format(&self, format: ColorFormat) -> String {
+        match format {
+            ColorFormat::Rgba => format!(
+                "rgba({:02x}{:02x}{:02x}{:02x})",
+                self.r, self.g, self.b, self.a
+            ),
+            ColorFormat::Rgb => format!("rgb({:02x}{:02x}{:02x})", self.r, self.g, self.b),
+            ColorFormat::Hex => {
+                format!("0x{:02x}{:02x}{:02x}{:02x}", self.a, self.r, self.g, self.b)
+            }
+        }
+    }
+
+    pub fn channel(&self, channel: ColorChannel) -> u8 {
+        match channel {
+            ColorChannel::Red => self.r,
+            ColorChannel::Green => self.g,
+            ColorChannel::Blue => self.b,
+            ColorChannel::Alpha => self.a,
+        }
+    }
+
+    pub fn set_channel(&mut self, channel: ColorChannel, value: u8) {
+        match channel {
+            ColorChannel::Red => self.r = value,
+            ColorChannel::Green => self.g = value,
+            ColorChannel::Blue => self.b = value,
+            ColorChannel::Alpha => self.a = value,
+        }
+    }
+
+    /// Nudge a channel up or down, clamping at 0 and 255
+    pub fn adjust_channel(&mut self, channel: ColorChannel, delta: i16) {
+        let value = (self.channel(channel) as i16 + delta).clamp(0, 255) as u8;
+        self.set_channel(channel, value);
+    }
+
+    /// Truecolor swatch for the terminal, with alpha blended over black so
+    /// transparency is visible while editing
+    pub fn to_terminal_color(self) -> Color {
+        let blend = |c: u8| ((c as u16 * self.a as u16) / 255) as u8;
+        Color::Rgb(blend(self.r), blend(self.g), blend(self.b))
+    }
+
+    /// The color without its alpha channel
+    pub fn to_opaque_color(self) -> Color {
+        Color::Rgb(self.r, self.g, self.b)
+    }
+}
+
+/// The color of an option as `hyprctl getoption` reports it: the first stop
+/// of a gradient, or a plain color printed as a decimal 0xAARRGGBB number
+pub fn first_color(value: &str) -> Option<HyprColor> {
+    if let Some(gradient) = HyprGradient::parse(value) {
+        return gradient.stops.first().map(|stop| stop.color);
+    }
+    let [a, r, g, b] = (value.trim().parse::<i64>().ok()? as u32).to_be_bytes();
+    Some(HyprColor::new(r, g, b, a))
+}
+
+/// Hyprland caps gradients at this many color stops
+pub const MAX_GRADIENT_STOPS: usize = 10;
+
+/// One color of a gradient, remembering the notation it was written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorStop {
+    pub color: HyprColor,
+    pub format: ColorFormat,
+}
+
+/// A color value as written for options like `col.active_border`:
+/// one or more color stops followed by an optional `<angle>deg`.
+/// A plain color is a gradient with a single stop and no angle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyprGradient {
+    pub stops: Vec<ColorStop>,
+    pub angle: Option<i32>,
+}
+
+impl HyprGradient {
+    /// Parse `rgba(ee1100ff) rgba(1100eeff) 45deg` and friends. Bare hex
+    /// words, as printed by `hyprctl getoption`, are read as AARRGGBB.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut stops = Vec::new();
+        let mut angle = None;
+
+        for token in value.split_whitespace() {
+            // The angle may only come last
+            if angle.is_some() {
+                return None;
+            }
+
+            if let Some(degrees) = token.to_ascii_lowercase().strip_suffix("deg") {
+                angle = Some(degrees.parse::<i32>().ok()?);
+            } else if let Some((color, format)) = HyprColor::parse(token) {
+                stops.push(ColorStop { color, format });
+            } else {
+                let color = parse_bare_hex(token)?;
+                stops.push(ColorStop {
+                    color,
+                    format: ColorFormat::Hex,
+                });
+            }
+        }
+
+        if stops.is_empty() || stops.len() > MAX_GRADIENT_STOPS {
+            return None;
+        }
+
+        Some(Self { stops, angle })
+    }
+
+    /// Whether this holds more than a single plain color
+    pub fn is_gradient(&self) -> bool {
+        self.stops.len() > 1 || self.angle.is_some()
+    }
+
+    /// Insert a copy of the stop at `index` right after it, returning the new
+    /// stop's index, or `None` when the gradient is already full
+    pub fn duplicate_stop(&mut self, index: usize) -> Option<usize> {
+        if self.stops.len() >= MAX_GRADIENT_STOPS {
+            return None;
+        }
+        let stop = *self.stops.get(index)?;
+        self.stops.insert(index + 1, stop);
+        Some(index + 1)
+    }
+
+    /// Remove the stop at `index`, keeping at least one color
+    pub fn remove_stop(&mut self, index: usize) -> bool {
+        if self.stops.len() <= 1 || index >= self.stops.len() {
+            return false;
+        }
+        self.stops.remove(index);
+        true
+    }
+
+    /// Rotate the gradient, wrapping the angle into 0..360
+    pub fn rotate(&mut self, delta: i32) {
+        let angle = self.angle.unwrap_or(0) + delta;
+        self.angle = Some(angle.rem_euclid(360));
+    }
+}
+
+impl std::fmt::Display for HyprGradient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stops: Vec<String> = self
+            .stops
+            .iter()
+            .map(|stop| stop.color.format(stop.format))
+            .collect();
+        write!(f, "{}", stops.join(" "))?;
+
+        if let Some(angle) = self.angle {
+            write!(f, " {angle}deg")?;
+        }
+        Ok(())
+    }
+}
+
+/// hyprctl prints colors as unpadded AARRGGBB hex without a prefix
+fn parse_bare_hex(token: &str) -> Option<HyprColor> {
+    if token.is_empty() || token.len() > 8 {
+        return None;
+    }
+    let padded = format!("{token:0>8}");
+    let [a, r, g, b] = parse_hex_bytes::<4>(&padded.to_ascii_lowercase())?;
+    Some(HyprColor::new(r, g, b, a))
+}
+
+fn parse_hex_bytes<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    if hex.len() != N * 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_all_notations() {
+        let (color, format) = HyprColor::parse("rgba(33ccffee)").unwrap();
+        assert_eq!(color, HyprColor::new(0x33, 0xcc, 0xff, 0xee));
+        assert_eq!(format, ColorFormat::Rgba);
+
+        let (color, format) = HyprColor::parse("rgb(595959)").unwrap();
+        assert_eq!(color, HyprColor::new(0x59, 0x59, 0x59, 0xff));
+        assert_eq!(format, ColorFormat::Rgb);
+
+        let (color, format) = HyprColor::parse("0xee33ccff").unwrap();
+        assert_eq!(color, HyprColor::new(0x33, 0xcc, 0xff, 0xee));
+        assert_eq!(format, ColorFormat::Hex);
+
+        assert!(HyprColor::parse("rgba(33ccff)").is_none());
+        assert!(HyprColor::parse("0xzz33ccff").is_none());
+        assert!(HyprColor::parse("blue").is_none());
+    }
+
+    #[test]
+    fn test_format_round_trip_and_conversion() {
+        for value in ["rgba(33ccffee)", "rgb(1a1a1a)", "0xee33ccff"] {
+            let (color, format) = HyprColor::parse(value).unwrap();
+            assert_eq!(color.format(format), value);
+        }
+
+        let (color, _) = HyprColor::parse("rgba(33ccffee)").unwrap();
+        assert_eq!(color.format(ColorFormat::Hex), "0xee33ccff");
+        assert_eq!(color.format(ColorFormat::Rgb), "rgb(33ccff)");
+    }
+
+    #[test]
+    fn test_two_stop_gradient_round_trip() {
+        let value = "rgba(ee1100ff) rgba(1100eeff) 45deg";
+        let gradient = HyprGradient::parse(value).unwrap();
+
+        assert_eq!(gradient.stops.len(), 2);
+        assert_eq!(
+            gradient.stops[0].color,
+            HyprColor::new(0xee, 0x11, 0x00, 0xff)
+        );
+        assert_eq!(
+            gradient.stops[1].color,
+            HyprColor::new(0x11, 0x00, 0xee, 0xff)
+        );
+        assert_eq!(gradient.angle, Some(45));
+        assert!(gradient.is_gradient());
+        assert_eq!(gradient.to_string(), value);
+    }
+
+    #[test]
+    fn test_three_stop_gradient_round_trip() {
+        let value = "rgba(33ccffee) rgb(00ff99) 0xff595959 270deg";
+        let gradient = HyprGradient::parse(value).unwrap();
+
+        assert_eq!(gradient.stops.len(), 3);
+        assert_eq!(gradient.stops[1].format, ColorFormat::Rgb);
+        assert_eq!(gradient.stops[2].format, ColorFormat::Hex);
+        assert_eq!(gradient.angle, Some(270));
+        assert_eq!(gradient.to_string(), value);
+
+        // Without an angle the gradient keeps no angle on output
+        let gradient = HyprGradient::parse("rgba(ee1100ff) rgba(1100eeff) rgba(00ee11ff)").unwrap();
+        assert_eq!(gradient.angle, None);
+        assert_eq!(
+            gradient.to_string(),
+            "rgba(ee1100ff) rgba(1100eeff) rgba(00ee11ff)"
+        );
+    }
+
+    #[test]
+    fn test_gradient_from_hyprctl_output() {
+        let gradient = HyprGradient::parse("ffee1100 ff1100ee 45deg").unwrap();
+        assert_eq!(gradient.to_string(), "0xffee1100 0xff1100ee 45deg");
+
+        let single = HyprGradient::parse("rgba(33ccffee)").unwrap();
+        assert!(!single.is_gradient());
+
+        assert!(HyprGradient::parse("45deg rgba(33ccffee)").is_none());
+        assert!(HyprGradient::parse("").is_none());
+        assert!(HyprGradient::parse("rgba(33ccffee) nonsense").is_none());
+    }
+
+    #[test]
+    fn test_gradient_stop_editing() {
+        let mut gradient = HyprGradient::parse("rgba(ee1100ff)").unwrap();
+
+        assert_eq!(gradient.duplicate_stop(0), Some(1));
+        gradient.stops[1].color.b = 0xee;
+        gradient.rotate(-90);
+        assert_eq!(gradient.to_string(), "rgba(ee1100ff) rgba(ee11eeff) 270deg");
+
+        assert!(gradient.remove_stop(0));
+        assert!(!gradient.remove_stop(0));
+        assert_eq!(gradient.to_string(), "rgba(ee11eeff) 270deg");
+
+        for _ in 1..MAX_GRADIENT_STOPS {
+            gradient.duplicate_stop(0).unwrap();
+        }
+        assert_eq!(gradient.duplicate_stop(0), None);
+    }
+
+    #[test]
+    fn test_adjust_channel_clamps() {
+        let mut color = HyprColor::new(250, 5, 0, 255);
+        color.adjust_channel(ColorChannel::Red, 16);
+        color.adjust_channel(ColorChannel::Green, -16);
+        color.adjust_channel(ColorChannel::Alpha, -1);
+        assert_eq!(color, HyprColor::new(255, 0, 0, 254));
+    }
+
+    #[test]
+    fn test_first_color_of_an_option() {
+        let first = |value: &str| first_color(value).map(HyprColor::to_opaque_color);
+        assert_eq!(
+            first("ee33ccff ee00ff99 45deg"),
+            Some(Color::Rgb(0x33, 0xcc, 0xff))
+        );
+        assert_eq!(first("rgba(595959aa)"), Some(Color::Rgb(0x59, 0x59, 0x59)));
+        // decoration:shadow:color is an int option
+        assert_eq!(first("3994688026"), Some(Color::Rgb(0x1a, 0x1a, 0x1a)));
+        assert_eq!(first("not a color"), None);
+    }
+}
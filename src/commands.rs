@@ -60,6 +60,12 @@ pub struct CommandContext {
     pub show_batch_dialog: bool,
     pub show_save_dialog: bool,
     pub show_reload_dialog: bool,
+    pub show_quit_dialog: bool,
+    pub show_restore_dialog: bool,
+    pub show_deprecations_dialog: bool,
+    pub show_setup_wizard: bool,
+    pub show_global_search: bool,
+    pub show_command_palette: bool,
 }
 
 impl CommandContext {
@@ -74,6 +80,12 @@ impl CommandContext {
                 || app.ui.show_batch_dialog
                 || app.ui.show_save_dialog
                 || app.ui.show_reload_dialog
+                || app.ui.show_quit_dialog
+                || app.ui.show_restore_dialog
+                || app.ui.show_deprecations_dialog
+                || app.ui.setup_wizard.is_some()
+                || app.ui.show_global_search
+                || app.ui.show_command_palette
                 || app.ui.show_preview_dialog,
             in_search_mode: app.ui.search_mode,
             in_edit_mode: app.ui.edit_mode != EditMode::None,
@@ -86,6 +98,12 @@ impl CommandContext {
             show_batch_dialog: app.ui.show_batch_dialog,
             show_save_dialog: app.ui.show_save_dialog,
             show_reload_dialog: app.ui.show_reload_dialog,
+            show_quit_dialog: app.ui.show_quit_dialog,
+            show_restore_dialog: app.ui.show_restore_dialog,
+            show_deprecations_dialog: app.ui.show_deprecations_dialog,
+            show_setup_wizard: app.ui.setup_wizard.is_some(),
+            show_global_search: app.ui.show_global_search,
+            show_command_palette: app.ui.show_command_palette,
         }
     }
 
@@ -116,7 +134,7 @@ impl CommandDispatcher {
     pub fn register(&mut self, command: Box<dyn Command>) {
         self.commands.push(command);
         // Sort by priority (highest first)
-        self.commands.sort_by(|a, b| b.priority().cmp(&a.priority()));
+        self.commands.sort_by_key(|c| std::cmp::Reverse(c.priority()));
     }
 
     /// Execute the first applicable command
@@ -130,6 +148,14 @@ impl CommandDispatcher {
         if context.has_popup {
             return app.handle_popup_key(key).await.map(|_| CommandResult::Handled);
         }
+
+        // The wizard is drawn over every other dialog
+        if context.show_setup_wizard {
+            return app
+                .handle_setup_wizard_key(key)
+                .await
+                .map(|_| CommandResult::Handled);
+        }
         
         if context.show_help {
             return app.handle_help_key(key).await.map(|_| CommandResult::Handled);
@@ -159,6 +185,29 @@ impl CommandDispatcher {
             return app.handle_reload_dialog_key(key).await.map(|_| CommandResult::Handled);
         }
         
+        if context.show_quit_dialog {
+            return app.handle_quit_dialog_key(key).await.map(|_| CommandResult::Handled);
+        }
+        
+        if context.show_restore_dialog {
+            return app.handle_restore_dialog_key(key).await.map(|_| CommandResult::Handled);
+        }
+        
+        if context.show_deprecations_dialog {
+            return app
+                .handle_deprecations_dialog_key(key)
+                .await
+                .map(|_| CommandResult::Handled);
+        }
+
+        if context.show_global_search {
+            return app.handle_global_search_key(key).await.map(|_| CommandResult::Handled);
+        }
+        
+        if context.show_command_palette {
+            return app.handle_command_palette_key(key).await.map(|_| CommandResult::Handled);
+        }
+        
         if context.show_preview_dialog {
             return app.handle_preview_dialog_key(key).await.map(|_| CommandResult::Handled);
         }
@@ -171,110 +220,12 @@ impl CommandDispatcher {
             return app.handle_edit_key(key).await.map(|_| CommandResult::Handled);
         }
         
-        // Handle quit
-        if matches!(key, KeyCode::Char('q') | KeyCode::Esc) && !context.has_modal_open() {
-            app.state = AppState::Quitting;
+        // Everything else in the list is in the key map
+        if let Some(action) = app.list_action_for(key, context.has_modal_open()) {
+            app.run_key_action(action).await?;
             return Ok(CommandResult::Handled);
         }
         
-        // Handle navigation
-        match key {
-            KeyCode::Tab | KeyCode::Right => {
-                app.ui.next_tab();
-                app.focused_panel = app.ui.current_tab;
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::BackTab | KeyCode::Left => {
-                app.ui.previous_tab();
-                app.focused_panel = app.ui.current_tab;
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Up => {
-                app.ui.scroll_up();
-                if app.ui.is_preview_mode() {
-                    if let Some(item) = app.ui.get_selected_item() {
-                        let item_key = item.key.clone();
-                        let item_value = item.value.clone();
-                        if let Err(e) = app.ui.handle_preview_change(&item_key, &item_value, &app.hyprctl).await {
-                            eprintln!("Preview error: {}", e);
-                        }
-                    }
-                }
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Down => {
-                app.ui.scroll_down();
-                if app.ui.is_preview_mode() {
-                    if let Some(item) = app.ui.get_selected_item() {
-                        let item_key = item.key.clone();
-                        let item_value = item.value.clone();
-                        if let Err(e) = app.ui.handle_preview_change(&item_key, &item_value, &app.hyprctl).await {
-                            eprintln!("Preview error: {}", e);
-                        }
-                    }
-                }
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::PageUp => {
-                app.ui.prev_page();
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::PageDown => {
-                app.ui.next_page();
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Home => {
-                let list_state = app.ui.get_current_list_state();
-                list_state.select(Some(0));
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::End => {
-                let list_state = app.ui.get_current_list_state();
-                list_state.select(Some(999));
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Enter => {
-                if let Some(item) = app.ui.get_selected_item() {
-                    app.take_config_snapshot(&format!("Edit {}", item.key));
-                    app.ui.start_editing().await?;
-                }
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Char('s') | KeyCode::Char('S') => {
-                app.ui.show_save_dialog = true;
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Char('r') | KeyCode::Char('R') => {
-                app.ui.show_reload_dialog = true;
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Char('/') => {
-                app.ui.start_search_debounced();
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Char('?') => {
-                app.ui.toggle_help();
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Char('n') | KeyCode::Char('N') => {
-                app.show_enhanced_preview().await;
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Char('b') | KeyCode::Char('B') => {
-                app.show_batch_dialog().await;
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Char('i') | KeyCode::Char('I') => {
-                app.show_add_item_dialog().await;
-                return Ok(CommandResult::Handled);
-            }
-            KeyCode::Char('e') | KeyCode::Char('E') => {
-                app.show_export_dialog().await;
-                return Ok(CommandResult::Handled);
-            }
-            _ => {}
-        }
-        
         Ok(CommandResult::NotHandled)
     }
 
@@ -290,6 +241,9 @@ impl CommandDispatcher {
         self.register(Box::new(BatchDialogCommand));
         self.register(Box::new(SaveDialogCommand));
         self.register(Box::new(ReloadDialogCommand));
+        self.register(Box::new(QuitDialogCommand));
+        self.register(Box::new(RestoreDialogCommand));
+        self.register(Box::new(GlobalSearchCommand));
         self.register(Box::new(PreviewDialogCommand));
         self.register(Box::new(SearchCommand));
         self.register(Box::new(EditCommand));
@@ -321,7 +275,7 @@ pub struct QuitCommand;
 #[async_trait::async_trait]
 impl Command for QuitCommand {
     async fn execute(&self, app: &mut App, _context: &CommandContext) -> Result<CommandResult> {
-        app.state = AppState::Quitting;
+        app.request_quit();
         Ok(CommandResult::Handled)
     }
 
@@ -485,6 +439,63 @@ impl Command for ReloadDialogCommand {
     fn description(&self) -> &'static str { "Handle reload dialog" }
 }
 
+/// Unsaved changes on quit dialog command
+#[derive(Debug)]
+pub struct QuitDialogCommand;
+
+#[async_trait::async_trait]
+impl Command for QuitDialogCommand {
+    async fn execute(&self, app: &mut App, context: &CommandContext) -> Result<CommandResult> {
+        app.handle_quit_dialog_key(context.key).await?;
+        Ok(CommandResult::Handled)
+    }
+
+    fn can_handle(&self, context: &CommandContext) -> bool {
+        context.show_quit_dialog
+    }
+
+    fn priority(&self) -> u8 { 80 }
+    fn description(&self) -> &'static str { "Handle quit dialog" }
+}
+
+/// Backup restore dialog command
+#[derive(Debug)]
+pub struct RestoreDialogCommand;
+
+#[async_trait::async_trait]
+impl Command for RestoreDialogCommand {
+    async fn execute(&self, app: &mut App, context: &CommandContext) -> Result<CommandResult> {
+        app.handle_restore_dialog_key(context.key).await?;
+        Ok(CommandResult::Handled)
+    }
+
+    fn can_handle(&self, context: &CommandContext) -> bool {
+        context.show_restore_dialog
+    }
+
+    fn priority(&self) -> u8 { 80 }
+    fn description(&self) -> &'static str { "Handle backup restore dialog" }
+}
+
+/// Search-all-panels dialog command
+#[derive(Debug)]
+pub struct GlobalSearchCommand;
+
+#[async_trait::async_trait]
+impl Command for GlobalSearchCommand {
+    async fn execute(&self, app: &mut App, context: &CommandContext) -> Result<CommandResult> {
+        app.handle_global_search_key(context.key).await?;
+        Ok(CommandResult::Handled)
+    }
+
+    fn can_handle(&self, context: &CommandContext) -> bool {
+        context.show_global_search
+    }
+
+    fn priority(&self) -> u8 { 80 }
+    fn description(&self) -> &'static str { "Handle search across all panels" }
+}
+
 /// Preview dialog command
 #[derive(Debug)]
 pub struct PreviewDialogCommand;
@@ -589,7 +600,7 @@ impl Command for VerticalNavigationCommand {
                         let item_key = item.key.clone();
                         let item_value = item.value.clone();
                         if let Err(e) = app.ui.handle_preview_change(&item_key, &item_value, &app.hyprctl).await {
-                            eprintln!("Preview error: {}", e);
+                            crate::stderr_log::warn(format!("Preview error: {}", e));
                         }
                     }
                 }
@@ -603,7 +614,7 @@ impl Command for VerticalNavigationCommand {
                         let item_key = item.key.clone();
                         let item_value = item.value.clone();
                         if let Err(e) = app.ui.handle_preview_change(&item_key, &item_value, &app.hyprctl).await {
-                            eprintln!("Preview error: {}", e);
+                            crate::stderr_log::warn(format!("Preview error: {}", e));
                         }
                     }
                 }
@@ -651,10 +662,8 @@ impl Command for ActionCommand {
     async fn execute(&self, app: &mut App, context: &CommandContext) -> Result<CommandResult> {
         match context.key {
             KeyCode::Enter => {
-                // Take snapshot before starting to edit
-                if let Some(item) = app.ui.get_selected_item() {
-                    app.take_config_snapshot(&format!("Edit {}", item.key));
-                    app.ui.start_editing().await?;
+                if app.ui.get_selected_item().is_some() {
+                    app.start_editing().await?;
                 }
             }
             KeyCode::Char('s') | KeyCode::Char('S') => {
@@ -663,6 +672,9 @@ impl Command for ActionCommand {
             KeyCode::Char('r') | KeyCode::Char('R') => {
                 app.ui.show_reload_dialog = true;
             }
+            KeyCode::Char('o') | KeyCode::Char('O') => {
+                app.show_restore_dialog().await;
+            }
             KeyCode::Char('/') => {
                 app.ui.start_search_debounced();
             }
@@ -692,6 +704,7 @@ impl Command for ActionCommand {
             KeyCode::Enter
                 | KeyCode::Char('s') | KeyCode::Char('S')
                 | KeyCode::Char('r') | KeyCode::Char('R')
+                | KeyCode::Char('o') | KeyCode::Char('O')
                 | KeyCode::Char('/') 
                 | KeyCode::Char('?')
                 | KeyCode::Char('n') | KeyCode::Char('N')
@@ -762,6 +775,12 @@ mod tests {
             show_batch_dialog: false,
             show_save_dialog: false,
             show_reload_dialog: false,
+            show_quit_dialog: false,
+            show_restore_dialog: false,
+            show_deprecations_dialog: false,
+            show_setup_wizard: false,
+            show_global_search: false,
+            show_command_palette: false,
         };
 
         assert!(quit_command.can_handle(&context));
@@ -786,6 +805,12 @@ mod tests {
             show_batch_dialog: false,
             show_save_dialog: false,
             show_reload_dialog: false,
+            show_quit_dialog: false,
+            show_restore_dialog: false,
+            show_deprecations_dialog: false,
+            show_setup_wizard: false,
+            show_global_search: false,
+            show_command_palette: false,
         };
 
         assert!(!context.has_modal_open());
@@ -797,4 +822,37 @@ mod tests {
         context.in_edit_mode = true;
         assert!(context.has_modal_open());
     }
+
+    #[test]
+    fn test_quit_dialog_takes_keys_while_open() {
+        let mut context = CommandContext {
+            key: KeyCode::Char('q'),
+            has_popup: false,
+            has_dialog: false,
+            in_search_mode: false,
+            in_edit_mode: false,
+            current_panel: FocusedPanel::General,
+            show_help: false,
+            show_preview_dialog: false,
+            show_import_dialog: false,
+            show_export_dialog: false,
+            show_nixos_export_dialog: false,
+            show_batch_dialog: false,
+            show_save_dialog: false,
+            show_reload_dialog: false,
+            show_quit_dialog: false,
+            show_restore_dialog: false,
+            show_deprecations_dialog: false,
+            show_setup_wizard: false,
+            show_global_search: false,
+            show_command_palette: false,
+        };
+        assert!(!QuitDialogCommand.can_handle(&context));
+
+        context.has_dialog = true;
+        context.show_quit_dialog = true;
+        assert!(QuitDialogCommand.can_handle(&context));
+        // Q again doesn't quit past the dialog
+        assert!(!QuitCommand.can_handle(&context));
+    }
 }
\ No newline at end of file
@@ -1,24 +1,137 @@
+use crate::autostart::{is_exec_line, ExecLine};
+use crate::diff::{edit_script, Edit};
+use crate::environment::is_env_line;
 use crate::errors::{FileError, FileResult, HyprConfigError, RecoveryContext, RecoveryStrategy};
 use crate::file_io::{FileOperations, FileUtils};
+use crate::includes::{resolve_includes, SourcedLine};
 use crate::nixos::{NixConfigType, NixOSEnvironment};
+use crate::platform::ConfigPathManager;
+use crate::plugins::{is_plugin_line, PluginLine};
 use crate::theme::ColorScheme;
+use crate::variables::{is_variable_line, Variables};
+use crate::workspace::is_workspace_rule_line;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use std::time::Duration;
 use tokio::fs as async_fs;
 use tokio::time::sleep;
 
+/// Environment variable naming the hyprland.conf to edit, like `--config`
+pub const CONFIG_PATH_ENV: &str = "R_HYPRCONFIG_CONFIG";
+
+/// The hyprland.conf chosen on the command line or in the environment, set
+/// once at startup
+static HYPRLAND_CONFIG_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// The hyprland.conf to edit instead of the configured one: the `--config`
+/// flag, then `R_HYPRCONFIG_CONFIG`. A leading `~/` is expanded and relative
+/// paths are taken from the current directory. The file has to exist, so a
+/// typo doesn't create a fresh config somewhere unexpected.
+pub fn resolve_config_override(
+    flag: Option<PathBuf>,
+    env: Option<OsString>,
+) -> Result<Option<PathBuf>> {
+    let (path, source) = match (flag, env) {
+        (Some(path), _) => (path, "--config"),
+        (None, Some(value)) if !value.is_empty() => (PathBuf::from(value), CONFIG_PATH_ENV),
+        _ => return Ok(None),
+    };
+    let path = match path.strip_prefix("~") {
+        Ok(rest) => dirs::home_dir()
+            .context("Failed to get home directory")?
+            .join(rest),
+        Err(_) => path,
+    };
+    let path = std::path::absolute(&path)
+        .with_context(|| format!("Failed to resolve {}", path.display()))?;
+    if !path.is_file() {
+        anyhow::bail!("{source} names {}, which is not a file", path.display());
+    }
+    Ok(Some(path))
+}
+
+/// Edit `path` instead of the hyprland.conf in config.toml, for the rest of
+/// the run
+pub fn set_hyprland_config_override(path: PathBuf) {
+    let _ = HYPRLAND_CONFIG_OVERRIDE.set(path);
+}
+
+pub fn hyprland_config_override() -> Option<&'static Path> {
+    HYPRLAND_CONFIG_OVERRIDE.get().map(PathBuf::as_path)
+}
+
+/// Set by `--no-apply`, on top of `file_only` in config.toml
+static FILE_ONLY_OVERRIDE: OnceLock<bool> = OnceLock::new();
+
+/// Only edit the file for the rest of the run, whatever config.toml says
+pub fn set_file_only_override() {
+    let _ = FILE_ONLY_OVERRIDE.set(true);
+}
+
+/// Which keys move around the panel list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeybindMode {
+    #[default]
+    Normal,
+    /// `h/j/k/l` move, `gg`/`G` jump to the ends and `dd` deletes
+    Vim,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub hyprland_config_path: PathBuf,
+    /// What config.toml says while an override is in use, so saving the
+    /// settings doesn't store the override
+    #[serde(skip)]
+    pub stored_hyprland_config_path: Option<PathBuf>,
     pub backup_enabled: bool,
+    /// Number of timestamped hyprland.conf backups to keep
+    #[serde(default = "default_backup_count")]
+    pub backup_count: usize,
+    /// Number of edits kept in the undo history
+    #[serde(default = "default_undo_depth")]
+    pub undo_depth: usize,
+    /// Minimum fuzzy match score (0-100) for search results
+    #[serde(default = "default_search_threshold")]
+    pub search_threshold: u8,
     pub auto_save: bool,
     pub nixos_mode: bool,
     pub current_values: HashMap<String, String>,
     #[serde(default)]
     pub theme: ColorScheme,
+    #[serde(default)]
+    pub keybind_mode: KeybindMode,
+    /// Never apply anything through hyprctl, only edit hyprland.conf, as
+    /// when it belongs to another machine. `--no-apply` does the same.
+    #[serde(default)]
+    pub file_only: bool,
+    /// Refuse to save binds whose dispatcher Hyprland doesn't know, instead
+    /// of only marking them in the Binds panel
+    #[serde(default)]
+    pub strict_dispatchers: bool,
+    /// Reload Hyprland after saving, so keybinds and rules take effect too,
+    /// and report the config errors it finds. Toggled in the save dialog.
+    #[serde(default)]
+    pub reload_after_save: bool,
+    /// The setup wizard was seen. Configs from before it had no wizard to
+    /// see, so they count as done.
+    #[serde(default = "default_first_run_completed")]
+    pub first_run_completed: bool,
+    /// File that keybinds and rules created in the editor are appended to,
+    /// instead of hyprland.conf. It should be one hyprland.conf sources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_items_file: Option<PathBuf>,
+    /// Per panel, the file its new items are appended to instead, like
+    /// `save_targets = { Binds = "binds.conf" }`. Paths are taken the way
+    /// `source =` takes them, relative to hyprland.conf.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub save_targets: HashMap<crate::app::FocusedPanel, PathBuf>,
 
     // NixOS-specific configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -29,17 +142,44 @@ pub struct Config {
     pub nixos_auto_rebuild: bool,
 }
 
+fn default_backup_count() -> usize {
+    10
+}
+
+fn default_undo_depth() -> usize {
+    50
+}
+
+fn default_first_run_completed() -> bool {
+    true
+}
+
+fn default_search_threshold() -> u8 {
+    crate::ui::DEFAULT_SEARCH_FUZZY_THRESHOLD
+}
+
 impl Default for Config {
     fn default() -> Self {
         let nixos_env = NixOSEnvironment::detect();
 
         Self {
             hyprland_config_path: Self::default_hyprland_config_path(),
+            stored_hyprland_config_path: None,
             backup_enabled: true,
+            backup_count: default_backup_count(),
+            undo_depth: default_undo_depth(),
+            search_threshold: default_search_threshold(),
             auto_save: false,
             nixos_mode: nixos_env.is_nixos,
             current_values: HashMap::new(),
             theme: ColorScheme::default(),
+            keybind_mode: KeybindMode::default(),
+            file_only: false,
+            strict_dispatchers: false,
+            reload_after_save: false,
+            first_run_completed: false,
+            new_items_file: None,
+            save_targets: HashMap::new(),
             nixos_config_type: nixos_env
                 .get_primary_config_location()
                 .map(|loc| loc.config_type.clone()),
@@ -65,16 +205,41 @@ impl Config {
                 toml::from_str(&content).context("Failed to parse config file")?;
 
             // Ensure hyprland config path exists
-            config.validate_hyprland_config_path().await?;
+            if hyprland_config_override().is_none() {
+                config.validate_hyprland_config_path().await?;
+            }
+            config.apply_override();
 
             Ok(config)
         } else {
-            let config = Self::default();
+            let mut config = Self::default();
             config.save().await?;
+            config.apply_override();
             Ok(config)
         }
     }
 
+    /// Point at the hyprland.conf from `--config` or `R_HYPRCONFIG_CONFIG`,
+    /// if one was given
+    fn apply_override(&mut self) {
+        if let Some(path) = hyprland_config_override() {
+            let stored = std::mem::replace(&mut self.hyprland_config_path, path.to_path_buf());
+            self.stored_hyprland_config_path = Some(stored);
+        }
+    }
+
+    /// The settings as config.toml keeps them
+    fn stored(&self) -> Cow<'_, Config> {
+        match &self.stored_hyprland_config_path {
+            Some(path) => {
+                let mut config = self.clone();
+                config.hyprland_config_path = path.clone();
+                Cow::Owned(config)
+            }
+            None => Cow::Borrowed(self),
+        }
+    }
+
     pub async fn save(&self) -> Result<()> {
         let config_path = Self::get_config_path()?;
 
@@ -85,7 +250,8 @@ impl Config {
                 .map_err(|e| anyhow::anyhow!("Failed to create config directory: {}", e))?;
         }
 
-        let content = toml::to_string_pretty(self).context("Failed to serialize config")?;
+        let content =
+            toml::to_string_pretty(&self.stored()).context("Failed to serialize config")?;
 
         // Use enhanced file I/O with atomic operations and backup
         FileUtils::safe_write(&config_path, &content)
@@ -112,7 +278,7 @@ impl Config {
         let backup_path = if config_path.exists() {
             let backup = config_path.with_extension("toml.backup");
             if let Err(e) = async_fs::copy(&config_path, &backup).await {
-                eprintln!("Warning: Failed to create backup: {}", e);
+                crate::stderr_log::warn(format!("Failed to create backup: {}", e));
                 None
             } else {
                 Some(backup)
@@ -136,20 +302,26 @@ impl Config {
                             RecoveryStrategy::Retry { max_attempts, base_delay_ms } => {
                                 if recovery_context.attempt_count <= max_attempts {
                                     let delay = base_delay_ms * recovery_context.attempt_count as u64;
-                                    eprintln!(
+                                    crate::stderr_log::note(format!(
                                         "Retrying config save (attempt {}/{}) after {}ms delay: {}",
                                         recovery_context.attempt_count, max_attempts, delay, error
-                                    );
+                                    ));
                                     sleep(Duration::from_millis(delay)).await;
                                     continue;
                                 }
                             }
                             RecoveryStrategy::Fallback { description } => {
-                                eprintln!("Using fallback strategy: {}", description);
+                                crate::stderr_log::note(format!(
+                                    "Using fallback strategy: {}",
+                                    description
+                                ));
                                 // Try saving to a temporary location
                                 let temp_path = config_path.with_extension("toml.temp");
                                 if self.attempt_save(&temp_path).await.is_ok() {
-                                    eprintln!("Successfully saved to temporary location: {}", temp_path.display());
+                                    crate::stderr_log::note(format!(
+                                        "Successfully saved to temporary location: {}",
+                                        temp_path.display()
+                                    ));
                                     return Ok(());
                                 }
                             }
@@ -157,9 +329,12 @@ impl Config {
                                 // Restore backup if we have one
                                 if let Some(backup) = backup_path {
                                     if let Err(e) = async_fs::copy(&backup, &config_path).await {
-                                        eprintln!("Failed to restore backup: {}", e);
+                                        crate::stderr_log::error(format!(
+                                            "Failed to restore backup: {}",
+                                            e
+                                        ));
                                     } else {
-                                        eprintln!("Configuration backup restored");
+                                        crate::stderr_log::note("Configuration backup restored");
                                     }
                                 }
                                 return Err(error);
@@ -183,19 +358,13 @@ impl Config {
         }
 
         // Serialize configuration
-        let content = toml::to_string_pretty(self)
+        let content = toml::to_string_pretty(&self.stored())
             .map_err(|e| HyprConfigError::ConfigParsingError {
                 message: format!("Failed to serialize config: {}", e),
             })?;
 
-        // Write file atomically by writing to temp file first
-        let temp_path = path.with_extension("toml.tmp");
-        async_fs::write(&temp_path, &content).await
-            .map_err(|e| HyprConfigError::file_operation("write temp file", &temp_path, e))?;
-
-        // Move temp file to final location (atomic on most filesystems)
-        async_fs::rename(&temp_path, path).await
-            .map_err(|e| HyprConfigError::file_operation("move temp file", path, e))?;
+        // Write the file atomically through a fsynced temp file
+        FileUtils::atomic_write(path, &content).await?;
 
         Ok(())
     }
@@ -208,8 +377,16 @@ impl Config {
         Ok(config_dir.join("config.toml"))
     }
 
+    /// Folder custom themes are loaded from
+    pub fn themes_dir() -> Result<PathBuf> {
+        let config_path = Self::get_config_path()?;
+        Ok(config_path.with_file_name("themes"))
+    }
+
     fn default_hyprland_config_path() -> PathBuf {
-        if let Some(config_dir) = dirs::config_dir() {
+        if let Ok(path) = ConfigPathManager::default_hyprland_config_file() {
+            path
+        } else if let Some(config_dir) = dirs::config_dir() {
             config_dir.join("hypr").join("hyprland.conf")
         } else {
             PathBuf::from("~/.config/hypr/hyprland.conf")
@@ -226,15 +403,9 @@ impl Config {
 
     async fn validate_hyprland_config_path(&mut self) -> Result<()> {
         if !self.hyprland_config_path.exists() {
-            // Try to find hyprland.conf in common locations
-            let possible_paths = vec![
-                dirs::config_dir().map(|d| d.join("hypr").join("hyprland.conf")),
-                Some(PathBuf::from("/etc/hypr/hyprland.conf")),
-                dirs::home_dir().map(|d| d.join(".config").join("hypr").join("hyprland.conf")),
-            ];
-
-            for path in possible_paths.into_iter().flatten() {
-                if path.exists() {
+            // Try to find hyprland.conf where Hyprland looks for it
+            if let Ok(path) = ConfigPathManager::default_hyprland_config_file() {
+                if path.is_file() {
                     self.hyprland_config_path = path;
                     return Ok(());
                 }
@@ -246,7 +417,7 @@ impl Config {
         Ok(())
     }
 
-    async fn create_default_hyprland_config(&self) -> Result<()> {
+    pub async fn create_default_hyprland_config(&self) -> Result<()> {
         if let Some(parent) = self.hyprland_config_path.parent() {
             async_fs::create_dir_all(parent)
                 .await
@@ -267,59 +438,361 @@ impl Config {
             return Ok(self.hyprland_config_path.clone());
         }
 
-        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-        let backup_path = self
-            .hyprland_config_path
-            .with_extension(format!("conf.backup.{timestamp}"));
+        // Nothing to back up yet on a fresh install
+        if !self.hyprland_config_path.exists() {
+            return Ok(self.hyprland_config_path.clone());
+        }
+
+        // Saves within the same second get a counter so no backup is overwritten
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+        let mut backup_path = self.backup_path_for(&timestamp);
+        let mut counter = 1;
+        while backup_path.exists() {
+            backup_path = self.backup_path_for(&format!("{timestamp}_{counter}"));
+            counter += 1;
+        }
 
         async_fs::copy(&self.hyprland_config_path, &backup_path)
             .await
             .context("Failed to create backup")?;
 
+        self.prune_backups().await?;
+
         Ok(backup_path)
     }
 
+    /// Backup file name for a timestamp: `hyprland.conf.<timestamp>.bak`
+    fn backup_path_for(&self, timestamp: &str) -> PathBuf {
+        let file_name = self
+            .hyprland_config_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "hyprland.conf".to_string());
+
+        self.hyprland_config_path
+            .with_file_name(format!("{file_name}.{timestamp}.bak"))
+    }
+
+    /// List existing backups of the Hyprland config, newest first
+    pub fn list_backups(&self) -> Result<Vec<PathBuf>> {
+        let file_name = self
+            .hyprland_config_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "hyprland.conf".to_string());
+        let prefix = format!("{file_name}.");
+
+        let dir = match self.hyprland_config_path.parent() {
+            Some(dir) if dir.exists() => dir,
+            _ => return Ok(Vec::new()),
+        };
+
+        let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read backup directory: {:?}", dir))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| n.starts_with(&prefix) && n.ends_with(".bak"))
+                    .unwrap_or(false)
+            })
+            .collect();
+
+        // Newest first. The same-second counter is compared as a number, so
+        // `_10` comes after `_2`.
+        backups.sort_by_cached_key(|path| std::cmp::Reverse(backup_order(path)));
+
+        Ok(backups)
+    }
+
+    /// Remove the oldest backups beyond `backup_count`
+    async fn prune_backups(&self) -> Result<()> {
+        for old_backup in self.list_backups()?.into_iter().skip(self.backup_count.max(1)) {
+            async_fs::remove_file(&old_backup)
+                .await
+                .with_context(|| format!("Failed to remove old backup: {:?}", old_backup))?;
+        }
+        Ok(())
+    }
+
+    /// Replace the Hyprland config with a backup. The current config is
+    /// backed up first so a restore can itself be rolled back.
+    pub async fn restore_backup(&self, backup_path: &Path) -> Result<()> {
+        if !backup_path.exists() {
+            anyhow::bail!("Backup does not exist: {:?}", backup_path);
+        }
+
+        let content = async_fs::read_to_string(backup_path)
+            .await
+            .with_context(|| format!("Failed to read backup: {:?}", backup_path))?;
+
+        ensure_writable(&self.hyprland_config_path)?;
+        self.backup_config().await?;
+
+        FileUtils::atomic_write(&self.hyprland_config_path, &content)
+            .await
+            .context("Failed to restore hyprland config")?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn save_hyprland_config_with_rules(
         &self,
         options: &HashMap<String, String>,
         keybinds: &[String],
         window_rules: &[String],
         layer_rules: &[String],
+        workspace_rules: &[String],
+        env_vars: &[String],
+        plugins: &[String],
+        autostart: &[String],
+        variables: &[String],
     ) -> Result<()> {
         if self.nixos_mode {
             return self.save_nixos_config(options).await;
         }
+        ensure_writable(&self.hyprland_config_path)?;
 
         // Backup current config
         let _backup_path = self.backup_config().await?;
 
-        // Read current config
+        // Read current config and apply options, keybinds, and rules
+        let (_current_content, updated_content) = self
+            .render_hyprland_config_with_rules(
+                options,
+                keybinds,
+                window_rules,
+                layer_rules,
+                workspace_rules,
+                env_vars,
+                plugins,
+                autostart,
+                variables,
+            )
+            .await?;
+
+        // Write updated config
+        FileUtils::atomic_write(&self.hyprland_config_path, &updated_content)
+            .await
+            .context("Failed to write hyprland config")?;
+
+        Ok(())
+    }
+
+    /// The file new items of `panel` are appended to: its entry in
+    /// `save_targets`, then `new_items_file`. `None` keeps them in
+    /// hyprland.conf.
+    pub fn save_target(&self, panel: crate::app::FocusedPanel) -> Option<PathBuf> {
+        match self.save_targets.get(&panel) {
+            Some(target) => Some(crate::includes::expand_source_path(
+                &target.to_string_lossy(),
+                &self.hyprland_config_path,
+            )),
+            None => self.new_items_file.clone(),
+        }
+    }
+
+    /// Move keybinds and rules that are not in hyprland.conf yet over to
+    /// their panel's save target, if one is configured. Binds inside submap
+    /// blocks stay where they are, since moving them would take them out of
+    /// their submap.
+    pub async fn route_new_lines(&self, lines: &mut ConfigLines) -> Result<()> {
+        use crate::app::FocusedPanel;
+
+        if self.new_items_file.is_none() && self.save_targets.is_empty() {
+            return Ok(());
+        }
+        let main_content = async_fs::read_to_string(&self.hyprland_config_path)
+            .await
+            .unwrap_or_default();
+        let existing: std::collections::HashSet<&str> =
+            main_content.lines().map(str::trim).collect();
+
+        let mut new_lines: Vec<(PathBuf, String)> = Vec::new();
+        if let Some(target) = self.save_target(FocusedPanel::Binds) {
+            let mut in_submap = false;
+            lines.keybinds.retain(|line| {
+                if let Some(name) = HyprlandConfigFile::parse_submap_line(line) {
+                    in_submap = name != "reset";
+                    return true;
+                }
+                let is_new = !in_submap && !existing.contains(line.trim());
+                if is_new {
+                    new_lines.push((target.clone(), line.clone()));
+                }
+                !is_new
+            });
+        }
+        // Variables stay in hyprland.conf, where they are defined before the
+        // lines using them
+        for (panel, rules) in [
+            (FocusedPanel::WindowRules, &mut lines.window_rules),
+            (FocusedPanel::LayerRules, &mut lines.layer_rules),
+            (FocusedPanel::Workspaces, &mut lines.workspace_rules),
+            (FocusedPanel::Environment, &mut lines.env_vars),
+            (FocusedPanel::Plugins, &mut lines.plugins),
+            (FocusedPanel::Autostart, &mut lines.autostart),
+        ] {
+            let Some(target) = self.save_target(panel) else {
+                continue;
+            };
+            rules.retain(|line| {
+                let is_new = !existing.contains(line.trim());
+                if is_new {
+                    new_lines.push((target.clone(), line.clone()));
+                }
+                !is_new
+            });
+        }
+
+        for (target, line) in new_lines {
+            match lines.included.iter_mut().find(|(file, _)| *file == target) {
+                Some((_, included)) => included.push(line),
+                None => lines.included.push((target, vec![line])),
+            }
+        }
+        Ok(())
+    }
+
+    /// Update every file hyprland.conf sources so it holds the given lines.
+    /// Sourced files with no lines left have their keybinds and rules removed.
+    pub async fn save_included_files(&self, included: &[(PathBuf, Vec<String>)]) -> Result<()> {
+        if self.nixos_mode {
+            return Ok(());
+        }
+
+        for (file, content, updated) in self.render_included_files(included).await? {
+            if updated != content {
+                ensure_writable(&file)?;
+                FileUtils::atomic_write(&file, &updated)
+                    .await
+                    .with_context(|| format!("Failed to write {}", file.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove every line setting `option` from hyprland.conf and the files it
+    /// sources, so Hyprland falls back to its default on the next reload.
+    /// Returns whether any line was removed.
+    pub async fn remove_option(&self, option: &str) -> Result<bool> {
+        let mut files = vec![self.hyprland_config_path.clone()];
+        files.extend(self.parse_hyprland_config().await?.sourced_files());
+
+        let mut removed = false;
+        for file in files {
+            let Ok(content) = async_fs::read_to_string(&file).await else {
+                continue;
+            };
+            if let Some(updated) = remove_option_lines(&content, option) {
+                ensure_writable(&file)?;
+                if !removed {
+                    self.backup_config().await?;
+                }
+                FileUtils::atomic_write(&file, &updated)
+                    .await
+                    .with_context(|| format!("Failed to write {}", file.display()))?;
+                removed = true;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Rewrite deprecated options in hyprland.conf and the files it sources,
+    /// after a backup, see `migrate_deprecated_lines`. Returns whether any
+    /// file changed.
+    pub async fn migrate_deprecated_options(&self) -> Result<bool> {
+        let mut files = vec![self.hyprland_config_path.clone()];
+        files.extend(self.parse_hyprland_config().await?.sourced_files());
+
+        let mut migrated = false;
+        for file in files {
+            let Ok(content) = async_fs::read_to_string(&file).await else {
+                continue;
+            };
+            if let Some(updated) = migrate_deprecated_lines(&content) {
+                ensure_writable(&file)?;
+                if !migrated {
+                    self.backup_config().await?;
+                }
+                FileUtils::atomic_write(&file, &updated)
+                    .await
+                    .with_context(|| format!("Failed to write {}", file.display()))?;
+                migrated = true;
+            }
+        }
+        Ok(migrated)
+    }
+
+    /// Compute what `save_included_files` would write without touching the
+    /// files. Returns each sourced file with its current and updated content.
+    pub async fn render_included_files(
+        &self,
+        included: &[(PathBuf, Vec<String>)],
+    ) -> Result<Vec<(PathBuf, String, String)>> {
+        let mut files = self.parse_hyprland_config().await?.sourced_files();
+        for (file, _) in included {
+            if !files.contains(file) {
+                files.push(file.clone());
+            }
+        }
+
+        let mut rendered = Vec::new();
+        for file in files {
+            let lines = included
+                .iter()
+                .find(|(f, _)| *f == file)
+                .map(|(_, lines)| lines.as_slice())
+                .unwrap_or_default();
+            // The new items file may not exist yet
+            let content = async_fs::read_to_string(&file).await.unwrap_or_default();
+            let updated = update_included_content(&content, lines);
+            rendered.push((file, content, updated));
+        }
+        Ok(rendered)
+    }
+
+    /// Compute what `save_hyprland_config_with_rules` would write without touching
+    /// the file. Returns the current and the updated config content.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn render_hyprland_config_with_rules(
+        &self,
+        options: &HashMap<String, String>,
+        keybinds: &[String],
+        window_rules: &[String],
+        layer_rules: &[String],
+        workspace_rules: &[String],
+        env_vars: &[String],
+        plugins: &[String],
+        autostart: &[String],
+        variables: &[String],
+    ) -> Result<(String, String)> {
         let current_content = async_fs::read_to_string(&self.hyprland_config_path)
             .await
             .unwrap_or_else(|_| String::new());
 
-        // Parse and update config with options, keybinds, and rules
         let updated_content = self.update_config_content_with_rules(
             &current_content,
             options,
             keybinds,
             window_rules,
             layer_rules,
+            workspace_rules,
+            env_vars,
+            plugins,
+            autostart,
+            variables,
         )?;
 
-        // Write updated config
-        async_fs::write(&self.hyprland_config_path, updated_content)
-            .await
-            .context("Failed to write hyprland config")?;
-
-        Ok(())
+        Ok((current_content, updated_content))
     }
 
-    #[allow(dead_code)]
     pub async fn save_hyprland_config(&self, options: &HashMap<String, String>) -> Result<()> {
         if self.nixos_mode {
             return self.save_nixos_config(options).await;
         }
+        ensure_writable(&self.hyprland_config_path)?;
 
         // Backup current config
         let _backup_path = self.backup_config().await?;
@@ -333,13 +806,36 @@ impl Config {
         let updated_content = self.update_config_content(&current_content, options)?;
 
         // Write updated config
-        async_fs::write(&self.hyprland_config_path, updated_content)
+        FileUtils::atomic_write(&self.hyprland_config_path, &updated_content)
             .await
             .context("Failed to write hyprland config")?;
 
         Ok(())
     }
 
+    /// Write one keybind or rule line to `file`, or hyprland.conf when it is
+    /// `None`, in place of whichever of `old_lines` it was loaded as. Nothing
+    /// else in the file changes. Returns whether the file was written.
+    pub async fn save_config_line(
+        &self,
+        file: Option<&Path>,
+        old_lines: &[String],
+        line: &str,
+    ) -> Result<bool> {
+        let path = file.unwrap_or(&self.hyprland_config_path);
+        let content = async_fs::read_to_string(path).await.unwrap_or_default();
+        let Some(updated) = replace_config_line(&content, old_lines, line) else {
+            return Ok(false);
+        };
+
+        ensure_writable(path)?;
+        self.backup_config().await?;
+        FileUtils::atomic_write(path, &updated)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(true)
+    }
+
     #[allow(dead_code)]
     async fn save_nixos_config(&self, _options: &HashMap<String, String>) -> Result<()> {
         // For NixOS, we can't directly modify the config file
@@ -366,6 +862,7 @@ impl Config {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn update_config_content_with_rules(
         &self,
         content: &str,
@@ -373,55 +870,67 @@ impl Config {
         keybinds: &[String],
         window_rules: &[String],
         layer_rules: &[String],
+        workspace_rules: &[String],
+        env_vars: &[String],
+        plugins: &[String],
+        autostart: &[String],
+        variables: &[String],
     ) -> Result<String> {
-        let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+        let mut lines = join_continued_lines(content);
 
-        // Remove existing keybinds, window rules, and layer rules
-        lines.retain(|line| {
-            let trimmed = line.trim();
-            !trimmed.starts_with("bind")
-                && !trimmed.starts_with("windowrule")
-                && !trimmed.starts_with("layerrule")
-                && !trimmed.starts_with("blurls")
-        });
+        // Plugins and environment variables are written back in place of the
+        // first line of their kind, or at the top of the file, since Hyprland
+        // applies them while parsing. Plugins have to be loaded before the
+        // `plugin` block sets their options.
+        replace_top_block(&mut lines, is_plugin_line, plugins, "# Plugins");
+        replace_top_block(&mut lines, is_env_line, env_vars, "# Environment Variables");
 
-        // Update configuration options using existing method
+        // Update configuration options in place
         let content_with_options = self.update_config_content(&lines.join("\n"), options)?;
         let mut updated_lines: Vec<String> = content_with_options
             .lines()
             .map(|s| s.to_string())
             .collect();
 
-        // Add new keybinds
-        if !keybinds.is_empty() {
-            updated_lines.push(String::new());
-            updated_lines.push("# Keybinds".to_string());
-            for keybind in keybinds {
-                updated_lines.push(keybind.clone());
-            }
+        // Keybinds and rules replace the lines they were read from, so
+        // unchanged ones keep their place and their comments. Autostart lines
+        // are merged the same way, so a moved command moves in the file too.
+        for (kind, new_lines, header) in [
+            ("bind", keybinds, "# Keybinds"),
+            ("windowrule", window_rules, "# Window Rules"),
+            ("layerrule", layer_rules, "# Layer Rules"),
+            ("workspace", workspace_rules, "# Workspace Rules"),
+            ("exec", autostart, "# Autostart"),
+        ] {
+            updated_lines = merge_rule_lines(updated_lines, kind, new_lines, header);
         }
 
-        // Add new window rules
-        if !window_rules.is_empty() {
-            updated_lines.push(String::new());
-            updated_lines.push("# Window Rules".to_string());
-            for rule in window_rules {
-                updated_lines.push(rule.clone());
-            }
+        // Variables are merged the same way, or go at the top of a config
+        // that has none yet. Then each moves up above the first line using it.
+        if updated_lines.iter().any(|line| is_variable_line(line)) {
+            updated_lines = merge_rule_lines(updated_lines, "variable", variables, "# Variables");
+        } else {
+            replace_top_block(
+                &mut updated_lines,
+                is_variable_line,
+                variables,
+                "# Variables",
+            );
         }
+        define_before_use(&mut updated_lines);
 
-        // Add new layer rules
-        if !layer_rules.is_empty() {
-            updated_lines.push(String::new());
-            updated_lines.push("# Layer Rules".to_string());
-            for rule in layer_rules {
-                updated_lines.push(rule.clone());
-            }
+        let mut updated = updated_lines.join("\n");
+        if content.ends_with('\n') && !updated.is_empty() {
+            updated.push('\n');
         }
-
-        Ok(updated_lines.join("\n"))
+        Ok(updated)
     }
 
+    /// Set `options` (keyed `section:option`) in a config. Lines whose value
+    /// changes are rewritten with their indentation and trailing comment kept;
+    /// every other line is left byte for byte, including lines whose value
+    /// already means the new one once their `$variables` are resolved. Options not in the config yet
+    /// go into their section block, which is created if needed.
     #[allow(dead_code)]
     fn update_config_content(
         &self,
@@ -429,82 +938,76 @@ impl Config {
         options: &HashMap<String, String>,
     ) -> Result<String> {
         let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
-        let mut updated_options = HashMap::new();
-
-        // Update existing options - collect indices first to avoid borrow checker issues
-        let mut line_updates = Vec::new();
-        for (i, line) in lines.iter().enumerate() {
-            let trimmed = line.trim();
-            if trimmed.starts_with('#') || trimmed.is_empty() {
+        let mut found = std::collections::HashSet::new();
+        // Variables defined so far, to compare values the way Hyprland reads them
+        let mut variables = Variables::default();
+
+        let mut sections: Vec<String> = Vec::new();
+        for line in lines.iter_mut() {
+            let (code, _) = split_comment(line);
+            let trimmed = code.trim();
+            if let Some((name, value)) = Variables::parse_definition(trimmed) {
+                variables.define(name, value);
                 continue;
             }
-
-            // Check if this line contains a configuration option we want to update
-            for (option, value) in options {
-                let option_prefix = if option.contains(':') {
-                    match option.split_once(':') {
-                        Some((_, suffix)) => suffix,
-                        None => continue, // Skip invalid option format
-                    }
-                } else {
-                    option.as_str()
-                };
-
-                if trimmed.starts_with(&format!("{option_prefix} ="))
-                    || trimmed.starts_with(&format!("{option_prefix}="))
-                {
-                    line_updates.push((i, format!("    {option_prefix} = {value}")));
-                    updated_options.insert(option.clone(), value.clone());
-                    break;
+            if let Some(section) = trimmed.strip_suffix('{') {
+                sections.push(section.trim().to_string());
+                continue;
+            }
+            if trimmed == "}" {
+                sections.pop();
+                continue;
+            }
+            let Some((key, value)) = trimmed.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            let full_key = if sections.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}:{key}", sections.join(":"))
+            };
+            if let Some(new_value) = options.get(&full_key) {
+                // A line that already means the new value is left as written
+                if !crate::diff::values_match(&variables.resolve(value.trim()), new_value) {
+                    *line = replace_value(line, new_value.trim());
                 }
+                found.insert(full_key);
             }
         }
 
-        // Apply the collected updates
-        for (i, new_line) in line_updates {
-            lines[i] = new_line;
-        }
-
-        // Add options that weren't found in the existing config
-        for (option, value) in options {
-            if !updated_options.contains_key(option) {
-                let _section = if let Some((section, option_name)) = option.split_once(':') {
-                    // Find or create section
-                    let section_header = format!("{section} {{");
-                    let mut section_found = false;
-                    let mut section_end = lines.len();
-
-                    for (i, line) in lines.iter().enumerate() {
-                        if line.trim().starts_with(&section_header) {
-                            section_found = true;
-                            // Find the end of this section
-                            for (j, inner_line) in lines.iter().enumerate().skip(i + 1) {
-                                if inner_line.trim() == "}" {
-                                    section_end = j;
-                                    break;
-                                }
-                            }
-                            break;
-                        }
-                    }
-
-                    if section_found {
-                        lines.insert(section_end, format!("    {option_name} = {value}"));
-                    } else {
-                        // Create new section
-                        lines.push(String::new());
-                        lines.push(format!("{section} {{"));
-                        lines.push(format!("    {option_name} = {value}"));
-                        lines.push("}".to_string());
-                    }
-                    section
-                } else {
-                    // Global option
-                    lines.push(format!("{option} = {value}"));
-                    "global"
-                };
+        // Add options that weren't found, in a stable order
+        let mut missing: Vec<(&String, &String)> = options
+            .iter()
+            .filter(|(option, _)| !found.contains(*option))
+            .collect();
+        missing.sort();
+        for (option, value) in missing {
+            let path: Vec<&str> = option.split(':').collect();
+            let section_path = &path[..path.len() - 1];
+            if section_path.is_empty() {
+                // Global option
+                lines.push(format!("{option} = {value}"));
+                continue;
+            }
 
-                updated_options.insert(option.clone(), value.clone());
+            // Use the deepest block of the section path that exists, like
+            // `blur` inside `decoration`, and name the rest of the path
+            let existing = (1..=section_path.len()).rev().find_map(|depth| {
+                find_block_end(&lines, &section_path[..depth]).map(|end| (depth, end))
+            });
+            match existing {
+                Some((depth, end)) => {
+                    let key = path[depth..].join(":");
+                    lines.insert(end, format!("{}{key} = {value}", "    ".repeat(depth)));
+                }
+                None => {
+                    // Create new section
+                    lines.push(String::new());
+                    lines.push(format!("{} {{", section_path[0]));
+                    lines.push(format!("    {} = {value}", path[1..].join(":")));
+                    lines.push("}".to_string());
+                }
             }
         }
 
@@ -561,6 +1064,11 @@ impl Config {
         self.nixos_mode
     }
 
+    /// Whether changes stay in the file, from config.toml or `--no-apply`
+    pub fn is_file_only(&self) -> bool {
+        self.file_only || FILE_ONLY_OVERRIDE.get().is_some_and(|&on| on)
+    }
+
     #[allow(dead_code)]
     pub fn set_nixos_mode(&mut self, enabled: bool) {
         self.nixos_mode = enabled;
@@ -571,54 +1079,704 @@ impl Config {
             .await
             .map_err(|e| anyhow::anyhow!("Failed to read Hyprland config file: {}", e))?;
 
-        HyprlandConfigFile::parse(&content)
+        // Follow `source = path` directives so split configs load fully
+        let lines = resolve_includes(&self.hyprland_config_path, &content);
+        HyprlandConfigFile::parse_lines(&lines)
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct HyprlandConfigFile {
-    pub keybinds: Vec<ParsedKeybind>,
+/// Keybinds and rules to save, split between hyprland.conf and the files it
+/// pulls in with `source =`
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLines {
+    pub keybinds: Vec<String>,
     pub window_rules: Vec<String>,
     pub layer_rules: Vec<String>,
     pub workspace_rules: Vec<String>,
-    #[allow(dead_code)]
-    pub options: HashMap<String, String>,
+    pub env_vars: Vec<String>,
+    pub plugins: Vec<String>,
+    pub autostart: Vec<String>,
+    pub variables: Vec<String>,
+    /// Lines for each sourced file, in the order they should appear there
+    pub included: Vec<(PathBuf, Vec<String>)>,
 }
 
-#[derive(Debug, Clone)]
-pub struct ParsedKeybind {
-    #[allow(dead_code)]
-    pub bind_type: String, // bind, bindm, binde, etc.
-    pub modifiers: String,
-    pub key: String,
-    pub dispatcher: String,
-    pub args: String,
-    #[allow(dead_code)]
-    pub original_line: String,
+impl ConfigLines {
+    pub fn is_empty(&self) -> bool {
+        self.keybinds.is_empty()
+            && self.window_rules.is_empty()
+            && self.layer_rules.is_empty()
+            && self.workspace_rules.is_empty()
+            && self.env_vars.is_empty()
+            && self.plugins.is_empty()
+            && self.autostart.is_empty()
+            && self.variables.is_empty()
+            && self.included.is_empty()
+    }
 }
 
-impl HyprlandConfigFile {
-    pub fn parse(content: &str) -> Result<Self> {
-        let mut keybinds = Vec::new();
-        let mut window_rules = Vec::new();
-        let mut layer_rules = Vec::new();
-        let mut workspace_rules = Vec::new();
-        let mut options = HashMap::new();
+/// The kind of keybind or rule a line is, so sourced files can be updated
+/// slot by slot. Options and other lines return `None`.
+fn managed_line_kind(line: &str) -> Option<&'static str> {
+    let trimmed = line.trim();
+    if is_variable_line(trimmed) {
+        return Some("variable");
+    }
+    if is_env_line(trimmed) {
+        return Some("env");
+    }
+    if is_plugin_line(trimmed) {
+        return Some("plugin");
+    }
+    if is_exec_line(trimmed) {
+        return Some("exec");
+    }
+    if is_workspace_rule_line(trimmed) {
+        return Some("workspace");
+    }
+    let (keyword, _) = trimmed.split_once('=')?;
+    let keyword = keyword.trim();
+    if keyword.starts_with("bind") {
+        Some("bind")
+    } else if keyword.starts_with("windowrule") {
+        Some("windowrule")
+    } else if keyword.starts_with("layerrule") {
+        Some("layerrule")
+    } else {
+        None
+    }
+}
+
+/// Update a sourced file in place so it holds `lines`: each keybind or rule
+/// takes the place of the next existing line of the same kind, surplus
+/// existing lines are removed, and lines without a slot are appended.
+/// Comments, options and layout are left untouched.
+pub fn update_included_content(content: &str, lines: &[String]) -> String {
+    let mut pending: HashMap<&str, std::collections::VecDeque<&str>> = HashMap::new();
+    for line in lines {
+        if let Some(kind) = managed_line_kind(line) {
+            pending.entry(kind).or_default().push_back(line.trim());
+        }
+    }
+
+    let mut updated: Vec<String> = Vec::new();
+    for line in join_continued_lines(content) {
+        let Some(kind) = managed_line_kind(&line) else {
+            updated.push(line);
+            continue;
+        };
+        if let Some(new_line) = pending.get_mut(kind).and_then(|queue| queue.pop_front()) {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            updated.push(format!("{indent}{new_line}"));
+        }
+    }
+
+    // Whatever is left had no slot, so append it in its original order
+    let mut leftover: Vec<&str> = Vec::new();
+    for line in lines {
+        if let Some(queue) = managed_line_kind(line).and_then(|kind| pending.get_mut(kind)) {
+            if queue.front() == Some(&line.trim()) {
+                leftover.extend(queue.pop_front());
+            }
+        }
+    }
+    updated.extend(leftover.into_iter().map(str::to_string));
+
+    let mut result = updated.join("\n");
+    if !result.is_empty() && (content.ends_with('\n') || content.is_empty()) {
+        result.push('\n');
+    }
+    result
+}
 
-        for line in content.lines() {
-            let line = line.trim();
+/// Put `line` in place of the first of `old_lines` in a config, keeping that
+/// line's indentation and comment. Without any of them, `line` goes after
+/// the last line of its kind, or at the end. Returns `None` when the config
+/// has `line` already.
+pub fn replace_config_line(content: &str, old_lines: &[String], line: &str) -> Option<String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let normalized = normalize_line(line);
+    if lines
+        .iter()
+        .any(|existing| normalize_line(existing) == normalized)
+    {
+        return None;
+    }
+
+    let old: Vec<String> = old_lines.iter().map(|old| normalize_line(old)).collect();
+    match lines
+        .iter()
+        .position(|existing| old.contains(&normalize_line(existing)))
+    {
+        Some(index) => lines[index] = rewrite_line(&lines[index], line),
+        None => {
+            let kind = rule_line_kind(line);
+            let index = lines
+                .iter()
+                .rposition(|existing| kind.is_some() && rule_line_kind(existing) == kind)
+                .map_or(lines.len(), |last| last + 1);
+            lines.insert(index, line.trim().to_string());
+        }
+    }
 
-            // Skip empty lines and comments
-            if line.is_empty() || line.starts_with('#') {
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') || content.is_empty() {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
+/// Remove the lines setting `option` (in `section:name` form) from a config,
+/// whether written in full at the top level or as `name = value` inside
+/// `section { }` blocks. Returns `None` when no line sets it.
+pub fn remove_option_lines(content: &str, option: &str) -> Option<String> {
+    let mut sections: Vec<String> = Vec::new();
+    let mut removed = false;
+    let mut kept = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if let Some(section) = trimmed.strip_suffix('{') {
+            sections.push(section.trim().to_string());
+        } else if trimmed == "}" {
+            sections.pop();
+        } else if let Some((key, _)) = trimmed.split_once('=') {
+            let key = key.trim();
+            let full_key = if sections.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}:{key}", sections.join(":"))
+            };
+            if full_key == option {
+                removed = true;
                 continue;
             }
+        }
+        kept.push(line);
+    }
 
-            // Parse keybinds
-            if line.starts_with("bind") {
-                if let Some(keybind) = Self::parse_keybind_line(line) {
-                    keybinds.push(keybind);
-                } else {
-                    eprintln!("Debug: Failed to parse keybind line: {line}");
+    if !removed {
+        return None;
+    }
+    let mut updated = kept.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
+/// Rewrite the deprecated options a config sets as their replacements, see
+/// `deprecations`. A replacement in the same section stays on its line, like
+/// `drop_shadow` becoming `shadow:enabled` inside `decoration { }`; one in
+/// another section goes to the end in full, and a removed option is
+/// commented out. Returns `None` when nothing was deprecated.
+pub fn migrate_deprecated_lines(content: &str) -> Option<String> {
+    use crate::deprecations::{migration, replacement, Migration};
+
+    let mut sections: Vec<String> = Vec::new();
+    let mut migrated = false;
+    let mut lines = Vec::new();
+    let mut moved = Vec::new();
+
+    for line in content.lines() {
+        let (code, comment) = split_comment(line);
+        let trimmed = code.trim();
+        if let Some(section) = trimmed.strip_suffix('{') {
+            sections.push(section.trim().to_string());
+        } else if trimmed == "}" {
+            sections.pop();
+        } else if let Some((key, value)) = trimmed.split_once('=') {
+            let prefix = sections.join(":");
+            let full_key = if prefix.is_empty() {
+                key.trim().to_string()
+            } else {
+                format!("{prefix}:{}", key.trim())
+            };
+            let indent = &line[..line.len() - line.trim_start().len()];
+            match replacement(&full_key).map(|found| migration(found, value)) {
+                Some(Migration::Set(new_key, new_value)) => {
+                    migrated = true;
+                    match new_key.strip_prefix(&format!("{prefix}:")) {
+                        Some(key) if !prefix.is_empty() => {
+                            lines.push(format!("{indent}{key} = {new_value}{comment}"))
+                        }
+                        _ if prefix.is_empty() => {
+                            lines.push(format!("{indent}{new_key} = {new_value}{comment}"))
+                        }
+                        _ => moved.push(format!("{new_key} = {new_value}")),
+                    }
+                    continue;
+                }
+                Some(Migration::Remove) => {
+                    migrated = true;
+                    lines.push(format!("{indent}# Removed from Hyprland: {trimmed}"));
+                    continue;
+                }
+                Some(Migration::Keep) | None => {}
+            }
+        }
+        lines.push(line.to_string());
+    }
+
+    if !migrated {
+        return None;
+    }
+    if !moved.is_empty() {
+        lines.push(String::new());
+        lines.extend(moved);
+    }
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Some(updated)
+}
+
+/// The lines of a config as Hyprland reads them: a line ending in `\` goes
+/// on with the next one
+pub fn join_continued_lines(content: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut pending: Option<String> = None;
+    for line in content.lines() {
+        let line = match pending.take() {
+            Some(start) => format!("{start} {}", line.trim_start()),
+            None => line.to_string(),
+        };
+        match line.trim_end().strip_suffix('\\') {
+            Some(start) => pending = Some(start.trim_end().to_string()),
+            None => lines.push(line),
+        }
+    }
+    lines.extend(pending);
+    lines
+}
+
+/// Split a line into its code and its comment, where the comment keeps the
+/// whitespace before its `#`. `##` is an escaped `#` and starts no comment.
+fn split_comment(line: &str) -> (&str, &str) {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    let mut end = line.len();
+    while i < bytes.len() {
+        if bytes[i] == b'#' {
+            if bytes.get(i + 1) == Some(&b'#') {
+                i += 2;
+                continue;
+            }
+            end = i;
+            break;
+        }
+        i += 1;
+    }
+    let code = line[..end].trim_end();
+    (code, &line[code.len()..])
+}
+
+/// `line` with its value replaced, keeping the key as written, the spacing
+/// around `=` and any trailing comment
+fn replace_value(line: &str, value: &str) -> String {
+    let (code, comment) = split_comment(line);
+    let Some(eq) = code.find('=') else {
+        return line.to_string();
+    };
+    let after = &code[eq + 1..];
+    let spacing = after.len() - after.trim_start().len();
+    format!("{}{value}{comment}", &code[..eq + 1 + spacing])
+}
+
+/// A line without its comment and with the spacing around `=` and `,`
+/// evened out, so `bind=SUPER,Q,killactive` matches `bind = SUPER, Q, killactive`
+fn normalize_line(line: &str) -> String {
+    // A disabled plugin or command is all comment, but still stands for its line
+    if let Some(disabled) = disabled_line(line) {
+        return disabled;
+    }
+    let code = split_comment(line).0.trim();
+    let even_commas = |text: &str| {
+        text.split(',')
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match code.split_once('=') {
+        Some((key, value)) => format!("{} = {}", key.trim(), even_commas(value)),
+        None => even_commas(code),
+    }
+}
+
+/// The line a commented-out plugin or command stands for
+fn disabled_line(line: &str) -> Option<String> {
+    match (PluginLine::parse(line), ExecLine::parse(line)) {
+        (Some(plugin), _) if !plugin.enabled => Some(plugin.to_line()),
+        (_, Some(exec)) if !exec.enabled => Some(exec.to_line()),
+        _ => None,
+    }
+}
+
+/// Index of the `}` closing the first block at `path`, like `decoration`
+/// then `blur`
+fn find_block_end(lines: &[String], path: &[&str]) -> Option<usize> {
+    let mut sections: Vec<&str> = Vec::new();
+    let mut inside = false;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = split_comment(line).0.trim();
+        if let Some(section) = trimmed.strip_suffix('{') {
+            sections.push(section.trim());
+            inside |= sections == path;
+        } else if trimmed == "}" {
+            if inside && sections == path {
+                return Some(i);
+            }
+            sections.pop();
+        }
+    }
+    None
+}
+
+/// The kind of keybind or rule a line of hyprland.conf is. Submap lines
+/// count as binds, so they stay around the binds they enclose.
+fn rule_line_kind(line: &str) -> Option<&'static str> {
+    let trimmed = line.trim();
+    if HyprlandConfigFile::parse_submap_line(trimmed).is_some() {
+        return Some("bind");
+    }
+    if trimmed.starts_with("blurls") {
+        return Some("layerrule");
+    }
+    managed_line_kind(trimmed).filter(|kind| !matches!(*kind, "env" | "plugin"))
+}
+
+/// Replace every line `is_kind` matches with `new_lines`, written where the
+/// first of them was. Without any, the new lines go at the top of the file
+/// under `header`.
+fn replace_top_block(
+    lines: &mut Vec<String>,
+    is_kind: fn(&str) -> bool,
+    new_lines: &[String],
+    header: &str,
+) {
+    let index = lines.iter().position(|line| is_kind(line));
+    lines.retain(|line| !is_kind(line));
+    if new_lines.is_empty() {
+        return;
+    }
+    let mut block = new_lines.to_vec();
+    let index = index.unwrap_or_else(|| {
+        block.insert(0, header.to_string());
+        block.push(String::new());
+        0
+    });
+    lines.splice(index..index, block);
+}
+
+/// Move each `$name = value` line above the first line using its variable,
+/// as Hyprland only knows a variable from its definition on. Only the first
+/// definition of a name moves, since later ones change its value from there.
+fn define_before_use(lines: &mut Vec<String>) {
+    let mut variables = Variables::default();
+    for line in lines.iter() {
+        if let Some((name, value)) = Variables::parse_definition(split_comment(line).0) {
+            variables.define(name, value);
+        }
+    }
+    // Bounded, since variables defined by each other can't all come first
+    for _ in 0..lines.len() {
+        let mut first_definitions: HashMap<String, usize> = HashMap::new();
+        for (i, line) in lines.iter().enumerate() {
+            if let Some((name, _)) = Variables::parse_definition(split_comment(line).0) {
+                first_definitions.entry(name).or_insert(i);
+            }
+        }
+        let misplaced = lines.iter().enumerate().find_map(|(i, line)| {
+            let code = split_comment(line).0;
+            let value = Variables::parse_definition(code).map(|(_, value)| value);
+            let uses = match &value {
+                Some(value) => variables.used_names(value),
+                None => variables.used_names(code),
+            };
+            uses.into_iter()
+                .filter_map(|name| first_definitions.get(name))
+                .find(|&&definition| definition > i)
+                .map(|&definition| (i, definition))
+        });
+        let Some((use_index, definition)) = misplaced else {
+            return;
+        };
+        let line = lines.remove(definition);
+        lines.insert(use_index, line);
+    }
+}
+
+/// Write `new_lines` over the lines of one kind in `lines`. The two are
+/// aligned like a diff: matching lines stay as they are, changed ones are
+/// rewritten where they were, removed ones are dropped, and added ones go
+/// after their predecessor. Without any lines of the kind, the new ones are
+/// appended under `header`.
+fn merge_rule_lines(
+    lines: Vec<String>,
+    kind: &str,
+    new_lines: &[String],
+    header: &str,
+) -> Vec<String> {
+    let positions: Vec<usize> = (0..lines.len())
+        .filter(|&i| rule_line_kind(&lines[i]) == Some(kind))
+        .collect();
+    if positions.is_empty() {
+        let mut lines = lines;
+        if !new_lines.is_empty() {
+            lines.push(String::new());
+            lines.push(header.to_string());
+            lines.extend(new_lines.iter().cloned());
+        }
+        return lines;
+    }
+
+    let old: Vec<String> = positions
+        .iter()
+        .map(|&i| normalize_line(&lines[i]))
+        .collect();
+    let new: Vec<String> = new_lines.iter().map(|line| normalize_line(line)).collect();
+    let old_refs: Vec<&str> = old.iter().map(String::as_str).collect();
+    let new_refs: Vec<&str> = new.iter().map(String::as_str).collect();
+    let script = edit_script(&old_refs, &new_refs);
+
+    // What each old line becomes, and what is inserted after it
+    let mut replacements: Vec<Option<String>> = vec![None; positions.len()];
+    let mut inserted_after: Vec<Vec<String>> = vec![Vec::new(); positions.len()];
+    let mut inserted_first: Vec<String> = Vec::new();
+    let (mut o, mut n, mut k) = (0, 0, 0);
+    while k < script.len() {
+        if script[k] == Edit::Keep {
+            replacements[o] = Some(lines[positions[o]].clone());
+            o += 1;
+            n += 1;
+            k += 1;
+            continue;
+        }
+
+        // A run of removals and additions: pair them up as changed lines
+        let run_end = (k..script.len())
+            .find(|&r| script[r] == Edit::Keep)
+            .unwrap_or(script.len());
+        let previous = o.checked_sub(1);
+        let mut removed = Vec::new();
+        let mut added = Vec::new();
+        for edit in &script[k..run_end] {
+            if *edit == Edit::Remove {
+                removed.push(o);
+                o += 1;
+            } else {
+                added.push(n);
+                n += 1;
+            }
+        }
+        for (&r, &a) in removed.iter().zip(&added) {
+            replacements[r] = Some(rewrite_line(&lines[positions[r]], &new_lines[a]));
+        }
+        let extra = added
+            .iter()
+            .skip(removed.len())
+            .map(|&a| new_lines[a].trim().to_string());
+        match removed.last().copied().or(previous) {
+            Some(anchor) => inserted_after[anchor].extend(extra),
+            None => inserted_first.extend(extra),
+        }
+        k = run_end;
+    }
+
+    let mut merged = Vec::with_capacity(lines.len() + new_lines.len());
+    let mut slot = 0;
+    for (i, line) in lines.into_iter().enumerate() {
+        if positions.get(slot) != Some(&i) {
+            merged.push(line);
+            continue;
+        }
+        if slot == 0 {
+            merged.append(&mut inserted_first);
+        }
+        merged.extend(replacements[slot].take());
+        merged.append(&mut inserted_after[slot]);
+        slot += 1;
+    }
+    merged
+}
+
+/// `new` in place of `old`, keeping the indentation and trailing comment of
+/// `old`. When the two differ only in spacing, `old` is kept as it is.
+fn rewrite_line(old: &str, new: &str) -> String {
+    if normalize_line(old) == normalize_line(new) {
+        return old.to_string();
+    }
+    let indent = &old[..old.len() - old.trim_start().len()];
+    if disabled_line(old).is_some() || disabled_line(new).is_some() {
+        return format!("{indent}{}", new.trim());
+    }
+    let comment = split_comment(old).1;
+    format!("{indent}{}{comment}", split_comment(new.trim()).0)
+}
+
+/// Whether `path` is, or links to, a file in the Nix store. Home Manager
+/// links hyprland.conf there, and the store is read-only.
+pub fn is_nix_managed(path: &Path) -> bool {
+    std::fs::canonicalize(path).is_ok_and(|target| target.starts_with("/nix/store"))
+}
+
+/// Refuse to edit a file Nix generates, saying where edits persist instead
+fn ensure_writable(path: &Path) -> Result<()> {
+    if is_nix_managed(path) {
+        anyhow::bail!(
+            "{} is generated by Nix and read-only. Live changes last until Hyprland \
+             reloads; export them as a Nix module with N to keep them.",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// The timestamp and same-second counter in a backup's name, for sorting
+fn backup_order(path: &Path) -> (String, u64) {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let stamp = name
+        .trim_end_matches(".bak")
+        .rsplit_once('.')
+        .map_or(name, |(_, stamp)| stamp);
+    stamp
+        .rsplit_once('_')
+        .filter(|(time, _)| time.contains('_'))
+        .and_then(|(time, counter)| Some((time.to_string(), counter.parse().ok()?)))
+        .unwrap_or_else(|| (stamp.to_string(), 0))
+}
+
+#[derive(Debug, Clone)]
+pub struct HyprlandConfigFile {
+    pub keybinds: Vec<ParsedKeybind>,
+    pub window_rules: Vec<String>,
+    pub layer_rules: Vec<String>,
+    pub workspace_rules: Vec<String>,
+    pub env_vars: Vec<String>,
+    /// Plugin lines, disabled ones commented out
+    pub plugins: Vec<String>,
+    /// `exec-once` and `exec` lines in order, disabled ones commented out
+    pub autostart: Vec<String>,
+    /// `$name = value` lines in order
+    pub variable_lines: Vec<String>,
+    #[allow(dead_code)]
+    pub options: HashMap<String, String>,
+    /// Lines that came from a file pulled in with `source =`, keyed by the
+    /// trimmed line. Lines from the main config are not listed.
+    pub line_sources: HashMap<String, PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedKeybind {
+    pub bind_type: String, // bind, bindm, binde, etc.
+    pub modifiers: String,
+    pub key: String,
+    /// What a bind with the `d` flag, like `bindd`, says it does
+    pub description: Option<String>,
+    pub dispatcher: String,
+    pub args: String,
+    #[allow(dead_code)]
+    pub original_line: String,
+    pub submap: Option<String>, // Enclosing `submap = name` block, if any
+}
+
+impl ParsedKeybind {
+    /// Format the keybind the same way as `HyprlandKeybind::display_string`,
+    /// prefixed with the submap name when the bind lives inside a submap.
+    /// Binds other than a plain `bind` end in a tag with their keyword and
+    /// description, like ` (bindeld: Volume up)`, see `UI::split_bind_tag`.
+    pub fn display_string(&self) -> String {
+        let submap_prefix = match &self.submap {
+            Some(name) => format!("[{name}] "),
+            None => String::new(),
+        };
+
+        let mods: Vec<&str> = self
+            .modifiers
+            .split_whitespace().collect();
+        let mod_string = if mods.is_empty() {
+            String::new()
+        } else {
+            format!("{} + ", mods.join(" + "))
+        };
+
+        let args_string = if self.args.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", self.args)
+        };
+
+        let tag = match (&self.description, self.bind_type.as_str()) {
+            (Some(description), bind_type) => format!(" ({bind_type}: {description})"),
+            (None, "bind") => String::new(),
+            (None, bind_type) => format!(" ({bind_type})"),
+        };
+
+        format!(
+            "{}{}{} → {}{}{}",
+            submap_prefix, mod_string, self.key, self.dispatcher, args_string, tag
+        )
+    }
+}
+
+impl HyprlandConfigFile {
+    pub fn parse(content: &str) -> Result<Self> {
+        let lines: Vec<SourcedLine> = join_continued_lines(content)
+            .into_iter()
+            .map(|text| SourcedLine { file: None, text })
+            .collect();
+        Self::parse_lines(&lines)
+    }
+
+    /// Parse lines that may come from several files, see `resolve_includes`
+    pub fn parse_lines(lines: &[SourcedLine]) -> Result<Self> {
+        let mut keybinds = Vec::new();
+        let mut window_rules = Vec::new();
+        let mut layer_rules = Vec::new();
+        let mut workspace_rules = Vec::new();
+        let mut env_vars = Vec::new();
+        let mut plugins = Vec::new();
+        let mut autostart = Vec::new();
+        let mut options = HashMap::new();
+        let mut current_submap: Option<String> = None;
+        let mut line_sources = HashMap::new();
+        let mut variable_lines = Vec::new();
+
+        for sourced in lines {
+            let line = sourced.text.trim();
+
+            // Skip empty lines and comments, except disabled plugins and commands
+            if line.is_empty()
+                || (line.starts_with('#') && !is_plugin_line(line) && !is_exec_line(line))
+            {
+                continue;
+            }
+
+            if let Some(file) = &sourced.file {
+                line_sources
+                    .entry(line.to_string())
+                    .or_insert_with(|| file.clone());
+            }
+
+            // Track submap blocks: `submap = name` opens one, `submap = reset` closes it
+            if let Some(name) = Self::parse_submap_line(line) {
+                current_submap = if name == "reset" { None } else { Some(name) };
+            }
+            // Collect variables
+            else if is_variable_line(line) {
+                variable_lines.push(line.to_string());
+            }
+            // Parse keybinds
+            else if line.starts_with("bind") {
+                if let Some(mut keybind) = Self::parse_keybind_line(line) {
+                    keybind.submap = current_submap.clone();
+                    keybinds.push(keybind);
+                } else {
+                    crate::stderr_log::note(format!("Failed to parse keybind line: {line}"));
                 }
             }
             // Parse window rules
@@ -637,9 +1795,21 @@ impl HyprlandConfigFile {
                 }
             }
             // Parse workspace rules
-            else if line.starts_with("workspace") {
+            else if is_workspace_rule_line(line) {
                 workspace_rules.push(line.to_string());
             }
+            // Parse environment variables
+            else if is_env_line(line) {
+                env_vars.push(line.to_string());
+            }
+            // Parse plugins
+            else if is_plugin_line(line) {
+                plugins.push(line.to_string());
+            }
+            // Parse autostart commands
+            else if is_exec_line(line) {
+                autostart.push(line.to_string());
+            }
             // Parse configuration options in sections
             else if line.contains('=') && !line.contains(' ') {
                 // Simple key=value pairs
@@ -654,15 +1824,76 @@ impl HyprlandConfigFile {
             window_rules,
             layer_rules,
             workspace_rules,
+            env_vars,
+            plugins,
+            autostart,
+            variable_lines,
             options,
+            line_sources,
         })
     }
 
+    /// Files pulled in with `source =` that contributed at least one line
+    pub fn sourced_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.line_sources.values().cloned().collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// The included file a line came from, or `None` if it is in the main config
+    pub fn source_of(&self, line: &str) -> Option<&Path> {
+        self.line_sources.get(line.trim()).map(PathBuf::as_path)
+    }
+
+    /// Original keybind lines, with binds inside submaps wrapped in their
+    /// `submap = name` / `submap = reset` blocks
+    pub fn keybind_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut submaps: Vec<(String, Vec<String>)> = Vec::new();
+
+        for keybind in &self.keybinds {
+            match &keybind.submap {
+                Some(name) => {
+                    if let Some((_, binds)) = submaps.iter_mut().find(|(n, _)| n == name) {
+                        binds.push(keybind.original_line.clone());
+                    } else {
+                        submaps.push((name.clone(), vec![keybind.original_line.clone()]));
+                    }
+                }
+                None => lines.push(keybind.original_line.clone()),
+            }
+        }
+
+        for (name, binds) in submaps {
+            lines.push(format!("submap = {name}"));
+            lines.extend(binds);
+            lines.push("submap = reset".to_string());
+        }
+
+        lines
+    }
+
+    /// Return the submap name for a `submap = name` line
+    pub fn parse_submap_line(line: &str) -> Option<String> {
+        let (key, value) = line.split_once('=')?;
+        if key.trim() != "submap" {
+            return None;
+        }
+        let value = value.split('#').next().unwrap_or("").trim();
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    }
+
     fn parse_keybind_line(line: &str) -> Option<ParsedKeybind> {
         // Parse different bind formats:
         // bind = SUPER, N, exec, swaync-client -t -sw
         // bindm = $mainMod, mouse:272, movewindow
         // binde = $mainMod, l, resizeactive, 30 0
+        // bindeld = , XF86AudioRaiseVolume, Volume up, exec, wpctl set-volume @DEFAULT_AUDIO_SINK@ 5%+
 
         let parts: Vec<&str> = line.splitn(2, '=').collect();
         if parts.len() != 2 {
@@ -670,24 +1901,40 @@ impl HyprlandConfigFile {
         }
 
         let bind_type = parts[0].trim().to_string();
-        let bind_content = parts[1].trim();
+        let bind_content = split_comment(parts[1]).0.trim();
 
-        // Split by commas, but be careful about commas in arguments
+        // A bind with the `d` flag has a description before its dispatcher
+        let described = crate::keybinds::bind_flags(&bind_type).is_some_and(|f| f.contains('d'));
+        let fields = if described { 4 } else { 3 };
+
+        // Split by commas, but be careful about commas in arguments.
+        // Only modifiers, key, description and dispatcher are comma
+        // separated; everything after them is the argument string and is
+        // kept verbatim, e.g. `exec, notify-send "a, b"`.
         let mut bind_parts = Vec::new();
         let mut current_part = String::new();
         let mut paren_depth = 0;
+        let mut in_quotes = false;
 
         for ch in bind_content.chars() {
+            if bind_parts.len() == fields {
+                current_part.push(ch);
+                continue;
+            }
             match ch {
-                ',' if paren_depth == 0 => {
+                ',' if paren_depth == 0 && !in_quotes => {
                     bind_parts.push(current_part.trim().to_string());
                     current_part.clear();
                 }
-                '(' | '[' => {
+                '"' => {
+                    in_quotes = !in_quotes;
+                    current_part.push(ch);
+                }
+                '(' | '[' if !in_quotes => {
                     paren_depth += 1;
                     current_part.push(ch);
                 }
-                ')' | ']' => {
+                ')' | ']' if !in_quotes => {
                     paren_depth -= 1;
                     current_part.push(ch);
                 }
@@ -698,26 +1945,1146 @@ impl HyprlandConfigFile {
             bind_parts.push(current_part.trim().to_string());
         }
 
-        if bind_parts.len() >= 3 {
+        if bind_parts.len() >= fields {
+            let description = described.then(|| bind_parts.remove(2));
             let modifiers = bind_parts[0].clone();
             let key = bind_parts[1].clone();
             let dispatcher = bind_parts[2].clone();
-            let args = if bind_parts.len() > 3 {
-                bind_parts[3..].join(", ")
-            } else {
-                String::new()
-            };
+            let args = bind_parts.get(3).cloned().unwrap_or_default();
 
             Some(ParsedKeybind {
                 bind_type,
                 modifiers,
                 key,
+                description,
                 dispatcher,
                 args,
                 original_line: line.to_string(),
+                submap: None,
             })
         } else {
             None
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::FocusedPanel;
+    use crate::ui::{ConfigDataType, ConfigItem, UI};
+
+    const SUBMAP_CONFIG: &str = "\
+bind = SUPER, Q, exec, kitty
+bind = SUPER, R, submap, resize
+
+submap = resize
+binde = , L, resizeactive, 10 0
+bind = , escape, submap, reset
+submap = reset
+
+bind = SUPER, M, submap, move
+submap = move
+bind = SUPER, L, movewindow, r
+submap = reset
+";
+
+    #[test]
+    fn test_parse_submaps() {
+        let parsed = HyprlandConfigFile::parse(SUBMAP_CONFIG).unwrap();
+
+        assert_eq!(parsed.keybinds.len(), 6);
+        assert_eq!(parsed.keybinds[0].submap, None);
+        assert_eq!(parsed.keybinds[1].dispatcher, "submap");
+        assert_eq!(parsed.keybinds[1].submap, None);
+        assert_eq!(parsed.keybinds[2].submap.as_deref(), Some("resize"));
+        assert_eq!(parsed.keybinds[3].submap.as_deref(), Some("resize"));
+        assert_eq!(parsed.keybinds[4].submap, None);
+        assert_eq!(parsed.keybinds[5].submap.as_deref(), Some("move"));
+
+        assert_eq!(
+            parsed.keybinds[5].display_string(),
+            "[move] SUPER + L → movewindow [r]"
+        );
+        assert_eq!(
+            parsed.keybinds[2].display_string(),
+            "[resize] L → resizeactive [10 0] (binde)"
+        );
+
+        let lines = parsed.keybind_lines();
+        assert_eq!(lines[3], "submap = resize");
+        assert_eq!(lines[4], "binde = , L, resizeactive, 10 0");
+        assert_eq!(lines[6], "submap = reset");
+        assert_eq!(lines[7], "submap = move");
+        assert_eq!(lines.len(), 10);
+    }
+
+    #[test]
+    fn test_parse_keybind_args_with_commas() {
+        let parsed = HyprlandConfigFile::parse(
+            "bind = SUPER, N, exec, notify-send \"a, b\" \"c,d\"\n\
+             bind = SUPER SHIFT, code:28, exec, hyprctl dispatch workspace 1, 2\n",
+        )
+        .unwrap();
+
+        assert_eq!(parsed.keybinds.len(), 2);
+        assert_eq!(parsed.keybinds[0].dispatcher, "exec");
+        assert_eq!(parsed.keybinds[0].args, "notify-send \"a, b\" \"c,d\"");
+        assert_eq!(parsed.keybinds[1].key, "code:28");
+        assert_eq!(parsed.keybinds[1].args, "hyprctl dispatch workspace 1, 2");
+    }
+
+    #[test]
+    fn test_submap_round_trip() {
+        let parsed = HyprlandConfigFile::parse(SUBMAP_CONFIG).unwrap();
+
+        let mut ui = UI::new();
+        let items = parsed
+            .keybinds
+            .iter()
+            .enumerate()
+            .map(|(i, bind)| ConfigItem {
+                key: format!("bind_{i}"),
+                value: bind.display_string(),
+                description: String::new(),
+                data_type: ConfigDataType::String,
+                suggestions: vec![],
+                source_file: None,
+            })
+            .collect();
+        ui.config_items.insert(FocusedPanel::Binds, items);
+
+        let keybinds = ui.collect_keybinds();
+        let config = Config::default();
+        let written = config
+            .update_config_content_with_rules(
+                SUBMAP_CONFIG,
+                &HashMap::new(),
+                &keybinds,
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+            )
+            .unwrap();
+
+        assert_eq!(written.matches("submap = resize").count(), 1);
+        assert_eq!(written.matches("submap = move").count(), 1);
+        assert_eq!(written.matches("submap = reset").count(), 2);
+
+        // Global binds are written first, followed by one block per submap
+        let summarize = |file: &HyprlandConfigFile| {
+            let mut binds: Vec<(Option<String>, String, String)> = file
+                .keybinds
+                .iter()
+                .map(|b| (b.submap.clone(), b.key.clone(), b.dispatcher.clone()))
+                .collect();
+            binds.sort();
+            binds
+        };
+        let reparsed = HyprlandConfigFile::parse(&written).unwrap();
+        assert_eq!(summarize(&reparsed), summarize(&parsed));
+    }
+
+    /// Binds as Omarchy and ML4W write them: `$variables`, descriptions,
+    /// empty modifiers for media keys and a line continued with `\`
+    const OMARCHY_BINDS: &str = "\
+$terminal = uwsm app -- alacritty
+$browser = omarchy-launch-browser
+$osdclient = swayosd-client --monitor $(hyprctl activeworkspace -j | jq -r .monitor)
+$mainMod = SUPER
+$key = Q
+
+bindd = SUPER, RETURN, Terminal, exec, $terminal
+bindd = SUPER SHIFT, B, Browser, exec, $browser
+bindd = SUPER, W, Close active window, killactive,
+bindd = SUPER, J, Toggle split, togglesplit, # dwindle
+bindd = SUPER, code:10, Switch to workspace 1, workspace, 1
+bindmd = SUPER, mouse:272, Move window, movewindow
+bindeld = ,XF86AudioRaiseVolume, Volume up, exec, $osdclient --output-volume raise
+bindeld = ,XF86MonBrightnessUp, Brightness up, exec, $osdclient --brightness raise
+bindld = , XF86AudioPlay, Play, exec, $osdclient --playerctl play-pause
+bind = $mainMod, $key, killactive
+bind = $mainMod CTRL, Q, exec, \\
+    ~/.config/ml4w/scripts/wlogout.sh
+bind = , XF86Calculator, exec, ~/.config/ml4w/settings/calculator.sh
+bindel = , XF86AudioLowerVolume, exec, wpctl set-volume @DEFAULT_AUDIO_SINK@ 5%-
+";
+
+    #[test]
+    fn test_omarchy_and_ml4w_binds_round_trip() {
+        let parsed = HyprlandConfigFile::parse(OMARCHY_BINDS).unwrap();
+        assert_eq!(parsed.keybinds.len(), 13);
+
+        let volume = &parsed.keybinds[6];
+        assert_eq!(volume.bind_type, "bindeld");
+        assert_eq!(volume.modifiers, "");
+        assert_eq!(volume.key, "XF86AudioRaiseVolume");
+        assert_eq!(volume.description.as_deref(), Some("Volume up"));
+        assert_eq!(volume.dispatcher, "exec");
+        assert_eq!(
+            volume.display_string(),
+            "XF86AudioRaiseVolume → exec [$osdclient --output-volume raise] (bindeld: Volume up)"
+        );
+        assert_eq!(parsed.keybinds[3].args, "");
+        assert_eq!(
+            parsed.keybinds[9].display_string(),
+            "$mainMod + $key → killactive"
+        );
+        assert_eq!(
+            parsed.keybinds[10].args,
+            "~/.config/ml4w/scripts/wlogout.sh"
+        );
+
+        let items: Vec<ConfigItem> = parsed
+            .keybinds
+            .iter()
+            .enumerate()
+            .map(|(i, bind)| ConfigItem {
+                key: format!("bind_{i}"),
+                value: bind.display_string(),
+                description: String::new(),
+                data_type: ConfigDataType::String,
+                suggestions: vec![],
+                source_file: None,
+            })
+            .collect();
+        assert!(crate::keybinds::unknown_dispatcher_keys(&items).is_empty());
+
+        let mut ui = UI::new();
+        ui.config_items.insert(FocusedPanel::Binds, items);
+        let keybinds = ui.collect_keybinds();
+        for line in &keybinds {
+            assert_eq!(
+                crate::keybinds::validate_bind_line(line, true),
+                Ok(()),
+                "{line}"
+            );
+        }
+
+        let written = Config::default()
+            .update_config_content_with_rules(
+                OMARCHY_BINDS,
+                &HashMap::new(),
+                &keybinds,
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+            )
+            .unwrap();
+        assert!(written.contains(
+            "bindeld = ,XF86AudioRaiseVolume, Volume up, exec, $osdclient --output-volume raise\n"
+        ));
+        let fields = |file: &HyprlandConfigFile| {
+            file.keybinds
+                .iter()
+                .map(|b| {
+                    format!(
+                        "{}|{}|{}|{:?}|{}|{}",
+                        b.bind_type, b.modifiers, b.key, b.description, b.dispatcher, b.args
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let reparsed = HyprlandConfigFile::parse(&written).unwrap();
+        assert_eq!(fields(&reparsed), fields(&parsed));
+    }
+
+    #[test]
+    fn test_gradient_option_round_trip() {
+        use crate::color::HyprGradient;
+
+        let config = Config::default();
+        let cases = [
+            (
+                "general:col.active_border = rgba(ee1100ff) rgba(1100eeff) 45deg",
+                "rgba(ee1100ff) rgba(1100eeff) 45deg",
+            ),
+            (
+                "general {\n    col.active_border = rgba(33ccffee) rgb(00ff99) 0xff595959 270deg\n}",
+                "rgba(33ccffee) rgb(00ff99) 0xff595959 270deg",
+            ),
+        ];
+
+        for (content, value) in cases {
+            let gradient = HyprGradient::parse(value).unwrap();
+
+            let mut options = HashMap::new();
+            options.insert(
+                "general:col.active_border".to_string(),
+                gradient.to_string(),
+            );
+            let updated = config.update_config_content(content, &options).unwrap();
+            assert_eq!(updated, content);
+        }
+    }
+
+    #[test]
+    fn test_values_formatted_by_hyprctl_leave_their_lines_alone() {
+        let content = "$r = 10\ndecoration {\n    rounding = $r\n    active_opacity = 1.0\n    blur {\n        enabled = true # keep blur\n    }\n}";
+        // The same values as hyprctl reports them
+        let options: HashMap<String, String> = [
+            ("decoration:rounding", "10"),
+            ("decoration:active_opacity", "1"),
+            ("decoration:blur:enabled", "1"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+        let updated = Config::default()
+            .update_config_content(content, &options)
+            .unwrap();
+        assert_eq!(updated, content);
+    }
+
+    #[test]
+    fn test_workspace_rules_round_trip() {
+        let content =
+            "gestures {\n    workspace_swipe = true\n}\nworkspace = 1, monitor:DP-1\nworkspace = 2";
+        let parsed = HyprlandConfigFile::parse(content).unwrap();
+        assert_eq!(
+            parsed.workspace_rules,
+            vec!["workspace = 1, monitor:DP-1", "workspace = 2"]
+        );
+
+        let rules = vec!["workspace = special:term, on-created-empty:kitty".to_string()];
+        let updated = Config::default()
+            .update_config_content_with_rules(
+                content,
+                &HashMap::new(),
+                &[],
+                &[],
+                &[],
+                &rules,
+                &[],
+                &[],
+                &[],
+                &[],
+            )
+            .unwrap();
+        assert!(updated.contains("    workspace_swipe = true"));
+        assert!(!updated.contains("workspace = 1"));
+        // The new rule takes the place of the first old one
+        assert!(updated.ends_with("}\nworkspace = special:term, on-created-empty:kitty"));
+
+        // Without rules in the file, they are appended under a header
+        let updated = Config::default()
+            .update_config_content_with_rules(
+                "gestures {\n    workspace_swipe = true\n}",
+                &HashMap::new(),
+                &[],
+                &[],
+                &[],
+                &rules,
+                &[],
+                &[],
+                &[],
+                &[],
+            )
+            .unwrap();
+        assert!(updated
+            .ends_with("# Workspace Rules\nworkspace = special:term, on-created-empty:kitty"));
+    }
+
+    const COMMENTED_CONFIG: &str = "\
+# Monitors
+monitor = ,preferred,auto,1
+
+general {
+    # Space between windows
+    gaps_in = 5    # inner
+    gaps_out=20
+    border_size = 2
+}
+
+decoration {
+    rounding = 10
+    blur {
+        enabled = true
+        size = 3 # keep it light
+    }
+}
+
+# Keybinds
+bind = SUPER, Q, killactive # close the window
+bind=SUPER,T,exec,kitty
+
+# Window rules
+windowrulev2 = float, class:^(pavucontrol)$
+";
+
+    #[test]
+    fn test_saving_keeps_comments_and_order() {
+        let parsed = HyprlandConfigFile::parse(COMMENTED_CONFIG).unwrap();
+        let mut options = HashMap::new();
+        options.insert("general:gaps_in".to_string(), "8".to_string());
+        options.insert("general:gaps_out".to_string(), "20".to_string());
+        options.insert("decoration:blur:size".to_string(), "3".to_string());
+        // Binds as the editor writes them, with even spacing
+        let keybinds = vec![
+            "bind = SUPER, Q, killactive".to_string(),
+            "bind = SUPER, T, exec, kitty".to_string(),
+        ];
+
+        let updated = Config::default()
+            .update_config_content_with_rules(
+                COMMENTED_CONFIG,
+                &options,
+                &keybinds,
+                &parsed.window_rules,
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+            )
+            .unwrap();
+
+        let expected = COMMENTED_CONFIG.replace("gaps_in = 5    # inner", "gaps_in = 8    # inner");
+        assert_eq!(updated, expected);
+    }
+
+    #[test]
+    fn test_moved_lines_are_saved_in_their_new_order() {
+        let keybinds = vec![
+            "bind = SUPER, T, exec, kitty".to_string(),
+            "bind = SUPER, Q, killactive".to_string(),
+        ];
+        let updated = Config::default()
+            .update_config_content_with_rules(
+                COMMENTED_CONFIG,
+                &HashMap::new(),
+                &keybinds,
+                &["windowrulev2 = float, class:^(pavucontrol)$".to_string()],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+            )
+            .unwrap();
+
+        // The bind that stayed put keeps its comment
+        assert!(updated.contains(
+            "# Keybinds\nbind = SUPER, T, exec, kitty\nbind = SUPER, Q, killactive # close the window\n\n# Window rules"
+        ));
+    }
+
+    #[test]
+    fn test_removed_and_added_lines_keep_their_neighbours() {
+        let keybinds = vec![
+            "bind=SUPER,T,exec,kitty".to_string(),
+            "bind = SUPER, F, fullscreen".to_string(),
+        ];
+        let mut options = HashMap::new();
+        options.insert("decoration:blur:passes".to_string(), "2".to_string());
+        options.insert("input:kb_layout".to_string(), "us".to_string());
+        let updated = Config::default()
+            .update_config_content_with_rules(
+                COMMENTED_CONFIG,
+                &options,
+                &keybinds,
+                &["windowrulev2 = float, class:^(pavucontrol)$".to_string()],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+            )
+            .unwrap();
+
+        assert!(!updated.contains("killactive"));
+        assert!(updated.contains(
+            "# Keybinds\nbind=SUPER,T,exec,kitty\nbind = SUPER, F, fullscreen\n\n# Window rules"
+        ));
+        assert!(updated.contains("        size = 3 # keep it light\n        passes = 2\n    }"));
+        assert!(updated.ends_with("\ninput {\n    kb_layout = us\n}\n"));
+    }
+
+    #[test]
+    fn test_env_vars_are_written_back_in_place() {
+        let content = "# My config\nenv = XCURSOR_SIZE,24\nmonitor = ,preferred,auto,1\nenvd = QT_QPA_PLATFORM,wayland";
+        let parsed = HyprlandConfigFile::parse(content).unwrap();
+        assert_eq!(
+            parsed.env_vars,
+            vec!["env = XCURSOR_SIZE,24", "envd = QT_QPA_PLATFORM,wayland"]
+        );
+
+        let env_vars = vec![
+            "envd = QT_QPA_PLATFORM,wayland".to_string(),
+            "env = XCURSOR_SIZE,32".to_string(),
+        ];
+        let config = Config::default();
+        let updated = config
+            .update_config_content_with_rules(
+                content,
+                &HashMap::new(),
+                &[],
+                &[],
+                &[],
+                &[],
+                &env_vars,
+                &[],
+                &[],
+                &[],
+            )
+            .unwrap();
+        assert!(updated.starts_with(
+            "# My config\nenvd = QT_QPA_PLATFORM,wayland\nenv = XCURSOR_SIZE,32\nmonitor"
+        ));
+        assert_eq!(updated.matches("QT_QPA_PLATFORM").count(), 1);
+
+        // Without existing env lines the block goes at the top
+        let updated = config
+            .update_config_content_with_rules(
+                "monitor = ,preferred,auto,1",
+                &HashMap::new(),
+                &[],
+                &[],
+                &[],
+                &[],
+                &env_vars,
+                &[],
+                &[],
+                &[],
+            )
+            .unwrap();
+        assert!(updated.starts_with("# Environment Variables\nenvd = QT_QPA_PLATFORM,wayland"));
+    }
+
+    #[test]
+    fn test_plugins_are_parsed_and_toggled_in_place() {
+        let content = "exec-once = waybar\nplugin = /usr/lib/libhyprexpo.so\n# plugin = /usr/lib/libhyprbars.so\n# just a comment\nplugin {\n    hyprexpo {\n        columns = 3\n    }\n}";
+        let parsed = HyprlandConfigFile::parse(content).unwrap();
+        assert_eq!(
+            parsed.plugins,
+            vec![
+                "plugin = /usr/lib/libhyprexpo.so",
+                "# plugin = /usr/lib/libhyprbars.so"
+            ]
+        );
+
+        let plugins = vec![
+            "# plugin = /usr/lib/libhyprexpo.so".to_string(),
+            "plugin = /usr/lib/libhyprbars.so".to_string(),
+        ];
+        let options = HashMap::from([("plugin:hyprexpo:columns".to_string(), "4".to_string())]);
+        let autostart = vec!["exec-once = waybar".to_string()];
+        let config = Config::default();
+        let updated = config
+            .update_config_content_with_rules(
+                content,
+                &options,
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &plugins,
+                &autostart,
+                &[],
+            )
+            .unwrap();
+        assert!(updated.starts_with(
+            "exec-once = waybar\n# plugin = /usr/lib/libhyprexpo.so\nplugin = /usr/lib/libhyprbars.so\n# just a comment"
+        ));
+        assert!(updated.contains("        columns = 4"));
+
+        // Without existing plugin lines the block goes at the top
+        let updated = config
+            .update_config_content_with_rules(
+                "exec-once = waybar",
+                &HashMap::new(),
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &plugins[1..],
+                &autostart,
+                &[],
+            )
+            .unwrap();
+        assert!(updated.starts_with("# Plugins\nplugin = /usr/lib/libhyprbars.so\n\nexec-once"));
+
+        // Saving one toggled line replaces the commented-out one
+        let updated = replace_config_line(
+            content,
+            &["# plugin = /usr/lib/libhyprbars.so".to_string()],
+            "plugin = /usr/lib/libhyprbars.so",
+        )
+        .unwrap();
+        assert!(updated.contains("\nplugin = /usr/lib/libhyprbars.so\n# just a comment"));
+    }
+
+    #[test]
+    fn test_autostart_lines_are_reordered_and_toggled_in_place() {
+        let content = "monitor = ,preferred,auto,1\n\n# Startup\nexec-once = waybar # bar\nexec-once = hypridle\n# exec = swaync\n\nbind = SUPER, Q, killactive\n";
+        let parsed = HyprlandConfigFile::parse(content).unwrap();
+        assert_eq!(
+            parsed.autostart,
+            vec![
+                "exec-once = waybar # bar",
+                "exec-once = hypridle",
+                "# exec = swaync"
+            ]
+        );
+        assert_eq!(parsed.keybinds.len(), 1);
+
+        // Move hypridle first, enable swaync and disable waybar
+        let autostart = vec![
+            "exec-once = hypridle".to_string(),
+            "# exec-once = waybar".to_string(),
+            "exec = swaync".to_string(),
+        ];
+        let config = Config::default();
+        let updated = config
+            .update_config_content_with_rules(
+                content,
+                &HashMap::new(),
+                &["bind = SUPER, Q, killactive".to_string()],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &autostart,
+                &[],
+            )
+            .unwrap();
+        assert_eq!(
+            updated,
+            "monitor = ,preferred,auto,1\n\n# Startup\nexec-once = hypridle\n# exec-once = waybar\nexec = swaync\n\nbind = SUPER, Q, killactive\n"
+        );
+
+        // Without existing lines they are appended under a header
+        let updated = config
+            .update_config_content_with_rules(
+                "monitor = ,preferred,auto,1",
+                &HashMap::new(),
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &[],
+                &autostart[..1],
+                &[],
+            )
+            .unwrap();
+        assert_eq!(
+            updated,
+            "monitor = ,preferred,auto,1\n\n# Autostart\nexec-once = hypridle"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sourced_files_are_parsed_recursively() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let conf_dir = temp_dir.path().join("conf");
+        std::fs::create_dir(&conf_dir).unwrap();
+        let binds = conf_dir.join("binds.conf");
+        let rules = conf_dir.join("rules.conf");
+        std::fs::write(&binds, "bind = SUPER, Q, killactive\nsource = rules.conf").unwrap();
+        std::fs::write(&rules, "windowrule = float, ^(pavucontrol)$").unwrap();
+
+        let config = Config {
+            hyprland_config_path: temp_dir.path().join("hyprland.conf"),
+            ..Config::default()
+        };
+        std::fs::write(
+            &config.hyprland_config_path,
+            format!("source = {}\nbind = SUPER, T, exec, kitty", binds.display()),
+        )
+        .unwrap();
+
+        let parsed = config.parse_hyprland_config().await.unwrap();
+        assert_eq!(
+            parsed.keybind_lines(),
+            vec![
+                "bind = SUPER, Q, killactive",
+                "bind = SUPER, T, exec, kitty"
+            ]
+        );
+        assert_eq!(
+            parsed.window_rules,
+            vec!["windowrule = float, ^(pavucontrol)$"]
+        );
+        assert_eq!(
+            parsed.source_of("bind = SUPER, Q, killactive"),
+            Some(binds.as_path())
+        );
+        assert_eq!(
+            parsed.source_of("windowrule = float, ^(pavucontrol)$"),
+            Some(rules.as_path())
+        );
+        assert_eq!(parsed.source_of("bind = SUPER, T, exec, kitty"), None);
+    }
+
+    #[test]
+    fn test_option_lines_are_removed_by_section() {
+        let content = "general {\n    gaps_in = 5\n    gaps_out = 20\n}\n\ndecoration {\n    blur {\n        size = 3\n    }\n}\ninput:kb_layout = de\n";
+
+        let updated = remove_option_lines(content, "general:gaps_in").unwrap();
+        assert_eq!(
+            updated,
+            "general {\n    gaps_out = 20\n}\n\ndecoration {\n    blur {\n        size = 3\n    }\n}\ninput:kb_layout = de\n"
+        );
+
+        let updated = remove_option_lines(content, "decoration:blur:size").unwrap();
+        assert!(!updated.contains("size = 3"));
+        let updated = remove_option_lines(content, "input:kb_layout").unwrap();
+        assert!(!updated.contains("kb_layout"));
+
+        // Same option name in another section is left alone
+        assert_eq!(remove_option_lines(content, "decoration:gaps_in"), None);
+    }
+
+    #[test]
+    fn test_deprecated_options_are_migrated_in_place() {
+        let content = "general {\n    no_cursor_warps = true # keep still\n    gaps_in = 5\n}\n\ndecoration {\n    drop_shadow = yes\n    col.shadow = rgba(1a1a1aee)\n    multisample_edges = true\n}\nmaster:new_is_master = false\nmisc:no_vfr = $vfr\n";
+
+        assert_eq!(
+            migrate_deprecated_lines(content).unwrap(),
+            "general {\n    gaps_in = 5\n}\n\ndecoration {\n    shadow:enabled = yes\n    shadow:color = rgba(1a1a1aee)\n    # Removed from Hyprland: multisample_edges = true\n}\nmaster:new_status = slave\nmisc:no_vfr = $vfr\n\ncursor:no_warps = true\n"
+        );
+        let current = "general {\n    gaps_in = 5\n}";
+        assert_eq!(migrate_deprecated_lines(current), None);
+    }
+
+    #[test]
+    fn test_single_lines_are_replaced_or_added() {
+        let content = "bind = SUPER, Q, killactive\n  bind=SUPER,T,exec,kitty # terminal\nwindowrule = float, ^(mpv)$\n\nexec-once = waybar\n";
+
+        // The line an edited bind was loaded as is rewritten in place
+        let old = vec!["bind = SUPER, T, exec, kitty".to_string()];
+        assert_eq!(
+            replace_config_line(content, &old, "bind = SUPER, T, exec, foot").unwrap(),
+            "bind = SUPER, Q, killactive\n  bind = SUPER, T, exec, foot # terminal\nwindowrule = float, ^(mpv)$\n\nexec-once = waybar\n"
+        );
+
+        // A new line goes after the last line of its kind, or at the end
+        assert_eq!(
+            replace_config_line(content, &[], "windowrule = tile, ^(kitty)$").unwrap(),
+            "bind = SUPER, Q, killactive\n  bind=SUPER,T,exec,kitty # terminal\nwindowrule = float, ^(mpv)$\nwindowrule = tile, ^(kitty)$\n\nexec-once = waybar\n"
+        );
+        assert!(replace_config_line(content, &[], "env = A,1")
+            .unwrap()
+            .ends_with("exec-once = waybar\nenv = A,1\n"));
+
+        // Nothing to write when the line is there already
+        assert_eq!(
+            replace_config_line(content, &old, "bind = SUPER, T, exec, kitty"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_variables_are_defined_before_their_first_use() {
+        let config = Config::default();
+        let save = |content: &str, variables: &[&str]| {
+            let variables: Vec<String> = variables.iter().map(|v| v.to_string()).collect();
+            let keybinds = HyprlandConfigFile::parse(content).unwrap().keybind_lines();
+            config
+                .update_config_content_with_rules(
+                    content,
+                    &HashMap::new(),
+                    &keybinds,
+                    &[],
+                    &[],
+                    &[],
+                    &[],
+                    &[],
+                    &[],
+                    &variables,
+                )
+                .unwrap()
+        };
+
+        // Edited definitions keep their place and comment
+        let content = "monitor = ,preferred,auto,1\n$mainMod = SUPER # main key\n$term = kitty\nbind = $mainMod, Return, exec, $term\n";
+        assert_eq!(
+            save(content, &["$mainMod = ALT", "$term = kitty"]),
+            "monitor = ,preferred,auto,1\n$mainMod = ALT # main key\n$term = kitty\nbind = $mainMod, Return, exec, $term\n"
+        );
+
+        // A new variable used above the others goes before that line, and so
+        // does a definition another definition uses
+        let content = "$mainMod = SUPER\nbind = $mainMod, Return, exec, $term\nbind = $mainMod, E, exec, $files\n";
+        assert_eq!(
+            save(
+                content,
+                &[
+                    "$mainMod = SUPER",
+                    "$files = $term -e yazi",
+                    "$term = kitty"
+                ]
+            ),
+            "$mainMod = SUPER\n$term = kitty\n$files = $term -e yazi\nbind = $mainMod, Return, exec, $term\nbind = $mainMod, E, exec, $files\n"
+        );
+
+        // A config without variables gets them at the top
+        let content = "bind = SUPER, Q, killactive\n";
+        assert!(save(content, &["$mainMod = SUPER"])
+            .starts_with("# Variables\n$mainMod = SUPER\n\nbind = SUPER, Q, killactive"));
+
+        // Removing the last one leaves the lines using it alone
+        let content = "$mainMod = SUPER\nbind = $mainMod, Q, killactive\n";
+        assert_eq!(save(content, &[]), "bind = $mainMod, Q, killactive\n");
+    }
+
+    #[test]
+    fn test_included_content_is_updated_in_place() {
+        let content = "# binds\nbind = SUPER, Q, killactive\n$mod = SUPER\nbind = SUPER, T, exec, kitty\nwindowrule = float, ^(mpv)$\n";
+        let lines = vec![
+            "bind = SUPER, Q, killactive".to_string(),
+            "$mod = SUPER".to_string(),
+            "bind = SUPER, T, exec, foot".to_string(),
+            "bind = SUPER, F, fullscreen".to_string(),
+        ];
+
+        let updated = update_included_content(content, &lines);
+        assert_eq!(
+            updated,
+            "# binds\nbind = SUPER, Q, killactive\n$mod = SUPER\nbind = SUPER, T, exec, foot\nbind = SUPER, F, fullscreen\n"
+        );
+        // Nothing changes when the lines are already there
+        assert_eq!(update_included_content(&updated, &lines), updated);
+    }
+
+    #[tokio::test]
+    async fn test_edits_to_sourced_items_stay_in_their_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let binds_path = temp_dir.path().join("binds.conf");
+        std::fs::write(
+            &binds_path,
+            "# Apps\nbind = SUPER, T, exec, kitty\nbind = SUPER, Q, killactive\n",
+        )
+        .unwrap();
+        let config = Config {
+            hyprland_config_path: temp_dir.path().join("hyprland.conf"),
+            backup_enabled: false,
+            nixos_mode: false,
+            ..Config::default()
+        };
+        std::fs::write(
+            &config.hyprland_config_path,
+            "source = binds.conf\nbind = SUPER, M, exit\n",
+        )
+        .unwrap();
+
+        let parsed = config.parse_hyprland_config().await.unwrap();
+        let mut ui = UI::new();
+        let items = parsed
+            .keybinds
+            .iter()
+            .enumerate()
+            .map(|(i, bind)| ConfigItem {
+                key: format!("bind_{i}"),
+                value: bind.display_string(),
+                description: String::new(),
+                data_type: ConfigDataType::String,
+                suggestions: vec![],
+                source_file: None,
+            })
+            .collect();
+        ui.config_items.insert(FocusedPanel::Binds, items);
+        ui.tag_item_sources(&parsed);
+
+        // Edit the kitty bind, which lives in binds.conf
+        let binds = ui.config_items.get_mut(&FocusedPanel::Binds).unwrap();
+        assert_eq!(binds[0].source_file.as_deref(), Some(binds_path.as_path()));
+        binds[0].value = binds[0].value.replace("kitty", "foot");
+
+        let lines = ui.collect_config_lines();
+        config
+            .save_hyprland_config_with_rules(
+                &HashMap::new(),
+                &lines.keybinds,
+                &lines.window_rules,
+                &lines.layer_rules,
+                &lines.workspace_rules,
+                &lines.env_vars,
+                &lines.plugins,
+                &lines.autostart,
+                &[],
+            )
+            .await
+            .unwrap();
+        config.save_included_files(&lines.included).await.unwrap();
+
+        let main = std::fs::read_to_string(&config.hyprland_config_path).unwrap();
+        assert!(main.contains("source = binds.conf"));
+        assert!(main.contains("bind = SUPER, M, exit"));
+        assert!(!main.contains("foot"));
+        assert!(!main.contains("killactive"));
+
+        let binds_conf = std::fs::read_to_string(&binds_path).unwrap();
+        assert_eq!(
+            binds_conf,
+            "# Apps\nbind = SUPER, T, exec, foot\nbind = SUPER, Q, killactive\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_new_lines_go_to_the_configured_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let target = temp_dir.path().join("new.conf");
+        let config = Config {
+            hyprland_config_path: temp_dir.path().join("hyprland.conf"),
+            new_items_file: Some(target.clone()),
+            ..Config::default()
+        };
+        std::fs::write(&config.hyprland_config_path, "bind = SUPER, M, exit\n").unwrap();
+
+        let mut lines = ConfigLines {
+            keybinds: vec![
+                "bind = SUPER, M, exit".to_string(),
+                "bind = SUPER, F, fullscreen".to_string(),
+            ],
+            window_rules: vec!["windowrule = float, ^(mpv)$".to_string()],
+            ..ConfigLines::default()
+        };
+        config.route_new_lines(&mut lines).await.unwrap();
+
+        assert_eq!(lines.keybinds, vec!["bind = SUPER, M, exit"]);
+        assert!(lines.window_rules.is_empty());
+        assert_eq!(
+            lines.included,
+            vec![(
+                target,
+                vec![
+                    "bind = SUPER, F, fullscreen".to_string(),
+                    "windowrule = float, ^(mpv)$".to_string()
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_only_new_configs_show_the_setup_wizard() {
+        let existing: Config = toml::from_str(
+            r#"
+            hyprland_config_path = "/home/user/.config/hypr/hyprland.conf"
+            backup_enabled = true
+            auto_save = false
+            nixos_mode = false
+            current_values = {}
+            "#,
+        )
+        .unwrap();
+        assert!(existing.first_run_completed);
+
+        let new = Config::default();
+        assert!(!new.first_run_completed);
+        let saved: Config = toml::from_str(&toml::to_string(&new).unwrap()).unwrap();
+        assert!(!saved.first_run_completed);
+    }
+
+    #[tokio::test]
+    async fn test_each_panel_can_save_to_its_own_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config: Config = toml::from_str(&format!(
+            r#"
+            hyprland_config_path = "{}"
+            backup_enabled = true
+            auto_save = false
+            nixos_mode = false
+            current_values = {{}}
+            new_items_file = "/elsewhere/new.conf"
+            save_targets = {{ Binds = "binds.conf", WindowRules = "~/rules.conf" }}
+            "#,
+            temp_dir.path().join("hyprland.conf").display()
+        ))
+        .unwrap();
+        std::fs::write(&config.hyprland_config_path, "").unwrap();
+
+        let binds = temp_dir.path().join("binds.conf");
+        let rules = dirs::home_dir().unwrap().join("rules.conf");
+        assert_eq!(config.save_target(FocusedPanel::Binds), Some(binds.clone()));
+        assert_eq!(
+            config.save_target(FocusedPanel::WindowRules),
+            Some(rules.clone())
+        );
+        assert_eq!(
+            config.save_target(FocusedPanel::Autostart),
+            Some(PathBuf::from("/elsewhere/new.conf"))
+        );
+
+        config.new_items_file = None;
+        let mut lines = ConfigLines {
+            keybinds: vec!["bind = SUPER, F, fullscreen".to_string()],
+            window_rules: vec!["windowrule = float, ^(mpv)$".to_string()],
+            autostart: vec!["exec-once = waybar".to_string()],
+            ..ConfigLines::default()
+        };
+        config.route_new_lines(&mut lines).await.unwrap();
+
+        assert_eq!(lines.autostart, vec!["exec-once = waybar"]);
+        assert_eq!(
+            lines.included,
+            vec![
+                (binds, vec!["bind = SUPER, F, fullscreen".to_string()]),
+                (rules, vec!["windowrule = float, ^(mpv)$".to_string()]),
+            ]
+        );
+        assert!(toml::to_string(&config)
+            .unwrap()
+            .contains(r#"Binds = "binds.conf""#));
+    }
+
+    #[tokio::test]
+    async fn test_backups_are_timestamped_and_pruned() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            hyprland_config_path: temp_dir.path().join("hyprland.conf"),
+            backup_count: 2,
+            ..Config::default()
+        };
+
+        // No config yet, so nothing to back up
+        config.backup_config().await.unwrap();
+        assert!(config.list_backups().unwrap().is_empty());
+
+        std::fs::write(&config.hyprland_config_path, "general {\n}\n").unwrap();
+        for _ in 0..3 {
+            config.backup_config().await.unwrap();
+        }
+
+        let backups = config.list_backups().unwrap();
+        assert_eq!(backups.len(), 2);
+        for backup in &backups {
+            let name = backup.file_name().unwrap().to_string_lossy().to_string();
+            assert!(name.starts_with("hyprland.conf."));
+            assert!(name.ends_with(".bak"));
+        }
+    }
+
+    #[test]
+    fn test_backups_saved_in_the_same_second_are_listed_newest_first() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            hyprland_config_path: temp_dir.path().join("hyprland.conf"),
+            ..Config::default()
+        };
+        for stamp in [
+            "20250101_120000",
+            "20250101_120000_2",
+            "20250101_120000_10",
+            "20241231_235959_11",
+        ] {
+            std::fs::write(config.backup_path_for(stamp), "").unwrap();
+        }
+
+        let names: Vec<String> = config
+            .list_backups()
+            .unwrap()
+            .iter()
+            .map(|backup| backup.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "hyprland.conf.20250101_120000_10.bak",
+                "hyprland.conf.20250101_120000_2.bak",
+                "hyprland.conf.20250101_120000.bak",
+                "hyprland.conf.20241231_235959_11.bak",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_backup() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            hyprland_config_path: temp_dir.path().join("hyprland.conf"),
+            ..Config::default()
+        };
+
+        std::fs::write(&config.hyprland_config_path, "working config\n").unwrap();
+        let backup = config.backup_config().await.unwrap();
+        std::fs::write(&config.hyprland_config_path, "broken config\n").unwrap();
+
+        config.restore_backup(&backup).await.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&config.hyprland_config_path).unwrap(),
+            "working config\n"
+        );
+        // The broken config was kept as a backup of its own
+        assert_eq!(config.list_backups().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_config_override_prefers_the_flag_and_needs_a_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let flag = temp_dir.path().join("work.conf");
+        let env = temp_dir.path().join("home.conf");
+        std::fs::write(&flag, "").unwrap();
+        std::fs::write(&env, "").unwrap();
+
+        assert_eq!(resolve_config_override(None, None).unwrap(), None);
+        assert_eq!(
+            resolve_config_override(None, Some(OsString::new())).unwrap(),
+            None
+        );
+        assert_eq!(
+            resolve_config_override(Some(flag.clone()), Some(env.clone().into())).unwrap(),
+            Some(flag.clone())
+        );
+        assert_eq!(
+            resolve_config_override(None, Some(env.clone().into())).unwrap(),
+            Some(env)
+        );
+
+        let missing = temp_dir.path().join("missing.conf");
+        let error = resolve_config_override(None, Some(missing.into()))
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with(CONFIG_PATH_ENV), "{error}");
+        assert!(resolve_config_override(Some(temp_dir.path().to_path_buf()), None).is_err());
+    }
+
+    #[test]
+    fn test_saving_settings_keeps_the_stored_hyprland_path() {
+        let mut config = Config {
+            hyprland_config_path: PathBuf::from("/tmp/fixture.conf"),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.stored().hyprland_config_path,
+            PathBuf::from("/tmp/fixture.conf")
+        );
+
+        config.stored_hyprland_config_path = Some(PathBuf::from("/home/me/hyprland.conf"));
+        let content = toml::to_string_pretty(&config.stored()).unwrap();
+        assert!(content.contains("/home/me/hyprland.conf"));
+        assert!(!content.contains("fixture"));
+        assert_eq!(
+            config.hyprland_config_path,
+            PathBuf::from("/tmp/fixture.conf")
+        );
+    }
+
+    #[test]
+    fn test_ordinary_files_are_not_nix_managed() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("hyprland.conf");
+        std::fs::write(&path, "").unwrap();
+        assert!(!is_nix_managed(&path));
+        assert!(ensure_writable(&path).is_ok());
+        // Missing files can be created
+        assert!(!is_nix_managed(&temp_dir.path().join("missing.conf")));
+    }
+}
@@ -0,0 +1,189 @@
+// Deprecated and removed options
+// Hyprland renames and drops options between releases, and an old config
+// keeps setting them without any effect. This table says what became of
+// each, so a loaded config can be linted for them and migrated.
+
+use crate::ui::{flip_boolean, is_on};
+
+/// What became of a deprecated option
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Replacement {
+    /// The same value under a new name
+    Renamed(&'static str),
+    /// A new name for the opposite, like `no_vfr` becoming `vfr`
+    Inverted(&'static str),
+    /// A keyword option in place of a true/false one: its value for on, then off
+    Keyword(&'static str, &'static str, &'static str),
+    /// Nothing, with what to do instead
+    Removed(&'static str),
+}
+
+const NO_GAPS_WHEN_ONLY: &str =
+    "use workspace rules like `workspace = w[tv1], gapsout:0, gapsin:0`";
+
+/// Options Hyprland no longer knows, keyed by their hyprctl name
+#[rustfmt::skip]
+const DEPRECATED_OPTIONS: &[(&str, Replacement)] = &[
+    // 0.45 moved the shadow options into their own section
+    ("decoration:drop_shadow", Replacement::Renamed("decoration:shadow:enabled")),
+    ("decoration:shadow_range", Replacement::Renamed("decoration:shadow:range")),
+    ("decoration:shadow_render_power", Replacement::Renamed("decoration:shadow:render_power")),
+    ("decoration:shadow_ignore_window", Replacement::Renamed("decoration:shadow:ignore_window")),
+    ("decoration:shadow_offset", Replacement::Renamed("decoration:shadow:offset")),
+    ("decoration:shadow_scale", Replacement::Renamed("decoration:shadow:scale")),
+    ("decoration:col.shadow", Replacement::Renamed("decoration:shadow:color")),
+    ("decoration:col.shadow_inactive", Replacement::Renamed("decoration:shadow:color_inactive")),
+    // Cursor options got a section of their own
+    ("general:no_cursor_warps", Replacement::Renamed("cursor:no_warps")),
+    ("general:cursor_inactive_timeout", Replacement::Renamed("cursor:inactive_timeout")),
+    ("misc:hide_cursor_on_touch", Replacement::Renamed("cursor:hide_on_touch")),
+    ("misc:hide_cursor_on_key_press", Replacement::Renamed("cursor:hide_on_key_press")),
+    ("misc:cursor_zoom_factor", Replacement::Renamed("cursor:zoom_factor")),
+    ("misc:cursor_zoom_rigid", Replacement::Renamed("cursor:zoom_rigid")),
+    ("misc:no_direct_scanout", Replacement::Keyword("render:direct_scanout", "0", "1")),
+    ("misc:no_vfr", Replacement::Inverted("misc:vfr")),
+    ("master:new_is_master", Replacement::Keyword("master:new_status", "master", "slave")),
+    ("master:no_gaps_when_only", Replacement::Removed(NO_GAPS_WHEN_ONLY)),
+    ("dwindle:no_gaps_when_only", Replacement::Removed(NO_GAPS_WHEN_ONLY)),
+    ("decoration:multisample_edges", Replacement::Removed("rounded corners are always smoothed")),
+    ("misc:render_ahead_of_time", Replacement::Removed("frames are scheduled on their own")),
+    ("misc:render_ahead_safezone", Replacement::Removed("frames are scheduled on their own")),
+    ("general:sensitivity", Replacement::Removed("set input:sensitivity instead")),
+];
+
+/// What became of an option, or `None` when it is current
+pub fn replacement(key: &str) -> Option<Replacement> {
+    DEPRECATED_OPTIONS
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, replacement)| *replacement)
+}
+
+/// What a deprecated option's line turns into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Migration {
+    /// Set this option to this value instead
+    Set(&'static str, String),
+    /// Drop the line
+    Remove,
+    /// The value can't be carried over, like `maybe` for an inverted option
+    Keep,
+}
+
+/// A deprecated option a config sets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    pub key: String,
+    pub value: String,
+    pub replacement: Replacement,
+}
+
+impl Deprecation {
+    /// `old → new`, or what to do about a removed option
+    pub fn advice(&self) -> String {
+        match self.replacement {
+            Replacement::Renamed(new) | Replacement::Keyword(new, _, _) => {
+                format!("{} → {new}", self.key)
+            }
+            Replacement::Inverted(new) => format!("{} → {new} (inverted)", self.key),
+            Replacement::Removed(instead) => format!("{} was removed, {instead}", self.key),
+        }
+    }
+
+    pub fn migration(&self) -> Migration {
+        migration(self.replacement, &self.value)
+    }
+}
+
+/// What a line setting an option to `value` becomes
+pub fn migration(replacement: Replacement, value: &str) -> Migration {
+    let value = value.trim();
+    match replacement {
+        Replacement::Renamed(new) => Migration::Set(new, value.to_string()),
+        Replacement::Inverted(new) => match flip_boolean(value) {
+            Some(flipped) => Migration::Set(new, flipped),
+            None => Migration::Keep,
+        },
+        Replacement::Keyword(new, on, off) => match flip_boolean(value) {
+            Some(_) if is_on(value) => Migration::Set(new, on.to_string()),
+            Some(_) => Migration::Set(new, off.to_string()),
+            None => Migration::Keep,
+        },
+        Replacement::Removed(_) => Migration::Remove,
+    }
+}
+
+/// The deprecated options among a config's options, in key order
+pub fn lint(options: &[(String, String)]) -> Vec<Deprecation> {
+    let mut found: Vec<Deprecation> = options
+        .iter()
+        .filter_map(|(key, value)| {
+            Some(Deprecation {
+                key: key.clone(),
+                value: value.clone(),
+                replacement: replacement(key)?,
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| a.key.cmp(&b.key));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_deprecated_options_are_found_with_their_replacements() {
+        let found = lint(&options(&[
+            ("general:gaps_in", "5"),
+            ("decoration:drop_shadow", "yes"),
+            ("master:new_is_master", "true"),
+            ("misc:no_vfr", "0"),
+            ("master:no_gaps_when_only", "1"),
+        ]));
+        let advice: Vec<String> = found.iter().map(Deprecation::advice).collect();
+        assert_eq!(
+            advice,
+            [
+                "decoration:drop_shadow → decoration:shadow:enabled",
+                "master:new_is_master → master:new_status",
+                format!("master:no_gaps_when_only was removed, {NO_GAPS_WHEN_ONLY}").as_str(),
+                "misc:no_vfr → misc:vfr (inverted)",
+            ]
+        );
+
+        let migrations: Vec<Migration> = found.iter().map(Deprecation::migration).collect();
+        assert_eq!(
+            migrations,
+            [
+                Migration::Set("decoration:shadow:enabled", "yes".to_string()),
+                Migration::Set("master:new_status", "master".to_string()),
+                Migration::Remove,
+                Migration::Set("misc:vfr", "1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_values_that_cant_be_carried_over_are_kept() {
+        let scanout = replacement("misc:no_direct_scanout").unwrap();
+        assert_eq!(
+            migration(scanout, "true"),
+            Migration::Set("render:direct_scanout", "0".to_string())
+        );
+        assert_eq!(
+            migration(replacement("master:new_is_master").unwrap(), "off"),
+            Migration::Set("master:new_status", "slave".to_string())
+        );
+        assert_eq!(migration(scanout, "$scanout"), Migration::Keep);
+        assert_eq!(replacement("decoration:shadow:enabled"), None);
+    }
+}
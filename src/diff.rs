@@ -0,0 +1,342 @@
+// Line diffs between the config on disk and what a save would write, and
+// option diffs between two configs
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// One line of a unified diff
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    /// A header naming the file the following hunks belong to
+    File(String),
+    /// An `@@ -a,b +c,d @@` hunk header
+    Hunk(String),
+    Context(String),
+    Removed(String),
+    Added(String),
+    /// A line replaced in place
+    Changed {
+        old: String,
+        new: String,
+    },
+}
+
+/// An edit turning `old` into `new`, one line at a time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edit {
+    Keep,
+    Remove,
+    Add,
+}
+
+/// Longest-common-subsequence edit script. The common prefix and suffix are
+/// skipped first, which keeps the table small for typical config edits.
+pub fn edit_script(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j] is the LCS length of old_mid[i..] and new_mid[j..]
+    let (n, m) = (old_mid.len(), new_mid.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut script = vec![Edit::Keep; prefix];
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_mid[i] == new_mid[j] {
+            script.push(Edit::Keep);
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            script.push(Edit::Add);
+            j += 1;
+        } else {
+            script.push(Edit::Remove);
+            i += 1;
+        }
+    }
+    script.extend(std::iter::repeat_n(Edit::Keep, suffix));
+    script
+}
+
+/// Unified diff of two texts with `context` unchanged lines around each
+/// change. Removals directly followed by additions are paired up as changed
+/// lines. Returns no lines when the texts are equal.
+pub fn unified_diff(old: &str, new: &str, context: usize) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let script = edit_script(&old_lines, &new_lines);
+
+    // Old and new line numbers before each step of the script
+    let mut positions = Vec::with_capacity(script.len());
+    let (mut o, mut n) = (0, 0);
+    for edit in &script {
+        positions.push((o, n));
+        match edit {
+            Edit::Keep => {
+                o += 1;
+                n += 1;
+            }
+            Edit::Remove => o += 1,
+            Edit::Add => n += 1,
+        }
+    }
+
+    // Group changes that are within 2 * context lines of each other
+    let changed: Vec<usize> = (0..script.len())
+        .filter(|&k| script[k] != Edit::Keep)
+        .collect();
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &k in &changed {
+        let start = k.saturating_sub(context);
+        let end = (k + context + 1).min(script.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (start, end) in hunks {
+        let (old_start, new_start) = positions[start];
+        let old_count = script[start..end]
+            .iter()
+            .filter(|e| **e != Edit::Add)
+            .count();
+        let new_count = script[start..end]
+            .iter()
+            .filter(|e| **e != Edit::Remove)
+            .count();
+        lines.push(DiffLine::Hunk(format!(
+            "@@ -{},{} +{},{} @@",
+            old_start + 1,
+            old_count,
+            new_start + 1,
+            new_count
+        )));
+
+        let mut k = start;
+        while k < end {
+            let (o, n) = positions[k];
+            match script[k] {
+                Edit::Keep => {
+                    lines.push(DiffLine::Context(old_lines[o].to_string()));
+                    k += 1;
+                }
+                _ => {
+                    // Collect the whole run of removals and additions
+                    let run_end = (k..end).find(|&r| script[r] == Edit::Keep).unwrap_or(end);
+                    let removed: Vec<&str> = script[k..run_end]
+                        .iter()
+                        .filter(|e| **e == Edit::Remove)
+                        .enumerate()
+                        .map(|(offset, _)| old_lines[o + offset])
+                        .collect();
+                    let added: Vec<&str> = script[k..run_end]
+                        .iter()
+                        .filter(|e| **e == Edit::Add)
+                        .enumerate()
+                        .map(|(offset, _)| new_lines[n + offset])
+                        .collect();
+
+                    let paired = removed.len().min(added.len());
+                    for (old, new) in removed.iter().zip(&added) {
+                        lines.push(DiffLine::Changed {
+                            old: old.to_string(),
+                            new: new.to_string(),
+                        });
+                    }
+                    for old in &removed[paired..] {
+                        lines.push(DiffLine::Removed(old.to_string()));
+                    }
+                    for new in &added[paired..] {
+                        lines.push(DiffLine::Added(new.to_string()));
+                    }
+                    k = run_end;
+                }
+            }
+        }
+    }
+    lines
+}
+
+/// An option set differently by two configs. `None` means that config
+/// leaves the option at its default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionDifference {
+    pub option: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Whether two option values mean the same to Hyprland, which formats values
+/// its own way: `true` is `1` and `0.50` is `0.5`
+pub fn values_match(a: &str, b: &str) -> bool {
+    fn canonical(value: &str) -> String {
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" => return "1".to_string(),
+            "false" | "no" | "off" => return "0".to_string(),
+            _ => {}
+        }
+        match value.parse::<f64>() {
+            Ok(number) => number.to_string(),
+            Err(_) => value,
+        }
+    }
+    canonical(a) == canonical(b)
+}
+
+/// Options whose values differ between `old` and `new`, grouped by section
+/// (the part of the name before the first `:`), with sections and options
+/// sorted by name
+pub fn option_differences(
+    old: &HashMap<String, String>,
+    new: &HashMap<String, String>,
+) -> Vec<(String, Vec<OptionDifference>)> {
+    let mut sections: BTreeMap<String, Vec<OptionDifference>> = BTreeMap::new();
+    let options: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for option in options {
+        let (old_value, new_value) = (old.get(option), new.get(option));
+        if let (Some(a), Some(b)) = (old_value, new_value) {
+            if values_match(a, b) {
+                continue;
+            }
+        }
+        let section = option.split_once(':').map_or("", |(section, _)| section);
+        sections
+            .entry(section.to_string())
+            .or_default()
+            .push(OptionDifference {
+                option: option.clone(),
+                old: old_value.cloned(),
+                new: new_value.cloned(),
+            });
+    }
+    sections.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equal_texts_have_no_diff() {
+        assert!(unified_diff("a\nb\n", "a\nb\n", 3).is_empty());
+    }
+
+    #[test]
+    fn test_added_removed_and_changed_lines() {
+        let old = "one\ntwo\nthree\nfour\nfive";
+        let new = "one\n2\nthree\nfive\nsix";
+        assert_eq!(
+            unified_diff(old, new, 1),
+            vec![
+                DiffLine::Hunk("@@ -1,5 +1,5 @@".to_string()),
+                DiffLine::Context("one".to_string()),
+                DiffLine::Changed {
+                    old: "two".to_string(),
+                    new: "2".to_string()
+                },
+                DiffLine::Context("three".to_string()),
+                DiffLine::Removed("four".to_string()),
+                DiffLine::Context("five".to_string()),
+                DiffLine::Added("six".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distant_changes_get_separate_hunks() {
+        let old: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+        let mut new = old.clone();
+        new[2] = "x".to_string();
+        new[17] = "y".to_string();
+
+        let diff = unified_diff(&old.join("\n"), &new.join("\n"), 2);
+        let hunks: Vec<&DiffLine> = diff
+            .iter()
+            .filter(|line| matches!(line, DiffLine::Hunk(_)))
+            .collect();
+        assert_eq!(
+            hunks,
+            vec![
+                &DiffLine::Hunk("@@ -1,5 +1,5 @@".to_string()),
+                &DiffLine::Hunk("@@ -16,5 +16,5 @@".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_option_differences_are_grouped_by_section() {
+        let options = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        let live = options(&[
+            ("general:gaps_in", "5"),
+            ("general:border_size", "2"),
+            ("input:kb_layout", "us"),
+            ("decoration:blur:enabled", "1"),
+            ("misc:vfr", "1"),
+        ]);
+        let file = options(&[
+            ("general:gaps_in", "10"),
+            ("general:border_size", "2"),
+            ("input:kb_layout", "us"),
+            ("decoration:blur:enabled", "true"),
+            ("decoration:rounding", "8"),
+        ]);
+
+        let differences = option_differences(&live, &file);
+        let sections: Vec<&str> = differences.iter().map(|(s, _)| s.as_str()).collect();
+        assert_eq!(sections, vec!["decoration", "general", "misc"]);
+        assert_eq!(
+            differences[0].1,
+            vec![OptionDifference {
+                option: "decoration:rounding".to_string(),
+                old: None,
+                new: Some("8".to_string()),
+            }]
+        );
+        assert_eq!(
+            differences[1].1,
+            vec![OptionDifference {
+                option: "general:gaps_in".to_string(),
+                old: Some("5".to_string()),
+                new: Some("10".to_string()),
+            }]
+        );
+        assert_eq!(differences[2].1[0].new, None);
+        assert!(option_differences(&live, &live).is_empty());
+    }
+
+    #[test]
+    fn test_values_match_across_formats() {
+        assert!(values_match("true", "1"));
+        assert!(values_match("0.50", "0.5"));
+        assert!(values_match(" us ", "us"));
+        assert!(values_match(
+            "rgba(33ccffee)  rgba(00ff99ee) 45deg",
+            "rgba(33ccffee) rgba(00ff99ee) 45deg"
+        ));
+        assert!(!values_match("us", "de"));
+        assert!(!values_match("5", "10"));
+    }
+}
@@ -0,0 +1,190 @@
+// Environment variables
+// Parses and formats `env = NAME,value` and `envd = NAME,value` lines
+
+/// Which keyword sets the variable. `envd` also exports it to the D-Bus
+/// activation environment.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EnvKind {
+    #[default]
+    Env,
+    Envd,
+}
+
+impl EnvKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            EnvKind::Env => "env",
+            EnvKind::Envd => "envd",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            EnvKind::Env => EnvKind::Envd,
+            EnvKind::Envd => EnvKind::Env,
+        }
+    }
+}
+
+/// Fields of the environment variable editor, in the order Tab visits them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnvField {
+    Kind,
+    Name,
+    Value,
+}
+
+impl EnvField {
+    pub const ALL: [EnvField; 3] = [EnvField::Kind, EnvField::Name, EnvField::Value];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let index = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EnvField::Kind => "Keyword",
+            EnvField::Name => "Name",
+            EnvField::Value => "Value",
+        }
+    }
+}
+
+/// A single `env`/`envd` line
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EnvVar {
+    pub kind: EnvKind,
+    pub name: String,
+    pub value: String,
+}
+
+/// Split a line into its keyword and body if it is an `env` or `envd` line
+fn split_env_line(line: &str) -> Option<(EnvKind, &str)> {
+    let (keyword, body) = line.trim().split_once('=')?;
+    let kind = match keyword.trim() {
+        "env" => EnvKind::Env,
+        "envd" => EnvKind::Envd,
+        _ => return None,
+    };
+    Some((kind, body))
+}
+
+/// Whether a config line sets an environment variable
+pub fn is_env_line(line: &str) -> bool {
+    split_env_line(line).is_some()
+}
+
+impl EnvVar {
+    /// Parse an `env = NAME,value` line. The value is everything after the
+    /// first comma, so values that contain commas survive.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (kind, body) = split_env_line(line)?;
+        let (name, value) = body.split_once(',').unwrap_or((body, ""));
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        Some(EnvVar {
+            kind,
+            name: name.to_string(),
+            value: value.trim().to_string(),
+        })
+    }
+
+    /// Format the variable as a config line
+    pub fn to_line(&self) -> String {
+        format!(
+            "{} = {},{}",
+            self.kind.keyword(),
+            self.name.trim(),
+            self.value.trim()
+        )
+    }
+
+    /// The text of a free-text field, or `None` for the keyword toggle
+    pub fn text_field_mut(&mut self, field: EnvField) -> Option<&mut String> {
+        match field {
+            EnvField::Kind => None,
+            EnvField::Name => Some(&mut self.name),
+            EnvField::Value => Some(&mut self.value),
+        }
+    }
+
+    /// Display value of a field for the editor
+    pub fn field_value(&self, field: EnvField) -> String {
+        match field {
+            EnvField::Kind => self.kind.keyword().to_string(),
+            EnvField::Name => self.name.clone(),
+            EnvField::Value => self.value.clone(),
+        }
+    }
+}
+
+/// Check that a variable name is uppercase letters, digits and underscores,
+/// not starting with a digit
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let name = name.trim();
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_uppercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err("name must be UPPERCASE_WITH_UNDERSCORES".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_round_trip() {
+        let var = EnvVar::parse("env = XCURSOR_SIZE,24").unwrap();
+        assert_eq!(var.kind, EnvKind::Env);
+        assert_eq!(var.name, "XCURSOR_SIZE");
+        assert_eq!(var.value, "24");
+        assert_eq!(var.to_line(), "env = XCURSOR_SIZE,24");
+
+        let var = EnvVar::parse("envd=QT_QPA_PLATFORM, wayland;xcb").unwrap();
+        assert_eq!(var.kind, EnvKind::Envd);
+        assert_eq!(var.to_line(), "envd = QT_QPA_PLATFORM,wayland;xcb");
+    }
+
+    #[test]
+    fn test_values_keep_commas() {
+        let var = EnvVar::parse("env = GDK_BACKEND,wayland,x11,*").unwrap();
+        assert_eq!(var.value, "wayland,x11,*");
+        assert_eq!(var.to_line(), "env = GDK_BACKEND,wayland,x11,*");
+    }
+
+    #[test]
+    fn test_env_lines_exclude_other_keywords() {
+        assert!(is_env_line("env = A,1"));
+        assert!(is_env_line("  envd=B,2"));
+        assert!(!is_env_line("environment = 1"));
+        assert!(!is_env_line("exec-once = env FOO=1 app"));
+    }
+
+    #[test]
+    fn test_name_validation() {
+        for ok in ["XCURSOR_SIZE", "_JAVA_AWT_WM_NONREPARENTING", "GTK2_RC"] {
+            assert!(validate_name(ok).is_ok(), "{ok}");
+        }
+        for bad in ["", "xcursor_size", "2FAST", "MY-VAR", "A B"] {
+            assert!(validate_name(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -101,6 +101,7 @@ pub enum ConfigError {
 }
 
 /// Enhanced file operation error types with specific error conditions and recovery strategies
+#[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum FileError {
     /// File not found
@@ -198,6 +199,10 @@ pub enum HyprctlError {
     /// Hyprctl command execution failed
     #[error("Hyprctl command failed: {command} - {stderr}")]
     ExecutionFailed { command: String, stderr: String },
+
+    /// Hyprland is never asked in file-only mode
+    #[error("hyprctl is not used in file-only mode")]
+    FileOnly,
 }
 
 /// Result type alias using our custom error
@@ -588,11 +593,17 @@ impl HyprctlError {
     pub fn user_message(&self) -> String {
         match self {
             HyprctlError::CommandNotFound => {
-                "Hyprctl command not found. Please ensure Hyprland is installed and running.".to_string()
+                format!(
+                    "Hyprctl command not found. {}",
+                    crate::platform::hyprland_install_message()
+                )
             }
             HyprctlError::HyprlandNotRunning => {
                 "Hyprland is not running. Please start Hyprland and try again.".to_string()
             }
+            HyprctlError::FileOnly => {
+                "File-only mode is on, so changes only go to the config file.".to_string()
+            }
             HyprctlError::InvalidOption { option } => {
                 format!("Configuration option '{}' is not supported by your Hyprland version. Please check the documentation or update Hyprland.", option)
             }
@@ -604,7 +615,7 @@ impl HyprctlError {
             }
             HyprctlError::ExecutionFailed { command, stderr } => {
                 if stderr.contains("could not connect") {
-                    format!("Cannot connect to Hyprland. Make sure Hyprland is running and try again.")
+                    "Cannot connect to Hyprland. Make sure Hyprland is running and try again.".to_string()
                 } else {
                     format!("Hyprland command '{}' failed: {}. Please check your configuration.", command, stderr)
                 }
@@ -82,16 +82,19 @@ impl FileOperations {
                             RecoveryStrategy::Retry { max_attempts, base_delay_ms } => {
                                 if recovery_context.attempt_count <= max_attempts {
                                     let delay = base_delay_ms * recovery_context.attempt_count as u64;
-                                    eprintln!(
+                                    crate::stderr_log::note(format!(
                                         "Retrying file read (attempt {}/{}) after {}ms delay: {}",
                                         recovery_context.attempt_count, max_attempts, delay, error
-                                    );
+                                    ));
                                     sleep(Duration::from_millis(delay)).await;
                                     continue;
                                 }
                             }
                             RecoveryStrategy::Fallback { description } => {
-                                eprintln!("Using fallback strategy: {}", description);
+                                crate::stderr_log::note(format!(
+                                    "Using fallback strategy: {}",
+                                    description
+                                ));
                                 // For file not found, return empty string as fallback
                                 if matches!(error, FileError::NotFound { .. }) {
                                     return Ok(String::new());
@@ -122,11 +125,11 @@ impl FileOperations {
         let backup_path = if self.config.create_backup && path.exists() {
             match self.create_backup(path).await {
                 Ok(backup) => {
-                    eprintln!("Created backup: {}", backup.display());
+                    crate::stderr_log::note(format!("Created backup: {}", backup.display()));
                     Some(backup)
                 }
                 Err(e) => {
-                    eprintln!("Warning: Failed to create backup: {}", e);
+                    crate::stderr_log::warn(format!("Failed to create backup: {}", e));
                     None
                 }
             }
@@ -140,7 +143,7 @@ impl FileOperations {
                     // Verify write if enabled
                     if self.config.verify_writes {
                         if let Err(e) = self.verify_file_content(path, content).await {
-                            eprintln!("Warning: File verification failed: {}", e);
+                            crate::stderr_log::warn(format!("File verification failed: {}", e));
                             // Don't fail the operation, just warn
                         }
                     }
@@ -157,10 +160,10 @@ impl FileOperations {
                             RecoveryStrategy::Retry { max_attempts, base_delay_ms } => {
                                 if recovery_context.attempt_count <= max_attempts {
                                     let delay = base_delay_ms * recovery_context.attempt_count as u64;
-                                    eprintln!(
+                                    crate::stderr_log::note(format!(
                                         "Retrying file write (attempt {}/{}) after {}ms delay: {}",
                                         recovery_context.attempt_count, max_attempts, delay, error
-                                    );
+                                    ));
                                     sleep(Duration::from_millis(delay)).await;
                                     continue;
                                 }
@@ -169,9 +172,14 @@ impl FileOperations {
                                 // Restore backup if we have one
                                 if let Some(backup) = backup_path {
                                     if let Err(e) = async_fs::copy(&backup, path).await {
-                                        eprintln!("Failed to restore backup: {}", e);
+                                        crate::stderr_log::error(format!(
+                                            "Failed to restore backup: {}",
+                                            e
+                                        ));
                                     } else {
-                                        eprintln!("Backup restored after write failure");
+                                        crate::stderr_log::note(
+                                            "Backup restored after write failure",
+                                        );
                                     }
                                 }
                                 return Err(error);
@@ -340,39 +348,50 @@ impl FileOperations {
         }
     }
 
-    /// Perform atomic write using temporary file
-    async fn atomic_write(&self, path: &Path, content: &str) -> FileResult<()> {
-        let temp_path = path.with_extension(
-            format!(
-                "{}{}",
-                path.extension()
-                    .map(|e| format!("{}.{}", e.to_string_lossy(), self.config.temp_suffix.trim_start_matches('.')))
-                    .unwrap_or_else(|| self.config.temp_suffix.clone()),
-                "atomic"
-            )
-        );
-
-        // Write to temporary file first
-        async_fs::write(&temp_path, content)
+    /// Write `content` to `path` atomically: the data goes to a temporary file
+    /// in the same directory, is fsynced, and is then renamed over the target.
+    /// A crash at any point leaves either the old or the new file, never a
+    /// truncated one.
+    pub async fn atomic_write(&self, path: &Path, content: &str) -> FileResult<()> {
+        // Write through symlinks (e.g. dotfile managers) instead of replacing them
+        let target = async_fs::canonicalize(path)
             .await
-            .map_err(|e| FileError::TempFileError {
-                temp_path: temp_path.clone(),
-                reason: e.to_string(),
-            })?;
+            .unwrap_or_else(|_| path.to_path_buf());
 
-        // Atomically move temp file to final location
-        async_fs::rename(&temp_path, path)
-            .await
-            .map_err(|e| {
-                // Clean up temp file on failure
-                let _ = std::fs::remove_file(&temp_path);
-                FileError::AtomicOperationFailed {
-                    path: path.to_path_buf(),
-                    stage: format!("rename temporary file: {}", e),
-                }
-            })?;
+        let temp_path = self.write_temp_file(&target, content).await?;
+        commit_temp_file(&temp_path, &target).await
+    }
 
-        Ok(())
+    /// First half of an atomic write: write and fsync a temporary sibling of `path`
+    async fn write_temp_file(&self, path: &Path, content: &str) -> FileResult<PathBuf> {
+        use tokio::io::AsyncWriteExt;
+
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "config".to_string());
+        let temp_path = path.with_file_name(format!(
+            ".{file_name}.{}{}",
+            std::process::id(),
+            self.config.temp_suffix
+        ));
+
+        let result = async {
+            let mut file = async_fs::File::create(&temp_path).await?;
+            file.write_all(content.as_bytes()).await?;
+            file.sync_all().await
+        }
+        .await;
+
+        if let Err(e) = result {
+            let _ = async_fs::remove_file(&temp_path).await;
+            return Err(FileError::TempFileError {
+                temp_path,
+                reason: e.to_string(),
+            });
+        }
+
+        Ok(temp_path)
     }
 
     /// Perform direct write (non-atomic)
@@ -421,6 +440,26 @@ impl Default for FileOperations {
     }
 }
 
+/// Second half of an atomic write: rename the temporary file over `path`
+async fn commit_temp_file(temp_path: &Path, path: &Path) -> FileResult<()> {
+    if let Err(e) = async_fs::rename(temp_path, path).await {
+        let _ = async_fs::remove_file(temp_path).await;
+        return Err(FileError::AtomicOperationFailed {
+            path: path.to_path_buf(),
+            stage: format!("rename temporary file: {}", e),
+        });
+    }
+
+    // Persist the rename itself; not every platform lets us fsync a directory
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = async_fs::File::open(parent).await {
+            let _ = dir.sync_all().await;
+        }
+    }
+
+    Ok(())
+}
+
 /// Convenience functions for common file operations
 pub struct FileUtils;
 
@@ -434,6 +473,14 @@ impl FileUtils {
         ops.write_to_file(path, content).await
     }
 
+    /// Write a file atomically, without the backup, retries and verification
+    /// of `safe_write`
+    pub async fn atomic_write<P: AsRef<Path>>(path: P, content: &str) -> FileResult<()> {
+        FileOperations::new()
+            .atomic_write(path.as_ref(), content)
+            .await
+    }
+
     /// Read file content with retry on failure
     pub async fn resilient_read<P: AsRef<Path>>(path: P) -> FileResult<String> {
         let ops = FileOperations::new();
@@ -568,4 +615,55 @@ mod tests {
         let backup_path = FileUtils::backup_file(&file_path).await.unwrap();
         assert!(backup_path.exists());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_atomic_write_leaves_original_until_rename() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("hyprland.conf");
+        std::fs::write(&path, "original\n").unwrap();
+
+        // Simulate a crash after the temp file is written but before the rename
+        let temp_path = FileOperations::new()
+            .write_temp_file(&path, "updated\n")
+            .await
+            .unwrap();
+        assert_eq!(temp_path.parent(), path.parent());
+        assert_eq!(std::fs::read_to_string(&temp_path).unwrap(), "updated\n");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "original\n");
+
+        commit_temp_file(&temp_path, &path).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "updated\n");
+        assert!(!temp_path.exists());
+
+        // The full helper leaves no temp files behind
+        FileUtils::atomic_write(&path, "final\n").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "final\n");
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_atomic_write_keeps_symlinks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let real = temp_dir.path().join("dotfiles.conf");
+        let link = temp_dir.path().join("hyprland.conf");
+        std::fs::write(&real, "original\n").unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        FileUtils::atomic_write(&link, "updated\n").await.unwrap();
+
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_to_string(&real).unwrap(), "updated\n");
+
+        // Writes with a backup go through the same helper
+        FileUtils::safe_write(&link, "safe\n").await.unwrap();
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_to_string(&real).unwrap(), "safe\n");
+    }
+}
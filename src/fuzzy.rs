@@ -0,0 +1,107 @@
+// Fuzzy matching for search
+// Scores a query as a subsequence of a candidate, so "gapsin" finds gaps_in
+// and "brdr" finds border_size
+
+/// Score for every matched character
+const MATCH_SCORE: i64 = 10;
+/// Bonus when a match directly follows the previous one
+const CONSECUTIVE_BONUS: i64 = 10;
+/// Bonus when a match starts a word (start of string or after a separator)
+const WORD_START_BONUS: i64 = 10;
+/// Penalty per skipped character between two matches, capped per gap
+const GAP_PENALTY: i64 = 1;
+const MAX_GAP_PENALTY: i64 = 5;
+/// Penalty per character before the first match, capped
+const LEADING_PENALTY: i64 = 1;
+const MAX_LEADING_PENALTY: i64 = 10;
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '_' | '.' | ':' | '-' | ' ' | '/')
+}
+
+/// Score `query` against `candidate`, case-insensitively.
+///
+/// Returns `None` when the query isn't a subsequence of the candidate,
+/// otherwise a score from 0 to 100 where 100 is an exact prefix match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u8> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(100);
+    }
+    let candidate: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+
+    // Try every occurrence of the first query character as a starting point,
+    // matching greedily from there, and keep the best result
+    let best = (0..candidate.len())
+        .filter(|&start| candidate[start] == query[0])
+        .filter_map(|start| score_from(&query, &candidate, start))
+        .max()?;
+
+    // An exact prefix match scores MATCH_SCORE + WORD_START_BONUS for the first
+    // character and MATCH_SCORE + CONSECUTIVE_BONUS for the rest
+    let perfect = query.len() as i64 * (MATCH_SCORE + CONSECUTIVE_BONUS);
+    Some((best.max(0) * 100 / perfect).min(100) as u8)
+}
+
+fn score_from(query: &[char], candidate: &[char], start: usize) -> Option<i64> {
+    let mut score = -(start as i64 * LEADING_PENALTY).min(MAX_LEADING_PENALTY);
+    let mut previous: Option<usize> = None;
+    let mut position = start;
+
+    for &qc in query {
+        let index = (position..candidate.len()).find(|&i| candidate[i] == qc)?;
+
+        score += MATCH_SCORE;
+        if index == 0 || is_separator(candidate[index - 1]) {
+            score += WORD_START_BONUS;
+        }
+        if let Some(previous) = previous {
+            if index == previous + 1 {
+                score += CONSECUTIVE_BONUS;
+            } else {
+                score -= ((index - previous - 1) as i64 * GAP_PENALTY).min(MAX_GAP_PENALTY);
+            }
+        }
+
+        previous = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_omitted_separators_match() {
+        assert!(fuzzy_score("gapsin", "gaps_in").unwrap() > 90);
+        assert!(fuzzy_score("GAPS", "gaps_out").unwrap() == 100);
+        assert!(fuzzy_score("xyz", "gaps_in").is_none());
+        assert!(fuzzy_score("gapsinn", "gaps_in").is_none());
+    }
+
+    #[test]
+    fn test_brdr_ranks_border_size_above_weaker_matches() {
+        let border_size = fuzzy_score("brdr", "border_size").unwrap();
+        let inactive = fuzzy_score("brdr", "col.inactive_border").unwrap();
+        let extend = fuzzy_score("brdr", "extend_border_grab_area").unwrap();
+        let scattered = fuzzy_score("brdr", "blur_render_hdr").unwrap();
+
+        assert!(border_size > inactive);
+        assert!(border_size > extend);
+        assert!(border_size > scattered);
+    }
+
+    #[test]
+    fn test_best_starting_point_is_used() {
+        // Matching greedily from the first 'b' would scatter the match;
+        // starting at "border" gives the contiguous one
+        assert!(fuzzy_score("border", "b_resize_on_border").unwrap() > 90);
+    }
+}
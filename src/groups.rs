@@ -0,0 +1,112 @@
+// Window group options
+// Grouped windows share a tile and switch like tabs. Their options live
+// under `group:`, and the tab bar's under `group:groupbar:`. This table
+// gives them their descriptions and the values they take; ranges of the
+// numeric ones are in `options`.
+
+use crate::ui::ConfigDataType;
+
+/// The values a group option takes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GroupValue {
+    Boolean,
+    /// A number with its range in `options`
+    Number,
+    Color,
+    Text,
+    Keyword(&'static [&'static str]),
+}
+
+/// Group options keyed by their hyprctl name
+#[rustfmt::skip]
+const GROUP_OPTIONS: &[(&str, GroupValue, &str)] = &[
+    // group
+    ("group:auto_group", GroupValue::Boolean, "New windows join the focused group"),
+    ("group:insert_after_current", GroupValue::Boolean, "New windows go after the current one rather than at the end"),
+    ("group:focus_removed_window", GroupValue::Boolean, "Focus a window that leaves a group"),
+    ("group:drag_into_group", GroupValue::Keyword(&["0", "1", "2"]), "Dragging a window onto a group: 0 never joins, 1 joins, 2 joins only over the groupbar"),
+    ("group:merge_groups_on_drag", GroupValue::Boolean, "Dragging a group onto another merges them"),
+    ("group:merge_groups_on_groupbar", GroupValue::Boolean, "Dropping a group on a groupbar merges them"),
+    ("group:merge_floated_into_tiled_on_groupbar", GroupValue::Boolean, "Dropping a floating window on a tiled groupbar adds it to the group"),
+    ("group:group_on_movetoworkspace", GroupValue::Boolean, "Moving a window to a workspace joins the group there"),
+    ("group:col.border_active", GroupValue::Color, "Border color of the focused group"),
+    ("group:col.border_inactive", GroupValue::Color, "Border color of unfocused groups"),
+    ("group:col.border_locked_active", GroupValue::Color, "Border color of the focused group when locked"),
+    ("group:col.border_locked_inactive", GroupValue::Color, "Border color of unfocused groups when locked"),
+    // groupbar
+    ("group:groupbar:enabled", GroupValue::Boolean, "Show the tab bar above grouped windows"),
+    ("group:groupbar:font_family", GroupValue::Text, "Font of the tab titles, the general font when empty"),
+    ("group:groupbar:font_size", GroupValue::Number, "Size of the tab titles"),
+    ("group:groupbar:gradients", GroupValue::Boolean, "Fill the tabs with a gradient"),
+    ("group:groupbar:height", GroupValue::Number, "Height of the tab bar"),
+    ("group:groupbar:indicator_height", GroupValue::Number, "Height of the line under each tab"),
+    ("group:groupbar:stacked", GroupValue::Boolean, "Stack the tabs vertically"),
+    ("group:groupbar:priority", GroupValue::Number, "Order of the tab bar among window decorations"),
+    ("group:groupbar:render_titles", GroupValue::Boolean, "Show window titles in the tabs"),
+    ("group:groupbar:scrolling", GroupValue::Boolean, "Scrolling over the tab bar switches windows"),
+    ("group:groupbar:rounding", GroupValue::Number, "Corner rounding of the tabs"),
+    ("group:groupbar:text_color", GroupValue::Color, "Color of the tab titles"),
+    ("group:groupbar:col.active", GroupValue::Color, "Color of the focused tab"),
+    ("group:groupbar:col.inactive", GroupValue::Color, "Color of the other tabs"),
+    ("group:groupbar:col.locked_active", GroupValue::Color, "Color of the focused tab of a locked group"),
+    ("group:groupbar:col.locked_inactive", GroupValue::Color, "Color of the other tabs of a locked group"),
+];
+
+fn group_option(key: &str) -> Option<&'static (&'static str, GroupValue, &'static str)> {
+    GROUP_OPTIONS.iter().find(|(name, _, _)| *name == key)
+}
+
+/// The hyprctl names of all group options
+pub fn option_keys() -> impl Iterator<Item = &'static str> {
+    GROUP_OPTIONS.iter().map(|(name, _, _)| *name)
+}
+
+/// What a group option does
+pub fn description(key: &str) -> Option<&'static str> {
+    group_option(key).map(|(_, _, description)| *description)
+}
+
+/// The data type of a group option other than a number. Numbers are typed
+/// by their range instead.
+pub fn data_type(key: &str) -> Option<ConfigDataType> {
+    match group_option(key)?.1 {
+        GroupValue::Boolean => Some(ConfigDataType::Boolean),
+        GroupValue::Number => None,
+        GroupValue::Color => Some(ConfigDataType::Color),
+        GroupValue::Text => Some(ConfigDataType::String),
+        GroupValue::Keyword(options) => Some(ConfigDataType::Keyword {
+            options: options.iter().map(|option| option.to_string()).collect(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_options_have_descriptions_and_types() {
+        assert_eq!(
+            data_type("group:col.border_active"),
+            Some(ConfigDataType::Color)
+        );
+        assert_eq!(
+            data_type("group:groupbar:enabled"),
+            Some(ConfigDataType::Boolean)
+        );
+        assert_eq!(
+            data_type("group:groupbar:font_family"),
+            Some(ConfigDataType::String)
+        );
+        assert_eq!(data_type("group:unknown"), None);
+        assert!(description("group:auto_group").is_some());
+
+        // Every numeric option has a range for the slider
+        for (key, value, _) in GROUP_OPTIONS {
+            assert!(key.starts_with("group:"));
+            if *value == GroupValue::Number {
+                assert!(crate::options::option_range(key).is_some(), "{key}");
+            }
+        }
+    }
+}
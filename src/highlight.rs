@@ -0,0 +1,288 @@
+// Syntax highlighting for config previews
+// Each line is split into comments, strings, numbers, keywords and the keys
+// being set, using a few rules per export format. Strings and comments that
+// span lines aren't tracked, which is enough to review a generated config.
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use std::ops::Range;
+
+use crate::import_export::ConfigFormat;
+use crate::theme::Theme;
+
+/// What a piece of a highlighted line is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Token {
+    Plain,
+    Comment,
+    String,
+    Number,
+    Keyword,
+    /// The name being assigned, like `gaps_in` in `gaps_in = 5`
+    Key,
+}
+
+impl Token {
+    fn style(self, theme: &Theme) -> Style {
+        match self {
+            Token::Plain => Style::default().fg(theme.fg_primary),
+            Token::Comment => Style::default()
+                .fg(theme.fg_muted)
+                .add_modifier(Modifier::ITALIC),
+            Token::String => Style::default().fg(theme.type_string),
+            Token::Number => Style::default().fg(theme.type_integer),
+            Token::Keyword => Style::default()
+                .fg(theme.type_keyword)
+                .add_modifier(Modifier::BOLD),
+            Token::Key => Style::default().fg(theme.accent_primary),
+        }
+    }
+}
+
+/// The rules for one format
+struct Syntax {
+    comment: Option<&'static str>,
+    single_quoted_strings: bool,
+    /// `[section]` lines are headers, as in TOML
+    section_headers: bool,
+    /// What follows a key, as in `key = value`, `section {` or `"key": value`
+    key_ends: &'static [char],
+    /// Characters besides letters and digits that are part of a word
+    word_chars: &'static str,
+    keywords: &'static [&'static str],
+}
+
+const HYPRLAND: Syntax = Syntax {
+    comment: Some("#"),
+    single_quoted_strings: false,
+    section_headers: false,
+    key_ends: &['=', '{'],
+    word_chars: "_-.:$@",
+    keywords: &["true", "false", "yes", "no", "on", "off"],
+};
+
+const NIX: Syntax = Syntax {
+    comment: Some("#"),
+    single_quoted_strings: false,
+    section_headers: false,
+    key_ends: &['='],
+    word_chars: "_-.",
+    keywords: &[
+        "let", "in", "with", "rec", "inherit", "if", "then", "else", "import", "assert", "true",
+        "false", "null",
+    ],
+};
+
+const JSON: Syntax = Syntax {
+    comment: None,
+    single_quoted_strings: false,
+    section_headers: false,
+    key_ends: &[':'],
+    word_chars: "_-.+",
+    keywords: &["true", "false", "null"],
+};
+
+/// JSON under a commented header
+const RHYPR: Syntax = Syntax {
+    comment: Some("#"),
+    ..JSON
+};
+
+const TOML: Syntax = Syntax {
+    comment: Some("#"),
+    single_quoted_strings: true,
+    section_headers: true,
+    key_ends: &['='],
+    word_chars: "_-.+",
+    keywords: &["true", "false"],
+};
+
+const YAML: Syntax = Syntax {
+    comment: Some("#"),
+    single_quoted_strings: true,
+    section_headers: false,
+    key_ends: &[':'],
+    word_chars: "_-./",
+    keywords: &["true", "false", "null", "yes", "no", "~"],
+};
+
+fn syntax(format: &ConfigFormat) -> &'static Syntax {
+    match format {
+        ConfigFormat::HyprlandConf => &HYPRLAND,
+        ConfigFormat::NixHomeManager | ConfigFormat::NixSystem => &NIX,
+        ConfigFormat::Json => &JSON,
+        ConfigFormat::RHyprConfig => &RHYPR,
+        ConfigFormat::Toml => &TOML,
+        ConfigFormat::Yaml => &YAML,
+    }
+}
+
+/// Split `line` into tokens, with neighbouring pieces of the same kind joined
+pub fn tokenize<'a>(line: &'a str, format: &ConfigFormat) -> Vec<(Token, &'a str)> {
+    let syntax = syntax(format);
+    if syntax.section_headers && line.trim_start().starts_with('[') {
+        return vec![(Token::Keyword, line)];
+    }
+
+    let is_word_char = |c: char| c.is_alphanumeric() || syntax.word_chars.contains(c);
+    let is_key = |after: &str| {
+        let after = after.trim_start();
+        after.starts_with(syntax.key_ends) && !after.starts_with("==")
+    };
+
+    let mut tokens: Vec<(Token, Range<usize>)> = Vec::new();
+    let mut pos = 0;
+    while let Some(c) = line[pos..].chars().next() {
+        let rest = &line[pos..];
+        let comment = syntax.comment.is_some_and(|c| rest.starts_with(c));
+        let (token, len) = if comment {
+            (Token::Comment, rest.len())
+        } else if c == '"' || (c == '\'' && syntax.single_quoted_strings) {
+            let len = quoted_len(rest, c);
+            let token = if is_key(&rest[len..]) {
+                Token::Key
+            } else {
+                Token::String
+            };
+            (token, len)
+        } else if is_word_char(c) {
+            let len = rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+            let word = &rest[..len];
+            let token = if word
+                .trim_start_matches('-')
+                .starts_with(|c: char| c.is_ascii_digit())
+            {
+                Token::Number
+            } else if syntax.keywords.contains(&word) {
+                Token::Keyword
+            } else if is_key(&rest[len..]) {
+                Token::Key
+            } else {
+                Token::Plain
+            };
+            (token, len)
+        } else {
+            (Token::Plain, c.len_utf8())
+        };
+
+        match tokens.last_mut() {
+            Some((last, range)) if *last == token => range.end = pos + len,
+            _ => tokens.push((token, pos..pos + len)),
+        }
+        pos += len;
+    }
+
+    tokens
+        .into_iter()
+        .map(|(token, range)| (token, &line[range]))
+        .collect()
+}
+
+/// The length of the string starting at `text`, up to and including its
+/// closing quote, or the rest of the line if it isn't closed
+fn quoted_len(text: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (index, c) in text.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return index + c.len_utf8();
+        }
+    }
+    text.len()
+}
+
+/// Highlight one line of a config in `format`
+pub fn highlight_line(line: &str, format: &ConfigFormat, theme: &Theme) -> Line<'static> {
+    Line::from(
+        tokenize(line, format)
+            .into_iter()
+            .map(|(token, text)| Span::styled(text.to_string(), token.style(theme)))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Highlight every line of a config in `format`
+pub fn highlight(text: &str, format: &ConfigFormat, theme: &Theme) -> Vec<Line<'static>> {
+    text.lines()
+        .map(|line| highlight_line(line, format, theme))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyprland_conf_tokens() {
+        assert_eq!(
+            tokenize("    gaps_in = 5 # inner gaps", &ConfigFormat::HyprlandConf),
+            vec![
+                (Token::Plain, "    "),
+                (Token::Key, "gaps_in"),
+                (Token::Plain, " = "),
+                (Token::Number, "5"),
+                (Token::Plain, " "),
+                (Token::Comment, "# inner gaps"),
+            ]
+        );
+        assert_eq!(
+            tokenize("general {", &ConfigFormat::HyprlandConf),
+            vec![(Token::Key, "general"), (Token::Plain, " {")]
+        );
+        assert_eq!(
+            tokenize(
+                "bind = $mainMod, Q, exec, kitty",
+                &ConfigFormat::HyprlandConf
+            )[0],
+            (Token::Key, "bind")
+        );
+    }
+
+    #[test]
+    fn test_strings_keys_and_keywords_per_format() {
+        assert_eq!(
+            tokenize(r#"  "gaps_in": -5, "on": true"#, &ConfigFormat::Json),
+            vec![
+                (Token::Plain, "  "),
+                (Token::Key, r#""gaps_in""#),
+                (Token::Plain, ": "),
+                (Token::Number, "-5"),
+                (Token::Plain, ", "),
+                (Token::Key, r#""on""#),
+                (Token::Plain, ": "),
+                (Token::Keyword, "true"),
+            ]
+        );
+        assert_eq!(
+            tokenize(r#"layout = "dwin\"dle" # ok"#, &ConfigFormat::Toml),
+            vec![
+                (Token::Key, "layout"),
+                (Token::Plain, " = "),
+                (Token::String, r#""dwin\"dle""#),
+                (Token::Plain, " "),
+                (Token::Comment, "# ok"),
+            ]
+        );
+        assert_eq!(
+            tokenize("[decoration.blur]", &ConfigFormat::Toml),
+            vec![(Token::Keyword, "[decoration.blur]")]
+        );
+        assert_eq!(
+            tokenize("  enable = true;", &ConfigFormat::NixHomeManager),
+            vec![
+                (Token::Plain, "  "),
+                (Token::Key, "enable"),
+                (Token::Plain, " = "),
+                (Token::Keyword, "true"),
+                (Token::Plain, ";"),
+            ]
+        );
+        assert_eq!(
+            tokenize("  - 'unclosed", &ConfigFormat::Yaml),
+            vec![(Token::Plain, "  - "), (Token::String, "'unclosed")]
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
 use tokio::process::Command as AsyncCommand;
 use tokio::time::{timeout, Duration as TokioDuration};
 
@@ -102,6 +105,8 @@ pub struct HyprlandKeybind {
     pub dispatcher: String,     // exec, killactive, togglefloating, etc.
     pub args: Option<String>,   // Command arguments
     pub bind_type: String,      // bind, bindm, bindr, etc.
+    #[serde(default)]
+    pub submap: Option<String>, // Submap the bind belongs to (None = global)
 }
 
 impl HyprlandKeybind {
@@ -118,6 +123,7 @@ impl HyprlandKeybind {
             dispatcher,
             args,
             bind_type: "bind".to_string(),
+            submap: None,
         }
     }
 
@@ -142,6 +148,12 @@ impl HyprlandKeybind {
     }
 
     pub fn display_string(&self) -> String {
+        let submap_prefix = if let Some(ref submap) = self.submap {
+            format!("[{submap}] ")
+        } else {
+            String::new()
+        };
+
         let mod_string = if self.modifiers.is_empty() {
             String::new()
         } else {
@@ -155,12 +167,63 @@ impl HyprlandKeybind {
         };
 
         format!(
-            "{}{} → {}{}",
-            mod_string, self.key, self.dispatcher, args_string
+            "{}{}{} → {}{}",
+            submap_prefix, mod_string, self.key, self.dispatcher, args_string
         )
     }
 }
 
+/// An open window, from `hyprctl clients -j`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct HyprlandClient {
+    #[serde(default)]
+    pub class: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub address: String,
+    /// The class and title the window opened with, which rules can also match
+    #[serde(default, rename = "initialClass")]
+    pub initial_class: String,
+    #[serde(default, rename = "initialTitle")]
+    pub initial_title: String,
+    #[serde(default)]
+    pub floating: bool,
+    #[serde(default)]
+    pub xwayland: bool,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub workspace: ClientWorkspace,
+}
+
+/// The workspace a window is on
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ClientWorkspace {
+    #[serde(default)]
+    pub id: i64,
+    #[serde(default)]
+    pub name: String,
+}
+
+impl HyprlandClient {
+    /// Parse the reply of `hyprctl clients -j`
+    pub fn list_from_json(json: &str) -> Result<Vec<Self>> {
+        serde_json::from_str(json).context("Unexpected reply from hyprctl clients")
+    }
+
+    /// Parse the reply of `hyprctl activewindow -j`, which is an empty
+    /// object when no window has focus
+    pub fn active_from_json(json: &str) -> Result<Option<Self>> {
+        if json.trim().is_empty() {
+            return Ok(None);
+        }
+        let window: Self =
+            serde_json::from_str(json).context("Unexpected reply from hyprctl activewindow")?;
+        Ok((!window.address.is_empty()).then_some(window))
+    }
+}
+
 /// Cache entry for hyprctl responses
 #[derive(Debug, Clone)]
 struct CacheEntry<T> {
@@ -273,21 +336,384 @@ pub struct CacheStats {
     pub bulk_ttl_secs: u64,
 }
 
+/// Number of changed options above which `hyprctl --batch` is used instead of
+/// one `hyprctl keyword` call per option
+pub const BATCH_THRESHOLD: usize = 3;
+
+/// Result of applying several options at once
+#[derive(Debug, Clone, Default)]
+pub struct BatchOutcome {
+    /// Options that were applied
+    pub applied: Vec<String>,
+    /// Options that failed, with hyprctl's error message
+    pub failed: Vec<(String, String)>,
+}
+
+impl BatchOutcome {
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// A `hyprctl --batch` request built from a set of options
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeywordBatch {
+    /// The `keyword a b ; keyword c d` argument
+    pub command: String,
+    /// Options included in `command`, in order
+    pub keys: Vec<String>,
+    /// Options that can't be batched and need their own `hyprctl keyword` call
+    pub unbatched: Vec<(String, String)>,
+}
+
+impl KeywordBatch {
+    /// Build a batch from the given options, sorted by key. Hyprland splits
+    /// batches on `;` with no way to escape it, so options containing one are
+    /// left out of the batch and applied individually.
+    pub fn from_options(options: &HashMap<String, String>) -> Self {
+        let mut sorted: Vec<(&String, &String)> = options.iter().collect();
+        sorted.sort();
+
+        let mut batch = Self::default();
+        let mut commands = Vec::new();
+        for (key, value) in sorted {
+            if key.contains(';') || value.contains(';') {
+                batch.unbatched.push((key.clone(), value.clone()));
+            } else {
+                commands.push(format!("keyword {key} {value}"));
+                batch.keys.push(key.clone());
+            }
+        }
+        batch.command = commands.join(" ; ");
+        batch
+    }
+
+    /// Whether hyprctl's reply reports success for every batched command.
+    /// Each successful command answers `ok`; separators vary between versions.
+    pub fn all_ok(&self, reply: &str) -> bool {
+        let compact: String = reply.split_whitespace().collect();
+        compact == "ok".repeat(self.keys.len())
+    }
+}
+
+/// The reply of `hyprctl getoption -j`, which carries exactly one of the
+/// typed value fields depending on the option
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct OptionValue {
+    #[serde(default)]
+    pub option: String,
+    pub int: Option<i64>,
+    pub float: Option<f64>,
+    pub str: Option<String>,
+    /// Gradients, gaps and other custom types, already formatted by Hyprland
+    pub custom: Option<String>,
+    pub vec2: Option<[f64; 2]>,
+    /// Whether the option was set by the config rather than left at its default
+    #[serde(default)]
+    pub set: bool,
+}
+
+impl OptionValue {
+    /// Deserialize the JSON printed by `hyprctl getoption -j`
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json.trim()).context("Failed to parse hyprctl getoption JSON")
+    }
+
+    /// The value formatted the way hyprland.conf writes it
+    pub fn value(&self) -> String {
+        if let Some(int) = self.int {
+            int.to_string()
+        } else if let Some(float) = self.float {
+            float.to_string()
+        } else if let Some(str) = &self.str {
+            str.trim().to_string()
+        } else if let Some(custom) = &self.custom {
+            custom.trim().to_string()
+        } else if let Some([x, y]) = self.vec2 {
+            format!("{x} {y}")
+        } else {
+            String::new()
+        }
+    }
+}
+
+/// A Hyprland release, as `hyprctl version` reports it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HyprlandVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+/// The oldest Hyprland release r-hyprconfig supports
+pub const MIN_HYPRLAND_VERSION: HyprlandVersion = HyprlandVersion {
+    major: 0,
+    minor: 50,
+    patch: 1,
+};
+
+impl HyprlandVersion {
+    /// The first version in the output of `hyprctl version`, which starts
+    /// `Hyprland 0.50.1 built from branch ...`. Older releases only give it
+    /// on a `Tag: v0.45.2-...` line.
+    pub fn parse(output: &str) -> Option<Self> {
+        output
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .find_map(|word| {
+                let word = word.strip_prefix('v').unwrap_or(word);
+                let mut numbers = word.split('-').next()?.split('.');
+                let version = HyprlandVersion {
+                    major: numbers.next()?.parse().ok()?,
+                    minor: numbers.next()?.parse().ok()?,
+                    patch: numbers.next()?.parse().ok()?,
+                };
+                numbers.next().is_none().then_some(version)
+            })
+    }
+}
+
+impl std::fmt::Display for HyprlandVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The Hyprland line of `--version`, from the output of `hyprctl version`
+/// when it answered
+pub fn hyprland_version_line(version_output: Option<&str>) -> String {
+    let supported = format!("{MIN_HYPRLAND_VERSION} or later");
+    match version_output.map(HyprlandVersion::parse) {
+        None => format!("Hyprland: not detected (supported: {supported})"),
+        Some(None) => format!("Hyprland: unknown version (supported: {supported})"),
+        Some(Some(version)) if version >= MIN_HYPRLAND_VERSION => {
+            format!("Hyprland: {version} (supported)")
+        }
+        Some(Some(version)) => {
+            format!("Hyprland: {version} (unsupported, r-hyprconfig needs {supported})")
+        }
+    }
+}
+
+/// Why hyprctl can't be used. When it isn't installed, this says how to
+/// install Hyprland on the detected distribution.
+pub fn unavailable_message() -> String {
+    if which::which("hyprctl").is_err() {
+        format!(
+            "hyprctl is not installed. {}",
+            crate::platform::hyprland_install_message()
+        )
+    } else {
+        "hyprctl is unavailable - is Hyprland running?".to_string()
+    }
+}
+
+/// Turn the reply of `hyprctl dispatch` into an error. Hyprland exits with
+/// status 0 either way and answers `ok` only when the dispatcher ran.
+pub fn check_dispatch_reply(dispatcher: &str, reply: &str) -> Result<()> {
+    let reply = reply.trim();
+    if reply.is_empty() || reply == "ok" {
+        return Ok(());
+    }
+    if reply.starts_with("Invalid dispatcher") {
+        anyhow::bail!("'{dispatcher}' is not a Hyprland dispatcher");
+    }
+    anyhow::bail!("hyprctl dispatch {dispatcher} failed: {reply}")
+}
+
+/// Pull the config errors out of `Hyprland --verify-config` output. Hyprland
+/// logs its startup before the parse result, so only what follows the result
+/// header is kept. Errors in `config_path` itself are reported by line number
+/// alone; errors in sourced files keep their file name.
+pub fn parse_verify_output(output: &str, config_path: &std::path::Path) -> Vec<String> {
+    const RESULT_HEADER: &str = "Config parsing result:";
+    let result = output
+        .split_once(RESULT_HEADER)
+        .map_or(output, |(_, result)| result);
+    let own_prefix = format!("Config error in file {} at ", config_path.display());
+
+    result
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.eq_ignore_ascii_case("config ok"))
+        .map(|line| match line.strip_prefix(&own_prefix) {
+            Some(rest) => rest.to_string(),
+            None => line.to_string(),
+        })
+        .collect()
+}
+
+/// The errors `hyprctl configerrors` lists for the loaded config, one a line.
+/// With none it prints an empty line.
+pub fn parse_config_errors(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether hyprctl can reach a running Hyprland. Without one the config file
+/// can still be edited and saved, only live changes are unavailable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HyprlandStatus {
+    Connected,
+    /// hyprctl is installed but no Hyprland instance answers
+    NotRunning,
+    /// There is no hyprctl on PATH
+    NotInstalled,
+    /// Hyprland isn't asked at all, see `HyprCtl::new_file_only`
+    FileOnly,
+}
+
+impl HyprlandStatus {
+    /// Classify the reply to the probe `new` sends: whether hyprctl exited
+    /// successfully, or why it couldn't run
+    pub fn from_probe(probe: HyprctlResult<bool>) -> Self {
+        match probe {
+            Ok(true) => HyprlandStatus::Connected,
+            Err(HyprctlError::CommandNotFound) => HyprlandStatus::NotInstalled,
+            // An exit with an error or a hang both mean nothing answered
+            Ok(false) | Err(_) => HyprlandStatus::NotRunning,
+        }
+    }
+
+    pub fn is_connected(self) -> bool {
+        self == HyprlandStatus::Connected
+    }
+
+    /// Short form for the header, empty when connected
+    pub fn label(self) -> &'static str {
+        match self {
+            HyprlandStatus::Connected => "",
+            HyprlandStatus::NotRunning => "Offline — editing file only",
+            HyprlandStatus::NotInstalled => "No hyprctl — editing file only",
+            HyprlandStatus::FileOnly => "file-only — changes are never applied live",
+        }
+    }
+}
+
+const SOCKET_NAME: &str = ".socket.sock";
+/// The socket Hyprland announces events on, one `EVENT>>DATA` line each
+const EVENT_SOCKET_NAME: &str = ".socket2.sock";
+
+/// Hyprland's socket `name` for the instance in `signature`, under
+/// `$XDG_RUNTIME_DIR/hypr` or under `/tmp/hypr` where versions before 0.40 put it
+pub fn ipc_socket_candidates(
+    runtime_dir: Option<&Path>,
+    signature: &str,
+    name: &str,
+) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(runtime_dir) = runtime_dir {
+        candidates.push(runtime_dir.join("hypr").join(signature).join(name));
+    }
+    candidates.push(Path::new("/tmp/hypr").join(signature).join(name));
+    candidates
+}
+
+/// The socket `name` of the Hyprland instance this process runs under, if
+/// it exists
+fn find_ipc_socket(name: &str) -> Option<PathBuf> {
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+    if signature.is_empty() {
+        return None;
+    }
+    let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from);
+    ipc_socket_candidates(runtime_dir.as_deref(), &signature, name)
+        .into_iter()
+        .find(|path| path.exists())
+}
+
+/// Whether a line from the event socket means option values may have
+/// changed. Hyprland sends `configreloaded` after every reload, whether
+/// `hyprctl reload` or its own reload when hyprland.conf is written.
+pub fn is_config_change_event(line: &str) -> bool {
+    let event = line.split_once(">>").map_or(line, |(event, _)| event);
+    event.trim() == "configreloaded"
+}
+
+/// Listen on Hyprland's event socket in the background and send a message
+/// each time the config changes. `None` outside a Hyprland session.
+pub fn watch_config_changes() -> Option<tokio::sync::mpsc::UnboundedReceiver<()>> {
+    find_ipc_socket(EVENT_SOCKET_NAME).map(watch_config_changes_at)
+}
+
+fn watch_config_changes_at(socket: PathBuf) -> tokio::sync::mpsc::UnboundedReceiver<()> {
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let Ok(stream) = UnixStream::connect(&socket).await else {
+            return;
+        };
+        let mut lines = BufReader::new(stream).lines();
+        // Ends when Hyprland exits or nobody listens any more
+        while let Ok(Some(line)) = lines.next_line().await {
+            if is_config_change_event(&line) && sender.send(()).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// The socket request for hyprctl arguments, for the commands that go over
+/// the socket: `getoption`, `keyword`, `dispatch` and `clients`. hyprctl
+/// sends its flags before a `/`, so `-j getoption gaps_in` becomes
+/// `j/getoption gaps_in`.
+pub fn ipc_request(args: &[&str]) -> Option<String> {
+    let json = args.contains(&"-j");
+    let words: Vec<&str> = args.iter().copied().filter(|arg| *arg != "-j").collect();
+    let command = *words.first()?;
+    if !matches!(command, "getoption" | "keyword" | "dispatch" | "clients") {
+        return None;
+    }
+    let request = words.join(" ");
+    if json {
+        Some(format!("j/{request}"))
+    } else {
+        Some(request)
+    }
+}
+
+/// hyprctl answers `keyword` with `ok` or the reason it was refused, and
+/// exits with 0 either way. Over the socket a refusal becomes a failed exit,
+/// so callers checking the status see it.
+fn ipc_output(request: &str, reply: Vec<u8>) -> std::process::Output {
+    use std::os::unix::process::ExitStatusExt;
+
+    let refused = request.starts_with("keyword ") && reply.trim_ascii() != b"ok";
+    if refused {
+        std::process::Output {
+            status: std::process::ExitStatus::from_raw(1 << 8),
+            stdout: Vec::new(),
+            stderr: reply,
+        }
+    } else {
+        std::process::Output {
+            status: std::process::ExitStatus::from_raw(0),
+            stdout: reply,
+            stderr: Vec::new(),
+        }
+    }
+}
+
 pub struct HyprCtl {
-    #[allow(dead_code)]
-    socket_path: Option<String>,
+    /// Hyprland's request socket, used instead of spawning hyprctl when found
+    socket_path: Option<PathBuf>,
+    status: HyprlandStatus,
     cache: std::sync::Mutex<HyprctlCache>,
     /// Timeout for hyprctl commands in milliseconds
     timeout_ms: u64,
+    /// Whether each option read so far is set by the config, from `getoption -j`
+    option_set: std::sync::Mutex<HashMap<String, bool>>,
+    /// How often `reload_config` was called, for tests
+    #[cfg(test)]
+    reloads: std::sync::atomic::AtomicUsize,
 }
 
 impl HyprCtl {
     pub async fn new() -> Result<Self> {
-        let mut hyprctl = Self { 
-            socket_path: None,
-            cache: std::sync::Mutex::new(HyprctlCache::new()),
-            timeout_ms: 5000, // Default 5 second timeout
-        };
+        let mut hyprctl = Self::new_disconnected();
 
         // Try to detect Hyprland socket
         hyprctl.detect_socket().await?;
@@ -298,11 +724,29 @@ impl HyprCtl {
     pub fn new_disconnected() -> Self {
         Self { 
             socket_path: None,
+            status: HyprlandStatus::NotRunning,
             cache: std::sync::Mutex::new(HyprctlCache::new()),
             timeout_ms: 5000, // Default 5 second timeout
+            option_set: std::sync::Mutex::new(HashMap::new()),
+            #[cfg(test)]
+            reloads: std::sync::atomic::AtomicUsize::new(0),
         }
     }
 
+    /// Never run hyprctl, for editing a config that belongs to another
+    /// machine. Everything is read from the config file and every command
+    /// fails with `HyprctlError::FileOnly`.
+    pub fn new_file_only() -> Self {
+        Self {
+            status: HyprlandStatus::FileOnly,
+            ..Self::new_disconnected()
+        }
+    }
+
+    pub fn is_file_only(&self) -> bool {
+        self.status == HyprlandStatus::FileOnly
+    }
+
     /// Clear all cached data (useful when configuration changes are made)
     pub fn clear_cache(&self) {
         if let Ok(mut cache) = self.cache.lock() {
@@ -327,10 +771,28 @@ impl HyprCtl {
         self.timeout_ms
     }
 
-    /// Execute a hyprctl command with timeout handling
+    /// Execute a hyprctl command with timeout handling. Commands the socket
+    /// understands skip the hyprctl process while Hyprland's socket is there.
     async fn execute_hyprctl_with_timeout(&self, args: &[&str]) -> HyprctlResult<std::process::Output> {
+        if self.is_file_only() {
+            return Err(HyprctlError::FileOnly);
+        }
         let command_str = format!("hyprctl {}", args.join(" "));
         let timeout_duration = TokioDuration::from_millis(self.timeout_ms);
+
+        if let (Some(socket), Some(request)) = (&self.socket_path, ipc_request(args)) {
+            match timeout(timeout_duration, Self::send_ipc_request(socket, &request)).await {
+                Ok(Ok(reply)) => return Ok(ipc_output(&request, reply)),
+                // A stale socket, try hyprctl instead
+                Ok(Err(_)) => {}
+                Err(_) => {
+                    return Err(HyprctlError::Timeout {
+                        command: command_str,
+                        timeout_ms: self.timeout_ms,
+                    })
+                }
+            }
+        }
         
         let future = AsyncCommand::new("hyprctl")
             .args(args)
@@ -349,8 +811,21 @@ impl HyprCtl {
         }
     }
 
+    /// Send one request over Hyprland's socket and read the reply, which
+    /// ends when Hyprland closes the connection
+    async fn send_ipc_request(socket: &Path, request: &str) -> std::io::Result<Vec<u8>> {
+        let mut stream = UnixStream::connect(socket).await?;
+        stream.write_all(request.as_bytes()).await?;
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).await?;
+        Ok(reply)
+    }
+
     /// Execute a hyprctl command with custom timeout (for testing or special cases)
     pub async fn execute_with_custom_timeout(&self, args: &[&str], timeout_ms: u64) -> HyprctlResult<std::process::Output> {
+        if self.is_file_only() {
+            return Err(HyprctlError::FileOnly);
+        }
         let command_str = format!("hyprctl {}", args.join(" "));
         let timeout_duration = TokioDuration::from_millis(timeout_ms);
         
@@ -371,6 +846,12 @@ impl HyprCtl {
         }
     }
 
+    /// How often Hyprland was asked to reload
+    #[cfg(test)]
+    pub fn reload_count(&self) -> usize {
+        self.reloads.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
     /// Test timeout functionality by running a command with very short timeout
     #[cfg(test)]
     pub async fn test_timeout_functionality(&self) -> bool {
@@ -400,24 +881,28 @@ impl HyprCtl {
     }
 
     async fn detect_socket(&mut self) -> Result<()> {
-        // Try to get Hyprland instance signature
-        let output = self.execute_hyprctl_with_timeout(&["getoption", "general:border_size"]).await;
-
-        match output {
-            Ok(output) => {
-                if output.status.success() {
-                    // hyprctl is available and working
-                    Ok(())
-                } else {
-                    eprintln!("Warning: hyprctl available but Hyprland not running");
-                    Ok(()) // Don't fail, just warn
-                }
-            }
-            Err(e) => {
-                eprintln!("Warning: Hyprland is not running or hyprctl is not available: {e}");
-                Ok(()) // Don't fail, just warn - we can use config file fallback
-            }
+        // Not finding Hyprland isn't an error, the config file is used instead
+        self.refresh_status().await;
+        Ok(())
+    }
+
+    /// Whether Hyprland answered when last asked
+    pub fn status(&self) -> HyprlandStatus {
+        self.status
+    }
+
+    /// Ask Hyprland again, for when it was started or stopped since
+    pub async fn refresh_status(&mut self) -> HyprlandStatus {
+        if self.is_file_only() {
+            return self.status;
         }
+        self.socket_path = find_ipc_socket(SOCKET_NAME);
+        let probe = self
+            .execute_hyprctl_with_timeout(&["getoption", "general:border_size"])
+            .await
+            .map(|output| output.status.success());
+        self.status = HyprlandStatus::from_probe(probe);
+        self.status
     }
 
     pub async fn get_option(&self, option: &str) -> Result<String> {
@@ -448,7 +933,12 @@ impl HyprCtl {
 
     /// Internal method to get option without caching (used by cached version)
     async fn get_option_uncached(&self, option: &str) -> Result<String> {
-        let output = self.execute_hyprctl_with_timeout(&["getoption", option])
+        Ok(self.get_option_value(option).await?.value())
+    }
+
+    /// Get an option along with its type and whether it differs from the default
+    pub async fn get_option_value(&self, option: &str) -> Result<OptionValue> {
+        let output = self.execute_hyprctl_with_timeout(&["-j", "getoption", option])
             .await
             .map_err(|e| anyhow::anyhow!("Failed to execute hyprctl getoption: {}", e))?;
 
@@ -458,44 +948,31 @@ impl HyprCtl {
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
+        // Unknown options are reported as plain text even with -j
+        if !stdout.trim_start().starts_with('{') {
+            anyhow::bail!("hyprctl getoption {}: {}", option, stdout.trim())
+        }
 
-        // Parse the structured output from hyprctl getoption
-        // Example output:
-        // int: 2
-        // set: true
-        //
-        // or:
-        // custom type: 2 2 2 2
-        // set: true
-
-        for line in stdout.lines() {
-            let line = line.trim();
-            if line.starts_with("int: ") {
-                return Ok(line.strip_prefix("int: ").unwrap_or("").to_string());
-            } else if line.starts_with("float: ") {
-                return Ok(line.strip_prefix("float: ").unwrap_or("").to_string());
-            } else if line.starts_with("str: ") {
-                return Ok(line.strip_prefix("str: ").unwrap_or("").to_string());
-            } else if line.starts_with("custom type: ") {
-                return Ok(line.strip_prefix("custom type: ").unwrap_or("").to_string());
-            } else if line.starts_with("vec2: ") {
-                return Ok(line.strip_prefix("vec2: ").unwrap_or("").to_string());
-            } else if line.starts_with("color: ") {
-                return Ok(line.strip_prefix("color: ").unwrap_or("").to_string());
-            } else if !line.starts_with("set: ") && !line.is_empty() {
-                // Fallback: if no type prefix found, return the first non-empty, non-"set:" line
-                return Ok(line.to_string());
-            }
+        let value = OptionValue::from_json(&stdout)?;
+        if let Ok(mut option_set) = self.option_set.lock() {
+            option_set.insert(option.to_string(), value.set);
         }
+        Ok(value)
+    }
 
-        // Fallback to original behavior
-        Ok(stdout.trim().to_string())
+    /// Whether each option read so far is set by the config rather than left
+    /// at Hyprland's default
+    pub fn option_set_flags(&self) -> HashMap<String, bool> {
+        self.option_set
+            .lock()
+            .map(|option_set| option_set.clone())
+            .unwrap_or_default()
     }
 
     /// Get hyprctl option with structured error handling
     pub async fn get_option_typed(&self, option: &str) -> HyprctlResult<String> {
 
-        let output = self.execute_hyprctl_with_timeout(&["getoption", option]).await?;
+        let output = self.execute_hyprctl_with_timeout(&["-j", "getoption", option]).await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -517,8 +994,18 @@ impl HyprCtl {
                 reason: "Empty output received".to_string(),
             });
         }
+        if stdout.contains("no such option") {
+            return Err(HyprctlError::InvalidOption {
+                option: option.to_string(),
+            });
+        }
 
-        Ok(stdout.trim().to_string())
+        OptionValue::from_json(&stdout)
+            .map(|value| value.value())
+            .map_err(|e| HyprctlError::ParseError {
+                command: format!("hyprctl -j getoption {}", option),
+                reason: e.to_string(),
+            })
     }
 
     /// Get hyprctl option with automatic retry and recovery
@@ -536,10 +1023,10 @@ impl HyprCtl {
                             RecoveryStrategy::Retry { max_attempts, base_delay_ms } => {
                                 if recovery_context.attempt_count <= max_attempts {
                                     let delay = base_delay_ms * (1 << (recovery_context.attempt_count - 1));
-                                    eprintln!(
+                                    crate::stderr_log::note(format!(
                                         "Retrying hyprctl command '{}' (attempt {}/{}) after {}ms delay...",
                                         option, recovery_context.attempt_count, max_attempts, delay
-                                    );
+                                    ));
                                     sleep(Duration::from_millis(delay)).await;
                                     continue;
                                 } else {
@@ -548,7 +1035,10 @@ impl HyprCtl {
                                 }
                             }
                             RecoveryStrategy::Fallback { description } => {
-                                eprintln!("Falling back for option '{}': {}", option, description);
+                                crate::stderr_log::note(format!(
+                                    "Falling back for option '{}': {}",
+                                    option, description
+                                ));
                                 // Return a reasonable default based on the option type
                                 return Ok(self.get_default_value_for_option(option));
                             }
@@ -599,6 +1089,50 @@ impl HyprCtl {
         Ok(())
     }
 
+    /// Apply many options with a single `hyprctl --batch` call.
+    ///
+    /// If the batch reports any failure, the options are re-applied one by
+    /// one so each failure can be attributed to its option.
+    pub async fn set_options_batch(
+        &self,
+        options: &HashMap<String, String>,
+    ) -> Result<BatchOutcome> {
+        let batch = KeywordBatch::from_options(options);
+        let mut outcome = BatchOutcome::default();
+        let mut retry_individually = batch.unbatched.clone();
+
+        if !batch.keys.is_empty() {
+            let output = self
+                .execute_hyprctl_with_timeout(&["--batch", &batch.command])
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to execute hyprctl --batch: {}", e))?;
+
+            let reply = String::from_utf8_lossy(&output.stdout);
+            if output.status.success() && batch.all_ok(&reply) {
+                outcome.applied.extend(batch.keys.iter().cloned());
+            } else {
+                retry_individually.extend(
+                    batch
+                        .keys
+                        .iter()
+                        .map(|key| (key.clone(), options[key].clone())),
+                );
+            }
+        }
+
+        for (key, value) in retry_individually {
+            match self.set_option(&key, &value).await {
+                Ok(()) => outcome.applied.push(key),
+                Err(e) => outcome.failed.push((key, e.to_string())),
+            }
+        }
+
+        // Clear cache after setting options since configuration has changed
+        self.clear_cache();
+
+        Ok(outcome)
+    }
+
     pub async fn get_all_options(&self) -> Result<HashMap<String, String>> {
         // Check cache first
         if let Ok(mut cache) = self.cache.lock() {
@@ -647,7 +1181,7 @@ impl HyprCtl {
                     options.insert(option.to_string(), value);
                 }
                 Err(e) => {
-                    eprintln!("Warning: Failed to get option {option}: {e}");
+                    crate::stderr_log::warn(format!("Failed to get option {option}: {e}"));
                 }
             }
         }
@@ -672,7 +1206,7 @@ impl HyprCtl {
                     options.insert(option.to_string(), value);
                 }
                 Err(e) => {
-                    eprintln!("Warning: Failed to get option {option}: {e}");
+                    crate::stderr_log::warn(format!("Failed to get option {option}: {e}"));
                 }
             }
         }
@@ -697,7 +1231,7 @@ impl HyprCtl {
                     options.insert(option.to_string(), value);
                 }
                 Err(e) => {
-                    eprintln!("Warning: Failed to get option {option}: {e}");
+                    crate::stderr_log::warn(format!("Failed to get option {option}: {e}"));
                 }
             }
         }
@@ -711,7 +1245,7 @@ impl HyprCtl {
                     options.insert(option.to_string(), value);
                 }
                 Err(e) => {
-                    eprintln!("Warning: Failed to get option {option}: {e}");
+                    crate::stderr_log::warn(format!("Failed to get option {option}: {e}"));
                 }
             }
         }
@@ -734,7 +1268,7 @@ impl HyprCtl {
                     options.insert(option.to_string(), value);
                 }
                 Err(e) => {
-                    eprintln!("Warning: Failed to get option {option}: {e}");
+                    crate::stderr_log::warn(format!("Failed to get option {option}: {e}"));
                 }
             }
         }
@@ -760,7 +1294,35 @@ impl HyprCtl {
                     options.insert(option.to_string(), value);
                 }
                 Err(e) => {
-                    eprintln!("Warning: Failed to get option {option}: {e}");
+                    crate::stderr_log::warn(format!("Failed to get option {option}: {e}"));
+                }
+            }
+        }
+
+        // Get options of the remaining categories: layouts, groups, the
+        // cursor, binds, rendering and XWayland
+        let other_options = crate::layouts::option_keys()
+            .chain(crate::groups::option_keys())
+            .chain([
+                "cursor:no_hardware_cursors",
+                "cursor:inactive_timeout",
+                "cursor:hide_on_key_press",
+                "binds:workspace_back_and_forth",
+                "binds:allow_workspace_cycles",
+                "binds:scroll_event_delay",
+                "render:direct_scanout",
+                "xwayland:enabled",
+                "xwayland:force_zero_scaling",
+                "opengl:nvidia_anti_flicker",
+            ]);
+
+        for option in other_options {
+            match self.get_option(option).await {
+                Ok(value) => {
+                    options.insert(option.to_string(), value);
+                }
+                Err(e) => {
+                    crate::stderr_log::warn(format!("Failed to get option {option}: {e}"));
                 }
             }
         }
@@ -827,6 +1389,7 @@ impl HyprCtl {
         Ok(binds)
     }
 
+    #[allow(clippy::collapsible_match)]
     fn parse_structured_bind(lines: &[&str], start_index: usize) -> Option<HyprlandKeybind> {
         // Parse the structured hyprctl binds output format:
         // bind
@@ -848,6 +1411,7 @@ impl HyprCtl {
         let mut key: Option<String> = None;
         let mut dispatcher: Option<String> = None;
         let mut arg: Option<String> = None;
+        let mut submap: Option<String> = None;
 
         // Parse the following lines until we hit the next bind or end of output
         let mut i = start_index + 1;
@@ -885,6 +1449,11 @@ impl HyprCtl {
                             arg = Some(value.to_string());
                         }
                     }
+                    "submap" => {
+                        if !value.is_empty() {
+                            submap = Some(value.to_string());
+                        }
+                    }
                     _ => {} // Ignore other fields
                 }
             }
@@ -906,6 +1475,7 @@ impl HyprCtl {
                 dispatcher: disp,
                 args: arg,
                 bind_type,
+                submap,
             })
         } else {
             None
@@ -950,6 +1520,7 @@ impl HyprCtl {
                 dispatcher,
                 args,
                 bind_type: "bind".to_string(), // Default, could be enhanced
+                submap: None,
             });
         }
 
@@ -978,40 +1549,55 @@ impl HyprCtl {
         }
     }
 
+    /// The windows open right now. Not cached, since windows come and go.
+    pub async fn get_clients(&self) -> Result<Vec<HyprlandClient>> {
+        let output = self
+            .execute_hyprctl_with_timeout(&["clients", "-j"])
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute hyprctl clients: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("hyprctl clients failed: {}", stderr);
+        }
+
+        HyprlandClient::list_from_json(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// The window that has focus, if any
+    pub async fn get_active_window(&self) -> Result<Option<HyprlandClient>> {
+        let output = self
+            .execute_hyprctl_with_timeout(&["activewindow", "-j"])
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute hyprctl activewindow: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("hyprctl activewindow failed: {}", stderr);
+        }
+
+        HyprlandClient::active_from_json(&String::from_utf8_lossy(&output.stdout))
+    }
+
     #[allow(dead_code)]
     pub async fn add_keybind(&self, bind: &HyprlandKeybind) -> Result<()> {
         let bind_command = bind.to_hyprland_config();
-        let result = self.dispatch(&format!("keyword {bind_command}")).await;
-        
-        // Clear cache after adding keybind since configuration has changed
-        if result.is_ok() {
-            self.clear_cache();
-        }
-        
-        result
+        let (keyword, value) = bind_command
+            .split_once(" = ")
+            .unwrap_or((&bind.bind_type, &bind_command));
+        self.set_option(keyword, value).await
     }
 
     #[allow(dead_code)]
     pub async fn remove_keybind(&self, modifiers: &[String], key: &str) -> Result<()> {
-        let mod_string = if modifiers.is_empty() {
-            String::new()
-        } else {
-            format!("{}_", modifiers.join("_"))
-        };
-
-        let unbind_command = format!("unbind {mod_string}{key}");
-        let result = self.dispatch(&unbind_command).await;
-        
-        // Clear cache after removing keybind since configuration has changed
-        if result.is_ok() {
-            self.clear_cache();
-        }
-        
-        result
+        self.set_option("unbind", &format!("{}, {key}", modifiers.join(" ")))
+            .await
     }
 
-    #[allow(dead_code)]
     pub async fn reload_config(&self) -> Result<()> {
+        #[cfg(test)]
+        self.reloads
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         let output = self.execute_hyprctl_with_timeout(&["reload"])
             .await
             .map_err(|e| anyhow::anyhow!("Failed to execute hyprctl reload: {}", e))?;
@@ -1027,9 +1613,78 @@ impl HyprCtl {
         Ok(())
     }
 
+    /// The errors Hyprland found in its config when it last loaded it
+    pub async fn config_errors(&self) -> Result<Vec<String>> {
+        let output = self
+            .execute_hyprctl_with_timeout(&["configerrors"])
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute hyprctl configerrors: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("hyprctl configerrors failed: {}", stderr);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parse_config_errors(&stdout))
+    }
+
+    /// Run `text` through Hyprland's own config parser without applying it and
+    /// return the errors it reports, one per offending line. The config is
+    /// written to a temporary file for `Hyprland --verify-config`, so relative
+    /// `source` paths resolve against the temp directory; use
+    /// `validate_config_in` to check them against the real config directory.
+    pub async fn validate_config(&self, text: &str) -> Result<Vec<String>> {
+        self.validate_config_in(text, &std::env::temp_dir()).await
+    }
+
+    /// `validate_config` with the temporary file placed in `dir`
+    pub async fn validate_config_in(
+        &self,
+        text: &str,
+        dir: &std::path::Path,
+    ) -> Result<Vec<String>> {
+        let path = dir.join(format!(".r-hyprconfig-verify-{}.conf", std::process::id()));
+        tokio::fs::write(&path, text)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        let future = AsyncCommand::new("Hyprland")
+            .arg("--verify-config")
+            .arg("--config")
+            .arg(&path)
+            .kill_on_drop(true)
+            .output();
+        let output = timeout(TokioDuration::from_millis(self.timeout_ms), future).await;
+        let _ = tokio::fs::remove_file(&path).await;
+
+        let output = output
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Hyprland --verify-config timed out after {}ms",
+                    self.timeout_ms
+                )
+            })?
+            .context("Failed to run Hyprland --verify-config")?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let errors = parse_verify_output(&stdout, &path);
+        if !output.status.success() && errors.is_empty() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Hyprland --verify-config failed: {}", stderr.trim());
+        }
+        Ok(errors)
+    }
+
     #[allow(dead_code)]
-    pub async fn dispatch(&self, command: &str) -> Result<()> {
-        let output = self.execute_hyprctl_with_timeout(&["dispatch", command])
+    /// Run `dispatcher` with `args` right away, as pressing its keybind would
+    pub async fn dispatch(&self, dispatcher: &str, args: &str) -> Result<()> {
+        let mut command = vec!["dispatch", dispatcher];
+        if !args.trim().is_empty() {
+            command.push(args.trim());
+        }
+        let output = self
+            .execute_hyprctl_with_timeout(&command)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to execute hyprctl dispatch: {}", e))?;
 
@@ -1038,10 +1693,42 @@ impl HyprCtl {
             anyhow::bail!("hyprctl dispatch failed: {}", stderr);
         }
 
+        check_dispatch_reply(dispatcher, &String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Load a plugin's shared object into the running Hyprland
+    pub async fn load_plugin(&self, path: &str) -> Result<()> {
+        self.plugin_command("load", path).await
+    }
+
+    /// Unload a plugin loaded from `path`
+    pub async fn unload_plugin(&self, path: &str) -> Result<()> {
+        self.plugin_command("unload", path).await
+    }
+
+    async fn plugin_command(&self, action: &str, path: &str) -> Result<()> {
+        let output = self
+            .execute_hyprctl_with_timeout(&["plugin", action, path])
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to execute hyprctl plugin {}: {}", action, e))?;
+
+        // Failures are reported on stdout with a successful exit status
+        let reply = String::from_utf8_lossy(&output.stdout);
+        if !output.status.success() || reply.trim() != "ok" {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = if reply.trim().is_empty() {
+                stderr
+            } else {
+                reply
+            };
+            anyhow::bail!("hyprctl plugin {} failed: {}", action, message.trim());
+        }
+
+        // Loaded plugins bring options of their own
+        self.clear_cache();
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub async fn get_version(&self) -> Result<String> {
         let output = self.execute_hyprctl_with_timeout(&["version"])
             .await
@@ -1265,41 +1952,17 @@ impl HyprCtl {
 
     #[allow(dead_code)]
     pub async fn add_window_rule(&self, rule: &str) -> Result<()> {
-        let command = format!("keyword windowrule {rule}");
-        let result = self.dispatch(&command).await;
-        
-        // Clear cache after adding rule since configuration has changed
-        if result.is_ok() {
-            self.clear_cache();
-        }
-        
-        result
+        self.set_option("windowrule", rule).await
     }
 
     #[allow(dead_code)]
     pub async fn add_layer_rule(&self, rule: &str) -> Result<()> {
-        let command = format!("keyword layerrule {rule}");
-        let result = self.dispatch(&command).await;
-        
-        // Clear cache after adding rule since configuration has changed
-        if result.is_ok() {
-            self.clear_cache();
-        }
-        
-        result
+        self.set_option("layerrule", rule).await
     }
 
     #[allow(dead_code)]
     pub async fn add_workspace_rule(&self, rule: &str) -> Result<()> {
-        let command = format!("keyword workspace {rule}");
-        let result = self.dispatch(&command).await;
-        
-        // Clear cache after adding rule since configuration has changed
-        if result.is_ok() {
-            self.clear_cache();
-        }
-        
-        result
+        self.set_option("workspace", rule).await
     }
 }
 
@@ -1350,6 +2013,154 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_status_tells_a_missing_hyprctl_from_a_stopped_hyprland() {
+        assert_eq!(
+            HyprlandStatus::from_probe(Ok(true)),
+            HyprlandStatus::Connected
+        );
+        assert_eq!(
+            HyprlandStatus::from_probe(Ok(false)),
+            HyprlandStatus::NotRunning
+        );
+        assert_eq!(
+            HyprlandStatus::from_probe(Err(HyprctlError::Timeout {
+                command: "hyprctl getoption general:border_size".to_string(),
+                timeout_ms: 5000,
+            })),
+            HyprlandStatus::NotRunning
+        );
+        assert_eq!(
+            HyprlandStatus::from_probe(Err(HyprctlError::CommandNotFound)),
+            HyprlandStatus::NotInstalled
+        );
+
+        assert!(HyprlandStatus::Connected.label().is_empty());
+        assert!(!HyprlandStatus::NotRunning.is_connected());
+        assert!(HyprlandStatus::NotInstalled
+            .label()
+            .contains("editing file only"));
+    }
+
+    #[tokio::test]
+    async fn test_file_only_never_runs_hyprctl() {
+        let mut hyprctl = HyprCtl::new_file_only();
+        assert_eq!(hyprctl.refresh_status().await, HyprlandStatus::FileOnly);
+        assert!(hyprctl.status().label().contains("file-only"));
+        assert!(!hyprctl.status().is_connected());
+
+        let error = hyprctl
+            .set_option("general:gaps_in", "5")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("file-only mode"));
+        assert!(hyprctl.dispatch("exec", "kitty").await.is_err());
+        assert!(hyprctl.reload_config().await.is_err());
+        assert!(!hyprctl.is_hyprland_running().await);
+    }
+
+    #[test]
+    fn test_socket_requests_match_hyprctl() {
+        assert_eq!(
+            ipc_request(&["-j", "getoption", "general:gaps_in"]).as_deref(),
+            Some("j/getoption general:gaps_in")
+        );
+        assert_eq!(
+            ipc_request(&["clients", "-j"]).as_deref(),
+            Some("j/clients")
+        );
+        assert_eq!(
+            ipc_request(&["keyword", "general:border_size", "3"]).as_deref(),
+            Some("keyword general:border_size 3")
+        );
+        assert_eq!(
+            ipc_request(&["dispatch", "exec", "kitty"]).as_deref(),
+            Some("dispatch exec kitty")
+        );
+        // Everything else still runs hyprctl
+        assert_eq!(ipc_request(&["--batch", "keyword a 1"]), None);
+        assert_eq!(ipc_request(&["reload"]), None);
+        assert_eq!(ipc_request(&[]), None);
+
+        assert_eq!(
+            ipc_socket_candidates(Some(Path::new("/run/user/1000")), "abc", SOCKET_NAME),
+            vec![
+                PathBuf::from("/run/user/1000/hypr/abc/.socket.sock"),
+                PathBuf::from("/tmp/hypr/abc/.socket.sock"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_config_reloads_are_picked_out_of_the_event_stream() {
+        assert!(is_config_change_event("configreloaded>>"));
+        assert!(is_config_change_event("configreloaded"));
+        assert!(!is_config_change_event("workspace>>2"));
+        assert!(!is_config_change_event("activewindow>>kitty,~"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join(EVENT_SOCKET_NAME);
+        let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        let mut changes = watch_config_changes_at(socket);
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+        stream
+            .write_all(b"workspace>>2\nconfigreloaded>>\nactivewindow>>kitty,~\n")
+            .await
+            .unwrap();
+        assert_eq!(changes.recv().await, Some(()));
+        stream.write_all(b"configreloaded>>\n").await.unwrap();
+        assert_eq!(changes.recv().await, Some(()));
+
+        // Hyprland going away ends the stream
+        drop(stream);
+        assert_eq!(changes.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_over_the_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join(".socket.sock");
+        let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        // Answers like Hyprland: one request per connection, then hang up
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for _ in 0..3 {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buffer = [0u8; 1024];
+                let read = stream.read(&mut buffer).await.unwrap();
+                let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+                let reply = match request.as_str() {
+                    "j/getoption general:gaps_in" => {
+                        r#"{"option": "general:gaps_in", "custom": "5 5 5 5", "set": true}"#
+                    }
+                    "keyword general:gaps_in 8" => "ok",
+                    _ => "config option <general:nope> does not exist.",
+                };
+                stream.write_all(reply.as_bytes()).await.unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+
+        let mut hyprctl = HyprCtl::new_disconnected();
+        hyprctl.socket_path = Some(socket);
+        let gaps = hyprctl.get_option_value("general:gaps_in").await.unwrap();
+        assert_eq!(gaps.value(), "5 5 5 5");
+        hyprctl.set_option("general:gaps_in", "8").await.unwrap();
+        let refused = hyprctl.set_option("general:nope", "1").await.unwrap_err();
+        assert!(refused.to_string().contains("does not exist"), "{refused}");
+
+        assert_eq!(
+            server.await.unwrap(),
+            vec![
+                "j/getoption general:gaps_in",
+                "keyword general:gaps_in 8",
+                "keyword general:nope 1",
+            ]
+        );
+    }
+
     #[tokio::test] 
     async fn test_timeout_functionality_helper() {
         let hyprctl = HyprCtl::new_disconnected();
@@ -1363,6 +2174,183 @@ mod tests {
         println!("Timeout functionality test result: {}", timeout_works);
     }
 
+    #[test]
+    fn test_option_value_from_json() {
+        let int =
+            OptionValue::from_json(r#"{"option": "decoration:rounding", "int": 10, "set": true}"#)
+                .unwrap();
+        assert_eq!(int.value(), "10");
+        assert!(int.set);
+
+        let float = OptionValue::from_json(
+            r#"{"option": "input:sensitivity", "float": -0.5, "set": false}"#,
+        )
+        .unwrap();
+        assert_eq!(float.value(), "-0.5");
+        assert!(!float.set);
+
+        // Values containing '=' or ':' used to be cut apart by the text parser
+        let string = OptionValue::from_json(
+            r#"{"option": "input:kb_options", "str": "grp:alt_shift_toggle,a=b", "set": true}"#,
+        )
+        .unwrap();
+        assert_eq!(string.value(), "grp:alt_shift_toggle,a=b");
+
+        let custom = OptionValue::from_json(
+            r#"{"option": "general:gaps_out", "custom": "20 20 20 20", "set": true}"#,
+        )
+        .unwrap();
+        assert_eq!(custom.value(), "20 20 20 20");
+
+        assert!(OptionValue::from_json("no such option").is_err());
+    }
+
+    #[test]
+    fn test_verify_output_errors() {
+        let path = std::path::Path::new("/tmp/.r-hyprconfig-verify-1.conf");
+        let output = "[LOG] Hyprland startup\n\
+            ======== Config parsing result:\n\
+            \n\
+            Config error in file /tmp/.r-hyprconfig-verify-1.conf at line 12: Invalid dispatcher, requested \"foo\" does not exist\n\
+            Config error in file /home/me/.config/hypr/binds.conf at line 3: config option <gaps_on> does not exist.\n";
+        assert_eq!(
+            parse_verify_output(output, path),
+            vec![
+                "line 12: Invalid dispatcher, requested \"foo\" does not exist".to_string(),
+                "Config error in file /home/me/.config/hypr/binds.conf at line 3: config option <gaps_on> does not exist.".to_string(),
+            ]
+        );
+
+        let ok = "[LOG] Hyprland startup\n======== Config parsing result:\n\nconfig ok\n";
+        assert!(parse_verify_output(ok, path).is_empty());
+    }
+
+    #[test]
+    fn test_hyprland_version_and_compatibility() {
+        let current = "Hyprland 0.50.1 built from branch v0.50.1 at commit 4e242d0 (version: bump to v0.50.1).\n\
+                       Date: Fri Jul 18 2025\nTag: v0.50.1, commits: 6461\nbuilt against:\n aquamarine 0.9.2";
+        assert_eq!(
+            HyprlandVersion::parse(current),
+            Some(HyprlandVersion {
+                major: 0,
+                minor: 50,
+                patch: 1
+            })
+        );
+        let old = "Hyprland, built from branch main at commit 12f9a0d0b93f691d4d9923716557154d74777b0a dirty\n\
+                   Date: Sat Dec 14 2024\nTag: v0.45.2-b-12-g12f9a0d0, commits: 5450";
+        assert_eq!(
+            HyprlandVersion::parse(old),
+            Some(HyprlandVersion {
+                major: 0,
+                minor: 45,
+                patch: 2
+            })
+        );
+        assert_eq!(
+            HyprlandVersion::parse("Hyprland, built from branch main"),
+            None
+        );
+
+        assert_eq!(
+            hyprland_version_line(Some(current)),
+            "Hyprland: 0.50.1 (supported)"
+        );
+        assert_eq!(
+            hyprland_version_line(Some(old)),
+            "Hyprland: 0.45.2 (unsupported, r-hyprconfig needs 0.50.1 or later)"
+        );
+        assert_eq!(
+            hyprland_version_line(None),
+            "Hyprland: not detected (supported: 0.50.1 or later)"
+        );
+    }
+
+    #[test]
+    fn test_config_errors_after_reload() {
+        let output = "Config error in file /home/me/.config/hypr/hyprland.conf at line 4: config option <gaps_on> does not exist.\n\
+            Config error in file /home/me/.config/hypr/binds.conf at line 9: Invalid dispatcher\n";
+        assert_eq!(parse_config_errors(output).len(), 2);
+        assert!(parse_config_errors(output)[1].ends_with("Invalid dispatcher"));
+        assert!(parse_config_errors("\n").is_empty());
+    }
+
+    #[test]
+    fn test_clients_from_json() {
+        let json = r#"[
+            {"address": "0x55d1a0c0", "mapped": true, "workspace": {"id": 1, "name": "1"},
+             "class": "kitty", "title": "~/src", "initialClass": "kitty", "pid": 4242},
+            {"address": "0x55d1b0d0", "class": "", "title": "Picture-in-Picture"}
+        ]"#;
+        assert_eq!(
+            HyprlandClient::list_from_json(json).unwrap(),
+            vec![
+                HyprlandClient {
+                    class: "kitty".to_string(),
+                    title: "~/src".to_string(),
+                    address: "0x55d1a0c0".to_string(),
+                    initial_class: "kitty".to_string(),
+                    workspace: ClientWorkspace {
+                        id: 1,
+                        name: "1".to_string(),
+                    },
+                    ..HyprlandClient::default()
+                },
+                HyprlandClient {
+                    class: String::new(),
+                    title: "Picture-in-Picture".to_string(),
+                    address: "0x55d1b0d0".to_string(),
+                    ..HyprlandClient::default()
+                },
+            ]
+        );
+        assert!(HyprlandClient::list_from_json("[]").unwrap().is_empty());
+        assert!(HyprlandClient::list_from_json("no clients").is_err());
+    }
+
+    #[test]
+    fn test_active_window_from_json() {
+        let json = r#"{"address": "0x55d1a0c0", "class": "firefox", "title": "Mozilla Firefox",
+                       "workspace": {"id": 2, "name": "2"}, "floating": false}"#;
+        assert_eq!(
+            HyprlandClient::active_from_json(json).unwrap(),
+            Some(HyprlandClient {
+                class: "firefox".to_string(),
+                title: "Mozilla Firefox".to_string(),
+                address: "0x55d1a0c0".to_string(),
+                workspace: ClientWorkspace {
+                    id: 2,
+                    name: "2".to_string(),
+                },
+                ..HyprlandClient::default()
+            })
+        );
+        // Nothing focused, such as an empty workspace
+        assert_eq!(HyprlandClient::active_from_json("{}").unwrap(), None);
+        assert_eq!(HyprlandClient::active_from_json("\n").unwrap(), None);
+        assert!(HyprlandClient::active_from_json("Invalid").is_err());
+    }
+
+    #[test]
+    fn test_dispatch_replies() {
+        assert!(check_dispatch_reply("exec", "ok\n").is_ok());
+        assert_eq!(
+            check_dispatch_reply(
+                "exce",
+                "Invalid dispatcher, requested \"exce\" does not exist"
+            )
+            .unwrap_err()
+            .to_string(),
+            "'exce' is not a Hyprland dispatcher"
+        );
+        assert_eq!(
+            check_dispatch_reply("workspace", "Invalid workspace")
+                .unwrap_err()
+                .to_string(),
+            "hyprctl dispatch workspace failed: Invalid workspace"
+        );
+    }
+
     #[test]
     fn test_error_types() {
         // Test that timeout errors are properly formatted
@@ -1377,6 +2365,44 @@ mod tests {
         assert!(error_string.contains("hyprctl test"));
     }
 
+    #[test]
+    fn test_keyword_batch_command() {
+        let mut options = HashMap::new();
+        options.insert("general:gaps_out".to_string(), "20".to_string());
+        options.insert("general:gaps_in".to_string(), "5".to_string());
+        options.insert(
+            "general:col.active_border".to_string(),
+            "rgba(ee1100ff) rgba(1100eeff) 45deg".to_string(),
+        );
+        options.insert("misc:splash_text".to_string(), "a;b".to_string());
+
+        let batch = KeywordBatch::from_options(&options);
+        assert_eq!(
+            batch.command,
+            "keyword general:col.active_border rgba(ee1100ff) rgba(1100eeff) 45deg ; \
+             keyword general:gaps_in 5 ; keyword general:gaps_out 20"
+        );
+        assert_eq!(batch.keys.len(), 3);
+        // Values containing `;` would split the batch, so they go on their own
+        assert_eq!(
+            batch.unbatched,
+            vec![("misc:splash_text".to_string(), "a;b".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_keyword_batch_reply() {
+        let mut options = HashMap::new();
+        options.insert("general:gaps_in".to_string(), "5".to_string());
+        options.insert("general:gaps_out".to_string(), "20".to_string());
+        let batch = KeywordBatch::from_options(&options);
+
+        assert!(batch.all_ok("okok"));
+        assert!(batch.all_ok("ok\n\nok\n"));
+        assert!(!batch.all_ok("ok"));
+        assert!(!batch.all_ok("ok\nconfig option <general:gaps_out> does not exist."));
+    }
+
     #[tokio::test]
     async fn test_cache_with_timeout() {
         let hyprctl = HyprCtl::new_disconnected();
@@ -0,0 +1,249 @@
+// Config downloads from a single URL
+// Raw links from gists, pastebin or raw.githubusercontent.com are fetched
+// with a size limit and a timeout. The format is detected from the text, since
+// raw links often have no extension or a misleading one.
+
+use anyhow::Result;
+use reqwest::header::CONTENT_TYPE;
+use std::time::Duration;
+use url::Url;
+
+use super::formats::{ConfigFormat, StructuredConfig};
+use crate::nixos::parser::HyprlandNixSettings;
+
+/// A hyprland.conf is a few hundred lines, anything near this is not a config
+pub const MAX_DOWNLOAD_SIZE: usize = 1024 * 1024;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A config downloaded from a URL
+#[derive(Debug, Clone)]
+pub struct UrlConfig {
+    pub url: String,
+    pub format: ConfigFormat,
+    pub content: String,
+}
+
+impl UrlConfig {
+    /// Read the settings with the parser for the detected format
+    pub fn settings(&self) -> Result<HyprlandNixSettings> {
+        match self.format {
+            ConfigFormat::HyprlandConf => {
+                Ok(HyprlandNixSettings::from_hyprland_conf(&self.content))
+            }
+            ref format => {
+                let config = StructuredConfig::parse(&self.content, format)?;
+                Ok(HyprlandNixSettings::from_structured(&config))
+            }
+        }
+    }
+}
+
+/// Turn a link to a page showing a file into the link to the file itself:
+/// GitHub `blob` pages, gists and pastebin pastes
+pub fn raw_url(input: &str) -> Result<Url> {
+    let input = input.trim();
+    let mut url = Url::parse(input)
+        .or_else(|_| Url::parse(&format!("https://{input}")))
+        .map_err(|_| anyhow::anyhow!("{input} is not a URL"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!("Only http and https URLs can be downloaded");
+    }
+
+    let segments: Vec<String> = url
+        .path_segments()
+        .map(|segments| {
+            segments
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    match (url.host_str(), segments.as_slice()) {
+        (Some("github.com" | "www.github.com"), [owner, repo, blob, rest @ ..])
+            if blob == "blob" && !rest.is_empty() =>
+        {
+            url = Url::parse(&format!(
+                "https://raw.githubusercontent.com/{owner}/{repo}/{}",
+                rest.join("/")
+            ))?;
+        }
+        (Some("gist.github.com"), [user, id]) => {
+            url = Url::parse(&format!("https://gist.github.com/{user}/{id}/raw"))?;
+        }
+        (Some("pastebin.com"), [id]) => {
+            url = Url::parse(&format!("https://pastebin.com/raw/{id}"))?;
+        }
+        _ => {}
+    }
+    Ok(url)
+}
+
+/// Whether a response with this content type can hold a config. Many hosts
+/// serve raw files as octet-stream, so those are checked by their bytes.
+fn is_text_content_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    (mime.starts_with("text/") && mime != "text/html")
+        || matches!(
+            mime.as_str(),
+            "application/json"
+                | "application/toml"
+                | "application/x-toml"
+                | "application/yaml"
+                | "application/x-yaml"
+                | "application/octet-stream"
+        )
+}
+
+/// The body as text, refusing anything with NUL bytes or invalid UTF-8
+fn decode_text(body: Vec<u8>) -> Result<String> {
+    if body.contains(&0) {
+        anyhow::bail!("The download is a binary file, not a config");
+    }
+    String::from_utf8(body).map_err(|_| anyhow::anyhow!("The download is not UTF-8 text"))
+}
+
+fn request_error(url: &Url, error: reqwest::Error) -> anyhow::Error {
+    if error.is_timeout() {
+        anyhow::anyhow!(
+            "{url} did not respond within {} seconds",
+            DOWNLOAD_TIMEOUT.as_secs()
+        )
+    } else {
+        anyhow::anyhow!("Failed to download {url}: {error}")
+    }
+}
+
+/// Download the config at `input` and detect its format
+pub async fn download_config(input: &str) -> Result<UrlConfig> {
+    let url = raw_url(input)?;
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("r-hyprconfig/", env!("CARGO_PKG_VERSION")))
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()?;
+    let mut response = client
+        .get(url.clone())
+        .send()
+        .await
+        .map_err(|e| request_error(&url, e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        anyhow::bail!("{url} returned HTTP {status}");
+    }
+    if let Some(content_type) = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    {
+        if content_type.starts_with("text/html") {
+            anyhow::bail!("{url} is a web page, not a config file. Use the link to the raw file.");
+        }
+        if !is_text_content_type(content_type) {
+            anyhow::bail!("{url} is {content_type}, not a text config file");
+        }
+    }
+    let too_large = || {
+        anyhow::anyhow!(
+            "{url} is larger than {} KiB, too large for a config",
+            MAX_DOWNLOAD_SIZE / 1024
+        )
+    };
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_DOWNLOAD_SIZE as u64)
+    {
+        return Err(too_large());
+    }
+
+    // The length header can be missing or wrong, so count while reading
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| request_error(&url, e))? {
+        body.extend_from_slice(&chunk);
+        if body.len() > MAX_DOWNLOAD_SIZE {
+            return Err(too_large());
+        }
+    }
+
+    let content = decode_text(body)?;
+    let format = ConfigFormat::from_content(&content).ok_or_else(|| {
+        anyhow::anyhow!("{url} doesn't look like a hyprland.conf, JSON, TOML or YAML config")
+    })?;
+    Ok(UrlConfig {
+        url: url.to_string(),
+        format,
+        content,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_links_become_raw_links() {
+        let raw = |input: &str| raw_url(input).unwrap().to_string();
+        assert_eq!(
+            raw("https://github.com/user/dotfiles/blob/main/hypr/hyprland.conf"),
+            "https://raw.githubusercontent.com/user/dotfiles/main/hypr/hyprland.conf"
+        );
+        assert_eq!(
+            raw("https://gist.github.com/user/0123abcd"),
+            "https://gist.github.com/user/0123abcd/raw"
+        );
+        assert_eq!(
+            raw("pastebin.com/AbCdEf12"),
+            "https://pastebin.com/raw/AbCdEf12"
+        );
+        assert_eq!(
+            raw("https://pastebin.com/raw/AbCdEf12"),
+            "https://pastebin.com/raw/AbCdEf12"
+        );
+        assert!(raw_url("ftp://example.com/hyprland.conf").is_err());
+    }
+
+    #[test]
+    fn test_only_text_responses_are_accepted() {
+        assert!(is_text_content_type("text/plain; charset=utf-8"));
+        assert!(is_text_content_type("application/json"));
+        assert!(is_text_content_type("application/octet-stream"));
+        assert!(!is_text_content_type("text/html; charset=utf-8"));
+        assert!(!is_text_content_type("image/png"));
+        assert!(!is_text_content_type("application/zip"));
+
+        assert!(decode_text(b"bind = SUPER, Q, killactive".to_vec()).is_ok());
+        assert!(decode_text(vec![0x89, b'P', b'N', b'G', 0, 0]).is_err());
+        assert!(decode_text(vec![0xff, 0xfe, b'a']).is_err());
+    }
+
+    #[test]
+    fn test_settings_come_from_the_matching_parser() {
+        let conf = UrlConfig {
+            url: "https://example.com/raw".to_string(),
+            format: ConfigFormat::HyprlandConf,
+            content: "general {\n    gaps_in = 4\n}\nbind = SUPER, Q, killactive\n".to_string(),
+        };
+        let settings = conf.settings().unwrap();
+        assert_eq!(
+            settings.options,
+            vec![("general:gaps_in".to_string(), "4".to_string())]
+        );
+        assert_eq!(settings.lines, vec!["bind = SUPER, Q, killactive"]);
+
+        let mut config = StructuredConfig::new("Shared");
+        config.general.gaps_out = Some(12);
+        let json = UrlConfig {
+            url: "https://example.com/raw".to_string(),
+            format: ConfigFormat::Json,
+            content: serde_json::to_string(&config).unwrap(),
+        };
+        assert_eq!(
+            json.settings().unwrap().options,
+            vec![("general:gaps_out".to_string(), "12".to_string())]
+        );
+    }
+}
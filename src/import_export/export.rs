@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::formats::{
     AnimationSettings, ConfigFormat, DecorationSettings, GeneralSettings, GestureSettings,
@@ -66,15 +66,7 @@ impl ConfigExporter {
         let filename = format!("{}.{}", name, format.extension());
         let output_path = self.output_dir.join(filename);
 
-        let content = match format {
-            ConfigFormat::HyprlandConf => self.to_hyprland_conf(config)?,
-            ConfigFormat::Json => self.to_json(config)?,
-            ConfigFormat::Toml => self.to_toml(config)?,
-            ConfigFormat::Yaml => self.to_yaml(config)?,
-            ConfigFormat::RHyprConfig => self.to_rhypr_config(config)?,
-            ConfigFormat::NixHomeManager => self.to_nix_home_manager(config)?,
-            ConfigFormat::NixSystem => self.to_nix_system(config)?,
-        };
+        let content = self.render_structured_config(config, &format)?;
 
         std::fs::write(&output_path, content)
             .with_context(|| format!("Failed to write config to: {:?}", output_path))?;
@@ -82,6 +74,53 @@ impl ConfigExporter {
         Ok(output_path)
     }
 
+    /// Export current system configuration to an explicit file path
+    pub async fn export_current_config_to_path(
+        &self,
+        format: ConfigFormat,
+        hyprctl: &crate::hyprctl::HyprCtl,
+        output_path: &Path,
+    ) -> Result<()> {
+        let content = self.render_current_config(format, hyprctl).await?;
+
+        if let Some(parent) = output_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create output directory: {:?}", parent))?;
+        }
+
+        std::fs::write(output_path, content)
+            .with_context(|| format!("Failed to write config to: {:?}", output_path))?;
+
+        Ok(())
+    }
+
+    /// Render the current system configuration without writing it anywhere
+    pub async fn render_current_config(
+        &self,
+        format: ConfigFormat,
+        hyprctl: &crate::hyprctl::HyprCtl,
+    ) -> Result<String> {
+        let config = self.extract_current_config(hyprctl).await?;
+        self.render_structured_config(&config, &format)
+    }
+
+    /// Render structured configuration in the specified format
+    pub fn render_structured_config(
+        &self,
+        config: &StructuredConfig,
+        format: &ConfigFormat,
+    ) -> Result<String> {
+        match format {
+            ConfigFormat::HyprlandConf => self.to_hyprland_conf(config),
+            ConfigFormat::Json => self.to_json(config),
+            ConfigFormat::Toml => self.to_toml(config),
+            ConfigFormat::Yaml => self.to_yaml(config),
+            ConfigFormat::RHyprConfig => self.to_rhypr_config(config),
+            ConfigFormat::NixHomeManager => self.to_nix_home_manager(config),
+            ConfigFormat::NixSystem => self.to_nix_system(config),
+        }
+    }
+
     /// Convert Hyprland config to structured format
     fn convert_to_structured(
         &self,
@@ -334,45 +373,19 @@ impl ConfigExporter {
             conf.push_str(&format!("\nanimations:enabled = {}\n", enabled));
         }
 
-        // Keybinds
-        if !config.keybinds.is_empty() {
-            conf.push_str("\n# Keybinds\n");
-            for keybind in &config.keybinds {
-                let modifiers = if keybind.modifiers.is_empty() {
-                    String::new()
-                } else {
-                    format!("{}, ", keybind.modifiers.join(" + "))
-                };
-
-                let args = if let Some(ref args) = keybind.args {
-                    format!(", {}", args)
-                } else {
-                    String::new()
-                };
-
-                conf.push_str(&format!(
-                    "{} = {}{}, {}{}\n",
-                    keybind.bind_type, modifiers, keybind.key, keybind.dispatcher, args
-                ));
-            }
-        }
-
-        // Window rules
-        if !config.window_rules.is_empty() {
-            conf.push_str("\n# Window rules\n");
-            for rule in &config.window_rules {
-                conf.push_str(&format!(
-                    "windowrule = {}, {}\n",
-                    rule.rule, rule.window_identifier
-                ));
-            }
-        }
-
-        // Layer rules
-        if !config.layer_rules.is_empty() {
-            conf.push_str("\n# Layer rules\n");
-            for rule in &config.layer_rules {
-                conf.push_str(&format!("layerrule = {}, {}\n", rule.rule, rule.layer));
+        // Repeated items, written the same way the import reads them back
+        let sections = [
+            ("# Keybinds", config.keybind_lines()),
+            ("# Window rules", config.window_rule_lines()),
+            ("# Layer rules", config.layer_rule_lines()),
+        ];
+        for (header, lines) in sections {
+            if !lines.is_empty() {
+                conf.push_str(&format!("\n{}\n", header));
+                for line in lines {
+                    conf.push_str(&line);
+                    conf.push('\n');
+                }
             }
         }
 
@@ -384,11 +397,11 @@ impl ConfigExporter {
     }
 
     fn to_toml(&self, config: &StructuredConfig) -> Result<String> {
-        toml::to_string_pretty(config).context("Failed to serialize config to TOML")
+        config.to_toml()
     }
 
     fn to_yaml(&self, config: &StructuredConfig) -> Result<String> {
-        serde_yaml::to_string(config).context("Failed to serialize config to YAML")
+        config.to_yaml()
     }
 
     fn to_rhypr_config(&self, config: &StructuredConfig) -> Result<String> {
@@ -538,6 +551,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_render_structured_config_matches_export() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let exporter = ConfigExporter::with_output_dir(temp_dir.path().to_path_buf());
+
+        let config = StructuredConfig::new("Test Config");
+        let output_path = exporter.export_structured_config(&config, "test", ConfigFormat::Yaml)?;
+        let rendered = exporter.render_structured_config(&config, &ConfigFormat::Yaml)?;
+
+        assert_eq!(std::fs::read_to_string(&output_path)?, rendered);
+
+        Ok(())
+    }
+
     #[test]
     fn test_export_structured_config_json() -> Result<()> {
         let temp_dir = TempDir::new()?;
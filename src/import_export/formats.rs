@@ -1,8 +1,10 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
+use super::schema::{Field, SECTIONS};
+
 // Allow dead code for format functionality that will be used by TUI in future
 #[allow(dead_code)]
 /// Supported configuration formats for import/export
@@ -82,9 +84,85 @@ impl ConfigFormat {
             ConfigFormat::RHyprConfig => "application/x-rhyprconfig",
         }
     }
+
+    /// Detect the format from the text itself, for downloads whose URL says
+    /// nothing about what they hold. JSON and TOML are tried before
+    /// hyprland.conf, and YAML last since it accepts almost any text.
+    pub fn from_content(content: &str) -> Option<Self> {
+        let trimmed = content.trim_start();
+        if trimmed.is_empty() {
+            return None;
+        }
+        if trimmed.starts_with("# r-hyprconfig format") {
+            return Some(ConfigFormat::RHyprConfig);
+        }
+        if trimmed.starts_with(['{', '['])
+            && serde_json::from_str::<serde_json::Value>(content).is_ok()
+        {
+            return Some(ConfigFormat::Json);
+        }
+        if toml::from_str::<toml::Table>(content).is_ok_and(|table| !table.is_empty()) {
+            return Some(ConfigFormat::Toml);
+        }
+        if looks_like_hyprland_conf(content) {
+            return Some(ConfigFormat::HyprlandConf);
+        }
+        if matches!(
+            serde_yaml::from_str::<serde_yaml::Value>(content),
+            Ok(serde_yaml::Value::Mapping(_))
+        ) {
+            return Some(ConfigFormat::Yaml);
+        }
+        None
+    }
 }
 
-/// Structured configuration data for import/export
+fn remove_nulls(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            mapping.retain(|_, value| !value.is_null());
+            mapping.values_mut().for_each(remove_nulls);
+        }
+        serde_yaml::Value::Sequence(items) => items.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+/// Most lines are `keyword = value`, `category {` or `}`
+fn looks_like_hyprland_conf(content: &str) -> bool {
+    let lines: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    let is_keyword = |word: &str| {
+        !word.is_empty()
+            && word
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':' | '.' | '$'))
+    };
+    let conf_lines = lines
+        .iter()
+        .filter(|line| {
+            **line == "}"
+                || line
+                    .strip_suffix('{')
+                    .is_some_and(|category| is_keyword(category.trim()))
+                || line
+                    .split_once('=')
+                    .is_some_and(|(keyword, _)| is_keyword(keyword.trim()))
+        })
+        .count();
+    conf_lines > 0 && conf_lines * 2 >= lines.len()
+}
+
+/// Structured configuration data for import/export.
+///
+/// A config holds two kinds of data. Scalar options set one value each and
+/// live in the per-section settings, with anything not modelled there in
+/// `custom_settings` under its `section:option` key. Repeated items are the
+/// keyword lines a hyprland.conf can have any number of, kept in order in
+/// `keybinds`, `window_rules` and `layer_rules`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StructuredConfig {
     pub metadata: ConfigMetadata,
@@ -93,10 +171,14 @@ pub struct StructuredConfig {
     pub decoration: DecorationSettings,
     pub animations: AnimationSettings,
     pub gestures: GestureSettings,
+    /// `bind` lines, including the `bindm`, `binde` and other variants
     pub keybinds: Vec<KeybindEntry>,
+    /// `windowrule` lines
     pub window_rules: Vec<WindowRuleEntry>,
+    /// `layerrule` lines
     pub layer_rules: Vec<LayerRuleEntry>,
     pub misc: MiscSettings,
+    /// Scalar options without a typed field, keyed like `misc:vrr`
     pub custom_settings: HashMap<String, String>,
 }
 
@@ -157,7 +239,9 @@ pub struct DecorationSettings {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnimationSettings {
     pub enabled: Option<bool>,
+    #[serde(default)]
     pub beziers: Vec<String>,
+    #[serde(default)]
     pub animations: Vec<String>,
 }
 
@@ -212,6 +296,82 @@ pub struct LayerRuleEntry {
     pub description: Option<String>,
 }
 
+/// The TOML and YAML layout of a [`StructuredConfig`]. Each settings section
+/// is a table such as `[general]`, options without a typed field go in
+/// `[options]`, and repeated items are arrays of tables named after their
+/// hyprland.conf keyword, so every `bind = ...` line becomes a `[[bind]]`.
+/// The names used before this layout are accepted as aliases.
+#[derive(Serialize, Deserialize)]
+struct ConfigDocument {
+    #[serde(default)]
+    metadata: ConfigMetadata,
+    #[serde(default)]
+    general: GeneralSettings,
+    #[serde(default)]
+    input: InputSettings,
+    #[serde(default)]
+    decoration: DecorationSettings,
+    #[serde(default)]
+    animations: AnimationSettings,
+    #[serde(default)]
+    gestures: GestureSettings,
+    #[serde(default)]
+    misc: MiscSettings,
+    /// Sorted, so exporting the same config always gives the same text
+    #[serde(
+        default,
+        alias = "custom_settings",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    options: BTreeMap<String, String>,
+    #[serde(default, alias = "keybinds", skip_serializing_if = "Vec::is_empty")]
+    bind: Vec<KeybindEntry>,
+    #[serde(default, alias = "window_rules", skip_serializing_if = "Vec::is_empty")]
+    windowrule: Vec<WindowRuleEntry>,
+    #[serde(default, alias = "layer_rules", skip_serializing_if = "Vec::is_empty")]
+    layerrule: Vec<LayerRuleEntry>,
+}
+
+impl From<&StructuredConfig> for ConfigDocument {
+    fn from(config: &StructuredConfig) -> Self {
+        Self {
+            metadata: config.metadata.clone(),
+            general: config.general.clone(),
+            input: config.input.clone(),
+            decoration: config.decoration.clone(),
+            animations: config.animations.clone(),
+            gestures: config.gestures.clone(),
+            misc: config.misc.clone(),
+            options: config
+                .custom_settings
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            bind: config.keybinds.clone(),
+            windowrule: config.window_rules.clone(),
+            layerrule: config.layer_rules.clone(),
+        }
+    }
+}
+
+impl From<ConfigDocument> for StructuredConfig {
+    fn from(document: ConfigDocument) -> Self {
+        Self {
+            metadata: document.metadata,
+            general: document.general,
+            input: document.input,
+            decoration: document.decoration,
+            animations: document.animations,
+            gestures: document.gestures,
+            keybinds: document.bind,
+            window_rules: document.windowrule,
+            layer_rules: document.layerrule,
+            misc: document.misc,
+            custom_settings: document.options.into_iter().collect(),
+        }
+    }
+}
+
 impl Default for ConfigMetadata {
     fn default() -> Self {
         let now = chrono::Utc::now();
@@ -343,6 +503,47 @@ impl StructuredConfig {
         }
     }
 
+    /// Read a config saved in one of the structured formats. hyprland.conf
+    /// and Nix files need a parser of their own.
+    pub fn parse(content: &str, format: &ConfigFormat) -> Result<Self> {
+        match format {
+            ConfigFormat::Json => {
+                serde_json::from_str(content).context("Failed to parse JSON configuration")
+            }
+            ConfigFormat::Toml => toml::from_str::<ConfigDocument>(content)
+                .map(StructuredConfig::from)
+                .context("Failed to parse TOML configuration"),
+            ConfigFormat::Yaml => serde_yaml::from_str::<ConfigDocument>(content)
+                .map(StructuredConfig::from)
+                .context("Failed to parse YAML configuration"),
+            ConfigFormat::RHyprConfig => {
+                // Skip the metadata comments and parse the JSON content
+                let json_start = content
+                    .find('{')
+                    .context("Invalid r-hyprconfig format: no JSON content found")?;
+                serde_json::from_str(&content[json_start..])
+                    .context("Failed to parse r-hyprconfig JSON content")
+            }
+            _ => anyhow::bail!("{} is not a structured format", format.description()),
+        }
+    }
+
+    /// Write the config in the TOML layout described on [`ConfigDocument`]
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(&ConfigDocument::from(self))
+            .context("Failed to serialize config to TOML")
+    }
+
+    /// Write the config in the same layout as [`Self::to_toml`]. Unset
+    /// options are left out like in TOML, and serde_yaml writes every value
+    /// in full, so the output has no anchors or aliases to follow.
+    pub fn to_yaml(&self) -> Result<String> {
+        let mut value = serde_yaml::to_value(ConfigDocument::from(self))
+            .context("Failed to serialize config to YAML")?;
+        remove_nulls(&mut value);
+        serde_yaml::to_string(&value).context("Failed to serialize config to YAML")
+    }
+
     /// Add a tag to the configuration
     pub fn add_tag(&mut self, tag: &str) {
         if !self.metadata.tags.contains(&tag.to_string()) {
@@ -399,6 +600,65 @@ impl StructuredConfig {
         Ok(())
     }
 
+    /// Flatten the typed settings into `section:option` keys as used by hyprctl
+    pub fn to_hyprland_options(&self) -> HashMap<String, String> {
+        // Every section is plain data, so serializing can't fail
+        let typed = serde_json::to_value(self).unwrap_or_default();
+
+        let mut options = HashMap::new();
+        for (section, fields) in SECTIONS {
+            for (name, key, field) in *fields {
+                if let Some(value) = option_value(&typed[section][name], *field) {
+                    options.insert(key.to_string(), value);
+                }
+            }
+        }
+
+        // Custom settings are already keyed the way Hyprland expects
+        for (key, value) in &self.custom_settings {
+            options.insert(key.clone(), value.clone());
+        }
+
+        options
+    }
+
+    /// Keybinds as hyprland.conf lines
+    pub fn keybind_lines(&self) -> Vec<String> {
+        self.keybinds
+            .iter()
+            .map(|bind| {
+                let args = match &bind.args {
+                    Some(args) => format!(", {args}"),
+                    None => String::new(),
+                };
+                format!(
+                    "{} = {}, {}, {}{}",
+                    bind.bind_type,
+                    bind.modifiers.join(" "),
+                    bind.key,
+                    bind.dispatcher,
+                    args
+                )
+            })
+            .collect()
+    }
+
+    /// Window rules as hyprland.conf lines
+    pub fn window_rule_lines(&self) -> Vec<String> {
+        self.window_rules
+            .iter()
+            .map(|rule| format!("windowrule = {}, {}", rule.rule, rule.window_identifier))
+            .collect()
+    }
+
+    /// Layer rules as hyprland.conf lines
+    pub fn layer_rule_lines(&self) -> Vec<String> {
+        self.layer_rules
+            .iter()
+            .map(|rule| format!("layerrule = {}, {}", rule.rule, rule.layer))
+            .collect()
+    }
+
     /// Get a summary of the configuration
     pub fn summary(&self) -> String {
         format!(
@@ -411,6 +671,18 @@ impl StructuredConfig {
     }
 }
 
+/// A typed setting as hyprctl takes it, or `None` when it's unset.
+/// Floats were `f32` before serializing, so they are written as one.
+fn option_value(value: &serde_json::Value, field: Field) -> Option<String> {
+    use serde_json::Value;
+    match (value, field) {
+        (Value::Null, _) => None,
+        (Value::String(text), _) => Some(text.clone()),
+        (Value::Number(number), Field::Float) => number.as_f64().map(|f| (f as f32).to_string()),
+        (value, _) => Some(value.to_string()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -588,4 +860,230 @@ mod tests {
             "NixOS Home Manager"
         );
     }
+
+    #[test]
+    fn test_structured_config_to_hyprland_lines() {
+        let mut config = StructuredConfig::new("Test");
+        config.general.gaps_in = Some(7);
+        config.decoration.blur_enabled = Some(false);
+        config
+            .custom_settings
+            .insert("misc:vrr".to_string(), "1".to_string());
+        config.keybinds.push(KeybindEntry {
+            bind_type: "bind".to_string(),
+            modifiers: vec!["SUPER".to_string(), "SHIFT".to_string()],
+            key: "Q".to_string(),
+            dispatcher: "exec".to_string(),
+            args: Some("kitty".to_string()),
+            description: None,
+        });
+        config.window_rules.push(WindowRuleEntry {
+            rule: "float".to_string(),
+            window_identifier: "^(pavucontrol)$".to_string(),
+            description: None,
+        });
+
+        let options = config.to_hyprland_options();
+        assert_eq!(options.get("general:gaps_in").map(String::as_str), Some("7"));
+        assert_eq!(
+            options.get("decoration:blur:enabled").map(String::as_str),
+            Some("false")
+        );
+        assert_eq!(options.get("misc:vrr").map(String::as_str), Some("1"));
+        assert!(!options.contains_key("general:gaps_out"));
+
+        assert_eq!(
+            config.keybind_lines(),
+            vec!["bind = SUPER SHIFT, Q, exec, kitty".to_string()]
+        );
+        assert_eq!(
+            config.window_rule_lines(),
+            vec!["windowrule = float, ^(pavucontrol)$".to_string()]
+        );
+        assert!(config.layer_rule_lines().is_empty());
+    }
+
+    #[test]
+    fn test_config_format_from_content() {
+        let conf = "# Hyprland\n$mod = SUPER\ngeneral {\n    gaps_in = 5\n}\n\
+            bind = $mod, Q, exec, kitty\nwindowrulev2 = float, class:^(pavucontrol)$\n";
+        assert_eq!(
+            ConfigFormat::from_content(conf),
+            Some(ConfigFormat::HyprlandConf)
+        );
+        assert_eq!(
+            ConfigFormat::from_content("{\"general\": {\"gaps_in\": 5}}"),
+            Some(ConfigFormat::Json)
+        );
+        assert_eq!(
+            ConfigFormat::from_content("[general]\ngaps_in = 5\n"),
+            Some(ConfigFormat::Toml)
+        );
+        assert_eq!(
+            ConfigFormat::from_content("general:\n  gaps_in: 5\nkeybinds: []\n"),
+            Some(ConfigFormat::Yaml)
+        );
+        assert_eq!(
+            ConfigFormat::from_content("# r-hyprconfig format\n# Name: Test\n\n{}"),
+            Some(ConfigFormat::RHyprConfig)
+        );
+        assert_eq!(ConfigFormat::from_content(""), None);
+        assert_eq!(
+            ConfigFormat::from_content("Just some notes about my setup."),
+            None
+        );
+    }
+
+    #[test]
+    fn test_structured_config_parse_round_trip() -> Result<()> {
+        let mut config = StructuredConfig::new("Shared");
+        config.general.gaps_in = Some(3);
+
+        let yaml = serde_yaml::to_string(&config)?;
+        let parsed = StructuredConfig::parse(&yaml, &ConfigFormat::Yaml)?;
+        assert_eq!(parsed.general.gaps_in, Some(3));
+
+        let toml = toml::to_string(&config)?;
+        let parsed = StructuredConfig::parse(&toml, &ConfigFormat::Toml)?;
+        assert_eq!(parsed.metadata.name, "Shared");
+
+        assert!(StructuredConfig::parse("gaps_in = 3", &ConfigFormat::HyprlandConf).is_err());
+        Ok(())
+    }
+
+    /// A config with every kind of item filled from `seed`, including
+    /// strings that need quoting or escaping in TOML
+    fn generated_config(seed: u64) -> StructuredConfig {
+        let mut state = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let mut next = move |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+        const TEXT: [&str; 6] = [
+            "kitty",
+            "rgba(33ccffee) rgba(00ff99ee) 45deg",
+            "^(pavucontrol|nm-connection-editor)$",
+            "notify-send \"hello # world\"",
+            "C:\\path with spaces",
+            "größe ✓",
+        ];
+
+        let mut config = StructuredConfig::new(TEXT[next(6) as usize]);
+        config.general.gaps_in = Some(next(40) as i32);
+        if next(2) == 0 {
+            config.general.col_active_border = Some(TEXT[next(6) as usize].to_string());
+        }
+        config.input.sensitivity = Some(next(20) as f32 / 10.0 - 1.0);
+        config.input.natural_scroll = Some(next(2) == 0);
+        config.decoration.dim_strength = Some(next(10) as f32 / 10.0);
+        config.animations.enabled = Some(next(2) == 0);
+        for i in 0..next(3) {
+            config
+                .animations
+                .beziers
+                .push(format!("curve{i}, 0.05, 0.9, 0.1, 1.05"));
+        }
+        for i in 0..next(4) {
+            config.custom_settings.insert(
+                format!("misc:option_{i}"),
+                TEXT[next(6) as usize].to_string(),
+            );
+        }
+        for i in 0..next(5) {
+            config.keybinds.push(KeybindEntry {
+                bind_type: ["bind", "binde", "bindm", "bindl"][next(4) as usize].to_string(),
+                modifiers: ["SUPER", "SUPER SHIFT", ""][..next(3) as usize + 1]
+                    .iter()
+                    .map(|m| m.to_string())
+                    .collect(),
+                key: format!("{i}"),
+                dispatcher: "exec".to_string(),
+                args: (next(2) == 0).then(|| TEXT[next(6) as usize].to_string()),
+                description: (next(2) == 0).then(|| "Launch".to_string()),
+            });
+        }
+        for _ in 0..next(4) {
+            config.window_rules.push(WindowRuleEntry {
+                rule: "float".to_string(),
+                window_identifier: TEXT[next(6) as usize].to_string(),
+                description: None,
+            });
+        }
+        for _ in 0..next(3) {
+            config.layer_rules.push(LayerRuleEntry {
+                rule: "blur".to_string(),
+                layer: "waybar".to_string(),
+                description: Some(TEXT[next(6) as usize].to_string()),
+            });
+        }
+        config
+    }
+
+    #[test]
+    fn test_toml_layout() -> Result<()> {
+        let mut config = generated_config(7);
+        config.keybinds.truncate(1);
+        config.window_rules.truncate(1);
+        config
+            .custom_settings
+            .insert("misc:vrr".to_string(), "1".to_string());
+        config.keybinds.push(KeybindEntry {
+            bind_type: "bind".to_string(),
+            modifiers: vec!["SUPER".to_string()],
+            key: "Q".to_string(),
+            dispatcher: "killactive".to_string(),
+            args: None,
+            description: None,
+        });
+        config.window_rules.push(WindowRuleEntry {
+            rule: "float".to_string(),
+            window_identifier: "^(pavucontrol)$".to_string(),
+            description: None,
+        });
+
+        let toml = config.to_toml()?;
+        let table: toml::Table = toml::from_str(&toml)?;
+        assert!(table["general"].is_table());
+        assert_eq!(table["options"]["misc:vrr"].as_str(), Some("1"));
+        assert!(table["bind"].is_array());
+        assert!(table["windowrule"].is_array());
+        assert!(!table.contains_key("keybinds"));
+        assert!(!table.contains_key("custom_settings"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_toml_export_import_export_is_stable() -> Result<()> {
+        for seed in 0..200 {
+            let config = generated_config(seed);
+            let exported = config.to_toml()?;
+            let imported = StructuredConfig::parse(&exported, &ConfigFormat::Toml)?;
+            assert_eq!(imported.to_toml()?, exported, "seed {seed}");
+
+            // Nothing is lost along the way
+            assert_eq!(
+                imported.keybind_lines(),
+                config.keybind_lines(),
+                "seed {seed}"
+            );
+            assert_eq!(imported.window_rule_lines(), config.window_rule_lines());
+            assert_eq!(imported.layer_rule_lines(), config.layer_rule_lines());
+            assert_eq!(imported.to_hyprland_options(), config.to_hyprland_options());
+            assert_eq!(imported.animations.beziers, config.animations.beziers);
+            let descriptions = |config: &StructuredConfig| -> Vec<Option<String>> {
+                config
+                    .keybinds
+                    .iter()
+                    .map(|b| b.description.clone())
+                    .collect()
+            };
+            assert_eq!(descriptions(&imported), descriptions(&config));
+            assert_eq!(imported.metadata.updated_at, config.metadata.updated_at);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,672 @@
+// GitHub repository access for imports, and gists for sharing exports
+// Files are read through the REST API rather than cloned, so only the config
+// files under the chosen folder are downloaded. A token in GITHUB_TOKEN or
+// GH_TOKEN gives access to private repositories and a higher rate limit, and
+// is required to create gists.
+
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderMap, ACCEPT};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+use url::Url;
+
+const API_BASE: &str = "https://api.github.com";
+
+/// Config folders are small, this keeps a wrong folder in a large monorepo
+/// from downloading hundreds of files
+pub const MAX_FILES: usize = 50;
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Configs of other Hypr tools that often sit next to hyprland.conf
+pub(super) const OTHER_TOOL_CONFIGS: [&str; 5] = [
+    "hyprlock.conf",
+    "hypridle.conf",
+    "hyprpaper.conf",
+    "hyprsunset.conf",
+    "xdph.conf",
+];
+
+/// A repository, with the branch and folder from a browser URL if given
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoSpec {
+    pub owner: String,
+    pub name: String,
+    pub branch: Option<String>,
+    pub subpath: Option<String>,
+}
+
+impl RepoSpec {
+    /// Parse `user/repo`, `github.com/user/repo`, an https or ssh clone URL,
+    /// or a browser URL like `https://github.com/user/repo/tree/main/hypr`
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim().trim_end_matches('/');
+        let rest = match input.strip_prefix("git@github.com:") {
+            Some(rest) => rest,
+            None => {
+                let without_scheme = input.split_once("://").map_or(input, |(_, rest)| rest);
+                let host_and_path = without_scheme
+                    .strip_prefix("www.")
+                    .unwrap_or(without_scheme);
+                match host_and_path.strip_prefix("github.com/") {
+                    Some(rest) => rest,
+                    None if input.contains("://")
+                        || host_and_path
+                            .split('/')
+                            .next()
+                            .is_some_and(|host| host.contains('.')) =>
+                    {
+                        anyhow::bail!("Only GitHub repositories can be imported: {input}")
+                    }
+                    None => host_and_path,
+                }
+            }
+        };
+
+        let mut segments = rest.split('/').filter(|segment| !segment.is_empty());
+        let owner = segments.next().unwrap_or_default();
+        let name = segments.next().unwrap_or_default().trim_end_matches(".git");
+        let valid = |part: &str| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        if !valid(owner) || !valid(name) {
+            anyhow::bail!("Expected a repository like user/repo, got '{input}'");
+        }
+
+        let (branch, subpath) = match segments.next() {
+            None => (None, None),
+            Some(kind @ ("tree" | "blob")) => {
+                let branch = segments.next().map(str::to_string);
+                let mut path: Vec<&str> = segments.collect();
+                // A blob URL points at a file, import the folder it is in
+                if kind == "blob" {
+                    path.pop();
+                }
+                let path = path.join("/");
+                (branch, (!path.is_empty()).then_some(path))
+            }
+            Some(other) => anyhow::bail!("Unexpected '{other}' in repository URL '{input}'"),
+        };
+
+        Ok(Self {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            branch,
+            subpath,
+        })
+    }
+
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+}
+
+/// A file in a repository tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoFile {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Config files downloaded from a repository, keyed by their path in it
+#[derive(Debug, Clone, Default)]
+pub struct RepoDownload {
+    pub files: BTreeMap<String, String>,
+    /// Files that were found but not downloaded, with the reason
+    pub skipped: Vec<String>,
+}
+
+impl RepoDownload {
+    /// The text to import and the files it was built from. The first
+    /// `hyprland.conf` is the main config, with the files it `source`s
+    /// spliced in where they are included. Without one, every config that
+    /// isn't for another Hypr tool is used.
+    pub fn combined_config(&self) -> (String, Vec<String>) {
+        let main = self
+            .files
+            .keys()
+            .filter(|path| file_name(path) == "hyprland.conf")
+            .min_by_key(|path| (path.matches('/').count(), path.len()));
+
+        let mut used = Vec::new();
+        let mut content = String::new();
+        match main {
+            Some(main) => {
+                let mut visited = HashSet::new();
+                self.splice(main, &mut visited, &mut used, &mut content);
+            }
+            None => {
+                for (path, file) in &self.files {
+                    if !OTHER_TOOL_CONFIGS.contains(&file_name(path)) {
+                        used.push(path.clone());
+                        content.push_str(file);
+                        content.push('\n');
+                    }
+                }
+            }
+        }
+        (content, used)
+    }
+
+    fn splice(
+        &self,
+        path: &str,
+        visited: &mut HashSet<String>,
+        used: &mut Vec<String>,
+        out: &mut String,
+    ) {
+        if !visited.insert(path.to_string()) {
+            return;
+        }
+        let Some(content) = self.files.get(path) else {
+            return;
+        };
+        used.push(path.to_string());
+        for line in content.lines() {
+            match crate::includes::parse_source_line(line) {
+                Some(source) => {
+                    for included in self.resolve_source(path, source) {
+                        self.splice(&included, visited, used, out);
+                    }
+                }
+                None => {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+        }
+    }
+
+    /// Downloaded files a `source = path` line in `from` refers to. Paths
+    /// under `~/.config/hypr` are looked up next to the main config, and a
+    /// `*` in the file name matches every file in that folder.
+    fn resolve_source(&self, from: &str, source: &str) -> Vec<String> {
+        let mut target = source.trim();
+        for prefix in ["~/", "$HOME/", "./"] {
+            target = target.strip_prefix(prefix).unwrap_or(target);
+        }
+        if let Some((_, inside)) = target.split_once(".config/hypr/") {
+            target = inside;
+        }
+        let dir = from.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let wanted = if dir.is_empty() {
+            target.to_string()
+        } else {
+            format!("{dir}/{target}")
+        };
+
+        let matches = |path: &str, wanted: &str| match wanted.split_once('*') {
+            Some((prefix, suffix)) => {
+                path.starts_with(prefix)
+                    && path.ends_with(suffix)
+                    && !path[prefix.len()..].contains('/')
+            }
+            None => path == wanted,
+        };
+        let found: Vec<String> = self
+            .files
+            .keys()
+            .filter(|path| matches(path, &wanted))
+            .cloned()
+            .collect();
+        if !found.is_empty() || target.contains('*') {
+            return found;
+        }
+        // Fall back to any file whose path ends the same way
+        self.files
+            .keys()
+            .filter(|path| path.ends_with(&format!("/{target}")))
+            .take(1)
+            .cloned()
+            .collect()
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// The `.conf` files in `files` that are inside `subpath`
+pub fn config_files_under(files: &[RepoFile], subpath: &str) -> Vec<RepoFile> {
+    let subpath = subpath.trim_matches('/');
+    files
+        .iter()
+        .filter(|file| file.path.ends_with(".conf"))
+        .filter(|file| {
+            subpath.is_empty()
+                || file.path == subpath
+                || file
+                    .path
+                    .strip_prefix(subpath)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Turn an unsuccessful response into an error that says what to do
+pub fn check_status(status: StatusCode, headers: &HeaderMap, authenticated: bool) -> Result<()> {
+    if status.is_success() {
+        return Ok(());
+    }
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && header("x-ratelimit-remaining") == Some("0"));
+    if rate_limited {
+        let reset = header("x-ratelimit-reset").and_then(|reset| reset.parse().ok());
+        anyhow::bail!(rate_limit_message(reset, authenticated));
+    }
+    match status {
+        StatusCode::UNAUTHORIZED => anyhow::bail!("GitHub rejected the token in GITHUB_TOKEN"),
+        StatusCode::NOT_FOUND if authenticated => {
+            anyhow::bail!("Repository, branch or folder not found")
+        }
+        StatusCode::NOT_FOUND => anyhow::bail!(
+            "Repository, branch or folder not found. Private repositories need a token in GITHUB_TOKEN."
+        ),
+        _ => anyhow::bail!("GitHub returned {status}"),
+    }
+}
+
+/// Like `check_status`, but a token without the gist scope gets 403 or 404
+/// from the gists endpoint rather than a hint about repositories
+fn check_gist_status(status: StatusCode, headers: &HeaderMap) -> Result<()> {
+    let rate_limited = headers
+        .get("x-ratelimit-remaining")
+        .is_some_and(|remaining| remaining == "0");
+    if matches!(status, StatusCode::FORBIDDEN | StatusCode::NOT_FOUND) && !rate_limited {
+        anyhow::bail!("The token in GITHUB_TOKEN can't create gists, give it the gist scope");
+    }
+    check_status(status, headers, true)
+}
+
+/// The request body for a gist holding a single file
+fn gist_body(filename: &str, content: &str, description: &str, public: bool) -> serde_json::Value {
+    serde_json::json!({
+        "description": description,
+        "public": public,
+        "files": { filename: { "content": content } },
+    })
+}
+
+fn rate_limit_message(reset: Option<i64>, authenticated: bool) -> String {
+    let when = reset
+        .and_then(|reset| chrono::DateTime::from_timestamp(reset, 0))
+        .map(|reset| {
+            format!(
+                ", try again at {}",
+                reset.with_timezone(&chrono::Local).format("%H:%M")
+            )
+        })
+        .unwrap_or_default();
+    let hint = if authenticated {
+        ""
+    } else {
+        ". Set GITHUB_TOKEN for a higher limit"
+    };
+    format!("GitHub's rate limit was reached{when}{hint}")
+}
+
+/// A small client for the parts of the GitHub API imports and sharing need
+pub struct GitHubClient {
+    client: reqwest::Client,
+    token: Option<String>,
+}
+
+impl GitHubClient {
+    pub fn new() -> Result<Self> {
+        let token = ["GITHUB_TOKEN", "GH_TOKEN"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|token| !token.trim().is_empty());
+        let client = reqwest::Client::builder()
+            // GitHub rejects requests without a user agent
+            .user_agent(concat!("r-hyprconfig/", env!("CARGO_PKG_VERSION")))
+            .timeout(Duration::from_secs(30))
+            .build()?;
+        Ok(Self { client, token })
+    }
+
+    fn endpoint(&self, repo: &RepoSpec, path: &[&str]) -> Result<Url> {
+        let mut url = Url::parse(API_BASE)?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("Invalid GitHub API URL"))?
+            .extend(["repos", &repo.owner, &repo.name])
+            .extend(path);
+        Ok(url)
+    }
+
+    async fn get(&self, url: Url, raw: bool) -> Result<reqwest::Response> {
+        let accept = if raw {
+            "application/vnd.github.raw"
+        } else {
+            "application/vnd.github+json"
+        };
+        let mut request = self.client.get(url).header(ACCEPT, accept);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.context("Could not reach GitHub")?;
+        check_status(response.status(), response.headers(), self.token.is_some())?;
+        Ok(response)
+    }
+
+    pub async fn default_branch(&self, repo: &RepoSpec) -> Result<String> {
+        #[derive(Deserialize)]
+        struct Repository {
+            default_branch: String,
+        }
+
+        let url = self.endpoint(repo, &[])?;
+        let repository: Repository = self.get(url, false).await?.json().await?;
+        Ok(repository.default_branch)
+    }
+
+    pub async fn branches(&self, repo: &RepoSpec) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct Branch {
+            name: String,
+        }
+
+        let mut url = self.endpoint(repo, &["branches"])?;
+        url.query_pairs_mut().append_pair("per_page", "100");
+        let branches: Vec<Branch> = self.get(url, false).await?.json().await?;
+        Ok(branches.into_iter().map(|branch| branch.name).collect())
+    }
+
+    /// Every file in the repository at `reference`
+    pub async fn files(&self, repo: &RepoSpec, reference: &str) -> Result<Vec<RepoFile>> {
+        #[derive(Deserialize)]
+        struct Tree {
+            tree: Vec<TreeEntry>,
+        }
+        #[derive(Deserialize)]
+        struct TreeEntry {
+            path: String,
+            #[serde(rename = "type")]
+            kind: String,
+            size: Option<u64>,
+        }
+
+        let mut url = self.endpoint(repo, &["git", "trees", reference])?;
+        url.query_pairs_mut().append_pair("recursive", "1");
+        let tree: Tree = self.get(url, false).await?.json().await?;
+        Ok(tree
+            .tree
+            .into_iter()
+            .filter(|entry| entry.kind == "blob")
+            .map(|entry| RepoFile {
+                path: entry.path,
+                size: entry.size.unwrap_or(0),
+            })
+            .collect())
+    }
+
+    pub async fn file_content(
+        &self,
+        repo: &RepoSpec,
+        reference: &str,
+        path: &str,
+    ) -> Result<String> {
+        let mut segments = vec!["contents"];
+        segments.extend(path.split('/'));
+        let mut url = self.endpoint(repo, &segments)?;
+        url.query_pairs_mut().append_pair("ref", reference);
+        self.get(url, true)
+            .await?
+            .text()
+            .await
+            .with_context(|| format!("Failed to download {path}"))
+    }
+
+    /// Download the `.conf` files under `subpath`. `progress` is called with
+    /// the number of the file about to be downloaded, the total and its path.
+    pub async fn download_configs(
+        &self,
+        repo: &RepoSpec,
+        reference: &str,
+        subpath: &str,
+        progress: impl Fn(usize, usize, &str),
+    ) -> Result<RepoDownload> {
+        let files = config_files_under(&self.files(repo, reference).await?, subpath);
+        if files.is_empty() {
+            let folder = if subpath.is_empty() { "/" } else { subpath };
+            anyhow::bail!(
+                "No .conf files in {folder} of {} on {reference}",
+                repo.full_name()
+            );
+        }
+
+        let mut download = RepoDownload::default();
+        let total = files.len().min(MAX_FILES);
+        for (index, file) in files.iter().enumerate() {
+            if index >= MAX_FILES {
+                download
+                    .skipped
+                    .push(format!("{} (more than {MAX_FILES} files)", file.path));
+                continue;
+            }
+            if file.size > MAX_FILE_SIZE {
+                download
+                    .skipped
+                    .push(format!("{} (larger than 1 MiB)", file.path));
+                continue;
+            }
+            progress(index + 1, total, &file.path);
+            let content = self.file_content(repo, reference, &file.path).await?;
+            download.files.insert(file.path.clone(), content);
+        }
+        Ok(download)
+    }
+
+    /// Create a gist holding `content` as `filename` and return its URL.
+    /// Secret gists are unlisted but readable by anyone with the link.
+    pub async fn create_gist(
+        &self,
+        filename: &str,
+        content: &str,
+        description: &str,
+        public: bool,
+    ) -> Result<String> {
+        #[derive(Deserialize)]
+        struct Gist {
+            html_url: String,
+        }
+
+        let Some(token) = &self.token else {
+            anyhow::bail!("Sharing a gist needs a token with the gist scope in GITHUB_TOKEN");
+        };
+        let response = self
+            .client
+            .post(format!("{API_BASE}/gists"))
+            .header(ACCEPT, "application/vnd.github+json")
+            .bearer_auth(token)
+            .json(&gist_body(filename, content, description, public))
+            .send()
+            .await
+            .context("Could not reach GitHub")?;
+        check_gist_status(response.status(), response.headers())?;
+        let gist: Gist = response.json().await.context("Unexpected gist reply")?;
+        Ok(gist.html_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(owner: &str, name: &str, branch: Option<&str>, subpath: Option<&str>) -> RepoSpec {
+        RepoSpec {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            branch: branch.map(str::to_string),
+            subpath: subpath.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_repo_spec_parsing() {
+        let plain = spec("user", "dotfiles", None, None);
+        assert_eq!(RepoSpec::parse("user/dotfiles").unwrap(), plain);
+        assert_eq!(RepoSpec::parse("github.com/user/dotfiles").unwrap(), plain);
+        assert_eq!(
+            RepoSpec::parse("https://github.com/user/dotfiles.git").unwrap(),
+            plain
+        );
+        assert_eq!(
+            RepoSpec::parse("git@github.com:user/dotfiles.git").unwrap(),
+            plain
+        );
+        assert_eq!(
+            RepoSpec::parse("https://www.github.com/user/dotfiles/tree/dev/.config/hypr/").unwrap(),
+            spec("user", "dotfiles", Some("dev"), Some(".config/hypr"))
+        );
+        assert_eq!(
+            RepoSpec::parse("https://github.com/user/dotfiles/blob/main/hypr/hyprland.conf")
+                .unwrap(),
+            spec("user", "dotfiles", Some("main"), Some("hypr"))
+        );
+
+        assert!(RepoSpec::parse("https://gitlab.com/user/dotfiles").is_err());
+        assert!(RepoSpec::parse("dotfiles").is_err());
+        assert!(RepoSpec::parse("user/dot files").is_err());
+    }
+
+    #[test]
+    fn test_config_files_under_subpath() {
+        let files: Vec<RepoFile> = [
+            "hypr/hyprland.conf",
+            "hypr/conf/binds.conf",
+            "hyprland/x.conf",
+            "hypr/README.md",
+            "kitty.conf",
+        ]
+        .iter()
+        .map(|path| RepoFile {
+            path: path.to_string(),
+            size: 10,
+        })
+        .collect();
+
+        let paths = |subpath: &str| -> Vec<String> {
+            config_files_under(&files, subpath)
+                .into_iter()
+                .map(|file| file.path)
+                .collect()
+        };
+        assert_eq!(
+            paths("hypr/"),
+            vec!["hypr/hyprland.conf", "hypr/conf/binds.conf"]
+        );
+        assert_eq!(paths("").len(), 4);
+    }
+
+    #[test]
+    fn test_rate_limit_and_not_found_errors() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        let error = check_status(StatusCode::FORBIDDEN, &headers, false).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("GitHub's rate limit was reached"));
+        assert!(error
+            .to_string()
+            .ends_with("Set GITHUB_TOKEN for a higher limit"));
+
+        let error = check_status(StatusCode::NOT_FOUND, &HeaderMap::new(), false).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Private repositories need a token"));
+
+        assert!(check_status(StatusCode::OK, &HeaderMap::new(), false).is_ok());
+        assert!(check_status(StatusCode::FORBIDDEN, &HeaderMap::new(), true)
+            .unwrap_err()
+            .to_string()
+            .contains("403"));
+    }
+
+    #[test]
+    fn test_gist_body_and_errors() {
+        let body = gist_body("hyprland.conf", "gaps_in = 5", "My setup", false);
+        assert_eq!(body["public"], false);
+        assert_eq!(body["description"], "My setup");
+        assert_eq!(body["files"]["hyprland.conf"]["content"], "gaps_in = 5");
+
+        for status in [StatusCode::FORBIDDEN, StatusCode::NOT_FOUND] {
+            assert!(check_gist_status(status, &HeaderMap::new())
+                .unwrap_err()
+                .to_string()
+                .contains("gist scope"));
+        }
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        assert!(check_gist_status(StatusCode::FORBIDDEN, &headers)
+            .unwrap_err()
+            .to_string()
+            .starts_with("GitHub's rate limit was reached"));
+        assert!(check_gist_status(StatusCode::CREATED, &HeaderMap::new()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_creating_a_gist_needs_a_token() {
+        let client = GitHubClient {
+            client: reqwest::Client::new(),
+            token: None,
+        };
+        let error = client
+            .create_gist("hyprland.conf", "", "", false)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("GITHUB_TOKEN"));
+    }
+
+    #[test]
+    fn test_sourced_files_are_spliced_into_the_main_config() {
+        let mut download = RepoDownload::default();
+        for (path, content) in [
+            (
+                "hypr/hyprland.conf",
+                "general {\n    gaps_in = 5\n}\nsource = ~/.config/hypr/conf/binds.conf\nsource = ./conf/rules/*.conf",
+            ),
+            ("hypr/conf/binds.conf", "bind = SUPER, Q, exec, kitty"),
+            ("hypr/conf/rules/float.conf", "windowrulev2 = float, class:^(pavucontrol)$"),
+            ("hypr/hyprlock.conf", "background {\n    path = screenshot\n}"),
+        ] {
+            download.files.insert(path.to_string(), content.to_string());
+        }
+
+        let (content, used) = download.combined_config();
+        assert_eq!(
+            content,
+            "general {\n    gaps_in = 5\n}\nbind = SUPER, Q, exec, kitty\nwindowrulev2 = float, class:^(pavucontrol)$\n"
+        );
+        assert_eq!(
+            used,
+            vec![
+                "hypr/hyprland.conf",
+                "hypr/conf/binds.conf",
+                "hypr/conf/rules/float.conf"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_without_a_main_config_other_tools_are_left_out() {
+        let mut download = RepoDownload::default();
+        download.files.insert(
+            "binds.conf".to_string(),
+            "bind = SUPER, Q, killactive".to_string(),
+        );
+        download
+            .files
+            .insert("hypridle.conf".to_string(), "general {\n}".to_string());
+
+        let (content, used) = download.combined_config();
+        assert_eq!(content, "bind = SUPER, Q, killactive\n");
+        assert_eq!(used, vec!["binds.conf"]);
+    }
+}
@@ -3,7 +3,10 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
+use super::download::download_config;
 use super::formats::{ConfigFormat, StructuredConfig};
+use super::github::{GitHubClient, RepoSpec, OTHER_TOOL_CONFIGS};
+use crate::nixos::parser::HyprlandNixSettings;
 use crate::platform::ConfigPathManager;
 
 // Allow dead code for import functionality that will be used by TUI in future
@@ -11,12 +14,15 @@ use crate::platform::ConfigPathManager;
 /// Different sources for importing configurations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ImportSource {
-    /// GitHub repository with optional branch/tag
+    /// GitHub repository with optional branch/tag, and the folder of the
+    /// repository the configs are in
     GitHub {
         url: String,
         branch: Option<String>,
         tag: Option<String>,
         commit: Option<String>,
+        #[serde(default)]
+        subpath: Option<String>,
     },
     /// Local folder containing configuration files
     LocalFolder { path: PathBuf },
@@ -227,10 +233,25 @@ impl ConfigImporter {
                 branch,
                 tag,
                 commit,
+                subpath,
             } => {
-                let repo_path = self
-                    .clone_repository(url, branch.as_deref(), tag.as_deref(), commit.as_deref())
-                    .await?;
+                // git2 is built without https, so GitHub repos go through the API
+                let repo_path = match RepoSpec::parse(url) {
+                    Ok(repo) => {
+                        let reference = commit.as_ref().or(tag.as_ref()).or(branch.as_ref());
+                        self.download_github_configs(&repo, reference, subpath.as_deref())
+                            .await?
+                    }
+                    Err(_) => {
+                        self.clone_repository(
+                            url,
+                            branch.as_deref(),
+                            tag.as_deref(),
+                            commit.as_deref(),
+                        )
+                        .await?
+                    }
+                };
                 self.scan_directory_for_configs(&repo_path, &repo_path)
             }
             ImportSource::LocalFolder { path } => self.scan_directory_for_configs(path, path),
@@ -291,25 +312,55 @@ impl ConfigImporter {
         Ok(target_path)
     }
 
-    /// Download a file from HTTP URL
-    async fn download_file(&self, url: &str) -> Result<PathBuf> {
-        let response = reqwest::get(url)
-            .await
-            .with_context(|| format!("Failed to download file: {}", url))?;
+    /// Download the `.conf` files of a GitHub repository into the temp
+    /// directory, keeping their paths. Without a reference the default
+    /// branch is used, and URL branches and folders fill in what isn't given.
+    async fn download_github_configs(
+        &self,
+        repo: &RepoSpec,
+        reference: Option<&String>,
+        subpath: Option<&str>,
+    ) -> Result<PathBuf> {
+        let client = GitHubClient::new()?;
+        let reference = match reference.or(repo.branch.as_ref()) {
+            Some(reference) => reference.clone(),
+            None => client.default_branch(repo).await?,
+        };
+        let subpath = subpath.or(repo.subpath.as_deref()).unwrap_or("");
+        let download = client
+            .download_configs(repo, &reference, subpath, |_, _, _| {})
+            .await?;
 
-        if !response.status().is_success() {
-            anyhow::bail!("HTTP error {}: {}", response.status(), url);
+        let target_path = self
+            .temp_dir
+            .join(format!("{}_{}", repo.name, uuid::Uuid::new_v4()));
+        for (path, content) in &download.files {
+            let file_path = target_path.join(path);
+            if let Some(parent) = file_path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&file_path, content)
+                .with_context(|| format!("Failed to write downloaded file: {:?}", file_path))?;
         }
+        Ok(target_path)
+    }
 
-        let filename = url.split('/').last().unwrap_or("downloaded_config");
-        let file_path = self.temp_dir.join(filename);
-
-        let content = response
-            .bytes()
-            .await
-            .context("Failed to read response body")?;
+    /// Download a config from a URL, named for the format found in it
+    async fn download_file(&self, url: &str) -> Result<PathBuf> {
+        let download = download_config(url).await?;
+
+        let stem = url
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .and_then(|name| name.split('.').next())
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or("downloaded_config");
+        let file_path = self
+            .temp_dir
+            .join(format!("{stem}.{}", download.format.extension()));
 
-        std::fs::write(&file_path, content)
+        std::fs::write(&file_path, &download.content)
             .with_context(|| format!("Failed to write downloaded file: {:?}", file_path))?;
 
         Ok(file_path)
@@ -483,11 +534,10 @@ impl ConfigImporter {
 
         match format {
             ConfigFormat::HyprlandConf => self.parse_hyprland_conf(&content, path),
-            ConfigFormat::Json => self.parse_json_config(&content),
-            ConfigFormat::Toml => self.parse_toml_config(&content),
-            ConfigFormat::Yaml => self.parse_yaml_config(&content),
-            ConfigFormat::RHyprConfig => self.parse_rhypr_config(&content),
-            _ => anyhow::bail!("Unsupported format for import: {:?}", format),
+            ConfigFormat::NixHomeManager | ConfigFormat::NixSystem => {
+                anyhow::bail!("Unsupported format for import: {:?}", format)
+            }
+            format => StructuredConfig::parse(&content, &format),
         }
     }
 
@@ -529,6 +579,14 @@ impl ConfigImporter {
                     if let Some(rule) = self.parse_window_rule_line(line) {
                         config.window_rules.push(rule);
                     }
+                } else if key == "layerrule" {
+                    if let Some(rule) = self.parse_window_rule_line(line) {
+                        config.layer_rules.push(super::formats::LayerRuleEntry {
+                            rule: rule.rule,
+                            layer: rule.window_identifier,
+                            description: None,
+                        });
+                    }
                 }
             }
         }
@@ -537,29 +595,6 @@ impl ConfigImporter {
         Ok(config)
     }
 
-    /// Parse other formats (JSON, TOML, YAML)
-    fn parse_json_config(&self, content: &str) -> Result<StructuredConfig> {
-        serde_json::from_str(content).context("Failed to parse JSON configuration")
-    }
-
-    fn parse_toml_config(&self, content: &str) -> Result<StructuredConfig> {
-        toml::from_str(content).context("Failed to parse TOML configuration")
-    }
-
-    fn parse_yaml_config(&self, content: &str) -> Result<StructuredConfig> {
-        serde_yaml::from_str(content).context("Failed to parse YAML configuration")
-    }
-
-    fn parse_rhypr_config(&self, content: &str) -> Result<StructuredConfig> {
-        // Skip metadata comments and parse JSON content
-        let json_start = content
-            .find('{')
-            .context("Invalid r-hyprconfig format: no JSON content found")?;
-
-        let json_content = &content[json_start..];
-        serde_json::from_str(json_content).context("Failed to parse r-hyprconfig JSON content")
-    }
-
     // Helper parsing methods would go here...
     fn parse_general_setting(
         &self,
@@ -629,7 +664,8 @@ impl ConfigImporter {
         if let Some(equals_pos) = line.find('=') {
             let bind_type = line[..equals_pos].trim();
             let bind_part = line[equals_pos + 1..].trim();
-            let parts: Vec<&str> = bind_part.split(',').map(|s| s.trim()).collect();
+            // Arguments can contain commas of their own, so keep them whole
+            let parts: Vec<&str> = bind_part.splitn(4, ',').map(|s| s.trim()).collect();
 
             if parts.len() >= 3 {
                 return Some(super::formats::KeybindEntry {
@@ -637,11 +673,7 @@ impl ConfigImporter {
                     modifiers: vec![parts[0].to_string()],
                     key: parts[1].to_string(),
                     dispatcher: parts[2].to_string(),
-                    args: if parts.len() > 3 {
-                        Some(parts[3..].join(","))
-                    } else {
-                        None
-                    },
+                    args: parts.get(3).map(|args| args.to_string()),
                     description: None,
                 });
             }
@@ -819,6 +851,167 @@ impl ConfigImporter {
     }
 }
 
+/// A config file found by a folder scan, with the settings read from it
+#[derive(Debug)]
+pub struct FolderFragment {
+    /// Path relative to the scanned folder
+    pub path: PathBuf,
+    pub settings: HyprlandNixSettings,
+}
+
+/// An option that more than one file sets, each to a different value
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionConflict {
+    pub key: String,
+    /// The files setting the option, in scan order, and their values
+    pub values: Vec<(PathBuf, String)>,
+    /// Index into `values` of the file that wins
+    pub chosen: usize,
+}
+
+impl OptionConflict {
+    /// Let the next file win, wrapping around
+    pub fn choose_next(&mut self) {
+        self.chosen = (self.chosen + 1) % self.values.len();
+    }
+
+    pub fn choose_previous(&mut self) {
+        self.chosen = (self.chosen + self.values.len() - 1) % self.values.len();
+    }
+}
+
+/// The Hyprland config fragments of a folder merged into one set of settings,
+/// like a `~/.config/hypr/` with separate files for binds, rules and env
+#[derive(Debug)]
+pub struct FolderMerge {
+    pub root: PathBuf,
+    pub fragments: Vec<FolderFragment>,
+    pub conflicts: Vec<OptionConflict>,
+}
+
+impl FolderMerge {
+    /// Read every `.conf` file under `root`. `hyprland.conf` comes first and
+    /// the rest follow by path. Where files disagree on an option the last
+    /// one wins until another is chosen, as when Hyprland sources them in
+    /// that order.
+    pub fn scan(root: &Path) -> Result<Self> {
+        if !root.is_dir() {
+            anyhow::bail!("{} is not a folder", root.display());
+        }
+
+        let mut paths: Vec<PathBuf> = WalkDir::new(root)
+            .max_depth(5)
+            .into_iter()
+            .filter_entry(|entry| {
+                entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+            })
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                name.ends_with(".conf") && !OTHER_TOOL_CONFIGS.contains(&name)
+            })
+            .collect();
+        paths.sort_by_key(|path| {
+            let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+            (relative != Path::new("hyprland.conf"), relative)
+        });
+        if paths.is_empty() {
+            anyhow::bail!("No Hyprland .conf files found in {}", root.display());
+        }
+
+        let mut fragments = Vec::new();
+        for path in paths {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read config file: {:?}", path))?;
+            fragments.push(FolderFragment {
+                path: path.strip_prefix(root).unwrap_or(&path).to_path_buf(),
+                settings: HyprlandNixSettings::from_hyprland_conf(&content),
+            });
+        }
+
+        let conflicts = find_conflicts(&fragments);
+        Ok(Self {
+            root: root.to_path_buf(),
+            fragments,
+            conflicts,
+        })
+    }
+
+    /// All options, with the chosen value for conflicting ones, and the lines
+    /// of every file. `source` lines are dropped, since the files they name
+    /// are merged in already.
+    pub fn merged(&self) -> HyprlandNixSettings {
+        let mut options: Vec<(String, String)> = Vec::new();
+        for fragment in &self.fragments {
+            for (key, value) in &fragment.settings.options {
+                match options.iter_mut().find(|(k, _)| k == key) {
+                    Some(option) => option.1 = value.clone(),
+                    None => options.push((key.clone(), value.clone())),
+                }
+            }
+        }
+        for conflict in &self.conflicts {
+            if let Some(option) = options.iter_mut().find(|(k, _)| *k == conflict.key) {
+                option.1 = conflict.values[conflict.chosen].1.clone();
+            }
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+        for line in self.fragments.iter().flat_map(|f| &f.settings.lines) {
+            let is_source = line
+                .split_once('=')
+                .is_some_and(|(keyword, _)| keyword.trim() == "source");
+            if !is_source && !lines.contains(line) {
+                lines.push(line.clone());
+            }
+        }
+
+        HyprlandNixSettings {
+            options,
+            lines,
+            skipped: Vec::new(),
+        }
+    }
+}
+
+/// Options set to different values by different files. Within one file the
+/// last value counts.
+fn find_conflicts(fragments: &[FolderFragment]) -> Vec<OptionConflict> {
+    let mut conflicts: Vec<OptionConflict> = Vec::new();
+    for fragment in fragments {
+        let mut file_options: Vec<(&String, &String)> = Vec::new();
+        for (key, value) in &fragment.settings.options {
+            match file_options.iter_mut().find(|(k, _)| *k == key) {
+                Some(option) => option.1 = value,
+                None => file_options.push((key, value)),
+            }
+        }
+        for (key, value) in file_options {
+            match conflicts.iter_mut().find(|c| c.key == *key) {
+                Some(conflict) => conflict.values.push((fragment.path.clone(), value.clone())),
+                None => conflicts.push(OptionConflict {
+                    key: key.clone(),
+                    values: vec![(fragment.path.clone(), value.clone())],
+                    chosen: 0,
+                }),
+            }
+        }
+    }
+
+    conflicts.retain(|conflict| {
+        conflict
+            .values
+            .iter()
+            .any(|(_, value)| *value != conflict.values[0].1)
+    });
+    for conflict in &mut conflicts {
+        conflict.chosen = conflict.values.len() - 1;
+    }
+    conflicts
+}
+
 impl Drop for ConfigImporter {
     fn drop(&mut self) {
         // Clean up temporary directory
@@ -863,7 +1056,9 @@ general:gaps_out = 20
 input:kb_layout = us
 
 bind = SUPER, Return, exec, kitty
+bind = SUPER SHIFT, S, exec, grim -g "$(slurp)", -
 windowrule = float, ^(kitty)$
+layerrule = blur, waybar
 "#;
 
         let importer = ConfigImporter::new()?;
@@ -872,8 +1067,94 @@ windowrule = float, ^(kitty)$
         assert_eq!(config.general.gaps_in, Some(10));
         assert_eq!(config.general.gaps_out, Some(20));
         assert_eq!(config.input.kb_layout, Some("us".to_string()));
-        assert_eq!(config.keybinds.len(), 1);
+        assert_eq!(config.keybinds.len(), 2);
         assert_eq!(config.window_rules.len(), 1);
+        assert_eq!(
+            config.keybind_lines()[1],
+            r#"bind = SUPER SHIFT, S, exec, grim -g "$(slurp)", -"#
+        );
+        assert_eq!(config.layer_rule_lines(), vec!["layerrule = blur, waybar"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_yaml_export_reads_back_unchanged() -> Result<()> {
+        use super::super::export::ConfigExporter;
+        use super::super::formats::{KeybindEntry, LayerRuleEntry, WindowRuleEntry};
+
+        let mut config = StructuredConfig::new("Sample");
+        config.metadata.description = Some("Colors: gradients & all".to_string());
+        config.add_tag("yes");
+        config.general.gaps_in = Some(5);
+        config.general.col_active_border = Some("rgba(33ccffee) rgba(00ff99ee) 45deg".to_string());
+        config.general.col_inactive_border = Some("0xff444444".to_string());
+        config.input.kb_layout = Some("us,de".to_string());
+        config.input.sensitivity = Some(-0.5);
+        config.decoration.col_shadow = Some("rgba(1a1a1aee)".to_string());
+        config.decoration.dim_strength = Some(0.2);
+        config.animations.enabled = Some(true);
+        config
+            .animations
+            .beziers
+            .push("myBezier, 0.05, 0.9, 0.1, 1.05".to_string());
+        config
+            .animations
+            .animations
+            .push("windows, 1, 7, myBezier".to_string());
+        config.misc.swallow_regex = Some("^(kitty)$".to_string());
+        config.custom_settings.insert(
+            "group:col.border_active".to_string(),
+            "rgb(ffaa00) rgb(00aaff) 90deg".to_string(),
+        );
+        config
+            .custom_settings
+            .insert("misc:vrr".to_string(), "1".to_string());
+        for (modifiers, key, args) in [("SUPER", "Return", Some("kitty")), ("", "Print", None)] {
+            config.keybinds.push(KeybindEntry {
+                bind_type: "bind".to_string(),
+                modifiers: vec![modifiers.to_string()],
+                key: key.to_string(),
+                dispatcher: "exec".to_string(),
+                args: args.map(str::to_string),
+                description: None,
+            });
+        }
+        config.window_rules.push(WindowRuleEntry {
+            rule: "opacity 0.9 0.8".to_string(),
+            window_identifier: "^(kitty)$".to_string(),
+            description: Some("# translucent terminals".to_string()),
+        });
+        config.layer_rules.push(LayerRuleEntry {
+            rule: "blur".to_string(),
+            layer: "waybar".to_string(),
+            description: None,
+        });
+
+        let temp_dir = TempDir::new()?;
+        let exporter = ConfigExporter::with_output_dir(temp_dir.path().to_path_buf());
+        let path = exporter.export_structured_config(&config, "sample", ConfigFormat::Yaml)?;
+        let written = std::fs::read_to_string(&path)?;
+        assert!(!written.contains("null"));
+        assert!(!written
+            .lines()
+            .any(|line| line.contains(": &") || line.contains(": *")));
+
+        let importer = ConfigImporter::new()?;
+        let imported = importer
+            .parse_config_file(&path, ConfigFormat::Yaml)
+            .await?;
+        assert_eq!(
+            exporter.render_structured_config(&imported, &ConfigFormat::Yaml)?,
+            written
+        );
+        assert_eq!(
+            imported.general.col_active_border.as_deref(),
+            Some("rgba(33ccffee) rgba(00ff99ee) 45deg")
+        );
+        assert_eq!(imported.metadata.tags, vec!["yes"]);
+        assert_eq!(imported.keybind_lines(), config.keybind_lines());
+        assert_eq!(imported.to_hyprland_options(), config.to_hyprland_options());
 
         Ok(())
     }
@@ -929,4 +1210,80 @@ windowrule = float, ^(kitty)$
             let _cloned = resolution.clone();
         }
     }
+
+    #[test]
+    fn test_folder_merge_with_conflicts() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path();
+        std::fs::create_dir(root.join("conf"))?;
+        std::fs::write(
+            root.join("hyprland.conf"),
+            "source = ~/.config/hypr/conf/binds.conf\n\
+             source = ~/.config/hypr/conf/looks.conf\n\
+             general {\n    gaps_in = 5\n    border_size = 2\n}\n\
+             env = XCURSOR_SIZE,24\n",
+        )?;
+        std::fs::write(
+            root.join("conf/binds.conf"),
+            "$mod = SUPER\nbind = $mod, Q, killactive\nbind = $mod, T, exec, kitty\n",
+        )?;
+        std::fs::write(
+            root.join("conf/looks.conf"),
+            "general {\n    gaps_in = 10\n    border_size = 2\n}\n\
+             decoration:rounding = 8\nwindowrulev2 = float, class:^(pavucontrol)$\n",
+        )?;
+        // Configs of other tools are left alone
+        std::fs::write(root.join("hyprlock.conf"), "general {\n    grace = 5\n}\n")?;
+
+        let mut merge = FolderMerge::scan(root)?;
+        let paths: Vec<&Path> = merge.fragments.iter().map(|f| f.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("hyprland.conf"),
+                Path::new("conf/binds.conf"),
+                Path::new("conf/looks.conf")
+            ]
+        );
+
+        // Only gaps_in differs, border_size is the same in both files
+        assert_eq!(merge.conflicts.len(), 1);
+        let conflict = &merge.conflicts[0];
+        assert_eq!(conflict.key, "general:gaps_in");
+        assert_eq!(
+            conflict.values,
+            vec![
+                (PathBuf::from("hyprland.conf"), "5".to_string()),
+                (PathBuf::from("conf/looks.conf"), "10".to_string())
+            ]
+        );
+        assert_eq!(conflict.chosen, 1);
+
+        let merged = merge.merged();
+        assert_eq!(
+            merged.options,
+            vec![
+                ("general:gaps_in".to_string(), "10".to_string()),
+                ("general:border_size".to_string(), "2".to_string()),
+                ("decoration:rounding".to_string(), "8".to_string()),
+            ]
+        );
+        assert_eq!(
+            merged.lines,
+            vec![
+                "env = XCURSOR_SIZE,24",
+                "$mod = SUPER",
+                "bind = $mod, Q, killactive",
+                "bind = $mod, T, exec, kitty",
+                "windowrulev2 = float, class:^(pavucontrol)$",
+            ]
+        );
+
+        merge.conflicts[0].choose_next();
+        assert_eq!(
+            merge.merged().options[0],
+            ("general:gaps_in".to_string(), "5".to_string())
+        );
+        Ok(())
+    }
 }
@@ -1,6 +1,9 @@
+pub mod download;
 pub mod export;
 pub mod formats;
+pub mod github;
 pub mod import;
+pub mod schema;
 
 // Re-export types needed for TUI integration (some may show as unused until TUI integration is complete)
 #[allow(unused_imports)]
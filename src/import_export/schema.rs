@@ -0,0 +1,407 @@
+// JSON Schema for the JSON export
+// Written out by hand from the StructuredConfig layout. The typed sections come
+// from the same table `StructuredConfig::to_hyprland_options` uses, so the
+// schema names the keys an import writes. Numeric limits come from the option
+// ranges the editor uses, so a file that passes the schema holds values the
+// TUI would accept too.
+
+use serde_json::{json, Map, Value};
+
+use crate::options::option_range;
+use crate::ui::ConfigDataType;
+
+const SCHEMA_ID: &str = "https://github.com/olafkfreund/r-hyprconfig/schemas/config.schema.json";
+
+/// One color in `rgba()`, `rgb()` or `0xAARRGGBB` notation
+const COLOR: &str = r"(rgba\(\s*[0-9a-fA-F]{8}\s*\)|rgb\(\s*[0-9a-fA-F]{6}\s*\)|0x[0-9a-fA-F]{8})";
+
+/// The kind of value a typed field holds, before its limits are looked up
+#[derive(Clone, Copy)]
+pub(super) enum Field {
+    Int,
+    Float,
+    Bool,
+    Text,
+    Color,
+    Keyword(&'static [&'static str]),
+}
+
+/// The typed settings sections with each field's hyprctl name
+#[allow(clippy::type_complexity)]
+#[rustfmt::skip]
+pub(super) const SECTIONS: &[(&str, &[(&str, &str, Field)])] = &[
+    ("general", &[
+        ("gaps_in", "general:gaps_in", Field::Int),
+        ("gaps_out", "general:gaps_out", Field::Int),
+        ("border_size", "general:border_size", Field::Int),
+        ("col_active_border", "general:col.active_border", Field::Color),
+        ("col_inactive_border", "general:col.inactive_border", Field::Color),
+        ("resize_on_border", "general:resize_on_border", Field::Bool),
+        ("extend_border_grab_area", "general:extend_border_grab_area", Field::Int),
+        ("hover_icon_on_border", "general:hover_icon_on_border", Field::Bool),
+    ]),
+    ("input", &[
+        ("kb_layout", "input:kb_layout", Field::Text),
+        ("kb_variant", "input:kb_variant", Field::Text),
+        ("kb_model", "input:kb_model", Field::Text),
+        ("kb_options", "input:kb_options", Field::Text),
+        ("kb_rules", "input:kb_rules", Field::Text),
+        ("follow_mouse", "input:follow_mouse", Field::Int),
+        ("mouse_refocus", "input:mouse_refocus", Field::Bool),
+        ("sensitivity", "input:sensitivity", Field::Float),
+        ("accel_profile", "input:accel_profile", Field::Keyword(&["adaptive", "flat", "custom"])),
+        ("natural_scroll", "input:natural_scroll", Field::Bool),
+    ]),
+    ("decoration", &[
+        ("rounding", "decoration:rounding", Field::Int),
+        ("blur_enabled", "decoration:blur:enabled", Field::Bool),
+        ("blur_size", "decoration:blur:size", Field::Int),
+        ("blur_passes", "decoration:blur:passes", Field::Int),
+        ("drop_shadow", "decoration:drop_shadow", Field::Bool),
+        ("shadow_range", "decoration:shadow:range", Field::Int),
+        ("shadow_render_power", "decoration:shadow:render_power", Field::Int),
+        ("col_shadow", "decoration:col.shadow", Field::Color),
+        ("dim_inactive", "decoration:dim_inactive", Field::Bool),
+        ("dim_strength", "decoration:dim_strength", Field::Float),
+    ]),
+    ("animations", &[
+        ("enabled", "animations:enabled", Field::Bool),
+    ]),
+    ("gestures", &[
+        ("workspace_swipe", "gestures:workspace_swipe", Field::Bool),
+        ("workspace_swipe_fingers", "gestures:workspace_swipe_fingers", Field::Int),
+        ("workspace_swipe_distance", "gestures:workspace_swipe_distance", Field::Int),
+        ("workspace_swipe_invert", "gestures:workspace_swipe_invert", Field::Bool),
+        ("workspace_swipe_min_speed_to_force", "gestures:workspace_swipe_min_speed_to_force", Field::Int),
+        ("workspace_swipe_cancel_ratio", "gestures:workspace_swipe_cancel_ratio", Field::Float),
+        ("workspace_swipe_create_new", "gestures:workspace_swipe_create_new", Field::Bool),
+        ("workspace_swipe_forever", "gestures:workspace_swipe_forever", Field::Bool),
+    ]),
+    ("misc", &[
+        ("disable_hyprland_logo", "misc:disable_hyprland_logo", Field::Bool),
+        ("disable_splash_rendering", "misc:disable_splash_rendering", Field::Bool),
+        ("mouse_move_enables_dpms", "misc:mouse_move_enables_dpms", Field::Bool),
+        ("key_press_enables_dpms", "misc:key_press_enables_dpms", Field::Bool),
+        ("always_follow_on_dnd", "misc:always_follow_on_dnd", Field::Bool),
+        ("layers_hog_keyboard_focus", "misc:layers_hog_keyboard_focus", Field::Bool),
+        ("animate_manual_resizes", "misc:animate_manual_resizes", Field::Bool),
+        ("animate_mouse_windowdragging", "misc:animate_mouse_windowdragging", Field::Bool),
+        ("disable_autoreload", "misc:disable_autoreload", Field::Bool),
+        ("enable_swallow", "misc:enable_swallow", Field::Bool),
+        ("swallow_regex", "misc:swallow_regex", Field::Text),
+    ]),
+];
+
+/// The data type of a field, with the limits of its option where known.
+/// Integers without a known range can't be negative.
+fn data_type(key: &str, field: Field) -> ConfigDataType {
+    match (field, option_range(key)) {
+        (Field::Int | Field::Float, Some(range)) if range.integer => ConfigDataType::Integer {
+            min: Some(range.min as i32),
+            max: Some(range.max as i32),
+        },
+        (Field::Int | Field::Float, Some(range)) => ConfigDataType::Float {
+            min: Some(range.min),
+            max: Some(range.max),
+        },
+        (Field::Int, None) => ConfigDataType::Integer {
+            min: Some(0),
+            max: None,
+        },
+        (Field::Float, None) => ConfigDataType::Float {
+            min: None,
+            max: None,
+        },
+        (Field::Bool, _) => ConfigDataType::Boolean,
+        (Field::Text, _) => ConfigDataType::String,
+        (Field::Color, _) => ConfigDataType::Color,
+        (Field::Keyword(options), _) => ConfigDataType::Keyword {
+            options: options.iter().map(|option| option.to_string()).collect(),
+        },
+    }
+}
+
+/// The schema of one value of the given type
+pub fn data_type_schema(data_type: &ConfigDataType) -> Value {
+    let mut schema = Map::new();
+    match data_type {
+        ConfigDataType::Integer { min, max } => {
+            schema.insert("type".into(), json!("integer"));
+            if let Some(min) = min {
+                schema.insert("minimum".into(), json!(min));
+            }
+            if let Some(max) = max {
+                schema.insert("maximum".into(), json!(max));
+            }
+        }
+        ConfigDataType::Float { min, max } => {
+            schema.insert("type".into(), json!("number"));
+            if let Some(min) = min {
+                schema.insert("minimum".into(), json!(min));
+            }
+            if let Some(max) = max {
+                schema.insert("maximum".into(), json!(max));
+            }
+        }
+        ConfigDataType::Boolean => {
+            schema.insert("type".into(), json!("boolean"));
+        }
+        ConfigDataType::String => {
+            schema.insert("type".into(), json!("string"));
+        }
+        ConfigDataType::Color => {
+            schema.insert("type".into(), json!("string"));
+            schema.insert(
+                "pattern".into(),
+                json!(format!(r"^{COLOR}(\s+{COLOR})*(\s+-?[0-9]+deg)?$")),
+            );
+            schema.insert(
+                "description".into(),
+                json!("A color, or a gradient of colors with an optional angle"),
+            );
+        }
+        ConfigDataType::Keyword { options } => {
+            schema.insert("type".into(), json!("string"));
+            schema.insert("enum".into(), json!(options));
+        }
+    }
+    Value::Object(schema)
+}
+
+/// Fields the export writes as `null` when unset
+fn nullable(schema: Value) -> Value {
+    json!({ "anyOf": [schema, { "type": "null" }] })
+}
+
+fn object(properties: Map<String, Value>, required: &[&str]) -> Value {
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn section_schema(fields: &[(&str, &str, Field)]) -> Value {
+    let properties = fields
+        .iter()
+        .map(|(name, key, field)| {
+            let mut schema = data_type_schema(&data_type(key, *field));
+            schema["description"] = json!(format!("`{key}` in hyprland.conf"));
+            (name.to_string(), nullable(schema))
+        })
+        .collect();
+    let required: Vec<&str> = fields.iter().map(|(name, _, _)| *name).collect();
+    object(properties, &required)
+}
+
+/// A repeated item: required strings, then optional ones
+fn entry_schema(required: &[&str], optional: &[&str]) -> Value {
+    let mut properties = Map::new();
+    for name in required {
+        properties.insert(
+            name.to_string(),
+            json!({ "type": "string", "minLength": 1 }),
+        );
+    }
+    for name in optional {
+        properties.insert(name.to_string(), nullable(json!({ "type": "string" })));
+    }
+    object(properties, required)
+}
+
+fn metadata_schema() -> Value {
+    let string = || json!({ "type": "string" });
+    let timestamp = || json!({ "type": "string", "format": "date-time" });
+    let mut properties = Map::new();
+    properties.insert("name".into(), json!({ "type": "string", "minLength": 1 }));
+    properties.insert("description".into(), nullable(string()));
+    properties.insert("author".into(), nullable(string()));
+    properties.insert("version".into(), string());
+    properties.insert("created_at".into(), timestamp());
+    properties.insert("updated_at".into(), timestamp());
+    properties.insert("tags".into(), json!({ "type": "array", "items": string() }));
+    properties.insert("source_url".into(), nullable(string()));
+    properties.insert(
+        "source_format".into(),
+        json!({
+            "enum": ["HyprlandConf", "Json", "Toml", "NixHomeManager", "NixSystem", "Yaml", "RHyprConfig"]
+        }),
+    );
+    properties.insert("r_hyprconfig_version".into(), string());
+    let required: Vec<String> = properties.keys().cloned().collect();
+    let required: Vec<&str> = required.iter().map(String::as_str).collect();
+    object(properties, &required)
+}
+
+/// The JSON Schema of a JSON export, the serialized form of a
+/// [`super::StructuredConfig`]
+pub fn json_schema() -> Value {
+    let mut properties = Map::new();
+    properties.insert("metadata".into(), metadata_schema());
+    for (section, fields) in SECTIONS {
+        properties.insert(section.to_string(), section_schema(fields));
+    }
+
+    // beziers and animations are whole hyprland.conf values
+    let lines = json!({ "type": "array", "items": { "type": "string" } });
+    let animations = &mut properties["animations"];
+    animations["properties"]["beziers"] = lines.clone();
+    animations["properties"]["animations"] = lines;
+    animations["required"] = json!(["enabled", "beziers", "animations"]);
+
+    let mut keybind = entry_schema(
+        &["bind_type", "key", "dispatcher"],
+        &["args", "description"],
+    );
+    keybind["properties"]["bind_type"]["pattern"] = json!("^bind[lrenmtidops]*$");
+    keybind["properties"]["modifiers"] = json!({ "type": "array", "items": { "type": "string" } });
+    keybind["required"] = json!(["bind_type", "modifiers", "key", "dispatcher"]);
+    properties.insert(
+        "keybinds".into(),
+        json!({ "type": "array", "items": keybind }),
+    );
+    properties.insert(
+        "window_rules".into(),
+        json!({
+            "type": "array",
+            "items": entry_schema(&["rule", "window_identifier"], &["description"]),
+        }),
+    );
+    properties.insert(
+        "layer_rules".into(),
+        json!({
+            "type": "array",
+            "items": entry_schema(&["rule", "layer"], &["description"]),
+        }),
+    );
+    properties.insert(
+        "custom_settings".into(),
+        json!({
+            "type": "object",
+            "description": "Options without a typed field, keyed like `misc:vrr`",
+            "propertyNames": { "pattern": "^[A-Za-z0-9_.\\-]+(:[A-Za-z0-9_.\\-]+)+$" },
+            "additionalProperties": { "type": "string" },
+        }),
+    );
+
+    let required: Vec<String> = properties.keys().cloned().collect();
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": SCHEMA_ID,
+        "title": "r-hyprconfig configuration export",
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::formats::{KeybindEntry, StructuredConfig};
+    use super::*;
+
+    /// Every key the export writes has a schema, and every required key is written
+    fn assert_covers(schema: &Value, value: &Value, path: &str) {
+        match value {
+            Value::Object(fields) if schema.get("properties").is_some() => {
+                for (name, field) in fields {
+                    let property = &schema["properties"][name];
+                    assert!(
+                        !property.is_null(),
+                        "{path}.{name} is missing from the schema"
+                    );
+                    let property = property.get("anyOf").map(|any| &any[0]).unwrap_or(property);
+                    assert_covers(property, field, &format!("{path}.{name}"));
+                }
+                for name in schema["required"].as_array().unwrap() {
+                    let name = name.as_str().unwrap();
+                    assert!(fields.contains_key(name), "{path}.{name} is not written");
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    assert_covers(&schema["items"], item, path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_schema_matches_the_export() {
+        let mut config = StructuredConfig::new("Schema");
+        config.keybinds.push(KeybindEntry {
+            bind_type: "bind".to_string(),
+            modifiers: vec!["SUPER".to_string()],
+            key: "Q".to_string(),
+            dispatcher: "killactive".to_string(),
+            args: None,
+            description: None,
+        });
+        let export = serde_json::to_value(&config).unwrap();
+        assert_covers(&json_schema(), &export, "config");
+    }
+
+    #[test]
+    fn test_limits_come_from_the_option_ranges() {
+        let schema = json_schema();
+        let field = |section: &str, name: &str| {
+            schema["properties"][section]["properties"][name]["anyOf"][0].clone()
+        };
+
+        let blur_size = field("decoration", "blur_size");
+        assert_eq!(blur_size["type"], "integer");
+        assert_eq!(blur_size["minimum"], 0);
+        assert_eq!(blur_size["maximum"], 32);
+
+        let sensitivity = field("input", "sensitivity");
+        assert_eq!(sensitivity["type"], "number");
+        assert_eq!(sensitivity["minimum"], -1.0);
+        assert_eq!(sensitivity["maximum"], 1.0);
+
+        let gaps_in = field("general", "gaps_in");
+        assert_eq!(gaps_in["minimum"], 0);
+        assert!(gaps_in.get("maximum").is_none());
+
+        assert_eq!(
+            field("input", "accel_profile")["enum"],
+            json!(["adaptive", "flat", "custom"])
+        );
+        assert!(field("general", "col_active_border")["pattern"]
+            .as_str()
+            .unwrap()
+            .contains("deg"));
+    }
+
+    #[test]
+    fn test_schema_keys_are_the_keys_an_import_writes() {
+        // Fill every typed field the schema knows with a value of its kind
+        let mut export = serde_json::to_value(StructuredConfig::new("Keys")).unwrap();
+        let mut expected = Vec::new();
+        for (section, fields) in SECTIONS {
+            for (name, key, field) in *fields {
+                let (value, written) = match field {
+                    Field::Int => (json!(2), "2".to_string()),
+                    Field::Float => (json!(0.1), "0.1".to_string()),
+                    Field::Bool => (json!(true), "true".to_string()),
+                    Field::Text => (json!("us"), "us".to_string()),
+                    Field::Color => (json!("rgba(33ccffee)"), "rgba(33ccffee)".to_string()),
+                    Field::Keyword(options) => (json!(options[0]), options[0].to_string()),
+                };
+                export[section][name] = value;
+                expected.push((key.to_string(), written));
+            }
+        }
+        let config: StructuredConfig = serde_json::from_value(export).unwrap();
+
+        let options = config.to_hyprland_options();
+        for (key, value) in &expected {
+            assert_eq!(options.get(key), Some(value), "{key} is not written");
+        }
+        assert_eq!(options.len(), expected.len());
+        assert_eq!(options["decoration:shadow:range"], "2");
+        assert_eq!(options["decoration:shadow:render_power"], "2");
+    }
+}
@@ -0,0 +1,160 @@
+// `source = path` include resolution
+// Split configs pull in other files with `source`; the included lines are
+// spliced in where the directive sits, remembering which file each came from
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// A config line and the included file it came from, `None` for the main file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourcedLine {
+    pub file: Option<PathBuf>,
+    pub text: String,
+}
+
+/// The path of a `source = path` directive, if the line is one
+pub fn parse_source_line(line: &str) -> Option<&str> {
+    let (keyword, path) = line.trim().split_once('=')?;
+    (keyword.trim() == "source")
+        .then(|| path.trim())
+        .filter(|path| !path.is_empty())
+}
+
+/// Expand `~` and environment variables in a sourced path. Relative paths are
+/// resolved against the directory of the file that sources them.
+pub fn expand_source_path(path: &str, including_file: &Path) -> PathBuf {
+    let expanded = shellexpand::full(path)
+        .map(|p| p.into_owned())
+        .unwrap_or_else(|_| path.to_string());
+    let expanded = PathBuf::from(expanded);
+    if expanded.is_absolute() {
+        expanded
+    } else {
+        including_file
+            .parent()
+            .map(|dir| dir.join(&expanded))
+            .unwrap_or(expanded)
+    }
+}
+
+/// Flatten `main_content` (read from `main_path`) and everything it sources,
+/// recursively. Each file is read at most once, which breaks include cycles
+/// and keeps a file sourced twice from duplicating its items. Missing files
+/// are skipped with a warning, as Hyprland itself does.
+pub fn resolve_includes(main_path: &Path, main_content: &str) -> Vec<SourcedLine> {
+    let mut visited = HashSet::new();
+    visited.insert(canonical(main_path));
+
+    let mut lines = Vec::new();
+    splice_file(main_path, None, main_content, &mut visited, &mut lines);
+    lines
+}
+
+fn splice_file(
+    path: &Path,
+    origin: Option<&Path>,
+    content: &str,
+    visited: &mut HashSet<PathBuf>,
+    lines: &mut Vec<SourcedLine>,
+) {
+    for line in crate::config::join_continued_lines(content) {
+        let Some(source) = parse_source_line(&line) else {
+            lines.push(SourcedLine {
+                file: origin.map(Path::to_path_buf),
+                text: line,
+            });
+            continue;
+        };
+
+        let included = expand_source_path(source, path);
+        if !visited.insert(canonical(&included)) {
+            crate::stderr_log::warn(format!(
+                "{} is sourced more than once, skipping",
+                included.display()
+            ));
+            continue;
+        }
+        match std::fs::read_to_string(&included) {
+            Ok(included_content) => {
+                splice_file(
+                    &included,
+                    Some(&included),
+                    &included_content,
+                    visited,
+                    lines,
+                );
+            }
+            Err(e) => {
+                crate::stderr_log::warn(format!(
+                    "Failed to read sourced file {}: {e}",
+                    included.display()
+                ));
+            }
+        }
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_lines() {
+        assert_eq!(
+            parse_source_line("source = ~/.config/hypr/binds.conf"),
+            Some("~/.config/hypr/binds.conf")
+        );
+        assert_eq!(parse_source_line("source=colors.conf"), Some("colors.conf"));
+        assert_eq!(parse_source_line("sources = a.conf"), None);
+        assert_eq!(parse_source_line("source ="), None);
+    }
+
+    #[test]
+    fn test_relative_and_home_paths() {
+        let main = Path::new("/etc/hypr/hyprland.conf");
+        assert_eq!(
+            expand_source_path("binds.conf", main),
+            PathBuf::from("/etc/hypr/binds.conf")
+        );
+        assert_eq!(
+            expand_source_path("/abs/rules.conf", main),
+            PathBuf::from("/abs/rules.conf")
+        );
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(
+                expand_source_path("~/hypr/binds.conf", main),
+                home.join("hypr/binds.conf")
+            );
+        }
+    }
+
+    #[test]
+    fn test_include_cycles_are_broken() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let main = dir.path().join("hyprland.conf");
+        let other = dir.path().join("other.conf");
+        std::fs::write(
+            &other,
+            "source = hyprland.conf\nbind = SUPER, Q, killactive",
+        )
+        .unwrap();
+        let main_content = "source = other.conf\nbind = SUPER, T, exec, kitty";
+        std::fs::write(&main, main_content).unwrap();
+
+        let lines = resolve_includes(&main, main_content);
+        let texts: Vec<&str> = lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "bind = SUPER, Q, killactive",
+                "bind = SUPER, T, exec, kitty"
+            ]
+        );
+        assert_eq!(lines[0].file.as_deref(), Some(other.as_path()));
+        assert_eq!(lines[1].file, None);
+    }
+}
@@ -0,0 +1,469 @@
+// Keybind conflicts and dispatcher checks
+// Two binds on the same modifiers and key both fire on one key press, which
+// is rarely intended, and a misspelled dispatcher makes Hyprland drop the
+// bind. Binds are checked by their Binds panel display value.
+
+use crate::ui::{ConfigItem, UI};
+
+/// Modifiers in Hyprland's conventional order, which normalizing sorts them into
+const MODIFIER_ORDER: [&str; 8] = [
+    "SUPER", "CTRL", "ALT", "SHIFT", "CAPS", "MOD2", "MOD3", "MOD5",
+];
+
+/// Every dispatcher Hyprland knows, offered as completions in the keybind
+/// editor. Keep in sync with the dispatcher list on the Hyprland wiki.
+#[rustfmt::skip]
+pub const DISPATCHERS: &[&str] = &[
+    "exec", "execr", "pass", "sendshortcut", "sendkeystate",
+    "killactive", "forcekillactive", "closewindow", "killwindow", "signal", "signalwindow",
+    "workspace", "movetoworkspace", "movetoworkspacesilent",
+    "togglefloating", "setfloating", "settiled", "fullscreen", "fullscreenstate",
+    "dpms", "pin", "movefocus", "movewindow", "swapwindow", "centerwindow",
+    "resizeactive", "moveactive", "resizewindowpixel", "movewindowpixel",
+    "cyclenext", "swapnext", "tagwindow", "focuswindow", "focusmonitor", "splitratio",
+    "movecursortocorner", "movecursor", "renameworkspace", "exit", "forcerendererreload",
+    "movecurrentworkspacetomonitor", "focusworkspaceoncurrentmonitor",
+    "moveworkspacetomonitor", "swapactiveworkspaces", "bringactivetotop", "alterzorder",
+    "togglespecialworkspace", "focusurgentorlast", "focuscurrentorlast",
+    "togglegroup", "changegroupactive", "lockgroups", "lockactivegroup",
+    "moveintogroup", "moveoutofgroup", "movewindoworgroup", "movegroupwindow",
+    "denywindowfromgroup", "setignoregrouplock", "global", "submap", "event",
+    "setprop", "toggleswallow", "pseudo", "togglesplit", "swapsplit", "layoutmsg",
+];
+
+/// Letters a bind keyword can add to `bind`, like `e` to repeat while held,
+/// `l` to work on the lock screen and `d` for a description
+const BIND_FLAGS: &str = "lrcgoenmtisdpu";
+
+/// Dispatchers only mouse binds (`bindm`) take
+const MOUSE_DISPATCHERS: [&str; 2] = ["movewindow", "resizewindow"];
+
+/// Dispatchers that close windows or end the session, which the keybind
+/// editor only tests after asking
+const DESTRUCTIVE_DISPATCHERS: [&str; 5] = [
+    "exit",
+    "killactive",
+    "forcekillactive",
+    "closewindow",
+    "killwindow",
+];
+
+/// Binds that share one key combination
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// The combination with its modifiers in canonical order, like `SUPER + SHIFT + q`
+    pub combo: String,
+    /// Binds in different submaps never clash
+    pub submap: Option<String>,
+    /// Keys of the clashing items, in panel order
+    pub item_keys: Vec<String>,
+}
+
+/// The name Hyprland knows a modifier by, merging its aliases
+fn canonical_modifier(name: &str) -> String {
+    let upper = name.to_ascii_uppercase();
+    match upper.as_str() {
+        "CONTROL" => "CTRL".to_string(),
+        "MOD1" => "ALT".to_string(),
+        "WIN" | "LOGO" | "MOD4" => "SUPER".to_string(),
+        _ => upper,
+    }
+}
+
+/// Modifiers as a sorted, deduplicated list, so `SUPER SHIFT`, `SHIFT SUPER`,
+/// `Super+shift` and `super_shift` all compare equal. Variables such as
+/// `$mainMod` are kept as written and come first, since they usually stand
+/// for SUPER. Unknown names go last.
+pub fn normalize_modifiers(modifiers: &str) -> Vec<String> {
+    let mut names: Vec<String> = modifiers
+        .split(|c: char| c.is_whitespace() || c == '+')
+        .filter(|token| !token.is_empty())
+        .flat_map(|token| {
+            if token.starts_with('$') {
+                vec![token.to_string()]
+            } else {
+                token
+                    .split('_')
+                    .filter(|part| !part.is_empty())
+                    .map(canonical_modifier)
+                    .collect()
+            }
+        })
+        .collect();
+    let rank = |name: &String| {
+        if name.starts_with('$') {
+            return 0;
+        }
+        MODIFIER_ORDER
+            .iter()
+            .position(|known| known == name)
+            .map_or(MODIFIER_ORDER.len() + 1, |index| index + 1)
+    };
+    names.sort_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| a.cmp(b)));
+    names.dedup();
+    names
+}
+
+/// The modifiers of the keybind editor in canonical form, for storing an edit
+pub fn canonical_modifiers(modifiers: &[String]) -> Vec<String> {
+    normalize_modifiers(&modifiers.join(" "))
+}
+
+/// Dispatchers completing what has been typed so far: those starting with it
+/// first, shortest first, then those containing it elsewhere
+pub fn dispatcher_completions(typed: &str) -> Vec<&'static str> {
+    let typed = typed.trim().to_ascii_lowercase();
+    let mut starting: Vec<&'static str> = DISPATCHERS
+        .iter()
+        .copied()
+        .filter(|name| name.starts_with(&typed))
+        .collect();
+    starting.sort_by_key(|name| (name.len(), *name));
+    let containing = DISPATCHERS
+        .iter()
+        .copied()
+        .filter(|name| !name.starts_with(&typed) && name.contains(&typed));
+    starting.into_iter().chain(containing).collect()
+}
+
+/// Whether Hyprland has a dispatcher called `name`. Names are matched exactly,
+/// as Hyprland does. Plugin dispatchers are namespaced, like `hyprexpo:expo`,
+/// and can't be checked, so they pass, as do `$variables`.
+pub fn is_known_dispatcher(name: &str) -> bool {
+    let name = name.trim();
+    name.contains(':')
+        || name.starts_with('$')
+        || DISPATCHERS.contains(&name)
+        || MOUSE_DISPATCHERS.contains(&name)
+}
+
+/// The flags of a bind keyword, like `el` for `bindel`, or `None` when the
+/// keyword isn't a bind
+pub fn bind_flags(keyword: &str) -> Option<&str> {
+    let flags = keyword.strip_prefix("bind")?;
+    flags
+        .chars()
+        .all(|flag| BIND_FLAGS.contains(flag))
+        .then_some(flags)
+}
+
+/// Check a bind line before it is saved. Modifiers may be empty, as for
+/// media keys, and any field may be a `$variable`. A bind with the `d` flag
+/// has a description before its dispatcher. Unknown dispatchers only fail
+/// in strict mode.
+pub fn validate_bind_line(line: &str, strict_dispatchers: bool) -> Result<(), String> {
+    let trimmed = line.trim();
+
+    // Skip empty lines or comments
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return Ok(());
+    }
+
+    // Submap openers and `submap = reset` closers group binds
+    if crate::config::HyprlandConfigFile::parse_submap_line(trimmed).is_some() {
+        return Ok(());
+    }
+
+    let keyword = trimmed
+        .split(|c: char| c == '=' || c.is_whitespace())
+        .next()
+        .unwrap_or_default();
+    let Some(flags) = bind_flags(keyword) else {
+        return Err("must start with a bind directive like bind, binde or bindm".to_string());
+    };
+    let Some((_, fields)) = trimmed.split_once('=') else {
+        return Err("must contain '=' separator".to_string());
+    };
+
+    let parts: Vec<&str> = fields.split(',').map(str::trim).collect();
+    if parts.len() < 2 {
+        return Err("must have at least: MODIFIERS, KEY after '='".to_string());
+    }
+    if parts[1].is_empty() {
+        return Err("key cannot be empty".to_string());
+    }
+
+    let dispatcher_index = if flags.contains('d') { 3 } else { 2 };
+    if let Some(dispatcher) = parts.get(dispatcher_index) {
+        if dispatcher.is_empty() {
+            return Err("dispatcher cannot be empty".to_string());
+        }
+        if strict_dispatchers && !is_known_dispatcher(dispatcher) {
+            return Err(format!("unknown dispatcher '{dispatcher}'"));
+        }
+    }
+    Ok(())
+}
+
+/// The dispatcher of a Binds panel value such as `SUPER + q → exec [kitty]`
+fn dispatcher(display_value: &str) -> Option<&str> {
+    let (_, value) = UI::split_submap_prefix(display_value);
+    let (_, command) = value.split_once(" → ")?;
+    command.split_whitespace().next()
+}
+
+/// Keys of the binds whose dispatcher Hyprland doesn't know, which it
+/// refuses to bind
+pub fn unknown_dispatcher_keys(items: &[ConfigItem]) -> Vec<String> {
+    items
+        .iter()
+        .filter(|item| dispatcher(&item.value).is_some_and(|name| !is_known_dispatcher(name)))
+        .map(|item| item.key.clone())
+        .collect()
+}
+
+/// Whether running `dispatcher` would close something the user may not want closed
+pub fn is_destructive_dispatcher(dispatcher: &str) -> bool {
+    DESTRUCTIVE_DISPATCHERS.contains(&dispatcher.trim().to_ascii_lowercase().as_str())
+}
+
+/// The submap, modifiers and key of a Binds panel value such as
+/// `[resize] SUPER + q → exec [kitty]`
+fn combination(display_value: &str) -> Option<(Option<String>, Vec<String>, String)> {
+    let (submap, value) = UI::split_submap_prefix(display_value);
+    let (keys, _) = value.split_once(" → ")?;
+    let (modifiers, key) = keys.trim().rsplit_once(" + ").unwrap_or(("", keys.trim()));
+    if key.is_empty() {
+        return None;
+    }
+    Some((submap, normalize_modifiers(modifiers), key.to_string()))
+}
+
+/// Groups of binds using the same modifiers and key in the same submap, in
+/// the order their first bind appears
+#[allow(clippy::type_complexity)]
+pub fn detect_keybind_conflicts(items: &[ConfigItem]) -> Vec<Conflict> {
+    let mut groups: Vec<((Option<String>, Vec<String>, String), Conflict)> = Vec::new();
+    for item in items {
+        let Some((submap, modifiers, key)) = combination(&item.value) else {
+            continue;
+        };
+        // Hyprland matches key names without regard to case
+        let combination = (submap, modifiers, key.to_lowercase());
+        match groups.iter_mut().find(|(seen, _)| *seen == combination) {
+            Some((_, conflict)) => conflict.item_keys.push(item.key.clone()),
+            None => {
+                let mut combo = combination.1.join(" + ");
+                if !combo.is_empty() {
+                    combo.push_str(" + ");
+                }
+                combo.push_str(&key);
+                let submap = combination.0.clone();
+                groups.push((
+                    combination,
+                    Conflict {
+                        combo,
+                        submap,
+                        item_keys: vec![item.key.clone()],
+                    },
+                ));
+            }
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(_, conflict)| conflict)
+        .filter(|conflict| conflict.item_keys.len() > 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::ConfigDataType;
+
+    fn bind(key: &str, value: &str) -> ConfigItem {
+        ConfigItem::new(
+            key.to_string(),
+            value.to_string(),
+            String::new(),
+            ConfigDataType::String,
+        )
+    }
+
+    #[test]
+    fn test_modifier_order_is_normalized() {
+        let expected = vec!["SUPER".to_string(), "SHIFT".to_string()];
+        assert_eq!(normalize_modifiers("SUPER SHIFT"), expected);
+        assert_eq!(normalize_modifiers("SHIFT SUPER"), expected);
+        assert_eq!(normalize_modifiers("SHIFT + SUPER"), expected);
+        assert_eq!(normalize_modifiers("super_shift"), expected);
+        assert_eq!(normalize_modifiers("SHIFT WIN SUPER"), expected);
+        assert_eq!(
+            normalize_modifiers("ALT CONTROL"),
+            vec!["CTRL".to_string(), "ALT".to_string()]
+        );
+        assert_eq!(
+            normalize_modifiers("SHIFT $mainMod"),
+            vec!["$mainMod".to_string(), "SHIFT".to_string()]
+        );
+        assert!(normalize_modifiers("").is_empty());
+    }
+
+    #[test]
+    fn test_edited_modifiers_become_canonical() {
+        let cases: &[(&[&str], &[&str])] = &[
+            (&["Super+shift"], &["SUPER", "SHIFT"]),
+            (&["SHIFT", "SUPER"], &["SUPER", "SHIFT"]),
+            (
+                &["shift", "alt", "ctrl", "super"],
+                &["SUPER", "CTRL", "ALT", "SHIFT"],
+            ),
+            (&["ALT", "CONTROL"], &["CTRL", "ALT"]),
+            (&["SUPER_ALT"], &["SUPER", "ALT"]),
+            (&["mod4", "Mod1"], &["SUPER", "ALT"]),
+            (&["SUPER", "super", "WIN"], &["SUPER"]),
+            (&["SHIFT", "$mainMod"], &["$mainMod", "SHIFT"]),
+            (&["caps", "shift"], &["SHIFT", "CAPS"]),
+            (&[], &[]),
+        ];
+        for (input, expected) in cases {
+            let input: Vec<String> = input.iter().map(|m| m.to_string()).collect();
+            assert_eq!(canonical_modifiers(&input), *expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_known_dispatchers_are_listed() {
+        for name in [
+            "exec",
+            "workspace",
+            "movetoworkspacesilent",
+            "togglespecialworkspace",
+            "pin",
+            "fullscreenstate",
+            "killactive",
+            "movefocus",
+            "submap",
+            "layoutmsg",
+        ] {
+            assert!(DISPATCHERS.contains(&name), "{name}");
+        }
+        let mut unique = DISPATCHERS.to_vec();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), DISPATCHERS.len());
+        for name in DESTRUCTIVE_DISPATCHERS {
+            assert!(DISPATCHERS.contains(&name), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_dispatcher_completions() {
+        assert_eq!(dispatcher_completions("exe"), vec!["exec", "execr"]);
+        assert_eq!(
+            dispatcher_completions("movetow"),
+            vec!["movetoworkspace", "movetoworkspacesilent"]
+        );
+        assert_eq!(
+            dispatcher_completions("Full"),
+            vec!["fullscreen", "fullscreenstate"]
+        );
+        // Prefix matches come before matches further into the name
+        let workspace = dispatcher_completions("workspace");
+        assert_eq!(workspace[0], "workspace");
+        assert!(workspace.contains(&"togglespecialworkspace"));
+        assert_eq!(dispatcher_completions("").len(), DISPATCHERS.len());
+        assert!(dispatcher_completions("nosuchthing").is_empty());
+    }
+
+    #[test]
+    fn test_unknown_dispatchers_are_flagged() {
+        for name in [
+            "exec",
+            "killactive",
+            "workspace",
+            "movetoworkspacesilent",
+            "togglespecialworkspace",
+            "layoutmsg",
+            "resizewindow",
+            "hyprexpo:expo",
+            " exec ",
+        ] {
+            assert!(is_known_dispatcher(name), "{name}");
+        }
+        for name in ["exce", "kill", "Exec", "togglefloat", "workspaces", ""] {
+            assert!(!is_known_dispatcher(name), "{name}");
+        }
+
+        let items = [
+            bind("bind_0", "SUPER + Return → exec [kitty]"),
+            bind("bind_1", "SUPER + T → exce [kitty]"),
+            bind("bind_2", "[resize] l → resizeactiv [10 0]"),
+            bind("bind_3", "SUPER + E → hyprexpo:expo [toggle]"),
+            bind("bind_4", "SUPER + q → killactive"),
+            bind("bind_5", "no dispatcher here"),
+        ];
+        assert_eq!(unknown_dispatcher_keys(&items), vec!["bind_1", "bind_2"]);
+    }
+
+    #[test]
+    fn test_bind_lines_are_validated() {
+        assert_eq!(bind_flags("bind"), Some(""));
+        assert_eq!(bind_flags("bindeld"), Some("eld"));
+        assert_eq!(bind_flags("bindx"), None);
+        assert_eq!(bind_flags("windowrule"), None);
+
+        for line in [
+            "bind = SUPER, Q, killactive",
+            "bind = , XF86AudioMute, exec, pamixer -t",
+            "bindd = SUPER, RETURN, Terminal, exec, $terminal",
+            "bindeld = ,XF86AudioRaiseVolume, Volume up, exec, $osdclient --output-volume raise",
+            "bind = $mainMod, $key, $action",
+            "bindm = $mainMod, mouse:272, movewindow",
+            "submap = resize",
+        ] {
+            assert_eq!(validate_bind_line(line, true), Ok(()), "{line}");
+        }
+
+        assert_eq!(
+            validate_bind_line("bindd = SUPER, T, Terminal, exce, kitty", true),
+            Err("unknown dispatcher 'exce'".to_string())
+        );
+        assert!(validate_bind_line("bindd = SUPER, T, Terminal, exce, kitty", false).is_ok());
+        assert_eq!(
+            validate_bind_line("bind = SUPER, , exec, kitty", false),
+            Err("key cannot be empty".to_string())
+        );
+        assert!(validate_bind_line("bindz = SUPER, T, exec, kitty", false).is_err());
+    }
+
+    #[test]
+    fn test_destructive_dispatchers() {
+        assert!(is_destructive_dispatcher("exit"));
+        assert!(is_destructive_dispatcher(" killactive "));
+        assert!(is_destructive_dispatcher("KillActive"));
+        assert!(!is_destructive_dispatcher("exec"));
+        assert!(!is_destructive_dispatcher("togglefloating"));
+    }
+
+    #[test]
+    fn test_same_combination_in_any_order_conflicts() {
+        let items = vec![
+            bind("bind_0", "SUPER + SHIFT + q → killactive"),
+            bind("bind_1", "SUPER + Return → exec [kitty]"),
+            bind("bind_2", "SHIFT + SUPER + Q → exec [firefox]"),
+            bind("bind_3", "SUPER + q → exec [kitty]"),
+        ];
+        assert_eq!(
+            detect_keybind_conflicts(&items),
+            vec![Conflict {
+                combo: "SUPER + SHIFT + q".to_string(),
+                submap: None,
+                item_keys: vec!["bind_0".to_string(), "bind_2".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_submaps_and_unparsed_values_do_not_conflict() {
+        let items = vec![
+            bind("bind_0", "escape → submap [reset]"),
+            bind("bind_1", "[resize] escape → submap [reset]"),
+            bind("bind_2", "[move] escape → submap [reset]"),
+            bind("bind_3", "not a keybind"),
+            bind("bind_4", "not a keybind"),
+        ];
+        assert!(detect_keybind_conflicts(&items).is_empty());
+    }
+}
@@ -0,0 +1,618 @@
+// Key map
+// Every key the panel list answers to, in one table. The dispatcher looks
+// keys up here and the help overlay is rendered from the same table, so the
+// two can't drift apart. Keys inside dialogs and editors are handled by the
+// dialogs themselves. The command palette lists the same actions, and a few
+// that have no key of their own. Vim mode puts a few keys of its own in
+// front of the map.
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use crate::app::FocusedPanel;
+use crate::fuzzy::fuzzy_score;
+use crate::ui::{ExportFormatType, ImportSourceType};
+
+/// What a key does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    NextPanel,
+    PreviousPanel,
+    SelectPrevious,
+    SelectNext,
+    PreviousPage,
+    NextPage,
+    SelectFirst,
+    SelectLast,
+    HalfPageUp,
+    HalfPageDown,
+    Search,
+    SearchAllPanels,
+    Quit,
+    Edit,
+    ToggleBoolean,
+    StepUp,
+    StepDown,
+    AddItem,
+    DeleteItem,
+    MoveItemUp,
+    MoveItemDown,
+    ResetToDefault,
+    Undo,
+    Redo,
+    RestoreDeleted,
+    ToggleMark,
+    ClearMarks,
+    CopyValue,
+    PasteValue,
+    TogglePlugin,
+    ToggleExec,
+    NextKeybindConflict,
+    SwitchLayout,
+    Save,
+    SaveItem,
+    Reload,
+    RestoreBackup,
+    PreviewSetting,
+    LivePreview,
+    Export,
+    Import,
+    Batch,
+    NextTheme,
+    ThemeFromHyprland,
+    Help,
+    ToggleWarnings,
+    ToggleResolvedVariables,
+    CommandPalette,
+    /// Only in the command palette
+    ExportAs(ExportFormatType),
+    /// Only in the command palette
+    ImportFrom(ImportSourceType),
+}
+
+impl KeyAction {
+    /// Whether the command palette offers the action. Moving around the
+    /// list is quicker with the keys themselves.
+    fn in_palette(self) -> bool {
+        !matches!(
+            self,
+            KeyAction::NextPanel
+                | KeyAction::PreviousPanel
+                | KeyAction::SelectPrevious
+                | KeyAction::SelectNext
+                | KeyAction::PreviousPage
+                | KeyAction::NextPage
+                | KeyAction::SelectFirst
+                | KeyAction::SelectLast
+                | KeyAction::HalfPageUp
+                | KeyAction::HalfPageDown
+                | KeyAction::PasteValue
+                | KeyAction::CommandPalette
+        )
+    }
+}
+
+/// Where a key does its action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// Even while a dialog or editor is open
+    Anywhere,
+    /// In the panel list
+    List,
+    /// In the list of one panel, before the keys of the whole list
+    Panel(FocusedPanel),
+}
+
+/// The help section a key is listed under
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Navigation,
+    Editing,
+    Files,
+    Interface,
+}
+
+impl Section {
+    pub const ALL: [Section; 4] = [
+        Section::Navigation,
+        Section::Editing,
+        Section::Files,
+        Section::Interface,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Section::Navigation => "🔍 Navigation & Search",
+            Section::Editing => "⚙️ Editing",
+            Section::Files => "💾 Saving, Import & Export",
+            Section::Interface => "🎨 Interface",
+        }
+    }
+}
+
+/// One or more keys and what they do
+#[derive(Debug)]
+pub struct KeyBinding {
+    pub keys: &'static [(KeyModifiers, KeyCode)],
+    pub action: KeyAction,
+    pub scope: Scope,
+    pub section: Section,
+    pub description: &'static str,
+}
+
+const NONE: KeyModifiers = KeyModifiers::NONE;
+const CTRL: KeyModifiers = KeyModifiers::CONTROL;
+const ALT: KeyModifiers = KeyModifiers::ALT;
+const SHIFT: KeyModifiers = KeyModifiers::SHIFT;
+
+const fn binding(
+    keys: &'static [(KeyModifiers, KeyCode)],
+    action: KeyAction,
+    scope: Scope,
+    section: Section,
+    description: &'static str,
+) -> KeyBinding {
+    KeyBinding {
+        keys,
+        action,
+        scope,
+        section,
+        description,
+    }
+}
+
+/// The keys, in the order they are looked up and listed in help
+#[rustfmt::skip]
+pub const KEY_MAP: &[KeyBinding] = &[
+    // Navigation
+    binding(&[(NONE, KeyCode::Tab), (NONE, KeyCode::Right)], KeyAction::NextPanel, Scope::List, Section::Navigation, "Next panel"),
+    binding(&[(NONE, KeyCode::BackTab), (NONE, KeyCode::Left)], KeyAction::PreviousPanel, Scope::List, Section::Navigation, "Previous panel"),
+    binding(&[(NONE, KeyCode::Up)], KeyAction::SelectPrevious, Scope::List, Section::Navigation, "Previous item"),
+    binding(&[(NONE, KeyCode::Down)], KeyAction::SelectNext, Scope::List, Section::Navigation, "Next item"),
+    binding(&[(NONE, KeyCode::PageUp)], KeyAction::PreviousPage, Scope::List, Section::Navigation, "Previous page"),
+    binding(&[(NONE, KeyCode::PageDown)], KeyAction::NextPage, Scope::List, Section::Navigation, "Next page"),
+    binding(&[(CTRL, KeyCode::Up), (CTRL, KeyCode::Char('u'))], KeyAction::HalfPageUp, Scope::List, Section::Navigation, "Up half a page"),
+    binding(&[(CTRL, KeyCode::Down)], KeyAction::HalfPageDown, Scope::List, Section::Navigation, "Down half a page"),
+    binding(&[(NONE, KeyCode::Home), (NONE, KeyCode::Char('g'))], KeyAction::SelectFirst, Scope::List, Section::Navigation, "First item"),
+    binding(&[(NONE, KeyCode::End), (NONE, KeyCode::Char('G'))], KeyAction::SelectLast, Scope::List, Section::Navigation, "Last item, on the last page"),
+    binding(&[(NONE, KeyCode::Char('/'))], KeyAction::Search, Scope::List, Section::Navigation, "Search this panel"),
+    // Many terminals report Ctrl+/ as Ctrl+7
+    binding(&[(CTRL, KeyCode::Char('/')), (CTRL, KeyCode::Char('7'))], KeyAction::SearchAllPanels, Scope::Anywhere, Section::Navigation, "Search all panels"),
+    binding(&[(NONE, KeyCode::Char('c')), (NONE, KeyCode::Char('C'))], KeyAction::NextKeybindConflict, Scope::Panel(FocusedPanel::Binds), Section::Navigation, "Jump to the next conflicting keybind"),
+    binding(&[(NONE, KeyCode::Char('v')), (NONE, KeyCode::Char('V'))], KeyAction::SwitchLayout, Scope::Panel(FocusedPanel::Layouts), Section::Navigation, "Switch between the dwindle and master options"),
+    binding(&[(NONE, KeyCode::Char('q')), (NONE, KeyCode::Esc)], KeyAction::Quit, Scope::List, Section::Navigation, "Quit"),
+    // Editing
+    binding(&[(NONE, KeyCode::Enter)], KeyAction::Edit, Scope::List, Section::Editing, "Edit the selected item"),
+    binding(&[(NONE, KeyCode::Char(' '))], KeyAction::TogglePlugin, Scope::Panel(FocusedPanel::Plugins), Section::Editing, "Enable or disable the selected plugin"),
+    binding(&[(NONE, KeyCode::Char(' '))], KeyAction::ToggleExec, Scope::Panel(FocusedPanel::Autostart), Section::Editing, "Enable or disable the selected command"),
+    binding(&[(NONE, KeyCode::Char(' '))], KeyAction::ToggleBoolean, Scope::List, Section::Editing, "Toggle the selected true/false option, or turn the marked ones on or off"),
+    binding(&[(NONE, KeyCode::Char('+')), (NONE, KeyCode::Char('=')), (SHIFT, KeyCode::Up)], KeyAction::StepUp, Scope::List, Section::Editing, "Step the selected number up"),
+    binding(&[(NONE, KeyCode::Char('-')), (SHIFT, KeyCode::Down)], KeyAction::StepDown, Scope::List, Section::Editing, "Step the selected number down"),
+    binding(&[(NONE, KeyCode::Char('i')), (NONE, KeyCode::Char('I'))], KeyAction::AddItem, Scope::List, Section::Editing, "Add a keybind, rule or line"),
+    binding(&[(NONE, KeyCode::Char('d')), (NONE, KeyCode::Char('D'))], KeyAction::DeleteItem, Scope::List, Section::Editing, "Delete the selected keybind, rule or line, or the marked ones"),
+    binding(&[(NONE, KeyCode::Char('x')), (CTRL, KeyCode::Char(' '))], KeyAction::ToggleMark, Scope::List, Section::Editing, "Mark the selected item for D or Space to act on all marked"),
+    binding(&[(NONE, KeyCode::Char('X'))], KeyAction::ClearMarks, Scope::List, Section::Editing, "Unmark every item in the panel"),
+    binding(&[(NONE, KeyCode::Char('['))], KeyAction::MoveItemUp, Scope::Panel(FocusedPanel::Autostart), Section::Editing, "Move the selected command up"),
+    binding(&[(NONE, KeyCode::Char(']'))], KeyAction::MoveItemDown, Scope::Panel(FocusedPanel::Autostart), Section::Editing, "Move the selected command down"),
+    binding(&[(ALT, KeyCode::Up)], KeyAction::MoveItemUp, Scope::List, Section::Editing, "Move the selected keybind, rule or command up"),
+    binding(&[(ALT, KeyCode::Down)], KeyAction::MoveItemDown, Scope::List, Section::Editing, "Move the selected keybind, rule or command down"),
+    binding(&[(CTRL, KeyCode::Char('d'))], KeyAction::ResetToDefault, Scope::Anywhere, Section::Editing, "Reset the selected option to its default"),
+    binding(&[(CTRL, KeyCode::Char('z'))], KeyAction::Undo, Scope::Anywhere, Section::Editing, "Undo"),
+    binding(&[(CTRL, KeyCode::Char('y'))], KeyAction::Redo, Scope::Anywhere, Section::Editing, "Redo"),
+    binding(&[(NONE, KeyCode::Char('u')), (NONE, KeyCode::Char('U'))], KeyAction::RestoreDeleted, Scope::List, Section::Editing, "Restore the last deleted item, until saving"),
+    binding(&[(CTRL, KeyCode::Char('c'))], KeyAction::CopyValue, Scope::Anywhere, Section::Editing, "Copy the value"),
+    binding(&[(CTRL, KeyCode::Char('v'))], KeyAction::PasteValue, Scope::Anywhere, Section::Editing, "Paste into a text edit"),
+    // Saving, import and export
+    binding(&[(NONE, KeyCode::Char('s')), (NONE, KeyCode::Char('S'))], KeyAction::Save, Scope::List, Section::Files, "Save the configuration"),
+    binding(&[(CTRL, KeyCode::Char('s'))], KeyAction::SaveItem, Scope::Anywhere, Section::Files, "Save only the selected item"),
+    binding(&[(NONE, KeyCode::Char('r')), (NONE, KeyCode::Char('R'))], KeyAction::Reload, Scope::List, Section::Files, "Reload the configuration"),
+    binding(&[(NONE, KeyCode::Char('o')), (NONE, KeyCode::Char('O'))], KeyAction::RestoreBackup, Scope::List, Section::Files, "Restore a backup"),
+    binding(&[(NONE, KeyCode::Char('e')), (NONE, KeyCode::Char('E'))], KeyAction::Export, Scope::List, Section::Files, "Export the configuration"),
+    binding(&[(NONE, KeyCode::Char('m')), (NONE, KeyCode::Char('M'))], KeyAction::Import, Scope::List, Section::Files, "Import a configuration"),
+    binding(&[(NONE, KeyCode::Char('b')), (NONE, KeyCode::Char('B'))], KeyAction::Batch, Scope::List, Section::Files, "Batch configuration profiles"),
+    // Interface
+    binding(&[(NONE, KeyCode::Char('n')), (NONE, KeyCode::Char('N'))], KeyAction::PreviewSetting, Scope::List, Section::Interface, "Preview the selected setting"),
+    binding(&[(NONE, KeyCode::Char('l')), (NONE, KeyCode::Char('L'))], KeyAction::LivePreview, Scope::List, Section::Interface, "Live preview; Enter keeps a value within 10s"),
+    binding(&[(NONE, KeyCode::Char('t')), (NONE, KeyCode::Char('T'))], KeyAction::NextTheme, Scope::List, Section::Interface, "Next theme"),
+    binding(&[(NONE, KeyCode::Char('h')), (NONE, KeyCode::Char('H'))], KeyAction::ThemeFromHyprland, Scope::List, Section::Interface, "Theme from Hyprland's colors"),
+    binding(&[(NONE, KeyCode::Char('w')), (NONE, KeyCode::Char('W')), (CTRL, KeyCode::Char('e'))], KeyAction::ToggleWarnings, Scope::List, Section::Interface, "Show or hide recent warnings and errors"),
+    binding(&[(NONE, KeyCode::Char('$'))], KeyAction::ToggleResolvedVariables, Scope::List, Section::Interface, "Show values with their $variables resolved, or as written"),
+    binding(&[(NONE, KeyCode::Char('?')), (NONE, KeyCode::F(1))], KeyAction::Help, Scope::List, Section::Interface, "Show this help"),
+    binding(&[(CTRL, KeyCode::Char('p'))], KeyAction::CommandPalette, Scope::List, Section::Interface, "Search every action in a command palette"),
+];
+
+/// The keys vim mode adds, looked up before the key map. The keys they
+/// replace are still there in upper case, like `L` for live preview.
+#[rustfmt::skip]
+pub const VIM_KEY_MAP: &[KeyBinding] = &[
+    binding(&[(NONE, KeyCode::Char('j'))], KeyAction::SelectNext, Scope::List, Section::Navigation, "Next item"),
+    binding(&[(NONE, KeyCode::Char('k'))], KeyAction::SelectPrevious, Scope::List, Section::Navigation, "Previous item"),
+    binding(&[(NONE, KeyCode::Char('h'))], KeyAction::PreviousPanel, Scope::List, Section::Navigation, "Previous panel"),
+    binding(&[(NONE, KeyCode::Char('l'))], KeyAction::NextPanel, Scope::List, Section::Navigation, "Next panel"),
+];
+
+/// Vim mode keys that act when pressed twice, like `dd`
+pub const VIM_DOUBLE_KEYS: [(char, KeyAction, &str); 2] = [
+    ('g', KeyAction::SelectFirst, "First item"),
+    ('d', KeyAction::DeleteItem, "Delete the selected item"),
+];
+
+/// Export formats and import sources, named for the command palette
+const EXPORT_FORMATS: [(ExportFormatType, &str); 6] = [
+    (ExportFormatType::HyprlandConf, "Export as hyprland.conf"),
+    (ExportFormatType::Json, "Export as JSON"),
+    (ExportFormatType::Toml, "Export as TOML"),
+    (ExportFormatType::Yaml, "Export as YAML"),
+    (
+        ExportFormatType::RHyprConfig,
+        "Export as an r-hyprconfig profile",
+    ),
+    (ExportFormatType::NixOS, "Export as a NixOS module"),
+];
+
+const IMPORT_SOURCES: [(ImportSourceType, &str); 5] = [
+    (ImportSourceType::LocalFile, "Import from a file"),
+    (ImportSourceType::LocalFolder, "Import from a folder"),
+    (
+        ImportSourceType::GitHubRepository,
+        "Import from a GitHub repository",
+    ),
+    (ImportSourceType::UrlDownload, "Import from a URL"),
+    (
+        ImportSourceType::NixHomeManager,
+        "Import from Nix Home Manager",
+    ),
+];
+
+/// The modifiers that matter for a key. Shift is part of the character for
+/// letters and symbols, and of Shift+Tab, so it only counts for other keys.
+fn significant_modifiers(code: KeyCode, modifiers: KeyModifiers) -> KeyModifiers {
+    let modifiers = modifiers & (CTRL | ALT | SHIFT);
+    if matches!(code, KeyCode::Char(_) | KeyCode::BackTab) {
+        modifiers - SHIFT
+    } else {
+        modifiers
+    }
+}
+
+/// Whether a key is held with a modifier that makes it a chord, like Ctrl+Z
+pub fn is_chord(code: KeyCode, modifiers: KeyModifiers) -> bool {
+    !significant_modifiers(code, modifiers).is_empty()
+}
+
+impl KeyBinding {
+    fn applies(&self, panel: FocusedPanel, modal_open: bool) -> bool {
+        match self.scope {
+            Scope::Anywhere => true,
+            Scope::List => !modal_open,
+            Scope::Panel(only) => !modal_open && only == panel,
+        }
+    }
+
+    /// The keys the way help shows them, such as `Tab, →` or `Ctrl+Z`. A
+    /// letter bound in both cases is shown once, in upper case.
+    pub fn label(&self) -> String {
+        let mut labels: Vec<String> = Vec::new();
+        for &(modifiers, code) in self.keys {
+            let label = match code {
+                KeyCode::Char(c)
+                    if modifiers.is_empty()
+                        && c.is_ascii_alphabetic()
+                        && !self.keys.contains(&(NONE, other_case(c))) =>
+                {
+                    c.to_string()
+                }
+                _ => key_label(modifiers, code),
+            };
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        labels.join(", ")
+    }
+}
+
+fn other_case(c: char) -> KeyCode {
+    if c.is_ascii_lowercase() {
+        KeyCode::Char(c.to_ascii_uppercase())
+    } else {
+        KeyCode::Char(c.to_ascii_lowercase())
+    }
+}
+
+fn key_label(modifiers: KeyModifiers, code: KeyCode) -> String {
+    let mut label = String::new();
+    if modifiers.contains(CTRL) {
+        label.push_str("Ctrl+");
+    }
+    if modifiers.contains(ALT) {
+        label.push_str("Alt+");
+    }
+    if modifiers.contains(SHIFT) {
+        label.push_str("Shift+");
+    }
+    match code {
+        KeyCode::Char(' ') => label.push_str("Space"),
+        KeyCode::Char(c) => label.extend(c.to_uppercase()),
+        KeyCode::Up => label.push('↑'),
+        KeyCode::Down => label.push('↓'),
+        KeyCode::Left => label.push('←'),
+        KeyCode::Right => label.push('→'),
+        KeyCode::BackTab => label.push_str("Shift+Tab"),
+        KeyCode::PageUp => label.push_str("PgUp"),
+        KeyCode::PageDown => label.push_str("PgDn"),
+        KeyCode::F(n) => label.push_str(&format!("F{n}")),
+        other => label.push_str(&format!("{other:?}")),
+    }
+    label
+}
+
+/// An action the command palette offers, with the keys that also do it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteEntry {
+    pub action: KeyAction,
+    pub title: &'static str,
+    pub keys: String,
+}
+
+/// Every action that can be run on `panel`, in key map order
+pub fn palette_entries(panel: FocusedPanel) -> Vec<PaletteEntry> {
+    let mut entries: Vec<PaletteEntry> = Vec::new();
+    for binding in KEY_MAP {
+        if !binding.action.in_palette() || !binding.applies(panel, false) {
+            continue;
+        }
+        // An action with keys in two places is listed once, with all of them
+        match entries
+            .iter_mut()
+            .find(|entry| entry.action == binding.action)
+        {
+            Some(entry) => entry.keys = format!("{}, {}", entry.keys, binding.label()),
+            None => entries.push(PaletteEntry {
+                action: binding.action,
+                title: binding.description,
+                keys: binding.label(),
+            }),
+        }
+    }
+    let dialog_entries = EXPORT_FORMATS
+        .iter()
+        .map(|&(format, title)| (KeyAction::ExportAs(format), title))
+        .chain(
+            IMPORT_SOURCES
+                .iter()
+                .map(|&(source, title)| (KeyAction::ImportFrom(source), title)),
+        );
+    for (action, title) in dialog_entries {
+        entries.push(PaletteEntry {
+            action,
+            title,
+            keys: String::new(),
+        });
+    }
+    entries
+}
+
+/// The palette entries matching `query`, best match first
+pub fn search_palette(panel: FocusedPanel, query: &str) -> Vec<PaletteEntry> {
+    let query = query.trim();
+    let entries = palette_entries(panel);
+    if query.is_empty() {
+        return entries;
+    }
+    let mut scored: Vec<(u8, PaletteEntry)> = entries
+        .into_iter()
+        .filter_map(|entry| fuzzy_score(query, entry.title).map(|score| (score, entry)))
+        .collect();
+    // A stable sort keeps key map order among equal scores
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+/// The action of a key where it was pressed, if it has one. Keys of a
+/// single panel win over keys of the whole list.
+pub fn action_for(
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    panel: FocusedPanel,
+    modal_open: bool,
+) -> Option<KeyAction> {
+    let key = (significant_modifiers(code, modifiers), code);
+    KEY_MAP
+        .iter()
+        .filter(|binding| binding.applies(panel, modal_open) && binding.keys.contains(&key))
+        .min_by_key(|binding| !matches!(binding.scope, Scope::Panel(_)))
+        .map(|binding| binding.action)
+}
+
+/// The action of a key in vim mode. `pending` holds a first `g` or `d`
+/// waiting for the second one, and is updated for the next key.
+pub fn vim_action_for(
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    panel: FocusedPanel,
+    modal_open: bool,
+    pending: &mut Option<char>,
+) -> Option<KeyAction> {
+    let first = pending.take();
+    let key = (significant_modifiers(code, modifiers), code);
+    if modal_open || key.0 != NONE {
+        return action_for(code, modifiers, panel, modal_open);
+    }
+
+    if let KeyCode::Char(c) = code {
+        if let Some(&(_, action, _)) = VIM_DOUBLE_KEYS.iter().find(|(double, ..)| *double == c) {
+            if first == Some(c) {
+                return Some(action);
+            }
+            *pending = Some(c);
+            return None;
+        }
+    }
+    VIM_KEY_MAP
+        .iter()
+        .find(|binding| binding.applies(panel, modal_open) && binding.keys.contains(&key))
+        .map(|binding| binding.action)
+        .or_else(|| action_for(code, modifiers, panel, modal_open))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_are_bound_once_per_scope() {
+        for (i, first) in KEY_MAP.iter().enumerate() {
+            for second in &KEY_MAP[i + 1..] {
+                if first.scope != second.scope {
+                    continue;
+                }
+                for key in first.keys {
+                    assert!(
+                        !second.keys.contains(key),
+                        "{} is bound to both {:?} and {:?}",
+                        key_label(key.0, key.1),
+                        first.action,
+                        second.action
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_panel_keys_win_over_list_keys() {
+        let space = |panel| action_for(KeyCode::Char(' '), NONE, panel, false);
+        assert_eq!(space(FocusedPanel::Plugins), Some(KeyAction::TogglePlugin));
+        assert_eq!(space(FocusedPanel::Autostart), Some(KeyAction::ToggleExec));
+        assert_eq!(space(FocusedPanel::General), Some(KeyAction::ToggleBoolean));
+        assert_eq!(
+            action_for(KeyCode::Char('['), NONE, FocusedPanel::General, false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_modifiers_and_modals() {
+        let general = FocusedPanel::General;
+        assert_eq!(
+            action_for(KeyCode::Char('S'), SHIFT, general, false),
+            Some(KeyAction::Save)
+        );
+        assert_eq!(
+            action_for(KeyCode::Char('s'), CTRL, general, false),
+            Some(KeyAction::SaveItem)
+        );
+        assert_eq!(
+            action_for(KeyCode::Up, SHIFT, general, false),
+            Some(KeyAction::StepUp)
+        );
+        assert_eq!(
+            action_for(KeyCode::BackTab, SHIFT, general, false),
+            Some(KeyAction::PreviousPanel)
+        );
+
+        // Only the keys that work anywhere get through an open editor
+        assert_eq!(action_for(KeyCode::Up, ALT, general, true), None);
+        assert_eq!(
+            action_for(KeyCode::Char('z'), CTRL, general, true),
+            Some(KeyAction::Undo)
+        );
+
+        assert!(is_chord(KeyCode::Up, SHIFT));
+        assert!(!is_chord(KeyCode::Char('S'), SHIFT));
+        assert!(is_chord(KeyCode::Char('z'), CTRL));
+    }
+
+    #[test]
+    fn test_palette_lists_actions_for_the_panel() {
+        let general = palette_entries(FocusedPanel::General);
+        let actions: Vec<KeyAction> = general.iter().map(|entry| entry.action).collect();
+        assert!(actions.contains(&KeyAction::Save));
+        assert!(actions.contains(&KeyAction::ExportAs(ExportFormatType::Json)));
+        assert!(actions.contains(&KeyAction::ImportFrom(ImportSourceType::UrlDownload)));
+        assert!(!actions.contains(&KeyAction::SelectNext));
+        assert!(!actions.contains(&KeyAction::TogglePlugin));
+
+        // The Autostart keys are merged into the entry of the whole list
+        let autostart = palette_entries(FocusedPanel::Autostart);
+        let move_up = autostart
+            .iter()
+            .find(|entry| entry.action == KeyAction::MoveItemUp)
+            .unwrap();
+        assert_eq!(move_up.keys, "[, Alt+↑");
+        assert_eq!(
+            autostart
+                .iter()
+                .filter(|entry| entry.action == KeyAction::MoveItemUp)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_palette_search() {
+        let results = search_palette(FocusedPanel::General, "export json");
+        assert_eq!(
+            results.first().map(|entry| entry.action),
+            Some(KeyAction::ExportAs(ExportFormatType::Json))
+        );
+        let results = search_palette(FocusedPanel::General, "undo");
+        assert_eq!(results[0].action, KeyAction::Undo);
+        assert!(search_palette(FocusedPanel::General, "zzzz").is_empty());
+    }
+
+    #[test]
+    fn test_labels() {
+        let label = |action| {
+            KEY_MAP
+                .iter()
+                .find(|binding| binding.action == action)
+                .unwrap()
+                .label()
+        };
+        assert_eq!(label(KeyAction::NextPanel), "Tab, →");
+        assert_eq!(label(KeyAction::Save), "S");
+        assert_eq!(label(KeyAction::SearchAllPanels), "Ctrl+/, Ctrl+7");
+        assert_eq!(label(KeyAction::StepUp), "+, =, Shift+↑");
+        assert_eq!(label(KeyAction::Help), "?, F1");
+        assert_eq!(label(KeyAction::SelectFirst), "Home, g");
+        assert_eq!(label(KeyAction::SelectLast), "End, G");
+        assert_eq!(VIM_KEY_MAP[0].label(), "j");
+    }
+
+    #[test]
+    fn test_vim_mode_keys() {
+        let general = FocusedPanel::General;
+        let mut pending = None;
+        let mut vim = |code| vim_action_for(code, NONE, general, false, &mut pending);
+
+        assert_eq!(vim(KeyCode::Char('j')), Some(KeyAction::SelectNext));
+        assert_eq!(vim(KeyCode::Char('h')), Some(KeyAction::PreviousPanel));
+        // The replaced keys are still there in upper case
+        assert_eq!(vim(KeyCode::Char('L')), Some(KeyAction::LivePreview));
+        assert_eq!(vim(KeyCode::Char('G')), Some(KeyAction::SelectLast));
+        assert_eq!(vim(KeyCode::Char('/')), Some(KeyAction::Search));
+
+        assert_eq!(vim(KeyCode::Char('g')), None);
+        assert_eq!(vim(KeyCode::Char('g')), Some(KeyAction::SelectFirst));
+        assert_eq!(vim(KeyCode::Char('d')), None);
+        assert_eq!(vim(KeyCode::Char('d')), Some(KeyAction::DeleteItem));
+        // Another key in between starts over
+        assert_eq!(vim(KeyCode::Char('d')), None);
+        assert_eq!(vim(KeyCode::Char('j')), Some(KeyAction::SelectNext));
+        assert_eq!(vim(KeyCode::Char('d')), None);
+        assert_eq!(vim(KeyCode::Char('g')), None);
+        assert_eq!(vim(KeyCode::Char('d')), None);
+
+        // Open editors keep their keys
+        let mut pending = None;
+        assert_eq!(
+            vim_action_for(KeyCode::Char('j'), NONE, general, true, &mut pending),
+            None
+        );
+        assert_eq!(
+            vim_action_for(KeyCode::Char('z'), CTRL, general, false, &mut pending),
+            Some(KeyAction::Undo)
+        );
+    }
+}
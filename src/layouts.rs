@@ -0,0 +1,142 @@
+// Dwindle and master layout options
+// The two tiling layouts each have a section of options. They share the
+// Layouts panel, which shows one layout's options after the other; this
+// table gives them their descriptions and the values they take. Ranges of
+// the numeric ones are in `options`.
+
+use crate::ui::ConfigDataType;
+
+/// A tiling layout with options of its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    Dwindle,
+    Master,
+}
+
+impl Layout {
+    /// The layout an option key such as `master:mfact` belongs to
+    pub fn of_key(key: &str) -> Option<Self> {
+        match key.split(':').next()? {
+            "dwindle" => Some(Layout::Dwindle),
+            "master" => Some(Layout::Master),
+            _ => None,
+        }
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            Layout::Dwindle => Layout::Master,
+            Layout::Master => Layout::Dwindle,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::Dwindle => "dwindle",
+            Layout::Master => "master",
+        }
+    }
+}
+
+/// The values a layout option takes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LayoutValue {
+    Boolean,
+    /// A number with its range in `options`
+    Number,
+    Keyword(&'static [&'static str]),
+}
+
+/// Layout options keyed by their hyprctl name
+#[rustfmt::skip]
+const LAYOUT_OPTIONS: &[(&str, LayoutValue, &str)] = &[
+    // dwindle
+    ("dwindle:pseudotile", LayoutValue::Boolean, "Pseudotiled windows keep their own size inside their tile"),
+    ("dwindle:force_split", LayoutValue::Keyword(&["0", "1", "2"]), "Where new windows split: 0 by the cursor, 1 left or top, 2 right or bottom"),
+    ("dwindle:preserve_split", LayoutValue::Boolean, "Keep a split's direction when the container changes size"),
+    ("dwindle:smart_split", LayoutValue::Boolean, "Split by the edge of the window the cursor is closest to"),
+    ("dwindle:smart_resizing", LayoutValue::Boolean, "Resize from the edge closest to the cursor"),
+    ("dwindle:permanent_direction_override", LayoutValue::Boolean, "Keep a preselected split direction until it is changed"),
+    ("dwindle:special_scale_factor", LayoutValue::Number, "Scale of windows on the special workspace"),
+    ("dwindle:split_width_multiplier", LayoutValue::Number, "Width multiplier when choosing a split direction"),
+    ("dwindle:use_active_for_splits", LayoutValue::Boolean, "Split the focused window rather than the one under the cursor"),
+    ("dwindle:default_split_ratio", LayoutValue::Number, "Ratio of a new split, 1.0 for even halves"),
+    ("dwindle:split_bias", LayoutValue::Keyword(&["0", "1"]), "Which window the split ratio favors: 0 the top or left one, 1 the new one"),
+    ("dwindle:precise_mouse_move", LayoutValue::Boolean, "Drop a dragged window exactly where the cursor is"),
+    // master
+    ("master:allow_small_split", LayoutValue::Boolean, "Allow a small split of the master area"),
+    ("master:special_scale_factor", LayoutValue::Number, "Scale of windows on the special workspace"),
+    ("master:mfact", LayoutValue::Number, "Share of the screen the master area takes"),
+    ("master:new_status", LayoutValue::Keyword(&["master", "slave", "inherit"]), "Whether new windows become master, slave, or take the focused window's role"),
+    ("master:new_on_top", LayoutValue::Boolean, "Put new windows at the top of the stack"),
+    ("master:new_on_active", LayoutValue::Keyword(&["before", "after", "none"]), "Put new windows before or after the focused one"),
+    ("master:orientation", LayoutValue::Keyword(&["left", "right", "top", "bottom", "center"]), "Side of the screen the master area is on"),
+    ("master:inherit_fullscreen", LayoutValue::Boolean, "A new master window takes over fullscreen"),
+    ("master:slave_count_for_center_master", LayoutValue::Number, "Slave windows needed before the center orientation centers the master"),
+    ("master:smart_resizing", LayoutValue::Boolean, "Resize from the edge closest to the cursor"),
+    ("master:drop_at_cursor", LayoutValue::Boolean, "Drop a dragged window where the cursor is"),
+    ("master:always_keep_position", LayoutValue::Boolean, "Keep the master in its position when there are no slaves"),
+    ("master:new_is_master", LayoutValue::Boolean, "New windows become master, before Hyprland 0.41 replaced it with new_status"),
+];
+
+fn layout_option(key: &str) -> Option<&'static (&'static str, LayoutValue, &'static str)> {
+    LAYOUT_OPTIONS.iter().find(|(name, _, _)| *name == key)
+}
+
+/// The hyprctl names of all layout options
+pub fn option_keys() -> impl Iterator<Item = &'static str> {
+    LAYOUT_OPTIONS.iter().map(|(name, _, _)| *name)
+}
+
+/// What a layout option does
+pub fn description(key: &str) -> Option<&'static str> {
+    layout_option(key).map(|(_, _, description)| *description)
+}
+
+/// The data type of a true/false or keyword layout option. Numbers are
+/// typed by their range instead.
+pub fn data_type(key: &str) -> Option<ConfigDataType> {
+    match layout_option(key)?.1 {
+        LayoutValue::Boolean => Some(ConfigDataType::Boolean),
+        LayoutValue::Number => None,
+        LayoutValue::Keyword(options) => Some(ConfigDataType::Keyword {
+            options: options.iter().map(|option| option.to_string()).collect(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_options_have_descriptions_and_types() {
+        assert_eq!(Layout::of_key("dwindle:pseudotile"), Some(Layout::Dwindle));
+        assert_eq!(Layout::of_key("master:mfact"), Some(Layout::Master));
+        assert_eq!(Layout::of_key("general:layout"), None);
+        assert_eq!(Layout::Master.other(), Layout::Dwindle);
+
+        assert_eq!(
+            data_type("master:orientation"),
+            Some(ConfigDataType::Keyword {
+                options: ["left", "right", "top", "bottom", "center"]
+                    .map(String::from)
+                    .to_vec()
+            })
+        );
+        assert_eq!(
+            data_type("dwindle:pseudotile"),
+            Some(ConfigDataType::Boolean)
+        );
+        assert_eq!(data_type("master:unknown"), None);
+        assert!(description("master:new_status").is_some());
+
+        // Every numeric option has a range for the slider
+        for (key, value, _) in LAYOUT_OPTIONS {
+            assert!(key.starts_with("dwindle:") || key.starts_with("master:"));
+            if *value == LayoutValue::Number {
+                assert!(crate::options::option_range(key).is_some(), "{key}");
+            }
+        }
+    }
+}
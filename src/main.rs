@@ -9,43 +9,466 @@
 
 use anyhow::Result;
 use clap::Parser;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
+mod animation;
 mod app;
+mod autostart;
 mod batch;
+mod bezier;
+mod clipboard;
+mod color;
 mod commands;
 mod config;
+mod deprecations;
+mod diff;
+mod environment;
 mod errors;
 mod file_io;
+mod fuzzy;
+mod groups;
+mod highlight;
 mod hyprctl;
 mod import_export;
+mod includes;
+mod keybinds;
+mod keymap;
+mod layouts;
 mod memory;
 mod nixos;
+mod opacity;
+mod options;
+mod placement;
 mod platform;
+mod plugins;
+mod setup;
 mod state;
+mod stderr_log;
 mod theme;
 mod ui;
 mod undo;
+mod variables;
+mod window_rules;
+mod workspace;
+mod xkb;
 
 use app::App;
 
 #[derive(Parser)]
 #[command(name = "r-hyprconfig")]
 #[command(about = "A modern TUI for managing Hyprland configuration")]
+#[command(disable_version_flag = true)]
 struct Cli {
     /// Enable debug mode
     #[arg(short, long)]
     debug: bool,
 
+    /// hyprland.conf to edit instead of the configured one. The
+    /// R_HYPRCONFIG_CONFIG environment variable does the same.
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Only edit the config file and never apply changes through hyprctl,
+    /// for a config that belongs to another machine. Same as `file_only`
+    /// in config.toml.
+    #[arg(long, visible_alias = "file-only")]
+    no_apply: bool,
+
+    /// Show the first-run setup wizard again
+    #[arg(long)]
+    setup: bool,
+
     /// Test save functionality without running TUI
     #[arg(long)]
     test_save: bool,
+
+    /// Export the live configuration without starting the TUI
+    /// (conf, json, toml, yaml, rhypr, nix)
+    #[arg(long, value_name = "FORMAT", requires = "output")]
+    export: Option<ui::ExportFormatType>,
+
+    /// Output file for --export
+    #[arg(short, long, value_name = "PATH", requires = "export")]
+    output: Option<PathBuf>,
+
+    /// Import a config file into hyprland.conf without starting the TUI
+    #[arg(long, value_name = "PATH", conflicts_with = "export")]
+    import: Option<PathBuf>,
+
+    /// With --import, print what would change instead of writing
+    #[arg(long, requires = "import")]
+    dry_run: bool,
+
+    /// Restore hyprland.conf from a backup: a path, a timestamp, or the newest if omitted
+    #[arg(
+        long,
+        value_name = "BACKUP",
+        num_args = 0..=1,
+        default_missing_value = "latest"
+    )]
+    restore: Option<String>,
+
+    /// Compare the live configuration with a hyprland.conf and print the
+    /// options that differ. Exits with 1 if any do, like diff(1).
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["export", "import"])]
+    diff: Option<PathBuf>,
+
+    /// Print the JSON Schema of the JSON export, for validating exports in CI
+    #[arg(long, hide = true)]
+    json_schema: bool,
+
+    /// Print the version, the commit it was built from and the Hyprland
+    /// version it runs against, for bug reports
+    #[arg(short = 'V', long)]
+    version: bool,
+}
+
+/// The crate version, with the commit it was built from when built from git
+fn build_version() -> String {
+    match option_env!("R_HYPRCONFIG_GIT_HASH") {
+        Some(hash) => format!("{} (git {hash})", env!("CARGO_PKG_VERSION")),
+        None => env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
+/// Print the version and whether the running Hyprland is supported
+async fn print_version() {
+    println!("r-hyprconfig {}", build_version());
+    let hyprland_version = match hyprctl::HyprCtl::new().await {
+        Ok(hyprctl) if hyprctl.status().is_connected() => hyprctl.get_version().await.ok(),
+        _ => None,
+    };
+    println!(
+        "{}",
+        hyprctl::hyprland_version_line(hyprland_version.as_deref())
+    );
+}
+
+/// Export the current Hyprland configuration for scripts and CI
+async fn run_export(format: ui::ExportFormatType, output: &Path) -> Result<()> {
+    let hyprctl = match hyprctl::HyprCtl::new().await {
+        Ok(hyprctl) if hyprctl.status().is_connected() => hyprctl,
+        _ => {
+            eprintln!("Error: {}", hyprctl::unavailable_message());
+            std::process::exit(1);
+        }
+    };
+
+    let exporter = import_export::ConfigExporter::new()?;
+    exporter
+        .export_current_config_to_path(format.to_config_format(), &hyprctl, output)
+        .await?;
+
+    println!("Exported configuration to {}", output.display());
+    Ok(())
+}
+
+/// Import a config file and merge it into hyprland.conf for provisioning
+async fn run_import(path: &Path, dry_run: bool) -> Result<()> {
+    use import_export::import::ImportOptions;
+
+    let importer = import_export::ConfigImporter::new()?;
+    let source = import_export::ImportSource::LocalFile {
+        path: path.to_path_buf(),
+    };
+    // The config save below takes its own backup, and assets are left alone
+    let options = ImportOptions {
+        create_backup: false,
+        import_assets: false,
+        ..Default::default()
+    };
+    let imported = importer.execute_import(&source, &options).await?;
+
+    if let Err(e) = imported.validate() {
+        eprintln!("Error: {} failed validation: {e}", path.display());
+        std::process::exit(1);
+    }
+
+    let config = config::Config::load().await?;
+    let existing = config.parse_hyprland_config().await.ok();
+
+    // Sections missing from the import keep what is already in hyprland.conf
+    let keybinds = if imported.keybinds.is_empty() {
+        existing.as_ref().map(|c| c.keybind_lines()).unwrap_or_default()
+    } else {
+        imported.keybind_lines()
+    };
+    let window_rules = if imported.window_rules.is_empty() {
+        existing.as_ref().map(|c| c.window_rules.clone()).unwrap_or_default()
+    } else {
+        imported.window_rule_lines()
+    };
+    let layer_rules = if imported.layer_rules.is_empty() {
+        existing.as_ref().map(|c| c.layer_rules.clone()).unwrap_or_default()
+    } else {
+        imported.layer_rule_lines()
+    };
+    // Imports carry no workspace rules, environment variables, plugins,
+    // autostart commands or variables, so the existing ones are kept
+    let workspace_rules = existing
+        .as_ref()
+        .map(|c| c.workspace_rules.clone())
+        .unwrap_or_default();
+    let env_vars = existing
+        .as_ref()
+        .map(|c| c.env_vars.clone())
+        .unwrap_or_default();
+    let plugins = existing
+        .as_ref()
+        .map(|c| c.plugins.clone())
+        .unwrap_or_default();
+    let autostart = existing
+        .as_ref()
+        .map(|c| c.autostart.clone())
+        .unwrap_or_default();
+    let variables = existing
+        .as_ref()
+        .map(|c| c.variable_lines.clone())
+        .unwrap_or_default();
+    let options = imported.to_hyprland_options();
+
+    if dry_run {
+        let (current, updated) = config
+            .render_hyprland_config_with_rules(
+                &options,
+                &keybinds,
+                &window_rules,
+                &layer_rules,
+                &workspace_rules,
+                &env_vars,
+                &plugins,
+                &autostart,
+                &variables,
+            )
+            .await?;
+        println!(
+            "Dry run: {} -> {}",
+            imported.summary(),
+            config.hyprland_config_path.display()
+        );
+        print_line_changes(&current, &updated);
+        return Ok(());
+    }
+
+    config
+        .save_hyprland_config_with_rules(
+            &options,
+            &keybinds,
+            &window_rules,
+            &layer_rules,
+            &workspace_rules,
+            &env_vars,
+            &plugins,
+            &autostart,
+            &variables,
+        )
+        .await?;
+
+    println!(
+        "Imported {} into {}",
+        imported.summary(),
+        config.hyprland_config_path.display()
+    );
+    Ok(())
+}
+
+/// Restore hyprland.conf from one of its timestamped backups
+async fn run_restore(selector: &str) -> Result<()> {
+    let config = config::Config::load().await?;
+    let backups = config.list_backups()?;
+
+    let backup = if selector == "latest" {
+        backups.first().cloned()
+    } else if Path::new(selector).is_file() {
+        Some(PathBuf::from(selector))
+    } else {
+        backups
+            .iter()
+            .find(|b| b.to_string_lossy().contains(selector))
+            .cloned()
+    };
+
+    let Some(backup) = backup else {
+        eprintln!("Error: no backup matching '{selector}'");
+        if backups.is_empty() {
+            eprintln!("No backups found next to {}", config.hyprland_config_path.display());
+        } else {
+            eprintln!("Available backups:");
+            for backup in &backups {
+                eprintln!("  {}", backup.display());
+            }
+        }
+        std::process::exit(1);
+    };
+
+    config.restore_backup(&backup).await?;
+    println!(
+        "Restored {} from {}",
+        config.hyprland_config_path.display(),
+        backup.display()
+    );
+    Ok(())
+}
+
+/// Print the options that differ between the live configuration and a file
+/// for drift checks. Exits with 1 if there are differences and 2 on errors.
+async fn run_diff(path: &Path) -> Result<()> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Error: failed to read {}: {e}", path.display());
+            std::process::exit(2);
+        }
+    };
+    let hyprctl = match hyprctl::HyprCtl::new().await {
+        Ok(hyprctl) if hyprctl.status().is_connected() => hyprctl,
+        _ => {
+            eprintln!("Error: {}", hyprctl::unavailable_message());
+            std::process::exit(2);
+        }
+    };
+
+    let file: HashMap<String, String> =
+        nixos::parser::HyprlandNixSettings::from_hyprland_conf(&content)
+            .options
+            .into_iter()
+            .collect();
+    let mut live = match hyprctl.get_all_options().await {
+        Ok(live) => live,
+        Err(e) => {
+            eprintln!("Error: failed to read the live options: {e}");
+            std::process::exit(2);
+        }
+    };
+    for option in file.keys() {
+        if !live.contains_key(option) {
+            if let Ok(value) = hyprctl.get_option(option).await {
+                live.insert(option.clone(), value);
+            }
+        }
+    }
+    // Options the live config leaves at their default aren't part of it
+    let set = hyprctl.option_set_flags();
+    live.retain(|option, _| file.contains_key(option) || set.get(option) == Some(&true));
+
+    let differences = diff::option_differences(&live, &file);
+    if differences.is_empty() {
+        println!("No differences.");
+        return Ok(());
+    }
+    print_option_differences(&differences, path, std::io::stdout().is_terminal());
+    std::process::exit(1);
+}
+
+/// Print option differences section by section, live values as removals and
+/// the file's as additions
+fn print_option_differences(
+    differences: &[(String, Vec<diff::OptionDifference>)],
+    path: &Path,
+    color: bool,
+) {
+    use crossterm::style::{Color, Stylize};
+
+    let paint = |text: String, fg: Color| {
+        if color {
+            text.with(fg).to_string()
+        } else {
+            text
+        }
+    };
+    println!("{}", paint("--- live (hyprctl)".to_string(), Color::Red));
+    println!("{}", paint(format!("+++ {}", path.display()), Color::Green));
+    for (section, options) in differences {
+        let title = match section.as_str() {
+            "" => "(top level)",
+            section => section,
+        };
+        println!("{}", paint(format!("[{title}]"), Color::Cyan));
+        for difference in options {
+            let side = |value: &Option<String>| match value {
+                Some(value) => format!("{} = {value}", difference.option),
+                None => format!("{} (not set)", difference.option),
+            };
+            let (old, new) = (side(&difference.old), side(&difference.new));
+            println!("{}", paint(format!("- {old}"), Color::Red));
+            println!("{}", paint(format!("+ {new}"), Color::Green));
+        }
+    }
+    let count: usize = differences.iter().map(|(_, options)| options.len()).sum();
+    println!("{count} option(s) differ");
+}
+
+/// Print lines removed from and added to a config, in file order
+fn print_line_changes(current: &str, updated: &str) {
+    let current_lines: Vec<&str> = current.lines().filter(|l| !l.trim().is_empty()).collect();
+    let updated_lines: Vec<&str> = updated.lines().filter(|l| !l.trim().is_empty()).collect();
+
+    let mut changes = 0;
+    for line in current_lines.iter().filter(|l| !updated_lines.contains(l)) {
+        println!("- {line}");
+        changes += 1;
+    }
+    for line in updated_lines.iter().filter(|l| !current_lines.contains(l)) {
+        println!("+ {line}");
+        changes += 1;
+    }
+
+    if changes == 0 {
+        println!("No changes.");
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    if cli.version {
+        print_version().await;
+        return Ok(());
+    }
+
+    if cli.json_schema {
+        let schema = import_export::schema::json_schema();
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+        return Ok(());
+    }
+
+    let env_path = std::env::var_os(config::CONFIG_PATH_ENV);
+    match config::resolve_config_override(cli.config.clone(), env_path) {
+        Ok(Some(path)) => config::set_hyprland_config_override(path),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+
+    if cli.no_apply {
+        config::set_file_only_override();
+    }
+
+    if let (Some(format), Some(output)) = (cli.export, cli.output.as_deref()) {
+        return run_export(format, output).await;
+    }
+
+    if let Some(selector) = cli.restore.as_deref() {
+        return run_restore(selector).await;
+    }
+
+    if let Some(path) = cli.import.as_deref() {
+        return run_import(path, cli.dry_run).await;
+    }
+
+    if let Some(path) = cli.diff.as_deref() {
+        return run_diff(path).await;
+    }
+
     let mut app = App::new(cli.debug).await?;
+    if cli.setup {
+        app.start_setup_wizard();
+    }
 
     if cli.test_save {
         app.test_save_functionality().await?;
@@ -134,6 +134,7 @@ impl Default for CommonStrings {
 }
 
 /// Object pool for reusing expensive objects
+#[allow(clippy::type_complexity)]
 pub struct ObjectPool<T> {
     objects: Arc<Mutex<Vec<T>>>,
     create_fn: Arc<dyn Fn() -> T + Send + Sync>,
@@ -2,7 +2,18 @@
 // Converts between traditional Hyprland config and NixOS Nix expressions
 
 use super::{NixConfigType, NixOSConfig};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+/// A node of the generated Home Manager `settings` attrset. Attrsets are
+/// sorted by name so the output doesn't depend on hash order.
+#[derive(Debug, Clone, PartialEq)]
+enum NixNode {
+    /// A rendered Nix value
+    Value(String),
+    Attrs(BTreeMap<String, NixNode>),
+    /// Config lines of one keyword, like every `bind`
+    List(Vec<String>),
+}
 
 pub struct ConfigConverter;
 
@@ -20,7 +31,7 @@ impl ConfigConverter {
         layer_rules: &[String],
         target_type: NixConfigType,
     ) -> Result<String, anyhow::Error> {
-        match target_type {
+        let nix_config = match target_type {
             NixConfigType::HomeManager => self.generate_home_manager_config(
                 traditional_config,
                 keybinds,
@@ -42,7 +53,9 @@ impl ConfigConverter {
                 window_rules,
                 layer_rules,
             ),
-        }
+        }?;
+        // The flake templates embed the module text at their own depth
+        Ok(super::formatter::pretty_print(&nix_config))
     }
 
     // Convert NixOS config back to traditional format
@@ -105,62 +118,155 @@ impl ConfigConverter {
         window_rules: &[String],
         layer_rules: &[String],
     ) -> Result<String, anyhow::Error> {
+        let mut settings = BTreeMap::new();
+        let mut extra_config = Vec::new();
+
+        // `general:gaps_in` nests as `general.gaps_in`, deeper keys like
+        // `decoration:blur:size` as `decoration.blur.size`
+        for (key, value) in traditional_config {
+            let path: Vec<&str> = key.split(':').collect();
+            Self::insert_setting(&mut settings, &path, self.convert_value_to_nix(value));
+        }
+
+        // Submap binds only work in order inside their `submap` block, which
+        // the settings lists can't express, so they go to extraConfig
+        let mut in_submap = false;
+        for keybind in keybinds {
+            let (keyword, body) =
+                Self::split_keyword(&self.convert_keybind_to_nix(keybind), "bind");
+            if keyword == "submap" {
+                in_submap = body != "reset";
+                extra_config.push(format!("submap = {body}"));
+            } else if in_submap {
+                extra_config.push(format!("{keyword} = {body}"));
+            } else {
+                Self::push_list_entry(&mut settings, &keyword, body);
+            }
+        }
+
+        // windowrule, windowrulev2 and layerrule lines each get their own list
+        for (rules, default_keyword) in [(window_rules, "windowrule"), (layer_rules, "layerrule")] {
+            for rule in rules {
+                let (keyword, body) = Self::split_keyword(rule, default_keyword);
+                Self::push_list_entry(&mut settings, &keyword, body);
+            }
+        }
+
         let mut nix_config = String::new();
 
         nix_config.push_str("{ config, pkgs, ... }: {\n");
         nix_config.push_str("  wayland.windowManager.hyprland = {\n");
         nix_config.push_str("    enable = true;\n");
         nix_config.push_str("    settings = {\n");
+        self.render_attrs(&settings, 6, &mut nix_config);
+        nix_config.push_str("    };\n");
 
-        // Convert traditional config sections to Nix attribute sets
-        let config_sections = self.group_config_by_section(traditional_config);
-
-        for (section, options) in config_sections {
-            if !options.is_empty() {
-                nix_config.push_str(&format!("      {section} = {{\n"));
-                for (key, value) in options {
-                    let nix_value = self.convert_value_to_nix(&value);
-                    nix_config.push_str(&format!("        {key} = {nix_value};\n"));
-                }
-                nix_config.push_str("      };\n");
+        if !extra_config.is_empty() {
+            nix_config.push_str("    extraConfig = ''\n");
+            for line in &extra_config {
+                nix_config.push_str(&format!(
+                    "      {}\n",
+                    self.escape_nix_indented_string(line)
+                ));
             }
+            nix_config.push_str("    '';\n");
         }
 
-        // Add keybinds
-        if !keybinds.is_empty() {
-            nix_config.push_str("      bind = [\n");
-            for keybind in keybinds {
-                let clean_bind = self.convert_keybind_to_nix(keybind);
-                nix_config.push_str(&format!("        \"{clean_bind}\"\n"));
-            }
-            nix_config.push_str("      ];\n");
+        nix_config.push_str("  };\n");
+        nix_config.push_str("}\n");
+
+        Ok(nix_config)
+    }
+
+    /// Set `path` in the nested settings. A value in the way of a deeper key
+    /// is replaced by an attrset.
+    fn insert_setting(settings: &mut BTreeMap<String, NixNode>, path: &[&str], value: String) {
+        let Some((name, rest)) = path.split_first() else {
+            return;
+        };
+        if rest.is_empty() {
+            settings.insert(name.to_string(), NixNode::Value(value));
+            return;
+        }
+        let node = settings
+            .entry(name.to_string())
+            .or_insert_with(|| NixNode::Attrs(BTreeMap::new()));
+        if !matches!(node, NixNode::Attrs(_)) {
+            *node = NixNode::Attrs(BTreeMap::new());
+        }
+        if let NixNode::Attrs(children) = node {
+            Self::insert_setting(children, rest, value);
         }
+    }
 
-        // Add window rules
-        if !window_rules.is_empty() {
-            nix_config.push_str("      windowrule = [\n");
-            for rule in window_rules {
-                let clean_rule = self.convert_rule_to_nix(rule);
-                nix_config.push_str(&format!("        \"{clean_rule}\"\n"));
-            }
-            nix_config.push_str("      ];\n");
+    fn push_list_entry(settings: &mut BTreeMap<String, NixNode>, keyword: &str, entry: String) {
+        let node = settings
+            .entry(keyword.to_string())
+            .or_insert_with(|| NixNode::List(Vec::new()));
+        if !matches!(node, NixNode::List(_)) {
+            *node = NixNode::List(Vec::new());
         }
+        if let NixNode::List(entries) = node {
+            entries.push(entry);
+        }
+    }
 
-        // Add layer rules
-        if !layer_rules.is_empty() {
-            nix_config.push_str("      layerrule = [\n");
-            for rule in layer_rules {
-                let clean_rule = self.convert_rule_to_nix(rule);
-                nix_config.push_str(&format!("        \"{clean_rule}\"\n"));
+    /// Split `keyword = body` off a config line. Lines without a keyword,
+    /// like a bare `SUPER, Q, exec, kitty`, use `default_keyword`.
+    fn split_keyword(line: &str, default_keyword: &str) -> (String, String) {
+        if let Some((keyword, body)) = line.split_once('=') {
+            let keyword = keyword.trim();
+            if !keyword.is_empty()
+                && keyword
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return (keyword.to_string(), body.trim().to_string());
             }
-            nix_config.push_str("      ];\n");
         }
+        (default_keyword.to_string(), line.trim().to_string())
+    }
 
-        nix_config.push_str("    };\n");
-        nix_config.push_str("  };\n");
-        nix_config.push_str("}\n");
+    fn render_attrs(&self, attrs: &BTreeMap<String, NixNode>, indent: usize, out: &mut String) {
+        let pad = " ".repeat(indent);
+        for (name, node) in attrs {
+            let name = self.nix_attr_name(name);
+            match node {
+                NixNode::Value(value) => out.push_str(&format!("{pad}{name} = {value};\n")),
+                NixNode::Attrs(children) => {
+                    out.push_str(&format!("{pad}{name} = {{\n"));
+                    self.render_attrs(children, indent + 2, out);
+                    out.push_str(&format!("{pad}}};\n"));
+                }
+                NixNode::List(entries) => {
+                    out.push_str(&format!("{pad}{name} = [\n"));
+                    for entry in entries {
+                        out.push_str(&format!("{pad}  \"{}\"\n", self.escape_nix_string(entry)));
+                    }
+                    out.push_str(&format!("{pad}];\n"));
+                }
+            }
+        }
+    }
 
-        Ok(nix_config)
+    /// Attribute names that aren't plain identifiers, like `col.active_border`
+    /// or `$mod`, have to be quoted
+    fn nix_attr_name(&self, name: &str) -> String {
+        const KEYWORDS: [&str; 9] = [
+            "assert", "else", "if", "in", "inherit", "let", "rec", "then", "with",
+        ];
+        let is_identifier = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '\''));
+        if is_identifier && !KEYWORDS.contains(&name) {
+            name.to_string()
+        } else {
+            format!("\"{}\"", self.escape_nix_string(name))
+        }
     }
 
     #[allow(dead_code)]
@@ -260,41 +366,18 @@ impl ConfigConverter {
     }
 
     // Helper methods for conversion
-    fn group_config_by_section(
-        &self,
-        config: &HashMap<String, String>,
-    ) -> HashMap<String, Vec<(String, String)>> {
-        let mut sections: HashMap<String, Vec<(String, String)>> = HashMap::new();
-
-        for (key, value) in config {
-            let (section, option) = if key.contains(':') {
-                let parts: Vec<&str> = key.splitn(2, ':').collect();
-                (parts[0].to_string(), parts[1].to_string())
-            } else {
-                // If no section prefix, assume it's general
-                ("general".to_string(), key.clone())
-            };
-
-            sections
-                .entry(section)
-                .or_default()
-                .push((option, value.clone()));
-        }
-
-        sections
-    }
-
     fn convert_value_to_nix(&self, value: &str) -> String {
         // Handle different value types for Nix
         if value == "true" || value == "false" {
             // Boolean values don't need quotes
             value.to_string()
-        } else if value.parse::<i32>().is_ok() || value.parse::<f32>().is_ok() {
-            // Numeric values don't need quotes
+        } else if value.parse::<f64>().is_ok()
+            && value.chars().any(|c| c.is_ascii_digit())
+            && value.chars().all(|c| matches!(c, '0'..='9' | '.' | '-'))
+        {
+            // Numeric values don't need quotes. The character check keeps
+            // `inf`, `NaN` and exponents, which Rust parses, as strings.
             value.to_string()
-        } else if value.starts_with("rgb(") || value.starts_with("rgba(") {
-            // Color values - keep as string but format nicely
-            format!("\"{value}\"")
         } else {
             // String values need quotes
             format!("\"{}\"", self.escape_nix_string(value))
@@ -348,11 +431,18 @@ impl ConfigConverter {
     }
 
     fn escape_nix_string(&self, s: &str) -> String {
-        // Escape special characters for Nix strings
+        // Escape special characters for Nix strings. `${` would start an
+        // interpolation; a lone `$` like `$mainMod` is fine as it is.
         s.replace('\\', "\\\\")
             .replace('"', "\\\"")
             .replace('\n', "\\n")
             .replace('\t', "\\t")
+            .replace("${", "\\${")
+    }
+
+    /// Escape a line for a `'' ... ''` indented string
+    fn escape_nix_indented_string(&self, s: &str) -> String {
+        s.replace("''", "'''").replace("${", "''${")
     }
 
     // Generate other config types
@@ -386,6 +476,7 @@ impl ConfigConverter {
             window_rules,
             layer_rules,
         )?;
+        let home_manager_config = home_manager_config.trim_end();
 
         // Wrap in flake structure
         let flake_config = format!(
@@ -512,6 +603,30 @@ mod tests {
         assert!(nix_config.contains("\"blur, waybar\""));
     }
 
+    #[test]
+    fn test_flake_export_indents_the_embedded_module() {
+        let converter = ConfigConverter::new();
+        let mut traditional_config = HashMap::new();
+        traditional_config.insert("general:gaps_in".to_string(), "5".to_string());
+
+        let nix_config = converter
+            .traditional_to_nixos(
+                &traditional_config,
+                &[],
+                &[],
+                &[],
+                NixConfigType::FlakeHomeManager,
+            )
+            .unwrap();
+
+        let lines: Vec<&str> = nix_config.lines().collect();
+        assert!(lines.contains(&"        ({ config, pkgs, ... }: {"));
+        assert!(lines.contains(&"          wayland.windowManager.hyprland = {"));
+        assert!(lines.contains(&"                gaps_in = 5;"));
+        assert!(lines.contains(&"        })"));
+        assert!(lines.iter().all(|line| line.trim_end() == *line));
+    }
+
     #[test]
     fn test_value_conversion() {
         let converter = ConfigConverter::new();
@@ -551,4 +666,79 @@ mod tests {
             "SUPER, Q, exec, kitty"
         );
     }
+
+    #[test]
+    fn test_home_manager_golden_file() {
+        let converter = ConfigConverter::new();
+
+        let traditional_config: HashMap<String, String> = [
+            ("general:gaps_in", "5"),
+            (
+                "general:col.active_border",
+                "rgba(33ccffee) rgba(00ff99ee) 45deg",
+            ),
+            ("general:layout", "dwindle"),
+            ("decoration:rounding", "10"),
+            ("decoration:blur:enabled", "true"),
+            ("decoration:blur:size", "8"),
+            ("decoration:active_opacity", "0.95"),
+            ("input:kb_layout", "us"),
+            ("input:touchpad:natural_scroll", "false"),
+            ("misc:disable_hyprland_logo", "true"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+        let keybinds: Vec<String> = [
+            "bind = $mainMod, Q, exec, kitty",
+            "bind = SUPER, E, exec, notify-send \"Hello ${USER}\"",
+            "binde = SUPER, L, resizeactive, 10 0",
+            "bindm = SUPER, mouse:272, movewindow",
+            "submap = resize",
+            "binde = , right, resizeactive, 10 0",
+            "bind = , escape, submap, reset",
+            "submap = reset",
+        ]
+        .map(String::from)
+        .to_vec();
+        let window_rules = vec![
+            "windowrule = float, ^(pavucontrol)$".to_string(),
+            "windowrulev2 = opacity 0.9 0.8, class:^(code)$".to_string(),
+        ];
+        let layer_rules = vec!["blur, waybar".to_string()];
+
+        let nix_config = converter
+            .traditional_to_nixos(
+                &traditional_config,
+                &keybinds,
+                &window_rules,
+                &layer_rules,
+                NixConfigType::HomeManager,
+            )
+            .unwrap();
+        assert_eq!(nix_config, include_str!("testdata/home_manager.nix"));
+    }
+
+    #[test]
+    fn test_nix_escaping() {
+        let converter = ConfigConverter::new();
+
+        assert_eq!(
+            converter.convert_value_to_nix("say \"hi\" to ${USER} and $HOME"),
+            "\"say \\\"hi\\\" to \\${USER} and $HOME\""
+        );
+        assert_eq!(converter.convert_value_to_nix("inf"), "\"inf\"");
+        assert_eq!(converter.convert_value_to_nix("-1"), "-1");
+        assert_eq!(converter.nix_attr_name("gaps_in"), "gaps_in");
+        assert_eq!(
+            converter.nix_attr_name("col.active_border"),
+            "\"col.active_border\""
+        );
+        assert_eq!(converter.nix_attr_name("$mod"), "\"$mod\"");
+        assert_eq!(converter.nix_attr_name("in"), "\"in\"");
+        assert_eq!(
+            converter.escape_nix_indented_string("echo ''${HOME}''"),
+            "echo '''''${HOME}'''"
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use super::parser::{NixParser, NixValue};
 use super::{NixConfigLocation, NixConfigType, NixOSEnvironment};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -135,6 +136,7 @@ fn discover_config_locations() -> Vec<NixConfigLocation> {
         path: system_config.clone(),
         exists: system_config.exists(),
         has_hyprland_config: system_config.exists() && check_hyprland_in_file(&system_config),
+        flake_path: None,
     });
 
     // Home Manager configuration (traditional)
@@ -146,6 +148,7 @@ fn discover_config_locations() -> Vec<NixConfigLocation> {
             exists: home_manager_config.exists(),
             has_hyprland_config: home_manager_config.exists()
                 && check_hyprland_in_file(&home_manager_config),
+            flake_path: None,
         });
     }
 
@@ -155,41 +158,108 @@ fn discover_config_locations() -> Vec<NixConfigLocation> {
     locations
 }
 
+/// Directories that commonly hold a system or Home Manager flake
+const FLAKE_DIRS: [&str; 7] = [
+    "/etc/nixos",
+    "~/.config/nixpkgs",
+    "~/.config/home-manager",
+    "~/.config/nixos",
+    "~/dotfiles",
+    "~/.dotfiles",
+    ".", // Current directory
+];
+
 fn discover_flake_configs() -> Vec<NixConfigLocation> {
-    let mut flake_locations = Vec::new();
-
-    // Common flake locations to check
-    let flake_paths = [
-        "/etc/nixos/flake.nix",
-        "~/.config/nixos/flake.nix",
-        "~/.config/home-manager/flake.nix",
-        "./flake.nix", // Current directory
-    ];
+    let dirs: Vec<PathBuf> = FLAKE_DIRS
+        .iter()
+        .filter_map(|dir| shellexpand::full(dir).ok())
+        .map(|dir| PathBuf::from(&*dir))
+        .collect();
+    discover_flake_configs_in(&dirs)
+}
 
-    for path_pattern in &flake_paths {
-        if let Ok(expanded) = shellexpand::full(path_pattern) {
-            let flake_path = PathBuf::from(&*expanded);
-            if flake_path.exists() {
-                // Determine if this is a system or home-manager flake
-                let config_type = if flake_path.to_string_lossy().contains("home-manager") {
-                    NixConfigType::FlakeHomeManager
-                } else {
-                    NixConfigType::FlakeSystem
-                };
-
-                flake_locations.push(NixConfigLocation {
-                    config_type,
-                    path: flake_path.clone(),
-                    exists: true,
-                    has_hyprland_config: check_hyprland_in_flake(&flake_path),
-                });
-            }
+fn discover_flake_configs_in(dirs: &[PathBuf]) -> Vec<NixConfigLocation> {
+    let mut flake_locations: Vec<NixConfigLocation> = Vec::new();
+
+    for dir in dirs {
+        let flake_path = dir.join("flake.nix");
+        let Ok(content) = fs::read_to_string(&flake_path) else {
+            continue;
+        };
+        // Flakes for dev shells or packages aren't configs
+        let Some(config_type) = classify_flake(&content) else {
+            continue;
+        };
+        // `~/.config/nixpkgs` and `.` can be the same directory
+        let flake_dir = dir.canonicalize().unwrap_or_else(|_| dir.clone());
+        if flake_locations
+            .iter()
+            .any(|loc| loc.flake_path.as_ref() == Some(&flake_dir))
+        {
+            continue;
         }
+
+        flake_locations.push(NixConfigLocation {
+            config_type,
+            has_hyprland_config: check_hyprland_in_flake(&content)
+                || check_hyprland_in_modules(dir),
+            path: flake_path,
+            exists: true,
+            flake_path: Some(flake_dir),
+        });
     }
 
     flake_locations
 }
 
+/// Whether a flake configures a home (`homeConfigurations`, or Home Manager
+/// used as a NixOS module) or only a system (`nixosConfigurations`).
+/// `None` for flakes that output neither.
+fn classify_flake(content: &str) -> Option<NixConfigType> {
+    let attributes = NixParser::new()
+        .parse_expression(content)
+        .map(|expression| expression.attributes)
+        .unwrap_or_default();
+
+    let (has_home, has_system, uses_home_manager_module) = match attributes.get("outputs") {
+        Some(outputs) => (
+            mentions(outputs, "homeConfigurations"),
+            mentions(outputs, "nixosConfigurations"),
+            mentions(outputs, "nixosModules.home-manager"),
+        ),
+        // Outputs this parser can't read, fall back to the raw text
+        None => (
+            content.contains("homeConfigurations"),
+            content.contains("nixosConfigurations"),
+            content.contains("nixosModules.home-manager"),
+        ),
+    };
+    let has_home_manager_input = match attributes.get("inputs") {
+        Some(NixValue::AttrSet(inputs)) => inputs.contains_key("home-manager"),
+        _ => content.contains("home-manager"),
+    };
+
+    if has_home || (has_system && has_home_manager_input && uses_home_manager_module) {
+        Some(NixConfigType::FlakeHomeManager)
+    } else if has_system {
+        Some(NixConfigType::FlakeSystem)
+    } else {
+        None
+    }
+}
+
+/// Whether `needle` appears in an attribute name or unevaluated expression
+fn mentions(value: &NixValue, needle: &str) -> bool {
+    match value {
+        NixValue::Expression(raw) => raw.contains(needle),
+        NixValue::List(items) => items.iter().any(|item| mentions(item, needle)),
+        NixValue::AttrSet(attrs) => attrs
+            .iter()
+            .any(|(name, value)| name == needle || mentions(value, needle)),
+        _ => false,
+    }
+}
+
 fn check_hyprland_in_file(path: &Path) -> bool {
     if let Ok(content) = fs::read_to_string(path) {
         // Look for Hyprland-related configuration
@@ -209,23 +279,30 @@ fn check_hyprland_in_file(path: &Path) -> bool {
     false
 }
 
-fn check_hyprland_in_flake(path: &Path) -> bool {
-    if let Ok(content) = fs::read_to_string(path) {
-        // Look for Hyprland in flake inputs or configuration
-        let hyprland_indicators = [
-            "hyprland.url",
-            "inputs.hyprland",
-            "programs.hyprland",
-            "wayland.windowManager.hyprland",
-        ];
+fn check_hyprland_in_flake(content: &str) -> bool {
+    // Look for Hyprland in flake inputs or configuration
+    let hyprland_indicators = [
+        "hyprland.url",
+        "inputs.hyprland",
+        "programs.hyprland",
+        "wayland.windowManager.hyprland",
+    ];
 
-        for indicator in &hyprland_indicators {
-            if content.contains(indicator) {
-                return true;
-            }
-        }
-    }
-    false
+    hyprland_indicators
+        .iter()
+        .any(|indicator| content.contains(indicator))
+}
+
+/// Flakes usually keep their settings in modules next to `flake.nix`
+fn check_hyprland_in_modules(dir: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "nix"))
+        .any(|path| check_hyprland_in_file(&path))
 }
 
 #[cfg(test)]
@@ -257,4 +334,143 @@ mod tests {
             PathBuf::from("/etc/nixos/configuration.nix")
         );
     }
+
+    const HOME_FLAKE: &str = r#"{
+  description = "My home";
+
+  inputs = {
+    nixpkgs.url = "github:nixos/nixpkgs/nixos-unstable";
+    home-manager.url = "github:nix-community/home-manager";
+  };
+
+  outputs = { nixpkgs, home-manager, ... }@inputs: {
+    homeConfigurations."me@laptop" = home-manager.lib.homeManagerConfiguration {
+      pkgs = nixpkgs.legacyPackages.x86_64-linux;
+      modules = [ ./home.nix ];
+    };
+  };
+}
+"#;
+
+    const SYSTEM_FLAKE: &str = r#"{
+  inputs.nixpkgs.url = "github:nixos/nixpkgs/nixos-unstable";
+
+  outputs = { self, nixpkgs }: {
+    # homeConfigurations come later
+    nixosConfigurations.laptop = nixpkgs.lib.nixosSystem {
+      system = "x86_64-linux";
+      modules = [ ./configuration.nix ];
+    };
+  };
+}
+"#;
+
+    const SYSTEM_FLAKE_WITH_HOME_MANAGER: &str = r#"{
+  inputs = {
+    nixpkgs.url = "github:nixos/nixpkgs/nixos-unstable";
+    home-manager = {
+      url = "github:nix-community/home-manager";
+      inputs.nixpkgs.follows = "nixpkgs";
+    };
+  };
+
+  outputs = inputs: {
+    nixosConfigurations.laptop = inputs.nixpkgs.lib.nixosSystem {
+      modules = [
+        ./configuration.nix
+        inputs.home-manager.nixosModules.home-manager
+      ];
+    };
+  };
+}
+"#;
+
+    const DEV_SHELL_FLAKE: &str = r#"{
+  inputs.flake-utils.url = "github:numtide/flake-utils";
+  outputs = { nixpkgs, flake-utils, ... }:
+    flake-utils.lib.eachDefaultSystem (system: {
+      devShells.default = nixpkgs.legacyPackages.${system}.mkShell { };
+    });
+}
+"#;
+
+    #[test]
+    fn test_flake_classification() {
+        assert_eq!(
+            classify_flake(HOME_FLAKE),
+            Some(NixConfigType::FlakeHomeManager)
+        );
+        // The comment mentioning homeConfigurations doesn't count
+        assert_eq!(
+            classify_flake(SYSTEM_FLAKE),
+            Some(NixConfigType::FlakeSystem)
+        );
+        assert_eq!(
+            classify_flake(SYSTEM_FLAKE_WITH_HOME_MANAGER),
+            Some(NixConfigType::FlakeHomeManager)
+        );
+        assert_eq!(classify_flake(DEV_SHELL_FLAKE), None);
+    }
+
+    #[test]
+    fn test_flake_discovery_in_directories() {
+        let home = tempfile::TempDir::new().unwrap();
+        let dotfiles = tempfile::TempDir::new().unwrap();
+        let project = tempfile::TempDir::new().unwrap();
+        std::fs::write(home.path().join("flake.nix"), HOME_FLAKE).unwrap();
+        std::fs::write(
+            home.path().join("home.nix"),
+            "{ wayland.windowManager.hyprland.enable = true; }",
+        )
+        .unwrap();
+        std::fs::write(dotfiles.path().join("flake.nix"), SYSTEM_FLAKE).unwrap();
+        std::fs::write(project.path().join("flake.nix"), DEV_SHELL_FLAKE).unwrap();
+
+        let locations = discover_flake_configs_in(&[
+            home.path().to_path_buf(),
+            dotfiles.path().to_path_buf(),
+            project.path().to_path_buf(),
+            home.path().to_path_buf(),
+        ]);
+        assert_eq!(locations.len(), 2);
+
+        assert_eq!(locations[0].config_type, NixConfigType::FlakeHomeManager);
+        assert_eq!(locations[0].path, home.path().join("flake.nix"));
+        assert_eq!(
+            locations[0].flake_path,
+            Some(home.path().canonicalize().unwrap())
+        );
+        assert!(locations[0].has_hyprland_config);
+
+        assert_eq!(locations[1].config_type, NixConfigType::FlakeSystem);
+        assert!(!locations[1].has_hyprland_config);
+    }
+
+    #[test]
+    fn test_flake_is_preferred_over_plain_home_manager() {
+        let plain = NixConfigLocation {
+            config_type: NixConfigType::HomeManager,
+            path: PathBuf::from("/home/me/.config/nixpkgs/home.nix"),
+            exists: true,
+            has_hyprland_config: true,
+            flake_path: None,
+        };
+        let flake = NixConfigLocation {
+            config_type: NixConfigType::FlakeHomeManager,
+            path: PathBuf::from("/home/me/.config/nixpkgs/flake.nix"),
+            exists: true,
+            has_hyprland_config: true,
+            flake_path: Some(PathBuf::from("/home/me/.config/nixpkgs")),
+        };
+        let env = NixOSEnvironment {
+            is_nixos: true,
+            config_locations: vec![plain, flake],
+            ..Default::default()
+        };
+        assert_eq!(
+            env.get_primary_config_location()
+                .map(|loc| &loc.config_type),
+            Some(&NixConfigType::FlakeHomeManager)
+        );
+    }
 }
@@ -0,0 +1,238 @@
+// Nix formatting for exported modules
+// Exports go through `alejandra` or `nixpkgs-fmt` when one is installed, so
+// they pass the same formatting check as the rest of a user's config. The
+// built-in pretty printer re-indents the output when neither is available.
+
+use anyhow::Result;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// An external Nix formatter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NixFormatter {
+    Alejandra,
+    NixpkgsFmt,
+}
+
+impl NixFormatter {
+    pub fn command(self) -> &'static str {
+        match self {
+            NixFormatter::Alejandra => "alejandra",
+            NixFormatter::NixpkgsFmt => "nixpkgs-fmt",
+        }
+    }
+
+    /// The first formatter found on `PATH`, preferring alejandra
+    pub fn detect() -> Option<Self> {
+        [NixFormatter::Alejandra, NixFormatter::NixpkgsFmt]
+            .into_iter()
+            .find(|formatter| which::which(formatter.command()).is_ok())
+    }
+
+    /// Format `source` by piping it through the formatter
+    pub fn format(self, source: &str) -> Result<String> {
+        let mut command = Command::new(self.command());
+        if self == NixFormatter::Alejandra {
+            // `-` reads stdin, --quiet drops the summary it prints
+            command.args(["--quiet", "-"]);
+        }
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(source.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed: {}",
+                self.command(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Run `source` through `formatter` if one is given. When it fails the
+/// source is returned unchanged along with the error, so the caller can
+/// mention it.
+pub fn format_nix(source: String, formatter: Option<NixFormatter>) -> (String, Option<String>) {
+    match formatter.map(|formatter| formatter.format(&source)) {
+        Some(Ok(formatted)) => (formatted, None),
+        Some(Err(e)) => (source, Some(e.to_string())),
+        None => (source, None),
+    }
+}
+
+/// Re-indent Nix source by two spaces per nesting level. Brackets opened on
+/// the same line, like `({`, share one level. Trailing whitespace and
+/// repeated blank lines are dropped. Lines inside `'' ... ''` strings keep
+/// their indentation relative to each other, since Nix only strips the
+/// common part.
+pub fn pretty_print(source: &str) -> String {
+    let mut out = String::new();
+    // Open brackets at each indentation level
+    let mut levels: Vec<usize> = Vec::new();
+    // Indentation of the first line of the indented string being copied
+    let mut string_indent: Option<usize> = None;
+    let mut in_string = false;
+    let mut blank = false;
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            // Blank lines are content inside an indented string
+            if in_string || (!blank && !out.is_empty()) {
+                out.push('\n');
+            }
+            blank = true;
+            continue;
+        }
+        blank = false;
+
+        let (brackets, still_in_string) = scan_line(trimmed, in_string);
+        let closes_string = in_string && !still_in_string && trimmed.starts_with("''");
+        if in_string && !closes_string {
+            let original = line.len() - line.trim_start().len();
+            let base = *string_indent.get_or_insert(original);
+            out.push_str(&" ".repeat(levels.len() * 2 + original.saturating_sub(base)));
+            out.push_str(line.trim_start().trim_end_matches(' '));
+        } else {
+            let leading = if closes_string {
+                1
+            } else {
+                trimmed
+                    .chars()
+                    .take_while(|c| matches!(c, '}' | ']' | ')'))
+                    .count()
+            };
+            out.push_str(&" ".repeat(closed_level(&levels, leading) * 2));
+            out.push_str(trimmed);
+        }
+        out.push('\n');
+
+        let mut opened = 0;
+        for open in brackets {
+            if open {
+                opened += 1;
+            } else if opened > 0 {
+                opened -= 1;
+            } else if let Some(top) = levels.last_mut() {
+                *top -= 1;
+                if *top == 0 {
+                    levels.pop();
+                }
+            }
+        }
+        if opened > 0 {
+            levels.push(opened);
+        }
+        if !still_in_string {
+            string_indent = None;
+        }
+        in_string = still_in_string;
+    }
+    out
+}
+
+/// The level a line starts at once its `leading` closing brackets are
+/// applied. A level counts as closed as soon as one of its brackets is.
+fn closed_level(levels: &[usize], mut leading: usize) -> usize {
+    let mut level = levels.len();
+    while leading > 0 && level > 0 {
+        leading = leading.saturating_sub(levels[level - 1]);
+        level -= 1;
+    }
+    level
+}
+
+/// The brackets on a line in order, `true` for opening ones, and whether an
+/// indented string is still open at its end. The `''` of an indented string
+/// counts as a bracket, so its content is indented one level.
+fn scan_line(line: &str, mut in_string: bool) -> (Vec<bool>, bool) {
+    let chars: Vec<char> = line.chars().collect();
+    let mut brackets = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if in_string {
+            if (c, next) == ('\'', Some('\'')) {
+                // `'''`, `''$` and `''\` are escapes, not the end of the string
+                if matches!(chars.get(i + 2), Some('\'' | '$' | '\\')) {
+                    i += 3;
+                    continue;
+                }
+                in_string = false;
+                brackets.push(false);
+                i += 1;
+            }
+            i += 1;
+            continue;
+        }
+        match c {
+            '#' => break,
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            '\'' if next == Some('\'') => {
+                in_string = true;
+                brackets.push(true);
+                i += 1;
+            }
+            '{' | '[' | '(' => brackets.push(true),
+            '}' | ']' | ')' => brackets.push(false),
+            _ => {}
+        }
+        i += 1;
+    }
+    (brackets, in_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pretty_print_reindents_nested_blocks() {
+        let source = "{\n      inputs = {\n nixpkgs.url = \"github:nixos/nixpkgs\";   \n  };\n  \n\n  modules = [\n        ({ config, ... }: {\n    enable = true;\n  })\n  ];\n}\n";
+        assert_eq!(
+            pretty_print(source),
+            "{\n  inputs = {\n    nixpkgs.url = \"github:nixos/nixpkgs\";\n  };\n\n  modules = [\n    ({ config, ... }: {\n      enable = true;\n    })\n  ];\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_strings_and_comments_are_not_brackets() {
+        let source =
+            "{\nbind = [\n\"SUPER, E, exec, echo \\\"{\\\"\"\n];\n# a } in a comment\nx = 1;\n}";
+        assert_eq!(
+            pretty_print(source),
+            "{\n  bind = [\n    \"SUPER, E, exec, echo \\\"{\\\"\"\n  ];\n  # a } in a comment\n  x = 1;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_indented_strings_keep_relative_indentation() {
+        let source = "{\nextraConfig = ''\n        submap = resize\n          bind = , escape, submap, reset\n        echo ''${HOME} {\n'';\n}";
+        assert_eq!(
+            pretty_print(source),
+            "{\n  extraConfig = ''\n    submap = resize\n      bind = , escape, submap, reset\n    echo ''${HOME} {\n  '';\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_generated_home_manager_module_is_already_pretty() {
+        let golden = include_str!("testdata/home_manager.nix");
+        assert_eq!(pretty_print(golden), golden);
+    }
+}
@@ -1,6 +1,7 @@
 pub mod config_discovery;
 pub mod converter;
 pub mod detection;
+pub mod formatter;
 pub mod generator;
 pub mod parser;
 
@@ -48,6 +49,8 @@ pub struct NixConfigLocation {
     pub path: PathBuf,
     pub exists: bool,
     pub has_hyprland_config: bool,
+    /// Directory of the flake this config was found in, as given to `--flake`
+    pub flake_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -67,10 +70,16 @@ impl NixOSEnvironment {
     }
 
     pub fn get_primary_config_location(&self) -> Option<&NixConfigLocation> {
-        // Prefer locations with existing Hyprland config
+        // Prefer locations with existing Hyprland config. A flake wins over
+        // the plain files next to it, since it is what imports them.
         self.config_locations
             .iter()
-            .find(|loc| loc.has_hyprland_config)
+            .find(|loc| loc.has_hyprland_config && loc.flake_path.is_some())
+            .or_else(|| {
+                self.config_locations
+                    .iter()
+                    .find(|loc| loc.has_hyprland_config)
+            })
             .or_else(|| {
                 // Fallback to first existing config file
                 self.config_locations.iter().find(|loc| loc.exists)
@@ -1,42 +1,54 @@
 // Nix expression parser module
-// This module will handle parsing and generating Nix expressions
-// For now, we use simple string parsing, but in Phase 2 we'll integrate a proper Nix parser
+// Reads the subset of Nix that Home Manager and NixOS configs use for
+// Hyprland: attribute sets with dotted paths, lists, strings, numbers and
+// booleans. Anything else, like function calls or `++`, is kept as raw text
+// so the rest of the file still parses.
 
+use anyhow::Result;
 use std::collections::HashMap;
 
-#[allow(dead_code)]
-pub struct NixParser {
-    // Future: Will use rnix parser for proper Nix parsing
-}
+use crate::import_export::StructuredConfig;
+
+pub struct NixParser {}
 
 impl NixParser {
-    #[allow(dead_code)]
     pub fn new() -> Self {
         Self {}
     }
 
-    // Placeholder for future Nix parsing functionality
-    #[allow(dead_code)]
-    pub fn parse_expression(&self, _content: &str) -> Result<NixExpression, anyhow::Error> {
-        // TODO: Implement proper Nix expression parsing
-        Ok(NixExpression::default())
+    /// Parse a Nix file into the attribute set it evaluates to. Function
+    /// headers like `{ config, pkgs, ... }:`, `let ... in` and `with pkgs;`
+    /// are looked through.
+    pub fn parse_expression(&self, content: &str) -> Result<NixExpression> {
+        let mut cursor = Cursor::new(content);
+        let value = cursor.parse_value()?;
+        cursor.skip_trivia();
+        if !cursor.at_end() {
+            return Err(cursor.error("expected the end of the file"));
+        }
+        match value {
+            NixValue::AttrSet(attributes) => Ok(NixExpression { attributes }),
+            _ => anyhow::bail!("The file doesn't evaluate to an attribute set"),
+        }
     }
 }
 
 #[derive(Debug, Default)]
 pub struct NixExpression {
-    #[allow(dead_code)]
     pub attributes: HashMap<String, NixValue>,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum NixValue {
     String(String),
     Integer(i64),
+    Float(f64),
     Boolean(bool),
+    Null,
     List(Vec<NixValue>),
     AttrSet(HashMap<String, NixValue>),
+    /// An expression this parser doesn't evaluate, as written
+    Expression(String),
 }
 
 impl Default for NixValue {
@@ -44,3 +56,756 @@ impl Default for NixValue {
         NixValue::String(String::new())
     }
 }
+
+impl NixValue {
+    /// The value as hyprland.conf text, `None` for lists, attrsets, `null`
+    /// and unevaluated expressions
+    pub fn as_config_text(&self) -> Option<String> {
+        match self {
+            NixValue::String(s) => Some(s.clone()),
+            NixValue::Integer(i) => Some(i.to_string()),
+            NixValue::Float(f) => Some(f.to_string()),
+            NixValue::Boolean(b) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Hyprland settings from a Nix file, flattened to the hyprland.conf model
+#[derive(Debug, Default, PartialEq)]
+pub struct HyprlandNixSettings {
+    /// Options keyed the way hyprctl names them, like `general:gaps_in`
+    pub options: Vec<(String, String)>,
+    /// Keyword lines like `bind = SUPER, Q, exec, kitty`, from the settings
+    /// lists first and then from `extraConfig`
+    pub lines: Vec<String>,
+    /// Settings that are expressions rather than plain values
+    pub skipped: Vec<String>,
+}
+
+impl HyprlandNixSettings {
+    /// Settings from plain hyprland.conf text, like a config in a dotfiles
+    /// repository
+    pub fn from_hyprland_conf(content: &str) -> Self {
+        let mut settings = Self::default();
+        flatten_hyprland_conf(content, &mut settings);
+        settings
+    }
+
+    /// Settings from a JSON, TOML or YAML export
+    pub fn from_structured(config: &StructuredConfig) -> Self {
+        let mut options: Vec<(String, String)> = config.to_hyprland_options().into_iter().collect();
+        options.sort();
+
+        let mut lines = config.keybind_lines();
+        lines.extend(config.window_rule_lines());
+        lines.extend(config.layer_rule_lines());
+        Self {
+            options,
+            lines,
+            skipped: Vec::new(),
+        }
+    }
+}
+
+impl NixExpression {
+    /// The `wayland.windowManager.hyprland` settings and `extraConfig`,
+    /// wherever they are in the file. Flakes nest them inside modules.
+    pub fn hyprland_settings(&self) -> Option<HyprlandNixSettings> {
+        let hyprland = find_hyprland(&self.attributes)?;
+
+        let mut settings = HyprlandNixSettings::default();
+        if let Some(NixValue::AttrSet(attrs)) = hyprland.get("settings") {
+            flatten_settings(attrs, &[], &mut settings);
+        }
+        match hyprland.get("extraConfig") {
+            Some(NixValue::String(extra)) => flatten_hyprland_conf(extra, &mut settings),
+            Some(NixValue::Expression(raw)) => {
+                settings.skipped.push(format!("extraConfig = {raw}"))
+            }
+            _ => {}
+        }
+        Some(settings)
+    }
+}
+
+fn nested<'a>(
+    attrs: &'a HashMap<String, NixValue>,
+    key: &str,
+) -> Option<&'a HashMap<String, NixValue>> {
+    match attrs.get(key) {
+        Some(NixValue::AttrSet(inner)) => Some(inner),
+        _ => None,
+    }
+}
+
+fn find_hyprland(attrs: &HashMap<String, NixValue>) -> Option<&HashMap<String, NixValue>> {
+    if let Some(hyprland) = nested(attrs, "wayland")
+        .and_then(|wayland| nested(wayland, "windowManager"))
+        .and_then(|wm| nested(wm, "hyprland"))
+    {
+        return Some(hyprland);
+    }
+
+    let mut names: Vec<&String> = attrs.keys().collect();
+    names.sort();
+    names
+        .into_iter()
+        .find_map(|name| find_hyprland_in(&attrs[name]))
+}
+
+fn find_hyprland_in(value: &NixValue) -> Option<&HashMap<String, NixValue>> {
+    match value {
+        NixValue::AttrSet(attrs) => find_hyprland(attrs),
+        NixValue::List(items) => items.iter().find_map(find_hyprland_in),
+        _ => None,
+    }
+}
+
+/// Nested attrsets become `section:option` keys. Lists become one keyword
+/// line per entry, named after their attribute, so `animations.bezier`
+/// gives `bezier = ...` lines.
+fn flatten_settings(
+    attrs: &HashMap<String, NixValue>,
+    prefix: &[&str],
+    settings: &mut HyprlandNixSettings,
+) {
+    let mut names: Vec<&String> = attrs.keys().collect();
+    names.sort();
+    for name in names {
+        let mut path = prefix.to_vec();
+        path.push(name);
+        match &attrs[name] {
+            NixValue::AttrSet(children) => flatten_settings(children, &path, settings),
+            NixValue::List(items) => {
+                for item in items {
+                    match item.as_config_text() {
+                        Some(text) => settings.lines.push(format!("{name} = {text}")),
+                        None => settings.skipped.push(path.join(".")),
+                    }
+                }
+            }
+            value => match value.as_config_text() {
+                Some(text) if prefix.is_empty() => settings.lines.push(format!("{name} = {text}")),
+                Some(text) => settings.options.push((path.join(":"), text)),
+                None => settings.skipped.push(path.join(".")),
+            },
+        }
+    }
+}
+
+/// Split hyprland.conf text into options and keyword lines. Options inside
+/// `section { ... }` blocks are keyed `section:option`.
+fn flatten_hyprland_conf(content: &str, settings: &mut HyprlandNixSettings) {
+    // Keywords that are lines even inside a section block
+    const SECTION_KEYWORDS: [&str; 2] = ["animation", "bezier"];
+
+    let mut sections: Vec<&str> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_suffix('{') {
+            sections.push(name.trim());
+        } else if line == "}" {
+            sections.pop();
+        } else if let Some((key, value)) = line.split_once('=') {
+            let (key, value) = (key.trim(), value.trim());
+            if !sections.is_empty() && !SECTION_KEYWORDS.contains(&key) {
+                settings
+                    .options
+                    .push((format!("{}:{key}", sections.join(":")), value.to_string()));
+            } else if sections.is_empty() && key.contains(':') {
+                settings.options.push((key.to_string(), value.to_string()));
+            } else {
+                settings.lines.push(format!("{key} = {value}"));
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Cursor<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '\'' | '-')
+}
+
+impl<'a> Cursor<'a> {
+    fn new(src: &'a str) -> Self {
+        Self { src, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.src.len()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> Result<()> {
+        self.skip_trivia();
+        if self.eat(s) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{s}'")))
+        }
+    }
+
+    /// Consume `keyword` if it is a whole word here
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let rest = self.rest();
+        let whole_word =
+            rest.starts_with(keyword) && !rest[keyword.len()..].starts_with(is_ident_char);
+        if whole_word {
+            self.pos += keyword.len();
+        }
+        whole_word
+    }
+
+    fn error(&self, message: &str) -> anyhow::Error {
+        let line = self.src[..self.pos].matches('\n').count() + 1;
+        anyhow::anyhow!("Nix parse error on line {line}: {message}")
+    }
+
+    /// Skip whitespace and comments
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with('#') {
+                let end = trimmed.find('\n').unwrap_or(trimmed.len());
+                self.pos += end;
+            } else if trimmed.starts_with("/*") {
+                let end = trimmed.find("*/").map_or(trimmed.len(), |i| i + 2);
+                self.pos += end;
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn read_word(&mut self) -> &'a str {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !"=;,()[]{}\"".contains(c))
+        {
+            self.bump();
+        }
+        &self.src[start..self.pos]
+    }
+
+    /// Skip a string or comment if one starts here
+    fn skip_atom(&mut self) -> Result<bool> {
+        let rest = self.rest();
+        if rest.starts_with("''") {
+            self.parse_indented_string()?;
+        } else if rest.starts_with('"') {
+            self.parse_string()?;
+        } else if rest.starts_with('#') || rest.starts_with("/*") {
+            self.skip_trivia();
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Move to the next `;` or unmatched closing bracket, stepping over
+    /// nested brackets, strings and comments
+    fn skip_raw(&mut self) -> Result<()> {
+        let mut depth = 0usize;
+        loop {
+            if self.skip_atom()? {
+                continue;
+            }
+            match self.peek() {
+                None => return Ok(()),
+                Some(';') if depth == 0 => return Ok(()),
+                Some(')' | ']' | '}') if depth == 0 => return Ok(()),
+                Some('(' | '[' | '{') => depth += 1,
+                Some(')' | ']' | '}') => depth -= 1,
+                _ => {}
+            }
+            self.bump();
+        }
+    }
+
+    /// Move past the bracketed block starting here
+    fn skip_balanced(&mut self) -> Result<()> {
+        let mut depth = 0usize;
+        loop {
+            if self.skip_atom()? {
+                continue;
+            }
+            match self.bump() {
+                None => return Err(self.error("unclosed bracket")),
+                Some('(' | '[' | '{') => depth += 1,
+                Some(')' | ']' | '}') => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// If a function header like `{ pkgs, ... }:`, `args@{ ... }:` or
+    /// `pkgs:` starts here, the position after it
+    fn lambda_header_end(&self) -> Option<usize> {
+        let mut probe = self.clone();
+        if probe.peek() == Some('{') {
+            probe.skip_balanced().ok()?;
+            probe.skip_trivia();
+            if probe.eat("@") {
+                probe.skip_trivia();
+                while probe.peek().is_some_and(is_ident_char) {
+                    probe.bump();
+                }
+                probe.skip_trivia();
+            }
+        } else {
+            let start = probe.pos;
+            while probe.peek().is_some_and(is_ident_char) {
+                probe.bump();
+            }
+            if start == probe.pos {
+                return None;
+            }
+            probe.skip_trivia();
+            if probe.eat("@") {
+                probe.skip_trivia();
+                if probe.peek() != Some('{') {
+                    return None;
+                }
+                probe.skip_balanced().ok()?;
+                probe.skip_trivia();
+            }
+        }
+        // `a:b` without a space is a URL, not a function
+        (probe.eat(":") && probe.peek().is_none_or(char::is_whitespace)).then_some(probe.pos)
+    }
+
+    /// A whole expression, ended by `;`, a closing bracket or the end of the
+    /// file
+    fn parse_value(&mut self) -> Result<NixValue> {
+        self.skip_trivia();
+        if let Some(end) = self.lambda_header_end() {
+            self.pos = end;
+            return self.parse_value();
+        }
+        if self.eat_keyword("let") {
+            self.parse_bindings(true)?;
+            return self.parse_value();
+        }
+        if self.eat_keyword("with") || self.eat_keyword("assert") {
+            self.skip_raw()?;
+            self.expect(";")?;
+            return self.parse_value();
+        }
+
+        let start = self.pos;
+        let value = self.parse_term()?;
+        self.skip_trivia();
+        match self.peek() {
+            None | Some(';' | ')' | ']' | '}') => Ok(value),
+            // More follows, like `a ++ b` or `lib.mkForce 5`
+            _ => {
+                self.pos = start;
+                self.skip_raw()?;
+                Ok(NixValue::Expression(
+                    self.src[start..self.pos].trim().to_string(),
+                ))
+            }
+        }
+    }
+
+    /// A single value, as written in a list
+    fn parse_term(&mut self) -> Result<NixValue> {
+        self.skip_trivia();
+        if self.eat_keyword("rec") {
+            self.skip_trivia();
+        }
+        let rest = self.rest();
+        match self.peek() {
+            Some('{') => {
+                self.bump();
+                Ok(NixValue::AttrSet(self.parse_bindings(false)?))
+            }
+            Some('[') => {
+                self.bump();
+                let mut items = Vec::new();
+                loop {
+                    self.skip_trivia();
+                    if self.eat("]") {
+                        return Ok(NixValue::List(items));
+                    }
+                    if self.at_end() {
+                        return Err(self.error("unclosed list"));
+                    }
+                    items.push(self.parse_term()?);
+                }
+            }
+            Some('(') => {
+                self.bump();
+                let value = self.parse_value()?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            Some('"') => Ok(NixValue::String(self.parse_string()?)),
+            _ if rest.starts_with("''") => Ok(NixValue::String(self.parse_indented_string()?)),
+            _ => {
+                let word = self.read_word();
+                if word.is_empty() {
+                    return Err(self.error("expected a value"));
+                }
+                Ok(match word {
+                    "true" => NixValue::Boolean(true),
+                    "false" => NixValue::Boolean(false),
+                    "null" => NixValue::Null,
+                    _ => {
+                        if let Ok(i) = word.parse::<i64>() {
+                            NixValue::Integer(i)
+                        } else if let Some(f) = word
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|_| word.chars().any(|c| c.is_ascii_digit()))
+                        {
+                            NixValue::Float(f)
+                        } else {
+                            NixValue::Expression(word.to_string())
+                        }
+                    }
+                })
+            }
+        }
+    }
+
+    /// Bindings of an attrset up to its `}`, or of a `let` up to its `in`
+    fn parse_bindings(&mut self, until_in: bool) -> Result<HashMap<String, NixValue>> {
+        let mut attrs = HashMap::new();
+        loop {
+            self.skip_trivia();
+            if until_in && self.eat_keyword("in") {
+                return Ok(attrs);
+            }
+            if !until_in && self.eat("}") {
+                return Ok(attrs);
+            }
+            if self.at_end() {
+                return Err(self.error("unclosed attribute set"));
+            }
+            if self.eat_keyword("inherit") {
+                self.skip_raw()?;
+                self.expect(";")?;
+                continue;
+            }
+
+            let path = self.parse_attr_path()?;
+            self.expect("=")?;
+            let value = self.parse_value()?;
+            self.expect(";")?;
+            insert_path(&mut attrs, &path, value);
+        }
+    }
+
+    /// `a.b."c.d"` as its segments
+    fn parse_attr_path(&mut self) -> Result<Vec<String>> {
+        let mut path = Vec::new();
+        loop {
+            self.skip_trivia();
+            let segment = if self.peek() == Some('"') {
+                self.parse_string()?
+            } else if self.rest().starts_with("${") {
+                let start = self.pos;
+                self.bump();
+                self.skip_balanced()?;
+                self.src[start..self.pos].to_string()
+            } else {
+                let start = self.pos;
+                while self.peek().is_some_and(is_ident_char) {
+                    self.bump();
+                }
+                if start == self.pos {
+                    return Err(self.error("expected an attribute name"));
+                }
+                self.src[start..self.pos].to_string()
+            };
+            path.push(segment);
+            self.skip_trivia();
+            if !self.eat(".") {
+                return Ok(path);
+            }
+        }
+    }
+
+    /// Copy a `${...}` interpolation as written, since it isn't evaluated
+    fn copy_interpolation(&mut self, out: &mut String) -> Result<()> {
+        let start = self.pos;
+        self.bump();
+        self.skip_balanced()?;
+        out.push_str(&self.src[start..self.pos]);
+        Ok(())
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.bump();
+        let mut out = String::new();
+        loop {
+            if self.rest().starts_with("${") {
+                self.copy_interpolation(&mut out)?;
+                continue;
+            }
+            match self.bump() {
+                None => return Err(self.error("unterminated string")),
+                Some('"') => return Ok(out),
+                Some('\\') => match self.bump() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some(c) => out.push(c),
+                    None => return Err(self.error("unterminated string")),
+                },
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    /// An `'' ... ''` string, with the common indentation removed
+    fn parse_indented_string(&mut self) -> Result<String> {
+        self.pos += 2;
+        let start = self.pos;
+        loop {
+            if self.eat("'''") || self.eat("''$") {
+                continue;
+            }
+            if self.eat("''\\") {
+                self.bump();
+                continue;
+            }
+            if self.rest().starts_with("''") {
+                break;
+            }
+            if self.bump().is_none() {
+                return Err(self.error("unterminated indented string"));
+            }
+        }
+        let raw = &self.src[start..self.pos];
+        self.pos += 2;
+
+        let text = strip_indentation(raw);
+        let mut out = String::with_capacity(text.len());
+        let mut escaped = Cursor::new(&text);
+        loop {
+            if escaped.eat("'''") {
+                out.push_str("''");
+            } else if escaped.eat("''$") {
+                out.push('$');
+            } else if escaped.eat("''\\") {
+                match escaped.bump() {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some(c) => out.push(c),
+                    None => break,
+                }
+            } else if escaped.rest().starts_with("${") {
+                escaped.copy_interpolation(&mut out)?;
+            } else {
+                match escaped.bump() {
+                    Some(c) => out.push(c),
+                    None => break,
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Nix drops a blank first line and the smallest indentation of the
+/// non-blank lines
+fn strip_indentation(raw: &str) -> String {
+    let mut lines: Vec<&str> = raw.split('\n').collect();
+    if lines.len() > 1 && lines[0].trim().is_empty() {
+        lines.remove(0);
+    }
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                ""
+            } else {
+                &line[indent..]
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Set a dotted path, merging attrsets the way Nix does for
+/// `a.b = 1; a.c = 2;`
+fn insert_path(attrs: &mut HashMap<String, NixValue>, path: &[String], value: NixValue) {
+    let Some((name, rest)) = path.split_first() else {
+        return;
+    };
+    if rest.is_empty() {
+        match (attrs.get_mut(name), value) {
+            (Some(NixValue::AttrSet(existing)), NixValue::AttrSet(new)) => {
+                for (key, value) in new {
+                    insert_path(existing, &[key], value);
+                }
+            }
+            (_, value) => {
+                attrs.insert(name.clone(), value);
+            }
+        }
+        return;
+    }
+    let entry = attrs
+        .entry(name.clone())
+        .or_insert_with(|| NixValue::AttrSet(HashMap::new()));
+    if !matches!(entry, NixValue::AttrSet(_)) {
+        *entry = NixValue::AttrSet(HashMap::new());
+    }
+    if let NixValue::AttrSet(children) = entry {
+        insert_path(children, rest, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOME_NIX: &str = r#"
+{ config, pkgs, lib, ... }:
+let
+  mod = "SUPER";
+in
+{
+  home.packages = with pkgs; [ kitty waybar ];
+  /* Hyprland */
+  wayland.windowManager.hyprland.enable = true;
+  wayland.windowManager.hyprland.settings = {
+    "$mod" = "SUPER"; # the main modifier
+    general = {
+      gaps_in = 5;
+      "col.active_border" = "rgba(33ccffee) rgba(00ff99ee) 45deg";
+    };
+    general.layout = "dwindle";
+    decoration.blur = { enabled = true; size = 8; };
+    decoration.active_opacity = 0.95;
+    input.sensitivity = -0.5;
+    misc.vrr = lib.mkDefault 1;
+    bind = [
+      "$mod, Q, exec, kitty"
+      "$mod, E, exec, notify-send \"Hello \${USER}\""
+    ];
+    windowrulev2 = [ "float, class:^(pavucontrol)$" ];
+  };
+  wayland.windowManager.hyprland.extraConfig = ''
+    general {
+      border_size = 2
+    }
+    submap = resize
+    binde = , right, resizeactive, 10 0
+    submap = reset
+    exec-once = echo ''${HOME}
+  '';
+}
+"#;
+
+    #[test]
+    fn test_parse_home_manager_file() {
+        let expression = NixParser::new().parse_expression(HOME_NIX).unwrap();
+        let settings = expression.hyprland_settings().unwrap();
+
+        assert_eq!(
+            settings.options,
+            vec![
+                ("decoration:active_opacity".to_string(), "0.95".to_string()),
+                ("decoration:blur:enabled".to_string(), "true".to_string()),
+                ("decoration:blur:size".to_string(), "8".to_string()),
+                (
+                    "general:col.active_border".to_string(),
+                    "rgba(33ccffee) rgba(00ff99ee) 45deg".to_string()
+                ),
+                ("general:gaps_in".to_string(), "5".to_string()),
+                ("general:layout".to_string(), "dwindle".to_string()),
+                ("input:sensitivity".to_string(), "-0.5".to_string()),
+                ("general:border_size".to_string(), "2".to_string()),
+            ]
+        );
+        assert_eq!(
+            settings.lines,
+            vec![
+                "$mod = SUPER",
+                "bind = $mod, Q, exec, kitty",
+                "bind = $mod, E, exec, notify-send \"Hello ${USER}\"",
+                "windowrulev2 = float, class:^(pavucontrol)$",
+                "submap = resize",
+                "binde = , right, resizeactive, 10 0",
+                "submap = reset",
+                "exec-once = echo ${HOME}",
+            ]
+        );
+        assert_eq!(settings.skipped, vec!["misc.vrr"]);
+    }
+
+    #[test]
+    fn test_exported_home_manager_config_round_trips() {
+        let exported = include_str!("testdata/home_manager.nix");
+        let expression = NixParser::new().parse_expression(exported).unwrap();
+        let settings = expression.hyprland_settings().unwrap();
+
+        assert!(settings
+            .options
+            .contains(&("decoration:blur:size".to_string(), "8".to_string())));
+        assert!(settings
+            .lines
+            .contains(&"bind = SUPER, E, exec, notify-send \"Hello ${USER}\"".to_string()));
+        assert!(settings.lines.ends_with(&["submap = reset".to_string()]));
+        assert!(settings.skipped.is_empty());
+    }
+
+    #[test]
+    fn test_files_without_hyprland_settings() {
+        let expression = NixParser::new()
+            .parse_expression("{ programs.git.enable = true; }")
+            .unwrap();
+        assert_eq!(expression.hyprland_settings(), None);
+        assert!(NixParser::new().parse_expression("{ a = ; }").is_err());
+    }
+}
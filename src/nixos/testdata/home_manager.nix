@@ -0,0 +1,54 @@
+{ config, pkgs, ... }: {
+  wayland.windowManager.hyprland = {
+    enable = true;
+    settings = {
+      bind = [
+        "$mainMod, Q, exec, kitty"
+        "SUPER, E, exec, notify-send \"Hello \${USER}\""
+      ];
+      binde = [
+        "SUPER, L, resizeactive, 10 0"
+      ];
+      bindm = [
+        "SUPER, mouse:272, movewindow"
+      ];
+      decoration = {
+        active_opacity = 0.95;
+        blur = {
+          enabled = true;
+          size = 8;
+        };
+        rounding = 10;
+      };
+      general = {
+        "col.active_border" = "rgba(33ccffee) rgba(00ff99ee) 45deg";
+        gaps_in = 5;
+        layout = "dwindle";
+      };
+      input = {
+        kb_layout = "us";
+        touchpad = {
+          natural_scroll = false;
+        };
+      };
+      layerrule = [
+        "blur, waybar"
+      ];
+      misc = {
+        disable_hyprland_logo = true;
+      };
+      windowrule = [
+        "float, ^(pavucontrol)$"
+      ];
+      windowrulev2 = [
+        "opacity 0.9 0.8, class:^(code)$"
+      ];
+    };
+    extraConfig = ''
+      submap = resize
+      binde = , right, resizeactive, 10 0
+      bind = , escape, submap, reset
+      submap = reset
+    '';
+  };
+}
@@ -0,0 +1,242 @@
+// Opacity window rules
+// `opacity ACTIVE [INACTIVE [FULLSCREEN]]` sets how opaque matching windows
+// are, and `override` after the values replaces the window's own opacity
+// instead of multiplying it. The rule editor edits them as separate fields.
+
+/// Fields of the opacity part of the rule editor, in the order Tab visits them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OpacityField {
+    Active,
+    Inactive,
+    Override,
+}
+
+impl OpacityField {
+    /// The field after this one, `None` after the last
+    pub fn next(self) -> Option<Self> {
+        match self {
+            OpacityField::Active => Some(OpacityField::Inactive),
+            OpacityField::Inactive => Some(OpacityField::Override),
+            OpacityField::Override => None,
+        }
+    }
+}
+
+/// The values of an `opacity` rule action, as typed
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OpacityRule {
+    pub active: String,
+    /// Empty to use the active opacity for unfocused windows too
+    pub inactive: String,
+    /// Kept as it was, the editor doesn't offer it
+    pub fullscreen: Option<String>,
+    pub overridden: bool,
+}
+
+impl OpacityRule {
+    /// Parse a rule action such as `opacity 0.9 override 0.8 override`. Rules
+    /// where only some values are overridden aren't taken apart, so saving
+    /// can't change what they mean.
+    pub fn parse(action: &str) -> Option<Self> {
+        let mut tokens = action.split_whitespace();
+        if tokens.next()? != "opacity" {
+            return None;
+        }
+        let tokens: Vec<&str> = tokens.collect();
+        let values: Vec<&str> = tokens
+            .iter()
+            .copied()
+            .filter(|token| *token != "override")
+            .collect();
+        let overrides = tokens.len() - values.len();
+        if values.len() > 3 || (overrides != 0 && overrides != values.len()) {
+            return None;
+        }
+
+        let value = |index: usize| values.get(index).map(|value| value.to_string());
+        Some(Self {
+            active: value(0).unwrap_or_default(),
+            inactive: value(1).unwrap_or_default(),
+            fullscreen: value(2),
+            overridden: overrides > 0,
+        })
+    }
+
+    /// The rule action, with `override` after each value when set
+    pub fn to_action(&self) -> String {
+        let mut action = "opacity".to_string();
+        let values = [
+            Some(&self.active),
+            Some(&self.inactive),
+            self.fullscreen.as_ref(),
+        ];
+        for value in values
+            .into_iter()
+            .flatten()
+            .filter(|value| !value.is_empty())
+        {
+            action.push(' ');
+            action.push_str(value);
+            if self.overridden {
+                action.push_str(" override");
+            }
+        }
+        action
+    }
+
+    /// Type `c` into `field`. Values take digits and a decimal point, and
+    /// Space flips override.
+    pub fn type_char(&mut self, field: OpacityField, c: char) {
+        match field {
+            OpacityField::Active if c.is_ascii_digit() || c == '.' => self.active.push(c),
+            OpacityField::Inactive if c.is_ascii_digit() || c == '.' => self.inactive.push(c),
+            OpacityField::Override if c == ' ' => self.overridden = !self.overridden,
+            _ => {}
+        }
+    }
+
+    /// Delete the last character of a value, or turn override off
+    pub fn backspace(&mut self, field: OpacityField) {
+        match field {
+            OpacityField::Active => {
+                self.active.pop();
+            }
+            OpacityField::Inactive => {
+                self.inactive.pop();
+            }
+            OpacityField::Override => self.overridden = false,
+        }
+    }
+
+    /// Check that the values are numbers from 0.0 up. Above 1.0 is only a
+    /// warning, see `warnings`.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.active.is_empty() {
+            return Err("the active opacity is missing".to_string());
+        }
+        for (name, value) in self.named_values() {
+            match value.parse::<f32>() {
+                Ok(number) if number.is_finite() && number >= 0.0 => {}
+                _ => {
+                    return Err(format!(
+                        "the {name} opacity must be a number from 0.0 to 1.0"
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Values above 1.0, which Hyprland accepts but rarely look intended
+    pub fn warnings(&self) -> Vec<String> {
+        self.named_values()
+            .filter(|(_, value)| value.parse::<f32>().is_ok_and(|number| number > 1.0))
+            .map(|(name, value)| format!("the {name} opacity {value} is above 1.0"))
+            .collect()
+    }
+
+    fn named_values(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("active", self.active.as_str()),
+            ("inactive", self.inactive.as_str()),
+            ("fullscreen", self.fullscreen.as_deref().unwrap_or_default()),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+    }
+}
+
+/// Change the opacity rule in a rule editor's `action`, if it is one
+pub fn edit_action(action: &mut String, edit: impl FnOnce(&mut OpacityRule)) {
+    if let Some(mut rule) = OpacityRule::parse(action) {
+        edit(&mut rule);
+        *action = rule.to_action();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opacity_actions_round_trip() {
+        let rule = OpacityRule::parse("opacity 0.9 0.8").unwrap();
+        assert_eq!(rule.active, "0.9");
+        assert_eq!(rule.inactive, "0.8");
+        assert!(!rule.overridden);
+        assert_eq!(rule.to_action(), "opacity 0.9 0.8");
+
+        let rule = OpacityRule::parse("opacity 0.9 override 0.8 override 1.0 override").unwrap();
+        assert!(rule.overridden);
+        assert_eq!(rule.fullscreen.as_deref(), Some("1.0"));
+        assert_eq!(
+            rule.to_action(),
+            "opacity 0.9 override 0.8 override 1.0 override"
+        );
+
+        let mut rule = OpacityRule::parse("opacity 0.7").unwrap();
+        assert_eq!(rule.inactive, "");
+        rule.overridden = true;
+        assert_eq!(rule.to_action(), "opacity 0.7 override");
+        rule.inactive = "0.5".to_string();
+        assert_eq!(rule.to_action(), "opacity 0.7 override 0.5 override");
+
+        assert_eq!(OpacityRule::parse("opacity"), Some(OpacityRule::default()));
+        assert_eq!(OpacityRule::parse("float"), None);
+        assert_eq!(OpacityRule::parse("opacityx 1"), None);
+        // Only some values overridden, or too many values
+        assert_eq!(OpacityRule::parse("opacity 0.9 override 0.8"), None);
+        assert_eq!(OpacityRule::parse("opacity 1 1 1 1"), None);
+    }
+
+    #[test]
+    fn test_fields_are_edited_in_the_action() {
+        let mut action = "opacity 0.8".to_string();
+        for c in "0.6x".chars() {
+            edit_action(&mut action, |rule| {
+                rule.type_char(OpacityField::Inactive, c)
+            });
+        }
+        assert_eq!(action, "opacity 0.8 0.6");
+        edit_action(&mut action, |rule| {
+            rule.type_char(OpacityField::Override, ' ')
+        });
+        assert_eq!(action, "opacity 0.8 override 0.6 override");
+        edit_action(&mut action, |rule| rule.backspace(OpacityField::Active));
+        edit_action(&mut action, |rule| rule.backspace(OpacityField::Override));
+        assert_eq!(action, "opacity 0. 0.6");
+
+        let mut action = "float".to_string();
+        edit_action(&mut action, |rule| {
+            rule.type_char(OpacityField::Active, '1')
+        });
+        assert_eq!(action, "float");
+        assert_eq!(OpacityField::Active.next(), Some(OpacityField::Inactive));
+        assert_eq!(OpacityField::Override.next(), None);
+    }
+
+    #[test]
+    fn test_opacity_values_are_checked() {
+        let rule = |action| OpacityRule::parse(action).unwrap();
+        assert_eq!(rule("opacity 0.8 0.8").validate(), Ok(()));
+        assert_eq!(rule("opacity 1 0").validate(), Ok(()));
+        assert!(rule("opacity 0.8 0.8").warnings().is_empty());
+
+        assert_eq!(
+            rule("opacity").validate(),
+            Err("the active opacity is missing".to_string())
+        );
+        assert_eq!(
+            rule("opacity 0.8 -0.1").validate(),
+            Err("the inactive opacity must be a number from 0.0 to 1.0".to_string())
+        );
+        assert!(rule("opacity abc").validate().is_err());
+
+        let bright = rule("opacity 1.2 override 0.9 override");
+        assert_eq!(bright.validate(), Ok(()));
+        assert_eq!(
+            bright.warnings(),
+            vec!["the active opacity 1.2 is above 1.0".to_string()]
+        );
+    }
+}
@@ -0,0 +1,222 @@
+// Metadata for numeric Hyprland options
+// Ranges follow the bounds Hyprland itself documents for each option, so the
+// slider editor never offers values hyprctl would reject or clamp
+
+use crate::ui::ConfigDataType;
+
+/// The accepted range of a numeric option and the step the slider moves by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptionRange {
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    /// Whether Hyprland stores the option as an integer
+    pub integer: bool,
+}
+
+impl OptionRange {
+    const fn int(min: i32, max: i32) -> Self {
+        Self {
+            min: min as f32,
+            max: max as f32,
+            step: 1.0,
+            integer: true,
+        }
+    }
+
+    const fn float(min: f32, max: f32, step: f32) -> Self {
+        Self {
+            min,
+            max,
+            step,
+            integer: false,
+        }
+    }
+
+    /// Clamp a value into the range
+    pub fn clamp(&self, value: f32) -> f32 {
+        value.clamp(self.min, self.max)
+    }
+
+    /// One step up, or down when `up` isn't set, staying in the range
+    pub fn step_by(&self, value: f32, up: bool) -> f32 {
+        let stepped = if up {
+            value + self.step
+        } else {
+            value - self.step
+        };
+        // Snap to the step so repeated float steps don't drift
+        self.clamp((stepped / self.step).round() * self.step)
+    }
+
+    /// A value the way it is written to the config, the same as the slider
+    pub fn format(&self, value: f32) -> String {
+        if self.integer || value.fract() == 0.0 {
+            (value.round() as i32).to_string()
+        } else {
+            format!("{value:.2}")
+        }
+    }
+}
+
+/// Numeric options keyed by their hyprctl name
+#[rustfmt::skip]
+const OPTION_RANGES: &[(&str, OptionRange)] = &[
+    // general
+    ("general:border_size", OptionRange::int(0, 20)),
+    ("general:gaps_workspaces", OptionRange::int(0, 100)),
+    ("general:resize_corner", OptionRange::int(0, 4)),
+    // decoration
+    ("decoration:rounding", OptionRange::int(0, 20)),
+    ("decoration:rounding_power", OptionRange::float(2.0, 10.0, 0.1)),
+    ("decoration:active_opacity", OptionRange::float(0.0, 1.0, 0.01)),
+    ("decoration:inactive_opacity", OptionRange::float(0.0, 1.0, 0.01)),
+    ("decoration:fullscreen_opacity", OptionRange::float(0.0, 1.0, 0.01)),
+    ("decoration:dim_strength", OptionRange::float(0.0, 1.0, 0.01)),
+    ("decoration:dim_special", OptionRange::float(0.0, 1.0, 0.01)),
+    ("decoration:dim_around", OptionRange::float(0.0, 1.0, 0.01)),
+    ("decoration:blur:size", OptionRange::int(0, 32)),
+    ("decoration:blur:passes", OptionRange::int(0, 10)),
+    ("decoration:blur:noise", OptionRange::float(0.0, 1.0, 0.01)),
+    ("decoration:blur:contrast", OptionRange::float(0.0, 2.0, 0.01)),
+    ("decoration:blur:brightness", OptionRange::float(0.0, 2.0, 0.01)),
+    ("decoration:blur:vibrancy", OptionRange::float(0.0, 1.0, 0.01)),
+    ("decoration:blur:vibrancy_darkness", OptionRange::float(0.0, 1.0, 0.01)),
+    ("decoration:blur:popups_ignorealpha", OptionRange::float(0.0, 1.0, 0.01)),
+    ("decoration:shadow:range", OptionRange::int(0, 100)),
+    ("decoration:shadow:render_power", OptionRange::int(1, 4)),
+    ("decoration:shadow:scale", OptionRange::float(0.0, 1.0, 0.01)),
+    // input
+    ("input:sensitivity", OptionRange::float(-1.0, 1.0, 0.05)),
+    ("input:follow_mouse", OptionRange::int(0, 3)),
+    ("input:repeat_rate", OptionRange::int(0, 200)),
+    ("input:repeat_delay", OptionRange::int(0, 2000)),
+    ("input:scroll_factor", OptionRange::float(0.0, 2.0, 0.05)),
+    ("input:touchpad:scroll_factor", OptionRange::float(0.0, 2.0, 0.05)),
+    // gestures
+    ("gestures:workspace_swipe_fingers", OptionRange::int(0, 5)),
+    ("gestures:workspace_swipe_distance", OptionRange::int(0, 2000)),
+    ("gestures:workspace_swipe_cancel_ratio", OptionRange::float(0.0, 1.0, 0.05)),
+    ("gestures:workspace_swipe_min_speed_to_force", OptionRange::int(0, 200)),
+    // layouts
+    ("dwindle:split_width_multiplier", OptionRange::float(0.1, 3.0, 0.1)),
+    ("dwindle:special_scale_factor", OptionRange::float(0.0, 1.0, 0.01)),
+    ("dwindle:default_split_ratio", OptionRange::float(0.1, 1.9, 0.05)),
+    ("master:mfact", OptionRange::float(0.0, 1.0, 0.01)),
+    ("master:special_scale_factor", OptionRange::float(0.0, 1.0, 0.01)),
+    ("master:slave_count_for_center_master", OptionRange::int(0, 10)),
+    // groups
+    ("group:groupbar:font_size", OptionRange::int(2, 64)),
+    ("group:groupbar:height", OptionRange::int(1, 64)),
+    ("group:groupbar:indicator_height", OptionRange::int(0, 20)),
+    ("group:groupbar:priority", OptionRange::int(0, 10)),
+    ("group:groupbar:rounding", OptionRange::int(0, 20)),
+    // misc
+    ("misc:vrr", OptionRange::int(0, 3)),
+    ("cursor:inactive_timeout", OptionRange::float(0.0, 20.0, 0.5)),
+    ("binds:scroll_event_delay", OptionRange::int(0, 2000)),
+];
+
+/// Look up the range of a numeric option by its hyprctl name
+pub fn option_range(key: &str) -> Option<OptionRange> {
+    OPTION_RANGES
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, range)| *range)
+}
+
+/// The range of a numeric option, or failing that the bounds of its data
+/// type, stepping integers by 1 and floats by 0.1
+pub fn numeric_range(key: &str, data_type: &ConfigDataType) -> Option<OptionRange> {
+    if let Some(range) = option_range(key) {
+        return Some(range);
+    }
+    match data_type {
+        ConfigDataType::Integer { min, max } => Some(OptionRange::int(
+            min.unwrap_or(i32::MIN),
+            max.unwrap_or(i32::MAX),
+        )),
+        ConfigDataType::Float { min, max } => Some(OptionRange::float(
+            min.unwrap_or(f32::MIN),
+            max.unwrap_or(f32::MAX),
+            0.1,
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_range(key: &str, min: f32, max: f32, integer: bool) {
+        let range = option_range(key).unwrap_or_else(|| panic!("no range for {key}"));
+        assert_eq!(
+            (range.min, range.max, range.integer),
+            (min, max, integer),
+            "{key}"
+        );
+    }
+
+    #[test]
+    fn test_well_known_option_ranges() {
+        assert_range("general:border_size", 0.0, 20.0, true);
+        assert_range("decoration:rounding", 0.0, 20.0, true);
+        assert_range("decoration:active_opacity", 0.0, 1.0, false);
+        assert_range("decoration:dim_strength", 0.0, 1.0, false);
+        assert_range("decoration:blur:size", 0.0, 32.0, true);
+        assert_range("decoration:blur:passes", 0.0, 10.0, true);
+        assert_range("decoration:shadow:range", 0.0, 100.0, true);
+        assert_range("decoration:shadow:render_power", 1.0, 4.0, true);
+        assert_range("input:sensitivity", -1.0, 1.0, false);
+        assert_range("gestures:workspace_swipe_fingers", 0.0, 5.0, true);
+    }
+
+    #[test]
+    fn test_unknown_options_have_no_range() {
+        assert!(option_range("general:gaps_in").is_none());
+        assert!(option_range("decoration:blur.size").is_none());
+    }
+
+    #[test]
+    fn test_clamp_and_steps() {
+        let range = option_range("decoration:blur:size").unwrap();
+        assert_eq!(range.clamp(100.0), 32.0);
+        assert_eq!(range.clamp(-3.0), 0.0);
+        assert_eq!(range.step, 1.0);
+        assert!(option_range("input:sensitivity").unwrap().step < 1.0);
+    }
+
+    #[test]
+    fn test_stepping_falls_back_to_the_data_type() {
+        let gaps = numeric_range(
+            "general:gaps_in",
+            &ConfigDataType::Integer {
+                min: Some(0),
+                max: Some(50),
+            },
+        )
+        .unwrap();
+        assert_eq!(gaps.format(gaps.step_by(5.0, true)), "6");
+        assert_eq!(gaps.format(gaps.step_by(0.0, false)), "0");
+
+        let float = numeric_range(
+            "misc:some_float",
+            &ConfigDataType::Float {
+                min: None,
+                max: None,
+            },
+        )
+        .unwrap();
+        let mut value = 0.0;
+        for _ in 0..3 {
+            value = float.step_by(value, true);
+        }
+        assert_eq!(float.format(value), "0.30");
+
+        let opacity = numeric_range("decoration:active_opacity", &ConfigDataType::String).unwrap();
+        assert_eq!(opacity.format(opacity.step_by(1.0, true)), "1");
+        assert_eq!(opacity.format(opacity.step_by(0.5, false)), "0.49");
+        assert!(numeric_range("general:layout", &ConfigDataType::String).is_none());
+    }
+}
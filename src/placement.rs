@@ -0,0 +1,399 @@
+// Size and move window rules
+// `size W H` and `move X Y` take pixels, percentages of the monitor or, for
+// move, expressions of the window size such as `100%-w-10`. Move can also
+// follow the cursor and be kept on screen, and `center` places a window
+// without coordinates. Values are kept as typed, so a rule saves in the
+// units it was written in.
+
+/// Which rule the action is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlacementKind {
+    Size,
+    Move,
+}
+
+/// Where a move rule puts the window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoveMode {
+    /// At X Y on the monitor
+    Position,
+    /// At the cursor, offset by X Y
+    Cursor,
+    /// In the middle of the monitor, the `center` rule
+    Center,
+}
+
+impl MoveMode {
+    fn next(self) -> Self {
+        match self {
+            MoveMode::Position => MoveMode::Cursor,
+            MoveMode::Cursor => MoveMode::Center,
+            MoveMode::Center => MoveMode::Position,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MoveMode::Position => "position",
+            MoveMode::Cursor => "at the cursor",
+            MoveMode::Center => "center",
+        }
+    }
+}
+
+/// Fields of the size and move part of the rule editor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlacementField {
+    Mode,
+    Onscreen,
+    X,
+    Y,
+}
+
+/// The parts of a `size`, `move` or `center` rule action, as typed
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacementRule {
+    pub kind: PlacementKind,
+    pub mode: MoveMode,
+    /// Keep the window on screen, for `move onscreen`
+    pub onscreen: bool,
+    /// The width, or where the window goes across
+    pub x: String,
+    /// The height, or where the window goes down
+    pub y: String,
+    /// What followed `center`, such as `1` to leave out reserved areas
+    center_arg: Option<String>,
+}
+
+impl PlacementRule {
+    /// Parse a rule action such as `size 50% 50%` or `move onscreen cursor
+    /// -50% -50%`. Missing values are left empty, for an action being typed.
+    pub fn parse(action: &str) -> Option<Self> {
+        let mut tokens = action.split_whitespace().peekable();
+        let mut rule = Self {
+            kind: PlacementKind::Move,
+            mode: MoveMode::Position,
+            onscreen: false,
+            x: String::new(),
+            y: String::new(),
+            center_arg: None,
+        };
+        match tokens.next()? {
+            "size" => rule.kind = PlacementKind::Size,
+            "move" => {
+                rule.onscreen = tokens.next_if_eq(&"onscreen").is_some();
+                if tokens.next_if_eq(&"cursor").is_some() {
+                    rule.mode = MoveMode::Cursor;
+                }
+            }
+            "center" => {
+                rule.mode = MoveMode::Center;
+                rule.center_arg = tokens.next().map(str::to_string);
+                return tokens.next().is_none().then_some(rule);
+            }
+            _ => return None,
+        }
+
+        let values: Vec<&str> = tokens.collect();
+        if values.len() > 2 {
+            return None;
+        }
+        rule.x = values.first().map_or_else(String::new, |x| x.to_string());
+        rule.y = values.get(1).map_or_else(String::new, |y| y.to_string());
+        Some(rule)
+    }
+
+    /// The rule action, with the values as typed
+    pub fn to_action(&self) -> String {
+        if self.mode == MoveMode::Center {
+            return match &self.center_arg {
+                Some(arg) => format!("center {arg}"),
+                None => "center".to_string(),
+            };
+        }
+        let mut parts = vec![match self.kind {
+            PlacementKind::Size => "size",
+            PlacementKind::Move => "move",
+        }];
+        if self.kind == PlacementKind::Move {
+            if self.onscreen {
+                parts.push("onscreen");
+            }
+            if self.mode == MoveMode::Cursor {
+                parts.push("cursor");
+            }
+        }
+        parts.extend(
+            [self.x.as_str(), self.y.as_str()]
+                .into_iter()
+                .filter(|v| !v.is_empty()),
+        );
+        parts.join(" ")
+    }
+
+    /// The fields the editor offers for this rule, in the order Tab visits them
+    pub fn fields(&self) -> &'static [PlacementField] {
+        match (self.kind, self.mode) {
+            (PlacementKind::Size, _) => &[PlacementField::X, PlacementField::Y],
+            (PlacementKind::Move, MoveMode::Center) => &[PlacementField::Mode],
+            (PlacementKind::Move, _) => &[
+                PlacementField::Mode,
+                PlacementField::Onscreen,
+                PlacementField::X,
+                PlacementField::Y,
+            ],
+        }
+    }
+
+    /// The field after `field`, `None` after the last
+    pub fn next_field(&self, field: PlacementField) -> Option<PlacementField> {
+        let fields = self.fields();
+        let index = fields.iter().position(|f| *f == field)?;
+        fields.get(index + 1).copied()
+    }
+
+    /// The label of the X or Y field
+    pub fn label(&self, field: PlacementField) -> &'static str {
+        match (self.kind, self.mode, field) {
+            (_, _, PlacementField::Mode) => "Mode",
+            (_, _, PlacementField::Onscreen) => "On screen",
+            (PlacementKind::Size, _, PlacementField::X) => "Width",
+            (PlacementKind::Size, _, PlacementField::Y) => "Height",
+            (_, MoveMode::Cursor, PlacementField::X) => "X offset",
+            (_, MoveMode::Cursor, PlacementField::Y) => "Y offset",
+            (_, _, PlacementField::X) => "X",
+            (_, _, PlacementField::Y) => "Y",
+        }
+    }
+
+    /// What a value means, such as `% of the monitor`, or `None` when it
+    /// isn't valid
+    pub fn unit(&self, value: &str) -> Option<&'static str> {
+        let (bound, value) = match value.strip_prefix('<').or_else(|| value.strip_prefix('>')) {
+            Some(rest) if self.kind == PlacementKind::Size => (Some(&value[..1]), rest),
+            _ => (None, value),
+        };
+        if is_number(value) {
+            return Some(match bound {
+                Some("<") => "px at most",
+                Some(_) => "px at least",
+                None => "px",
+            });
+        }
+        if value.strip_suffix('%').is_some_and(is_number) {
+            return Some(match (bound, self.mode) {
+                (Some("<"), _) => "% of the monitor at most",
+                (Some(_), _) => "% of the monitor at least",
+                (None, MoveMode::Cursor) => "% of the window",
+                (None, _) => "% of the monitor",
+            });
+        }
+        (bound.is_none() && self.kind == PlacementKind::Move && is_expression(value))
+            .then_some("expression")
+    }
+
+    /// Type `c` into `field`. Space cycles the mode, flips on screen, or
+    /// switches a value between pixels and a percentage.
+    pub fn type_char(&mut self, field: PlacementField, c: char) {
+        match field {
+            PlacementField::Mode if c == ' ' => self.mode = self.mode.next(),
+            PlacementField::Onscreen if c == ' ' => self.onscreen = !self.onscreen,
+            PlacementField::X | PlacementField::Y => {
+                let value = if field == PlacementField::X {
+                    &mut self.x
+                } else {
+                    &mut self.y
+                };
+                if c == ' ' {
+                    toggle_percent(value);
+                } else if !c.is_whitespace() {
+                    value.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Delete the last character of a value
+    pub fn backspace(&mut self, field: PlacementField) {
+        match field {
+            PlacementField::X => {
+                self.x.pop();
+            }
+            PlacementField::Y => {
+                self.y.pop();
+            }
+            _ => {}
+        }
+    }
+
+    /// Check that the values are there and in units Hyprland understands
+    pub fn validate(&self) -> Result<(), String> {
+        if self.mode == MoveMode::Center {
+            return Ok(());
+        }
+        // Cursor offsets are optional, as long as both or neither are given
+        if self.mode == MoveMode::Cursor && self.x.is_empty() && self.y.is_empty() {
+            return Ok(());
+        }
+        for field in [PlacementField::X, PlacementField::Y] {
+            let name = self.label(field).to_lowercase();
+            let value = if field == PlacementField::X {
+                &self.x
+            } else {
+                &self.y
+            };
+            if value.is_empty() {
+                return Err(format!("the {name} is missing"));
+            }
+            if self.unit(value).is_none() {
+                return Err(match self.kind {
+                    PlacementKind::Size => format!(
+                        "the {name} must be pixels like 800 or a percentage like 50%, \
+                         optionally after < or >"
+                    ),
+                    PlacementKind::Move => format!(
+                        "the {name} must be pixels, a percentage like 50%, \
+                         or an expression like 100%-w-10"
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Change the size or move rule in a rule editor's `action`, if it is one
+pub fn edit_action(action: &mut String, edit: impl FnOnce(&mut PlacementRule)) {
+    if let Some(mut rule) = PlacementRule::parse(action) {
+        edit(&mut rule);
+        *action = rule.to_action();
+    }
+}
+
+fn is_number(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.parse::<f64>().is_ok()
+}
+
+/// Terms joined by `+` or `-`, each a number, a percentage, or `w` or `h`
+/// for the window's width and height
+fn is_expression(text: &str) -> bool {
+    let text = text.strip_prefix('-').unwrap_or(text);
+    text.split(['+', '-']).all(|term| {
+        matches!(term, "w" | "h")
+            || is_number(term)
+            || term.strip_suffix('%').is_some_and(is_number)
+    })
+}
+
+/// `50` becomes `50%` and back, keeping a leading `<` or `>`
+fn toggle_percent(value: &mut String) {
+    let number = value.trim_start_matches(['<', '>']);
+    if number.strip_suffix('%').is_some_and(is_number) {
+        value.pop();
+    } else if is_number(number) {
+        value.push('%');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_round_trip_in_their_units() {
+        for action in [
+            "size 50% 50%",
+            "size 1280 720",
+            "size <50% >300",
+            "move 100 200",
+            "move 100%-w-10 5%",
+            "move cursor -50% -50%",
+            "move onscreen cursor 10 10",
+            "move onscreen 0 0",
+            "move cursor",
+            "center",
+            "center 1",
+        ] {
+            let rule = PlacementRule::parse(action).unwrap();
+            assert_eq!(rule.to_action(), action);
+            assert_eq!(rule.validate(), Ok(()), "{action}");
+        }
+
+        let rule = PlacementRule::parse("move onscreen cursor 10 20").unwrap();
+        assert_eq!(rule.kind, PlacementKind::Move);
+        assert_eq!(rule.mode, MoveMode::Cursor);
+        assert!(rule.onscreen);
+        assert_eq!((rule.x.as_str(), rule.y.as_str()), ("10", "20"));
+
+        assert_eq!(PlacementRule::parse("float"), None);
+        assert_eq!(PlacementRule::parse("size 1 2 3"), None);
+        assert_eq!(PlacementRule::parse("center 1 2"), None);
+        assert_eq!(PlacementRule::parse("sizex 1 2"), None);
+    }
+
+    #[test]
+    fn test_values_are_checked_and_described() {
+        let check = |action| PlacementRule::parse(action).unwrap().validate();
+        assert_eq!(check("size 50%"), Err("the height is missing".to_string()));
+        assert!(check("size 50%% 10")
+            .unwrap_err()
+            .starts_with("the width must be"));
+        assert!(check("size 100%-w 10").is_err());
+        assert!(check("move 10px 10")
+            .unwrap_err()
+            .starts_with("the x must be"));
+        assert!(check("move cursor 10").is_err());
+        assert!(check("move <10 10").is_err());
+
+        let size = PlacementRule::parse("size 1 1").unwrap();
+        assert_eq!(size.unit("800"), Some("px"));
+        assert_eq!(size.unit("50%"), Some("% of the monitor"));
+        assert_eq!(size.unit("<50%"), Some("% of the monitor at most"));
+        assert_eq!(size.unit(">300"), Some("px at least"));
+        let cursor = PlacementRule::parse("move cursor").unwrap();
+        assert_eq!(cursor.unit("-50%"), Some("% of the window"));
+        assert_eq!(cursor.unit("100%-w-10"), Some("expression"));
+        assert_eq!(cursor.unit("abc"), None);
+    }
+
+    #[test]
+    fn test_fields_are_edited_in_the_action() {
+        let mut action = "size 50 50".to_string();
+        edit_action(&mut action, |rule| rule.type_char(PlacementField::X, ' '));
+        assert_eq!(action, "size 50% 50");
+        edit_action(&mut action, |rule| rule.type_char(PlacementField::X, ' '));
+        edit_action(&mut action, |rule| rule.backspace(PlacementField::Y));
+        edit_action(&mut action, |rule| rule.type_char(PlacementField::Y, '9'));
+        assert_eq!(action, "size 50 59");
+
+        let mut action = "move 10 10".to_string();
+        edit_action(&mut action, |rule| {
+            rule.type_char(PlacementField::Onscreen, ' ')
+        });
+        edit_action(&mut action, |rule| {
+            rule.type_char(PlacementField::Mode, ' ')
+        });
+        assert_eq!(action, "move onscreen cursor 10 10");
+        edit_action(&mut action, |rule| {
+            rule.type_char(PlacementField::Mode, ' ')
+        });
+        assert_eq!(action, "center");
+
+        let move_rule = PlacementRule::parse("move 1 1").unwrap();
+        assert_eq!(
+            move_rule.next_field(PlacementField::Onscreen),
+            Some(PlacementField::X)
+        );
+        assert_eq!(move_rule.next_field(PlacementField::Y), None);
+        let center = PlacementRule::parse("center").unwrap();
+        assert_eq!(center.fields(), &[PlacementField::Mode]);
+        assert_eq!(
+            PlacementRule::parse("size 1 1").unwrap().fields()[0],
+            PlacementField::X
+        );
+    }
+}
@@ -43,6 +43,51 @@ impl std::fmt::Display for DistributionType {
     }
 }
 
+impl DistributionType {
+    /// How to install Hyprland, which provides hyprctl
+    pub fn hyprland_install_hint(&self) -> &'static str {
+        match self {
+            DistributionType::Arch | DistributionType::Manjaro => "sudo pacman -S hyprland",
+            DistributionType::Fedora => "sudo dnf install hyprland",
+            // Not in the base repositories
+            DistributionType::CentOS | DistributionType::Rhel => {
+                "sudo dnf copr enable solopasha/hyprland && sudo dnf install hyprland"
+            }
+            DistributionType::OpenSUSE => "sudo zypper install hyprland",
+            DistributionType::Ubuntu | DistributionType::Debian => "sudo apt install hyprland",
+            DistributionType::Gentoo => "sudo emerge gui-wm/hyprland",
+            DistributionType::Alpine => "doas apk add hyprland",
+            DistributionType::NixOS => {
+                "set programs.hyprland.enable = true; in your NixOS configuration and rebuild"
+            }
+            DistributionType::Unknown(_) => {
+                "see https://wiki.hyprland.org/Getting-Started/Installation/"
+            }
+        }
+    }
+
+    /// Whether hyprland.conf is usually generated from a declarative system
+    /// config, so edits have to go back into that config to persist
+    pub fn has_declarative_config(&self) -> bool {
+        matches!(self, DistributionType::NixOS)
+    }
+}
+
+/// Tells someone without hyprctl how to get it on their distribution
+pub fn hyprland_install_message() -> String {
+    match DistributionDetector::detect() {
+        Ok(info) if !matches!(info.distribution_type, DistributionType::Unknown(_)) => format!(
+            "To install Hyprland on {}: {}",
+            info.distribution_type,
+            info.distribution_type.hyprland_install_hint()
+        ),
+        _ => format!(
+            "To install Hyprland, {}",
+            DistributionType::Unknown(String::new()).hyprland_install_hint()
+        ),
+    }
+}
+
 /// Information about the detected distribution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DistributionInfo {
@@ -609,6 +654,33 @@ DISTRIB_DESCRIPTION="Ubuntu 20.04.3 LTS"
         assert!(!arch_info.is_based_on(&DistributionType::Debian));
     }
 
+    #[test]
+    fn test_install_hints_name_the_distribution_package() {
+        assert_eq!(
+            DistributionType::Arch.hyprland_install_hint(),
+            "sudo pacman -S hyprland"
+        );
+        assert_eq!(
+            DistributionType::Manjaro.hyprland_install_hint(),
+            DistributionType::Arch.hyprland_install_hint()
+        );
+        assert!(DistributionType::Fedora
+            .hyprland_install_hint()
+            .starts_with("sudo dnf install"));
+        assert!(DistributionType::Gentoo
+            .hyprland_install_hint()
+            .contains("gui-wm/hyprland"));
+        assert!(DistributionType::NixOS
+            .hyprland_install_hint()
+            .contains("programs.hyprland.enable"));
+        assert!(DistributionType::Unknown("Custom".to_string())
+            .hyprland_install_hint()
+            .contains("wiki.hyprland.org"));
+
+        assert!(DistributionType::NixOS.has_declarative_config());
+        assert!(!DistributionType::Arch.has_declarative_config());
+    }
+
     #[test]
     fn test_distribution_type_display() {
         assert_eq!(DistributionType::Ubuntu.to_string(), "Ubuntu");
@@ -1,5 +1,5 @@
 pub mod distribution;
 pub mod paths;
 
-pub use distribution::{DistributionDetector, DistributionType};
+pub use distribution::{hyprland_install_message, DistributionDetector, DistributionType};
 pub use paths::ConfigPathManager;
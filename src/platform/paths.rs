@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::sync::{Mutex, OnceLock};
 
@@ -80,6 +81,54 @@ impl PathConfiguration {
     }
 }
 
+/// XDG base directories as the base directory specification defines them.
+/// Variables that are unset, empty or relative fall back to the defaults
+/// under the home directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XdgDirs {
+    pub home: PathBuf,
+    pub config_home: PathBuf,
+    pub cache_home: PathBuf,
+    pub data_home: PathBuf,
+    /// System-wide config folders, most preferred first
+    pub config_dirs: Vec<PathBuf>,
+}
+
+impl XdgDirs {
+    pub fn from_env() -> Result<Self> {
+        let home = dirs::home_dir().context("Could not determine home directory")?;
+        Ok(Self::resolve(home, |name| std::env::var_os(name)))
+    }
+
+    /// Resolve the directories with `var` looking up environment variables
+    pub fn resolve(home: PathBuf, var: impl Fn(&str) -> Option<OsString>) -> Self {
+        let absolute = |name: &str| {
+            var(name)
+                .map(PathBuf::from)
+                .filter(|path| path.is_absolute())
+        };
+        let config_dirs: Vec<PathBuf> = var("XDG_CONFIG_DIRS")
+            .map(|value| {
+                std::env::split_paths(&value)
+                    .filter(|path| path.is_absolute())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            config_home: absolute("XDG_CONFIG_HOME").unwrap_or_else(|| home.join(".config")),
+            cache_home: absolute("XDG_CACHE_HOME").unwrap_or_else(|| home.join(".cache")),
+            data_home: absolute("XDG_DATA_HOME").unwrap_or_else(|| home.join(".local/share")),
+            config_dirs: if config_dirs.is_empty() {
+                vec![PathBuf::from("/etc/xdg")]
+            } else {
+                config_dirs
+            },
+            home,
+        }
+    }
+}
+
 /// Path cache for performance optimization
 static PATH_CACHE: OnceLock<Mutex<Option<PathConfiguration>>> = OnceLock::new();
 
@@ -100,7 +149,15 @@ impl ConfigPathManager {
         }
 
         let distribution = DistributionDetector::detect()?;
-        let paths = Self::resolve_paths(&distribution)?;
+        let mut paths = Self::resolve_paths(&distribution)?;
+
+        // --config or R_HYPRCONFIG_CONFIG picks the hyprland.conf
+        if let Some(file) = crate::config::hyprland_config_override() {
+            if let Some(dir) = file.parent() {
+                paths.hyprland_config_dir = dir.to_path_buf();
+            }
+            paths.hyprland_config_file = file.to_path_buf();
+        }
 
         {
             let mut cached = cache.lock()
@@ -131,28 +188,35 @@ impl ConfigPathManager {
 
     /// Resolve paths for standard Linux distributions
     fn resolve_standard_paths(distribution: &DistributionInfo) -> Result<PathConfiguration> {
-        let home_dir = dirs::home_dir().context("Could not determine home directory")?;
+        Ok(Self::resolve_xdg_paths(&XdgDirs::from_env()?, distribution))
+    }
 
-        // Standard XDG paths
-        let config_home = dirs::config_dir().unwrap_or_else(|| home_dir.join(".config"));
-        let cache_home = dirs::cache_dir().unwrap_or_else(|| home_dir.join(".cache"));
-        let data_home = dirs::data_dir().unwrap_or_else(|| home_dir.join(".local/share"));
+    /// Resolve paths specifically for NixOS. Home Manager and system-wide
+    /// setups both write the config under the XDG config home.
+    fn resolve_nixos_paths(distribution: &DistributionInfo) -> Result<PathConfiguration> {
+        Ok(Self::resolve_xdg_paths(&XdgDirs::from_env()?, distribution))
+    }
 
+    fn resolve_xdg_paths(xdg: &XdgDirs, distribution: &DistributionInfo) -> PathConfiguration {
         // Hyprland configuration paths
-        let hyprland_config_dir = Self::resolve_hyprland_config_dir(&config_home, distribution)?;
-        let hyprland_config_file = hyprland_config_dir.join("hyprland.conf");
+        let candidates = Self::hyprland_config_candidates(xdg, distribution);
+        let hyprland_config_file = Self::locate_hyprland_config(&candidates, Path::is_file);
+        let hyprland_config_dir = hyprland_config_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| xdg.config_home.join("hypr"));
 
         // Application paths
-        let app_config_dir = config_home.join("r-hyprconfig");
-        let app_cache_dir = cache_home.join("r-hyprconfig");
-        let app_data_dir = data_home.join("r-hyprconfig");
+        let app_config_dir = xdg.config_home.join("r-hyprconfig");
+        let app_cache_dir = xdg.cache_home.join("r-hyprconfig");
+        let app_data_dir = xdg.data_home.join("r-hyprconfig");
 
         // Specialized directories
         let backup_dir = app_data_dir.join("backups");
         let profiles_dir = app_data_dir.join("profiles");
         let exports_dir = app_data_dir.join("exports");
 
-        Ok(PathConfiguration {
+        PathConfiguration {
             hyprland_config_dir,
             hyprland_config_file,
             app_config_dir,
@@ -161,57 +225,51 @@ impl ConfigPathManager {
             backup_dir,
             profiles_dir,
             exports_dir,
-        })
+        }
     }
 
-    /// Resolve paths specifically for NixOS
-    fn resolve_nixos_paths(_distribution: &DistributionInfo) -> Result<PathConfiguration> {
-        let home_dir = dirs::home_dir().context("Could not determine home directory")?;
-
-        // Check for NixOS-specific environment variables
-        let config_home = std::env::var("XDG_CONFIG_HOME")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| home_dir.join(".config"));
-
-        let cache_home = std::env::var("XDG_CACHE_HOME")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| home_dir.join(".cache"));
-
-        let data_home = std::env::var("XDG_DATA_HOME")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| home_dir.join(".local/share"));
-
-        // NixOS-specific Hyprland paths
-        let hyprland_config_dir = if std::env::var("HOME_MANAGER_CONFIG").is_ok() {
-            // Using home-manager
-            config_home.join("hypr")
-        } else {
-            // System-wide NixOS configuration
-            config_home.join("hypr")
-        };
-
-        let hyprland_config_file = hyprland_config_dir.join("hyprland.conf");
+    /// Places Hyprland reads hyprland.conf from, most preferred first: the
+    /// XDG config home, the legacy `~/.hyprland.conf`, then the system-wide
+    /// XDG config dirs and `/etc/hypr`
+    pub fn hyprland_config_candidates(
+        xdg: &XdgDirs,
+        distribution: &DistributionInfo,
+    ) -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+        // The distribution's preferred folder can differ from hypr/
+        if let Ok(dir) = Self::resolve_hyprland_config_dir(&xdg.config_home, distribution) {
+            candidates.push(dir.join("hyprland.conf"));
+        }
+        candidates.push(xdg.config_home.join("hypr").join("hyprland.conf"));
+        candidates.push(xdg.home.join(".hyprland.conf"));
+        for dir in &xdg.config_dirs {
+            candidates.push(dir.join("hypr").join("hyprland.conf"));
+        }
+        candidates.push(PathBuf::from("/etc/hypr/hyprland.conf"));
 
-        // Application paths (same as standard for user data)
-        let app_config_dir = config_home.join("r-hyprconfig");
-        let app_cache_dir = cache_home.join("r-hyprconfig");
-        let app_data_dir = data_home.join("r-hyprconfig");
+        let mut seen = std::collections::HashSet::new();
+        candidates.retain(|candidate| seen.insert(candidate.clone()));
+        candidates
+    }
 
-        // NixOS-specific directories
-        let backup_dir = app_data_dir.join("backups");
-        let profiles_dir = app_data_dir.join("profiles");
-        let exports_dir = app_data_dir.join("exports");
+    /// The first candidate that exists, or the first one, to be created
+    pub fn locate_hyprland_config(
+        candidates: &[PathBuf],
+        exists: impl Fn(&Path) -> bool,
+    ) -> PathBuf {
+        candidates
+            .iter()
+            .find(|candidate| exists(candidate))
+            .or(candidates.first())
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from("hyprland.conf"))
+    }
 
-        Ok(PathConfiguration {
-            hyprland_config_dir,
-            hyprland_config_file,
-            app_config_dir,
-            app_cache_dir,
-            app_data_dir,
-            backup_dir,
-            profiles_dir,
-            exports_dir,
-        })
+    /// Where hyprland.conf is found on this system, ignoring `--config`
+    pub fn default_hyprland_config_file() -> Result<PathBuf> {
+        let distribution = DistributionDetector::detect()?;
+        let candidates = Self::hyprland_config_candidates(&XdgDirs::from_env()?, &distribution);
+        Ok(Self::locate_hyprland_config(&candidates, Path::is_file))
     }
 
     /// Resolve Hyprland configuration directory for different distributions
@@ -562,4 +620,122 @@ mod tests {
 
         Ok(())
     }
+
+    fn arch() -> DistributionInfo {
+        DistributionInfo {
+            distribution_type: DistributionType::Arch,
+            version: None,
+            version_id: None,
+            name: "Arch Linux".to_string(),
+            pretty_name: None,
+            id: "arch".to_string(),
+            id_like: None,
+            home_url: None,
+            support_url: None,
+            bug_report_url: None,
+        }
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| {
+            vars.iter()
+                .find(|(var, _)| var == name)
+                .map(|(_, value)| OsString::from(value))
+        }
+    }
+
+    #[test]
+    fn test_xdg_variables_are_honored() {
+        let home = PathBuf::from("/home/me");
+        let xdg = XdgDirs::resolve(
+            home.clone(),
+            env(&[
+                ("XDG_CONFIG_HOME", "/home/me/cfg"),
+                ("XDG_DATA_HOME", "/data"),
+                ("XDG_CONFIG_DIRS", "/etc/xdg-custom:/opt/xdg"),
+            ]),
+        );
+        assert_eq!(xdg.config_home, PathBuf::from("/home/me/cfg"));
+        assert_eq!(xdg.cache_home, PathBuf::from("/home/me/.cache"));
+        assert_eq!(xdg.data_home, PathBuf::from("/data"));
+        assert_eq!(
+            xdg.config_dirs,
+            vec![PathBuf::from("/etc/xdg-custom"), PathBuf::from("/opt/xdg")]
+        );
+
+        let paths = ConfigPathManager::resolve_xdg_paths(&xdg, &arch());
+        assert_eq!(
+            paths.hyprland_config_file,
+            PathBuf::from("/home/me/cfg/hypr/hyprland.conf")
+        );
+        assert_eq!(paths.hyprland_config_dir, Path::new("/home/me/cfg/hypr"));
+        assert_eq!(paths.app_config_dir, Path::new("/home/me/cfg/r-hyprconfig"));
+        assert_eq!(paths.backup_dir, Path::new("/data/r-hyprconfig/backups"));
+    }
+
+    #[test]
+    fn test_unset_empty_and_relative_xdg_variables_use_defaults() {
+        let home = PathBuf::from("/home/me");
+        let expected = XdgDirs {
+            home: home.clone(),
+            config_home: home.join(".config"),
+            cache_home: home.join(".cache"),
+            data_home: home.join(".local/share"),
+            config_dirs: vec![PathBuf::from("/etc/xdg")],
+        };
+        assert_eq!(XdgDirs::resolve(home.clone(), env(&[])), expected);
+        assert_eq!(
+            XdgDirs::resolve(
+                home.clone(),
+                env(&[
+                    ("XDG_CONFIG_HOME", ""),
+                    ("XDG_CACHE_HOME", "relative/cache"),
+                    ("XDG_CONFIG_DIRS", ""),
+                ])
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_existing_configs_are_found_in_other_locations() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let home = temp_dir.path().join("home");
+        let (cfg, etc) = (temp_dir.path().join("cfg"), temp_dir.path().join("etc"));
+        let xdg = XdgDirs::resolve(
+            home.clone(),
+            env(&[
+                ("XDG_CONFIG_HOME", cfg.to_str().unwrap()),
+                ("XDG_CONFIG_DIRS", etc.to_str().unwrap()),
+            ]),
+        );
+        let candidates = ConfigPathManager::hyprland_config_candidates(&xdg, &arch());
+        // Leave out /etc/hypr, which the machine running the test may have
+        let exists = |path: &Path| path.starts_with(temp_dir.path()) && path.is_file();
+        let locate = || ConfigPathManager::locate_hyprland_config(&candidates, exists);
+        let preferred = temp_dir.path().join("cfg/hypr/hyprland.conf");
+
+        // Nothing exists yet, so the config would be created in the XDG home
+        assert_eq!(locate(), preferred);
+
+        let system = temp_dir.path().join("etc/hypr/hyprland.conf");
+        std::fs::create_dir_all(system.parent().unwrap())?;
+        std::fs::write(&system, "")?;
+        assert_eq!(locate(), system);
+
+        let legacy = home.join(".hyprland.conf");
+        std::fs::create_dir_all(&home)?;
+        std::fs::write(&legacy, "")?;
+        assert_eq!(locate(), legacy);
+
+        std::fs::create_dir_all(preferred.parent().unwrap())?;
+        std::fs::write(&preferred, "")?;
+        assert_eq!(locate(), preferred);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,129 @@
+// Plugins
+// `plugin = PATH` lines load a plugin's shared object. The Plugins panel keeps
+// a disabled plugin as a commented-out line, `# plugin = PATH`, so enabling it
+// again doesn't mean looking up its path. Plugin options live in the
+// `plugin { NAME { ... } }` block and hyprctl names them `plugin:NAME:OPTION`.
+
+use std::path::Path;
+
+/// A single `plugin` line
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginLine {
+    pub path: String,
+    /// Disabled plugins are commented out
+    pub enabled: bool,
+}
+
+impl PluginLine {
+    /// Parse `plugin = PATH`, or `# plugin = PATH` for a disabled plugin
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let (enabled, body) = match line.strip_prefix('#') {
+            Some(rest) => (false, rest.trim_start()),
+            None => (true, line),
+        };
+        let (keyword, path) = body.split_once('=')?;
+        // A trailing comment isn't part of the path
+        let path = path.split('#').next().unwrap_or_default().trim();
+        if keyword.trim() != "plugin" || path.is_empty() {
+            return None;
+        }
+        Some(PluginLine {
+            path: path.to_string(),
+            enabled,
+        })
+    }
+
+    /// The whole config line
+    pub fn to_line(&self) -> String {
+        if self.enabled {
+            format!("plugin = {}", self.path)
+        } else {
+            format!("# plugin = {}", self.path)
+        }
+    }
+
+    /// The name the plugin's file gives it: `libhyprexpo.so` and `hyprexpo.so`
+    /// are both `hyprexpo`
+    pub fn name(&self) -> String {
+        let stem = Path::new(&self.path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(&self.path);
+        stem.strip_prefix("lib").unwrap_or(stem).to_string()
+    }
+}
+
+/// Whether a config line loads a plugin, or would if it weren't commented out
+pub fn is_plugin_line(line: &str) -> bool {
+    PluginLine::parse(line).is_some()
+}
+
+/// Whether an option belongs to a plugin, like `plugin:hyprexpo:columns`
+pub fn is_plugin_option(option: &str) -> bool {
+    option.starts_with("plugin:")
+}
+
+/// The plugin a `plugin:NAME:OPTION` option belongs to
+pub fn option_plugin(option: &str) -> Option<&str> {
+    option.strip_prefix("plugin:")?.split(':').next()
+}
+
+/// Check an edited plugin line
+pub fn validate_line(line: &str) -> Result<(), String> {
+    let plugin = PluginLine::parse(line)
+        .ok_or_else(|| "expected plugin = /path/to/plugin.so".to_string())?;
+    if !plugin.path.ends_with(".so") {
+        return Err(format!("'{}' is not a shared object (.so)", plugin.path));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_enabled_and_disabled_lines() {
+        let plugin = PluginLine::parse("plugin = /usr/lib/libhyprexpo.so").unwrap();
+        assert!(plugin.enabled);
+        assert_eq!(plugin.path, "/usr/lib/libhyprexpo.so");
+        assert_eq!(plugin.name(), "hyprexpo");
+
+        let plugin = PluginLine::parse("  #plugin=/opt/borders-plus-plus.so # old").unwrap();
+        assert!(!plugin.enabled);
+        assert_eq!(plugin.name(), "borders-plus-plus");
+        assert_eq!(plugin.to_line(), "# plugin = /opt/borders-plus-plus.so");
+
+        for other in ["plugin =", "plugins = /a.so", "# a comment", "plugin {"] {
+            assert!(!is_plugin_line(other), "{other}");
+        }
+    }
+
+    #[test]
+    fn test_toggling_round_trips() {
+        let mut plugin = PluginLine::parse("plugin = /a/libhyprbars.so").unwrap();
+        plugin.enabled = false;
+        let disabled = plugin.to_line();
+        assert_eq!(disabled, "# plugin = /a/libhyprbars.so");
+        let mut plugin = PluginLine::parse(&disabled).unwrap();
+        plugin.enabled = true;
+        assert_eq!(plugin.to_line(), "plugin = /a/libhyprbars.so");
+    }
+
+    #[test]
+    fn test_plugin_options() {
+        assert!(is_plugin_option("plugin:hyprexpo:columns"));
+        assert!(!is_plugin_option("general:gaps_in"));
+        assert_eq!(option_plugin("plugin:hyprexpo:columns"), Some("hyprexpo"));
+        assert_eq!(option_plugin("misc:vfr"), None);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(validate_line("plugin = /usr/lib/libhyprexpo.so").is_ok());
+        assert!(validate_line("# plugin = /usr/lib/libhyprexpo.so").is_ok());
+        assert!(validate_line("plugin = /usr/lib/hyprexpo").is_err());
+        assert!(validate_line("exec-once = waybar").is_err());
+    }
+}
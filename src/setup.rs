@@ -0,0 +1,244 @@
+// First-run setup
+// The first time r-hyprconfig starts, before it has a config.toml, a short
+// wizard says what it found: the distribution, whether Hyprland answers and
+// where hyprland.conf is. It offers to create hyprland.conf when there is
+// none, lets the user pick a theme and lists the keys to get going.
+// `first_run_completed` in config.toml keeps it from showing again, and
+// `--setup` brings it back.
+
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::hyprctl::HyprlandStatus;
+use crate::keymap::{KeyAction, KEY_MAP};
+use crate::platform::{DistributionDetector, DistributionType};
+
+/// A page of the wizard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupStep {
+    Environment,
+    ConfigFile,
+    Theme,
+    Keys,
+}
+
+impl SetupStep {
+    pub const ALL: [SetupStep; 4] = [
+        SetupStep::Environment,
+        SetupStep::ConfigFile,
+        SetupStep::Theme,
+        SetupStep::Keys,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            SetupStep::Environment => "Your system",
+            SetupStep::ConfigFile => "hyprland.conf",
+            SetupStep::Theme => "Theme",
+            SetupStep::Keys => "Keys",
+        }
+    }
+
+    /// The step's place, counting from 1
+    pub fn number(self) -> usize {
+        Self::ALL.iter().position(|step| *step == self).unwrap_or(0) + 1
+    }
+
+    /// The step after this one, or `None` on the last
+    pub fn next(self) -> Option<Self> {
+        Self::ALL.get(self.number()).copied()
+    }
+
+    pub fn previous(self) -> Option<Self> {
+        Self::ALL.get(self.number().checked_sub(2)?).copied()
+    }
+}
+
+/// What the wizard found out about the system
+#[derive(Debug, Clone, PartialEq)]
+pub struct Environment {
+    /// The distribution's name, when it could be told
+    pub distribution: Option<String>,
+    pub nixos: bool,
+    pub status: HyprlandStatus,
+    pub config_path: PathBuf,
+    pub config_exists: bool,
+    /// hyprland.conf links into the Nix store, so saving to it fails
+    pub nix_managed: bool,
+}
+
+impl Environment {
+    pub fn detect(config: &Config, status: HyprlandStatus) -> Self {
+        let distribution = DistributionDetector::detect()
+            .ok()
+            .filter(|info| !matches!(info.distribution_type, DistributionType::Unknown(_)))
+            .map(|info| info.pretty_name.unwrap_or(info.name));
+        Self {
+            distribution,
+            nixos: config.nixos_mode,
+            status,
+            config_path: config.hyprland_config_path.clone(),
+            config_exists: config.hyprland_config_path.is_file(),
+            nix_managed: crate::config::is_nix_managed(&config.hyprland_config_path),
+        }
+    }
+
+    /// The lines of the first page
+    pub fn system_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Distribution: {}",
+            self.distribution.as_deref().unwrap_or("not recognized")
+        )];
+        if self.nixos {
+            lines.push(
+                "NixOS: yes, so hyprland.conf may be generated by your Nix configuration"
+                    .to_string(),
+            );
+        }
+        lines.push(String::new());
+        lines.push(match self.status {
+            HyprlandStatus::Connected => {
+                "Hyprland is running: changes can be previewed live and apply as you make them"
+                    .to_string()
+            }
+            HyprlandStatus::NotRunning => "Hyprland isn't running, so changes are only saved \
+                 to hyprland.conf. They take effect once Hyprland starts. Run r-hyprconfig \
+                 inside Hyprland to preview them live."
+                .to_string(),
+            HyprlandStatus::NotInstalled => format!(
+                "hyprctl isn't installed, so changes are only saved to hyprland.conf. {}",
+                crate::platform::hyprland_install_message()
+            ),
+            HyprlandStatus::FileOnly => {
+                "File-only mode: changes are saved to hyprland.conf and never sent to Hyprland"
+                    .to_string()
+            }
+        });
+        lines
+    }
+
+    /// The lines of the hyprland.conf page
+    pub fn config_file_lines(&self) -> Vec<String> {
+        let path = self.config_path.display();
+        if !self.config_exists {
+            return vec![
+                format!("There is no hyprland.conf at {path}."),
+                String::new(),
+                "Press C to create one from a starter config with the usual options, \
+                 or Enter to go on without it."
+                    .to_string(),
+            ];
+        }
+        let mut lines = vec![format!("Editing {path}")];
+        if self.nix_managed {
+            lines.push(String::new());
+            lines.push(
+                "It is generated by Nix, so saving to it will fail. Enable nixos_mode in \
+                 config.toml, or export a Nix module with N from the export dialog."
+                    .to_string(),
+            );
+        } else {
+            lines.push(String::new());
+            lines.push("It is backed up before every save, and O restores a backup.".to_string());
+        }
+        lines.push(String::new());
+        lines.push(
+            "To edit another file, start r-hyprconfig with --config PATH or change \
+             hyprland_config_path in config.toml."
+                .to_string(),
+        );
+        lines
+    }
+}
+
+/// The keys the last page lists, with what they do
+const GETTING_STARTED: [KeyAction; 8] = [
+    KeyAction::NextPanel,
+    KeyAction::Edit,
+    KeyAction::Search,
+    KeyAction::Save,
+    KeyAction::Undo,
+    KeyAction::CommandPalette,
+    KeyAction::Help,
+    KeyAction::Quit,
+];
+
+/// The first binding of each getting-started action, as its keys and what
+/// it does
+pub fn getting_started_keys() -> Vec<(String, &'static str)> {
+    GETTING_STARTED
+        .iter()
+        .filter_map(|action| KEY_MAP.iter().find(|binding| binding.action == *action))
+        .map(|binding| (binding.label(), binding.description))
+        .collect()
+}
+
+/// The wizard while it is open
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetupWizard {
+    pub step: SetupStep,
+    pub environment: Environment,
+}
+
+impl SetupWizard {
+    pub fn new(environment: Environment) -> Self {
+        Self {
+            step: SetupStep::Environment,
+            environment,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn environment(status: HyprlandStatus, config_exists: bool) -> Environment {
+        Environment {
+            distribution: Some("Arch Linux".to_string()),
+            nixos: false,
+            status,
+            config_path: PathBuf::from("/home/user/.config/hypr/hyprland.conf"),
+            config_exists,
+            nix_managed: false,
+        }
+    }
+
+    #[test]
+    fn test_steps_go_forward_and_back() {
+        assert_eq!(SetupStep::Environment.number(), 1);
+        assert_eq!(SetupStep::Environment.next(), Some(SetupStep::ConfigFile));
+        assert_eq!(SetupStep::Keys.next(), None);
+        assert_eq!(SetupStep::Theme.previous(), Some(SetupStep::ConfigFile));
+        assert_eq!(SetupStep::Environment.previous(), None);
+    }
+
+    #[test]
+    fn test_wizard_says_what_it_found() {
+        let running = environment(HyprlandStatus::Connected, true);
+        assert_eq!(running.system_lines()[0], "Distribution: Arch Linux");
+        assert!(running.system_lines()[2].starts_with("Hyprland is running"));
+
+        let offline = environment(HyprlandStatus::NotRunning, true);
+        assert!(offline.system_lines()[2].starts_with("Hyprland isn't running"));
+
+        assert_eq!(
+            running.config_file_lines()[0],
+            "Editing /home/user/.config/hypr/hyprland.conf"
+        );
+        let missing = environment(HyprlandStatus::NotRunning, false);
+        assert_eq!(
+            missing.config_file_lines()[0],
+            "There is no hyprland.conf at /home/user/.config/hypr/hyprland.conf."
+        );
+        assert!(missing.config_file_lines()[2].starts_with("Press C"));
+    }
+
+    #[test]
+    fn test_getting_started_keys_come_from_the_key_map() {
+        let keys = getting_started_keys();
+        assert_eq!(keys.len(), GETTING_STARTED.len());
+        assert_eq!(keys[0], ("Tab, →".to_string(), "Next panel"));
+        assert!(keys.contains(&("S".to_string(), "Save the configuration")));
+    }
+}
@@ -20,10 +20,18 @@ pub struct UIState {
     pub decoration_list_state: ListState,
     pub animations_list_state: ListState,
     pub gestures_list_state: ListState,
+    pub layouts_list_state: ListState,
+    pub groups_list_state: ListState,
     pub binds_list_state: ListState,
     pub window_rules_list_state: ListState,
     pub layer_rules_list_state: ListState,
+    pub workspaces_list_state: ListState,
+    pub environment_list_state: ListState,
+    pub plugins_list_state: ListState,
+    pub autostart_list_state: ListState,
+    pub variables_list_state: ListState,
     pub misc_list_state: ListState,
+    pub other_list_state: ListState,
     pub import_list_state: ListState,
     pub export_list_state: ListState,
 
@@ -56,10 +64,18 @@ impl Default for UIState {
             decoration_list_state: ListState::default(),
             animations_list_state: ListState::default(),
             gestures_list_state: ListState::default(),
+            layouts_list_state: ListState::default(),
+            groups_list_state: ListState::default(),
             binds_list_state: ListState::default(),
             window_rules_list_state: ListState::default(),
             layer_rules_list_state: ListState::default(),
+            workspaces_list_state: ListState::default(),
+            environment_list_state: ListState::default(),
+            plugins_list_state: ListState::default(),
+            autostart_list_state: ListState::default(),
+            variables_list_state: ListState::default(),
             misc_list_state: ListState::default(),
+            other_list_state: ListState::default(),
             import_list_state: ListState::default(),
             export_list_state: ListState::default(),
             current_tab: FocusedPanel::General,
@@ -89,10 +105,18 @@ impl UIState {
             FocusedPanel::Decoration => &mut self.decoration_list_state,
             FocusedPanel::Animations => &mut self.animations_list_state,
             FocusedPanel::Gestures => &mut self.gestures_list_state,
+            FocusedPanel::Layouts => &mut self.layouts_list_state,
+            FocusedPanel::Groups => &mut self.groups_list_state,
             FocusedPanel::Binds => &mut self.binds_list_state,
             FocusedPanel::WindowRules => &mut self.window_rules_list_state,
             FocusedPanel::LayerRules => &mut self.layer_rules_list_state,
+            FocusedPanel::Workspaces => &mut self.workspaces_list_state,
+            FocusedPanel::Environment => &mut self.environment_list_state,
+            FocusedPanel::Plugins => &mut self.plugins_list_state,
+            FocusedPanel::Autostart => &mut self.autostart_list_state,
+            FocusedPanel::Variables => &mut self.variables_list_state,
             FocusedPanel::Misc => &mut self.misc_list_state,
+            FocusedPanel::Other => &mut self.other_list_state,
             FocusedPanel::Import => &mut self.import_list_state,
             FocusedPanel::Export => &mut self.export_list_state,
         }
@@ -105,10 +129,18 @@ impl UIState {
             FocusedPanel::Decoration => &self.decoration_list_state,
             FocusedPanel::Animations => &self.animations_list_state,
             FocusedPanel::Gestures => &self.gestures_list_state,
+            FocusedPanel::Layouts => &self.layouts_list_state,
+            FocusedPanel::Groups => &self.groups_list_state,
             FocusedPanel::Binds => &self.binds_list_state,
             FocusedPanel::WindowRules => &self.window_rules_list_state,
             FocusedPanel::LayerRules => &self.layer_rules_list_state,
+            FocusedPanel::Workspaces => &self.workspaces_list_state,
+            FocusedPanel::Environment => &self.environment_list_state,
+            FocusedPanel::Plugins => &self.plugins_list_state,
+            FocusedPanel::Autostart => &self.autostart_list_state,
+            FocusedPanel::Variables => &self.variables_list_state,
             FocusedPanel::Misc => &self.misc_list_state,
+            FocusedPanel::Other => &self.other_list_state,
             FocusedPanel::Import => &self.import_list_state,
             FocusedPanel::Export => &self.export_list_state,
         }
@@ -126,10 +158,18 @@ impl UIState {
         self.decoration_list_state = ListState::default();
         self.animations_list_state = ListState::default();
         self.gestures_list_state = ListState::default();
+        self.layouts_list_state = ListState::default();
+        self.groups_list_state = ListState::default();
         self.binds_list_state = ListState::default();
         self.window_rules_list_state = ListState::default();
         self.layer_rules_list_state = ListState::default();
+        self.workspaces_list_state = ListState::default();
+        self.environment_list_state = ListState::default();
+        self.plugins_list_state = ListState::default();
+        self.autostart_list_state = ListState::default();
+        self.variables_list_state = ListState::default();
         self.misc_list_state = ListState::default();
+        self.other_list_state = ListState::default();
         self.import_list_state = ListState::default();
         self.export_list_state = ListState::default();
     }
@@ -216,6 +256,12 @@ pub struct DialogState {
     pub popup_message: String,
     pub show_save_dialog: bool,
     pub show_reload_dialog: bool,
+    pub show_quit_dialog: bool,
+    pub show_restore_dialog: bool,
+    pub show_deprecations_dialog: bool,
+    pub show_setup_wizard: bool,
+    pub show_global_search: bool,
+    pub show_command_palette: bool,
     pub show_help: bool,
 
     // NixOS export dialog
@@ -252,6 +298,12 @@ impl Default for DialogState {
             popup_message: String::new(),
             show_save_dialog: false,
             show_reload_dialog: false,
+            show_quit_dialog: false,
+            show_restore_dialog: false,
+            show_deprecations_dialog: false,
+            show_setup_wizard: false,
+            show_global_search: false,
+            show_command_palette: false,
             show_help: false,
             show_nixos_export_dialog: false,
             nixos_export_config_type: NixConfigType::SystemConfig,
@@ -285,6 +337,12 @@ impl DialogState {
         self.show_popup
             || self.show_save_dialog
             || self.show_reload_dialog
+            || self.show_quit_dialog
+            || self.show_restore_dialog
+            || self.show_deprecations_dialog
+            || self.show_setup_wizard
+            || self.show_global_search
+            || self.show_command_palette
             || self.show_help
             || self.show_nixos_export_dialog
             || self.show_batch_dialog
@@ -298,6 +356,12 @@ impl DialogState {
         self.show_popup = false;
         self.show_save_dialog = false;
         self.show_reload_dialog = false;
+        self.show_quit_dialog = false;
+        self.show_restore_dialog = false;
+        self.show_deprecations_dialog = false;
+        self.show_setup_wizard = false;
+        self.show_global_search = false;
+        self.show_command_palette = false;
         self.show_help = false;
         self.show_nixos_export_dialog = false;
         self.show_batch_dialog = false;
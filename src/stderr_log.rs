@@ -0,0 +1,206 @@
+// Stderr while the TUI is up
+// Warnings printed to stderr land on top of the alternate screen and scramble
+// the interface, so while it is shown they go to a log file instead. The
+// recent ones are also kept in memory for the warnings panel, so failures to
+// load, save or reach Hyprland can be seen without leaving the interface.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::fd::{AsFd, AsRawFd, OwnedFd};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use crate::platform::paths::XdgDirs;
+
+/// How many entries the warnings panel keeps
+const RECENT_CAPACITY: usize = 100;
+
+static RECENT: Mutex<RecentLog> = Mutex::new(RecentLog::new(RECENT_CAPACITY));
+
+/// How serious a logged message is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// Something worth knowing that went fine, like a fallback being used
+    Note,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn prefix(self) -> &'static str {
+        match self {
+            Level::Note => "",
+            Level::Warning => "Warning: ",
+            Level::Error => "Error: ",
+        }
+    }
+}
+
+/// A logged message with when it was logged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    pub message: String,
+}
+
+/// The last few entries, dropping the oldest once full
+#[derive(Debug)]
+pub struct RecentLog {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+    /// Every entry ever pushed, so readers can tell which ones are new
+    total: usize,
+}
+
+impl RecentLog {
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+            total: 0,
+        }
+    }
+
+    pub fn push(&mut self, entry: LogEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+        self.total += 1;
+    }
+}
+
+/// Write a message to stderr and keep it for the warnings panel
+pub fn log(level: Level, message: impl Into<String>) {
+    let message = message.into();
+    eprintln!("{}{message}", level.prefix());
+    RECENT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(LogEntry {
+            time: Local::now(),
+            level,
+            message,
+        });
+}
+
+pub fn note(message: impl Into<String>) {
+    log(Level::Note, message);
+}
+
+pub fn warn(message: impl Into<String>) {
+    log(Level::Warning, message);
+}
+
+pub fn error(message: impl Into<String>) {
+    log(Level::Error, message);
+}
+
+/// The kept entries, oldest first
+pub fn recent_entries() -> Vec<LogEntry> {
+    let recent = RECENT.lock().unwrap_or_else(PoisonError::into_inner);
+    recent.entries.iter().cloned().collect()
+}
+
+/// How many entries were ever logged. Entries past what a reader saw last
+/// time are new to it.
+pub fn total_logged() -> usize {
+    RECENT.lock().unwrap_or_else(PoisonError::into_inner).total
+}
+
+/// Where warnings from the last session are kept
+pub fn log_file_path() -> Result<PathBuf> {
+    Ok(XdgDirs::from_env()?
+        .cache_home
+        .join("r-hyprconfig")
+        .join("r-hyprconfig.log"))
+}
+
+/// Sends stderr to a file until dropped, then restores it
+pub struct StderrRedirect {
+    saved: OwnedFd,
+}
+
+impl StderrRedirect {
+    /// Start writing stderr to `path`, replacing what the last session wrote
+    pub fn to_file(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let saved = std::io::stderr().as_fd().try_clone_to_owned()?;
+
+        std::io::stderr().flush()?;
+        // SAFETY: both descriptors are open for the duration of the call
+        if unsafe { libc::dup2(file.as_raw_fd(), libc::STDERR_FILENO) } == -1 {
+            return Err(std::io::Error::last_os_error()).context("Failed to redirect stderr");
+        }
+        Ok(Self { saved })
+    }
+}
+
+impl Drop for StderrRedirect {
+    fn drop(&mut self) {
+        let _ = std::io::stderr().flush();
+        // SAFETY: `saved` stays open until after the call
+        unsafe {
+            libc::dup2(self.saved.as_raw_fd(), libc::STDERR_FILENO);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_log_keeps_the_newest_entries() {
+        let mut log = RecentLog::new(3);
+        for n in 0..5 {
+            log.push(LogEntry {
+                time: Local::now(),
+                level: Level::Warning,
+                message: format!("warning {n}"),
+            });
+        }
+        let messages: Vec<&str> = log.entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["warning 2", "warning 3", "warning 4"]);
+        assert_eq!(log.total, 5);
+
+        error("hyprctl reload failed");
+        let logged = recent_entries();
+        assert!(logged
+            .iter()
+            .any(|e| e.level == Level::Error && e.message == "hyprctl reload failed"));
+        assert!(total_logged() >= 1);
+    }
+
+    #[test]
+    fn test_stderr_goes_to_the_file_until_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join("r-hyprconfig.log");
+
+        let redirect = StderrRedirect::to_file(&path).unwrap();
+        std::io::stderr()
+            .write_all(b"Warning: hyprctl is not installed\n")
+            .unwrap();
+        drop(redirect);
+
+        let logged = fs::read_to_string(&path).unwrap();
+        assert!(logged.contains("Warning: hyprctl is not installed"));
+
+        // A new session starts a fresh log
+        drop(StderrRedirect::to_file(&path).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+    }
+}
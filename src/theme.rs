@@ -1,5 +1,7 @@
 use ratatui::style::{Color, Style};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -10,8 +12,61 @@ pub enum ColorScheme {
     Nord,
     Catppuccin,
     Dracula,
+    /// A theme from `~/.config/r-hyprconfig/themes/`, stored in config.toml
+    /// by its name like the built-in ones
+    #[serde(untagged)]
+    Custom(String),
 }
 
+/// Colors a custom theme file can set, named as in `Theme`
+pub const THEME_FIELDS: [&str; 24] = [
+    "bg_primary",
+    "bg_secondary",
+    "bg_tertiary",
+    "bg_selected",
+    "bg_search",
+    "fg_primary",
+    "fg_secondary",
+    "fg_muted",
+    "fg_bright",
+    "accent_primary",
+    "accent_secondary",
+    "accent_success",
+    "accent_warning",
+    "accent_error",
+    "accent_info",
+    "border_normal",
+    "border_focused",
+    "border_active",
+    "type_integer",
+    "type_float",
+    "type_boolean",
+    "type_string",
+    "type_color",
+    "type_keyword",
+];
+
+/// User-defined themes by name
+pub type CustomThemes = BTreeMap<String, Theme>;
+
+/// Name of the theme made from Hyprland's own colors
+pub const HYPRLAND_THEME_NAME: &str = "Hyprland";
+
+/// Hyprland colors the Hyprland theme is made from, with the theme fields
+/// each one sets. `decoration:col.shadow` is the shadow color's name before
+/// Hyprland 0.45.
+pub const HYPRLAND_THEME_COLORS: [(&str, &[&str]); 6] = [
+    (
+        "general:col.active_border",
+        &["accent_primary", "border_focused", "border_active"],
+    ),
+    ("general:col.inactive_border", &["border_normal"]),
+    ("group:col.border_active", &["accent_secondary"]),
+    ("decoration:shadow:color", &["bg_secondary"]),
+    ("decoration:col.shadow", &["bg_secondary"]),
+    ("misc:background_color", &["bg_primary", "bg_search"]),
+];
+
 #[derive(Debug, Clone)]
 pub struct Theme {
     #[allow(dead_code)]
@@ -266,16 +321,89 @@ impl Theme {
         }
     }
 
-    pub fn from_scheme(scheme: ColorScheme) -> Self {
+    /// The theme for a scheme. A custom scheme with no theme of that name
+    /// falls back to the default theme.
+    pub fn from_scheme(scheme: ColorScheme, custom: &CustomThemes) -> Self {
         match scheme {
             ColorScheme::Default => Self::default_theme(),
             ColorScheme::Gruvbox => Self::gruvbox(),
             ColorScheme::Nord => Self::nord(),
             ColorScheme::Catppuccin => Self::catppuccin(),
             ColorScheme::Dracula => Self::dracula(),
+            ColorScheme::Custom(name) => custom.get(&name).cloned().unwrap_or_default(),
         }
     }
 
+    /// The color of a field in `THEME_FIELDS`
+    fn color_mut(&mut self, field: &str) -> Option<&mut Color> {
+        Some(match field {
+            "bg_primary" => &mut self.bg_primary,
+            "bg_secondary" => &mut self.bg_secondary,
+            "bg_tertiary" => &mut self.bg_tertiary,
+            "bg_selected" => &mut self.bg_selected,
+            "bg_search" => &mut self.bg_search,
+            "fg_primary" => &mut self.fg_primary,
+            "fg_secondary" => &mut self.fg_secondary,
+            "fg_muted" => &mut self.fg_muted,
+            "fg_bright" => &mut self.fg_bright,
+            "accent_primary" => &mut self.accent_primary,
+            "accent_secondary" => &mut self.accent_secondary,
+            "accent_success" => &mut self.accent_success,
+            "accent_warning" => &mut self.accent_warning,
+            "accent_error" => &mut self.accent_error,
+            "accent_info" => &mut self.accent_info,
+            "border_normal" => &mut self.border_normal,
+            "border_focused" => &mut self.border_focused,
+            "border_active" => &mut self.border_active,
+            "type_integer" => &mut self.type_integer,
+            "type_float" => &mut self.type_float,
+            "type_boolean" => &mut self.type_boolean,
+            "type_string" => &mut self.type_string,
+            "type_color" => &mut self.type_color,
+            "type_keyword" => &mut self.type_keyword,
+            _ => return None,
+        })
+    }
+
+    /// A custom theme from a theme file: an optional `name` and a `#rrggbb`
+    /// color for each field in `THEME_FIELDS`. Missing or invalid colors
+    /// keep the default theme's color; invalid ones and unknown fields are
+    /// returned as warnings.
+    pub fn parse_custom(default_name: &str, content: &str) -> anyhow::Result<(Self, Vec<String>)> {
+        let table: toml::Table = toml::from_str(content)?;
+        let name = match table.get("name") {
+            Some(toml::Value::String(name)) if !name.trim().is_empty() => name.trim(),
+            Some(_) => anyhow::bail!("name must be a non-empty string"),
+            None => default_name,
+        };
+        if ColorScheme::builtin_from_str(name).is_some() {
+            anyhow::bail!("{name} is the name of a built-in theme");
+        }
+
+        let mut theme = Self {
+            name: name.to_string(),
+            scheme: ColorScheme::Custom(name.to_string()),
+            ..Self::default()
+        };
+        let mut warnings = Vec::new();
+        for (field, value) in &table {
+            if field == "name" {
+                continue;
+            }
+            let Some(color) = theme.color_mut(field) else {
+                warnings.push(format!("{name}: unknown color {field}"));
+                continue;
+            };
+            match value.as_str().and_then(parse_hex_color) {
+                Some(parsed) => *color = parsed,
+                None => warnings.push(format!(
+                    "{name}: {field} = {value} is not a #rrggbb color, using the default"
+                )),
+            }
+        }
+        Ok((theme, warnings))
+    }
+
     // Helper methods for creating styled components
     pub fn header_style(&self) -> Style {
         Style::default().fg(self.accent_primary).bg(self.bg_primary)
@@ -362,26 +490,118 @@ impl std::fmt::Display for ColorScheme {
             ColorScheme::Nord => write!(f, "Nord"),
             ColorScheme::Catppuccin => write!(f, "Catppuccin"),
             ColorScheme::Dracula => write!(f, "Dracula"),
+            ColorScheme::Custom(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// Theme colors matching Hyprland's, from `hyprctl getoption` values keyed
+/// by option. Gradients give their first color, and alpha is dropped.
+pub fn hyprland_theme_colors(
+    values: &std::collections::HashMap<String, String>,
+) -> Vec<(&'static str, Color)> {
+    let mut colors: Vec<(&'static str, Color)> = Vec::new();
+    for (option, fields) in HYPRLAND_THEME_COLORS {
+        let Some(color) = values
+            .get(option)
+            .and_then(|v| crate::color::first_color(v))
+        else {
+            continue;
+        };
+        for field in fields {
+            if !colors.iter().any(|(set, _)| set == field) {
+                colors.push((field, color.to_opaque_color()));
+            }
+        }
+    }
+    colors
+}
+
+/// The contents of a theme file setting these colors
+pub fn custom_theme_file(name: &str, colors: &[(&str, Color)]) -> String {
+    let mut content = format!("name = \"{name}\"\n");
+    for (field, color) in colors {
+        if let Color::Rgb(r, g, b) = color {
+            content.push_str(&format!("{field} = \"#{r:02x}{g:02x}{b:02x}\"\n"));
         }
     }
+    content
+}
+
+/// A `#rrggbb` or `rrggbb` color
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Load every `.toml` theme in `dir`, keyed by name. Files that can't be
+/// read or parsed are skipped; they and any invalid colors are reported in
+/// the returned warnings. A missing folder holds no themes.
+pub fn load_custom_themes(dir: &Path) -> (CustomThemes, Vec<String>) {
+    let mut themes = CustomThemes::new();
+    let mut warnings = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (themes, warnings);
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| Theme::parse_custom(&stem, &content));
+        match parsed {
+            Ok((theme, theme_warnings)) => {
+                warnings.extend(theme_warnings);
+                if themes.contains_key(&theme.name) {
+                    warnings.push(format!(
+                        "{}: another theme is already named {}",
+                        path.display(),
+                        theme.name
+                    ));
+                } else {
+                    themes.insert(theme.name.clone(), theme);
+                }
+            }
+            Err(e) => warnings.push(format!("{}: {e}", path.display())),
+        }
+    }
+    (themes, warnings)
 }
 
 impl FromStr for ColorScheme {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "default" => Ok(ColorScheme::Default),
-            "gruvbox" => Ok(ColorScheme::Gruvbox),
-            "nord" => Ok(ColorScheme::Nord),
-            "catppuccin" => Ok(ColorScheme::Catppuccin),
-            "dracula" => Ok(ColorScheme::Dracula),
-            _ => Err(format!("Unknown color scheme: {s}")),
-        }
+        Self::builtin_from_str(s).ok_or_else(|| format!("Unknown color scheme: {s}"))
     }
 }
 
 impl ColorScheme {
+    /// The built-in scheme with this name, in any case
+    fn builtin_from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "default" => Some(ColorScheme::Default),
+            "gruvbox" => Some(ColorScheme::Gruvbox),
+            "nord" => Some(ColorScheme::Nord),
+            "catppuccin" => Some(ColorScheme::Catppuccin),
+            "dracula" => Some(ColorScheme::Dracula),
+            _ => None,
+        }
+    }
+
     #[allow(dead_code)]
     pub fn all() -> Vec<ColorScheme> {
         vec![
@@ -393,24 +613,175 @@ impl ColorScheme {
         ]
     }
 
-    pub fn next(&self) -> Self {
-        match self {
-            ColorScheme::Default => ColorScheme::Gruvbox,
-            ColorScheme::Gruvbox => ColorScheme::Nord,
-            ColorScheme::Nord => ColorScheme::Catppuccin,
-            ColorScheme::Catppuccin => ColorScheme::Dracula,
-            ColorScheme::Dracula => ColorScheme::Default,
+    /// The built-in schemes followed by the custom themes, in the order T
+    /// cycles through them
+    fn rotation(custom: &CustomThemes) -> Vec<ColorScheme> {
+        let mut schemes = vec![
+            ColorScheme::Default,
+            ColorScheme::Gruvbox,
+            ColorScheme::Nord,
+            ColorScheme::Catppuccin,
+            ColorScheme::Dracula,
+        ];
+        schemes.extend(custom.keys().cloned().map(ColorScheme::Custom));
+        schemes
+    }
+
+    /// The scheme after this one. A custom scheme that no longer has a
+    /// theme is followed by the first one.
+    pub fn next(&self, custom: &CustomThemes) -> Self {
+        let schemes = Self::rotation(custom);
+        match schemes.iter().position(|scheme| scheme == self) {
+            Some(index) => schemes[(index + 1) % schemes.len()].clone(),
+            None => schemes[0].clone(),
         }
     }
 
     #[allow(dead_code)]
-    pub fn previous(&self) -> Self {
-        match self {
-            ColorScheme::Default => ColorScheme::Dracula,
-            ColorScheme::Gruvbox => ColorScheme::Default,
-            ColorScheme::Nord => ColorScheme::Gruvbox,
-            ColorScheme::Catppuccin => ColorScheme::Nord,
-            ColorScheme::Dracula => ColorScheme::Catppuccin,
+    pub fn previous(&self, custom: &CustomThemes) -> Self {
+        let schemes = Self::rotation(custom);
+        match schemes.iter().position(|scheme| scheme == self) {
+            Some(index) => schemes[(index + schemes.len() - 1) % schemes.len()].clone(),
+            None => schemes[0].clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OCEAN: &str = r##"
+name = "Ocean"
+bg_primary = "#0b1021"
+accent_primary = "5fb3b3"
+accent_error = "#red"
+sparkle = "#ffffff"
+"##;
+
+    #[test]
+    fn test_custom_theme_files_set_their_colors() {
+        let (theme, warnings) = Theme::parse_custom("ocean", OCEAN).unwrap();
+        assert_eq!(theme.name, "Ocean");
+        assert_eq!(theme.scheme, ColorScheme::Custom("Ocean".to_string()));
+        assert_eq!(theme.bg_primary, Color::Rgb(0x0b, 0x10, 0x21));
+        assert_eq!(theme.accent_primary, Color::Rgb(0x5f, 0xb3, 0xb3));
+        // Invalid and missing colors come from the default theme
+        assert_eq!(theme.accent_error, Theme::default().accent_error);
+        assert_eq!(theme.type_float, Theme::default().type_float);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("accent_error"), "{warnings:?}");
+        assert!(
+            warnings[1].contains("unknown color sparkle"),
+            "{warnings:?}"
+        );
+
+        let (theme, _) = Theme::parse_custom("ocean", "fg_muted = \"#808080\"").unwrap();
+        assert_eq!(theme.name, "ocean");
+        assert!(Theme::parse_custom("nord", "").is_err());
+        assert!(Theme::parse_custom("x", "bg_primary = ").is_err());
+    }
+
+    #[test]
+    fn test_every_field_can_be_set() {
+        let mut theme = Theme::default();
+        for field in THEME_FIELDS {
+            assert!(theme.color_mut(field).is_some(), "{field}");
+        }
+    }
+
+    #[test]
+    fn test_themes_load_from_a_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("ocean.toml"), OCEAN).unwrap();
+        std::fs::write(dir.path().join("forest.toml"), "bg_primary = \"#102010\"").unwrap();
+        std::fs::write(dir.path().join("broken.toml"), "name = [").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a theme").unwrap();
+
+        let (themes, warnings) = load_custom_themes(dir.path());
+        assert_eq!(themes.keys().collect::<Vec<_>>(), vec!["Ocean", "forest"]);
+        assert!(
+            warnings.iter().any(|w| w.contains("broken.toml")),
+            "{warnings:?}"
+        );
+
+        let (themes, warnings) = load_custom_themes(&dir.path().join("missing"));
+        assert!(themes.is_empty() && warnings.is_empty());
+    }
+
+    #[test]
+    fn test_themes_follow_hyprland_colors() {
+        let values: std::collections::HashMap<String, String> = [
+            ("general:col.active_border", "ee33ccff ee00ff99 45deg"),
+            ("general:col.inactive_border", "aa595959"),
+            ("decoration:shadow:color", "3994688026"),
+            ("misc:background_color", "not a color"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let colors = hyprland_theme_colors(&values);
+        assert_eq!(
+            colors,
+            vec![
+                ("accent_primary", Color::Rgb(0x33, 0xcc, 0xff)),
+                ("border_focused", Color::Rgb(0x33, 0xcc, 0xff)),
+                ("border_active", Color::Rgb(0x33, 0xcc, 0xff)),
+                ("border_normal", Color::Rgb(0x59, 0x59, 0x59)),
+                ("bg_secondary", Color::Rgb(0x1a, 0x1a, 0x1a)),
+            ]
+        );
+
+        let content = custom_theme_file(HYPRLAND_THEME_NAME, &colors);
+        let (theme, warnings) = Theme::parse_custom("hyprland", &content).unwrap();
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(theme.name, HYPRLAND_THEME_NAME);
+        assert_eq!(theme.border_focused, Color::Rgb(0x33, 0xcc, 0xff));
+        assert_eq!(theme.bg_secondary, Color::Rgb(0x1a, 0x1a, 0x1a));
+        assert_eq!(theme.bg_primary, Theme::default().bg_primary);
+    }
+
+    #[test]
+    fn test_custom_themes_join_the_rotation() {
+        let mut custom = CustomThemes::new();
+        for name in ["Ocean", "Forest"] {
+            let (theme, _) = Theme::parse_custom(name, "").unwrap();
+            custom.insert(name.to_string(), theme);
+        }
+        let forest = ColorScheme::Custom("Forest".to_string());
+        let ocean = ColorScheme::Custom("Ocean".to_string());
+        assert_eq!(ColorScheme::Dracula.next(&custom), forest);
+        assert_eq!(forest.next(&custom), ocean);
+        assert_eq!(ocean.next(&custom), ColorScheme::Default);
+        assert_eq!(ColorScheme::Default.previous(&custom), ocean);
+        assert_eq!(
+            ColorScheme::Dracula.next(&CustomThemes::new()),
+            ColorScheme::Default
+        );
+        // A theme that was removed since it was picked
+        let gone = ColorScheme::Custom("Gone".to_string());
+        assert_eq!(gone.next(&custom), ColorScheme::Default);
+        assert_eq!(Theme::from_scheme(gone, &custom).name, "Gruvbox");
+    }
+
+    #[test]
+    fn test_custom_schemes_are_stored_by_name() {
+        #[derive(Serialize, Deserialize)]
+        struct Stored {
+            theme: ColorScheme,
         }
+        let stored = |theme| toml::to_string(&Stored { theme }).unwrap();
+        assert_eq!(stored(ColorScheme::Nord), "theme = \"Nord\"\n");
+        assert_eq!(
+            stored(ColorScheme::Custom("Ocean".to_string())),
+            "theme = \"Ocean\"\n"
+        );
+        let loaded = |text: &str| toml::from_str::<Stored>(text).unwrap().theme;
+        assert_eq!(loaded("theme = \"Nord\""), ColorScheme::Nord);
+        assert_eq!(
+            loaded("theme = \"Ocean\""),
+            ColorScheme::Custom("Ocean".to_string())
+        );
     }
 }
@@ -32,146 +32,252 @@ impl ConfigSnapshot {
     }
 }
 
-/// Manages undo/redo operations for configuration changes
+/// What happened to a single config item
+#[derive(Debug, Clone)]
+pub enum ChangeKind {
+    /// The item's value was changed
+    Edit {
+        old_value: String,
+        new_value: String,
+    },
+    /// The item was added at `index`
+    Add { index: usize, item: ConfigItem },
+    /// The item was removed from `index`
+    Delete { index: usize, item: ConfigItem },
+}
+
+/// One reversible change to the config items shown in the UI
+#[derive(Debug, Clone)]
+pub struct ChangeRecord {
+    pub panel: FocusedPanel,
+    pub key: String,
+    pub kind: ChangeKind,
+}
+
+impl ChangeRecord {
+    pub fn edit(panel: FocusedPanel, key: &str, old_value: &str, new_value: &str) -> Self {
+        Self {
+            panel,
+            key: key.to_string(),
+            kind: ChangeKind::Edit {
+                old_value: old_value.to_string(),
+                new_value: new_value.to_string(),
+            },
+        }
+    }
+
+    pub fn add(panel: FocusedPanel, index: usize, item: ConfigItem) -> Self {
+        Self {
+            panel,
+            key: item.key.clone(),
+            kind: ChangeKind::Add { index, item },
+        }
+    }
+
+    pub fn delete(panel: FocusedPanel, index: usize, item: ConfigItem) -> Self {
+        Self {
+            panel,
+            key: item.key.clone(),
+            kind: ChangeKind::Delete { index, item },
+        }
+    }
+
+    /// Short description for status messages
+    pub fn description(&self) -> String {
+        match &self.kind {
+            ChangeKind::Edit { .. } => format!("Edit {}", self.key),
+            ChangeKind::Add { .. } => format!("Add {}", self.key),
+            ChangeKind::Delete { .. } => format!("Delete {}", self.key),
+        }
+    }
+
+    /// The value the item has after undoing this change, if it still exists
+    pub fn undone_value(&self) -> Option<&str> {
+        match &self.kind {
+            ChangeKind::Edit { old_value, .. } => Some(old_value),
+            ChangeKind::Delete { item, .. } => Some(&item.value),
+            ChangeKind::Add { .. } => None,
+        }
+    }
+
+    /// The value the item has after redoing this change, if it still exists
+    pub fn redone_value(&self) -> Option<&str> {
+        match &self.kind {
+            ChangeKind::Edit { new_value, .. } => Some(new_value),
+            ChangeKind::Add { item, .. } => Some(&item.value),
+            ChangeKind::Delete { .. } => None,
+        }
+    }
+
+    /// Revert this change in `config_items`
+    pub fn undo(&self, config_items: &mut HashMap<FocusedPanel, Vec<ConfigItem>>) {
+        let items = config_items.entry(self.panel).or_default();
+        match &self.kind {
+            ChangeKind::Edit { old_value, .. } => set_value(items, &self.key, old_value),
+            ChangeKind::Add { .. } => items.retain(|item| item.key != self.key),
+            ChangeKind::Delete { index, item } => {
+                items.insert((*index).min(items.len()), item.clone())
+            }
+        }
+    }
+
+    /// Apply this change again to `config_items`
+    pub fn redo(&self, config_items: &mut HashMap<FocusedPanel, Vec<ConfigItem>>) {
+        let items = config_items.entry(self.panel).or_default();
+        match &self.kind {
+            ChangeKind::Edit { new_value, .. } => set_value(items, &self.key, new_value),
+            ChangeKind::Add { index, item } => {
+                items.insert((*index).min(items.len()), item.clone())
+            }
+            ChangeKind::Delete { .. } => items.retain(|item| item.key != self.key),
+        }
+    }
+}
+
+fn set_value(items: &mut [ConfigItem], key: &str, value: &str) {
+    if let Some(item) = items.iter_mut().find(|item| item.key == key) {
+        item.value = value.to_string();
+    }
+}
+
+/// Bounded undo/redo history of individual config item changes
 #[derive(Debug)]
-pub struct UndoManager {
-    /// Stack of previous states (undo stack)
-    undo_stack: Vec<ConfigSnapshot>,
-    /// Stack of undone states (redo stack)
-    redo_stack: Vec<ConfigSnapshot>,
-    /// Maximum number of undo operations to keep in memory
-    max_history: usize,
-    /// Current snapshot (if any)
-    current_snapshot: Option<ConfigSnapshot>,
+pub struct ChangeHistory {
+    undo_stack: Vec<ChangeRecord>,
+    redo_stack: Vec<ChangeRecord>,
+    max_depth: usize,
+    /// Deletions since the last save, most recent last. Unlike the undo
+    /// stack, restoring one doesn't undo the changes made after it.
+    trash: Vec<ChangeRecord>,
 }
 
-impl Default for UndoManager {
+impl Default for ChangeHistory {
     fn default() -> Self {
-        Self::new(50) // Default to 50 undo operations
+        Self::new(50)
     }
 }
 
-impl UndoManager {
-    pub fn new(max_history: usize) -> Self {
+impl ChangeHistory {
+    pub fn new(max_depth: usize) -> Self {
         Self {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
-            max_history,
-            current_snapshot: None,
+            max_depth,
+            trash: Vec::new(),
         }
     }
 
-    /// Take a snapshot of the current state before making changes
-    pub fn take_snapshot(&mut self, snapshot: ConfigSnapshot) {
-        // If we have a current snapshot, move it to undo stack
-        if let Some(current) = self.current_snapshot.take() {
-            self.undo_stack.push(current);
-
-            // Limit undo stack size
-            if self.undo_stack.len() > self.max_history {
-                self.undo_stack.remove(0);
-            }
+    /// Record a new change, dropping the oldest one past `max_depth`
+    pub fn push(&mut self, record: ChangeRecord) {
+        self.undo_stack.push(record);
+        if self.undo_stack.len() > self.max_depth {
+            self.undo_stack.remove(0);
         }
 
-        // Clear redo stack when new changes are made
+        // A new change invalidates anything that was undone
         self.redo_stack.clear();
-
-        // Set new current snapshot
-        self.current_snapshot = Some(snapshot);
     }
 
-    /// Undo the last operation and return the previous state
-    pub fn undo(&mut self) -> Option<ConfigSnapshot> {
-        if let Some(current) = self.current_snapshot.take() {
-            // Move current to redo stack
-            self.redo_stack.push(current);
+    /// Record the deletion of an item, keeping it in the trash as well
+    pub fn push_deleted(&mut self, record: ChangeRecord) {
+        self.trash.push(record.clone());
+        if self.trash.len() > self.max_depth {
+            self.trash.remove(0);
+        }
+        self.push(record);
+    }
 
-            // Pop from undo stack to become current
-            if let Some(previous) = self.undo_stack.pop() {
-                let snapshot = previous.clone();
-                self.current_snapshot = Some(previous);
-                return Some(snapshot);
-            } else {
-                // No undo available, restore current
-                self.current_snapshot = self.redo_stack.pop();
+    /// Put the most recently deleted item back where it was, recorded as an
+    /// addition so it can be undone. Items that are back already, say by
+    /// undo, are skipped.
+    pub fn restore_deleted(
+        &mut self,
+        config_items: &mut HashMap<FocusedPanel, Vec<ConfigItem>>,
+    ) -> Option<ChangeRecord> {
+        while let Some(record) = self.trash.pop() {
+            let ChangeKind::Delete { index, item } = record.kind else {
+                continue;
+            };
+            let items = config_items.entry(record.panel).or_default();
+            if items.iter().any(|existing| existing.key == item.key) {
+                continue;
             }
+            let index = index.min(items.len());
+            items.insert(index, item.clone());
+            let restored = ChangeRecord::add(record.panel, index, item);
+            self.push(restored.clone());
+            return Some(restored);
         }
         None
     }
 
-    /// Redo the last undone operation and return the state
-    pub fn redo(&mut self) -> Option<ConfigSnapshot> {
-        if let Some(current) = self.current_snapshot.take() {
-            // Move current to undo stack
-            self.undo_stack.push(current);
-        }
+    /// Forget the deleted items, once the deletions are saved
+    pub fn empty_trash(&mut self) {
+        self.trash.clear();
+    }
 
-        // Pop from redo stack to become current
-        if let Some(next) = self.redo_stack.pop() {
-            let snapshot = next.clone();
-            self.current_snapshot = Some(next);
-            Some(snapshot)
-        } else {
-            // No redo available, restore current
-            self.current_snapshot = self.undo_stack.pop();
-            None
-        }
+    /// Change the maximum depth, trimming the oldest records if needed
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+        let excess = self.undo_stack.len().saturating_sub(max_depth);
+        self.undo_stack.drain(..excess);
+    }
+
+    /// Revert the most recent change in `config_items` and return it
+    pub fn undo(
+        &mut self,
+        config_items: &mut HashMap<FocusedPanel, Vec<ConfigItem>>,
+    ) -> Option<ChangeRecord> {
+        let record = self.undo_stack.pop()?;
+        record.undo(config_items);
+        self.redo_stack.push(record.clone());
+        Some(record)
+    }
+
+    /// Re-apply the most recently undone change in `config_items` and return it
+    pub fn redo(
+        &mut self,
+        config_items: &mut HashMap<FocusedPanel, Vec<ConfigItem>>,
+    ) -> Option<ChangeRecord> {
+        let record = self.redo_stack.pop()?;
+        record.redo(config_items);
+        self.undo_stack.push(record.clone());
+        Some(record)
     }
 
-    /// Check if undo is available
     pub fn can_undo(&self) -> bool {
         !self.undo_stack.is_empty()
     }
 
-    /// Check if redo is available
     pub fn can_redo(&self) -> bool {
         !self.redo_stack.is_empty()
     }
 
-    /// Get the number of available undo operations
+    /// Values an item had before each of its recorded edits, oldest first
+    pub fn previous_values(&self, panel: FocusedPanel, key: &str) -> Vec<String> {
+        self.undo_stack
+            .iter()
+            .filter(|record| record.panel == panel && record.key == key)
+            .filter_map(|record| match &record.kind {
+                ChangeKind::Edit { old_value, .. } => Some(old_value.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn undo_count(&self) -> usize {
         self.undo_stack.len()
     }
 
-    /// Get the number of available redo operations
     pub fn redo_count(&self) -> usize {
         self.redo_stack.len()
     }
 
-    /// Get description of the last undo operation
-    pub fn undo_description(&self) -> Option<&str> {
-        self.undo_stack
-            .last()
-            .and_then(|s| s.description.as_deref())
-    }
-
-    /// Get description of the last redo operation
-    pub fn redo_description(&self) -> Option<&str> {
-        self.redo_stack
-            .last()
-            .and_then(|s| s.description.as_deref())
-    }
-
     /// Clear all undo/redo history
     pub fn clear(&mut self) {
         self.undo_stack.clear();
         self.redo_stack.clear();
-        self.current_snapshot = None;
-    }
-
-    /// Get current snapshot (for debugging/inspection)
-    pub fn current_snapshot(&self) -> Option<&ConfigSnapshot> {
-        self.current_snapshot.as_ref()
-    }
-
-    /// Get undo stack history (for debugging/UI display)
-    pub fn undo_history(&self) -> &[ConfigSnapshot] {
-        &self.undo_stack
-    }
-
-    /// Get redo stack history (for debugging/UI display)
-    pub fn redo_history(&self) -> &[ConfigSnapshot] {
-        &self.redo_stack
+        self.trash.clear();
     }
 }
 
@@ -180,111 +286,191 @@ mod tests {
     use super::*;
     use crate::ui::{ConfigDataType, ConfigItem};
 
-    fn create_test_config_items() -> HashMap<FocusedPanel, Vec<ConfigItem>> {
+    fn item(key: &str, value: &str) -> ConfigItem {
+        ConfigItem {
+            key: key.to_string(),
+            value: value.to_string(),
+            description: String::new(),
+            data_type: ConfigDataType::String,
+            suggestions: vec![],
+            source_file: None,
+        }
+    }
+
+    fn values(
+        items: &HashMap<FocusedPanel, Vec<ConfigItem>>,
+        panel: FocusedPanel,
+    ) -> Vec<(String, String)> {
+        items
+            .get(&panel)
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|i| (i.key.clone(), i.value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_change_history_edits_and_deletions() {
         let mut items = HashMap::new();
         items.insert(
             FocusedPanel::General,
-            vec![ConfigItem {
-                key: "test_key".to_string(),
-                value: "test_value".to_string(),
-                description: "Test item".to_string(),
-                data_type: ConfigDataType::String,
-                suggestions: vec![],
-            }],
+            vec![item("gaps_in", "5"), item("gaps_out", "20")],
         );
-        items
-    }
+        let original = values(&items, FocusedPanel::General);
+        let mut history = ChangeHistory::new(10);
 
-    #[test]
-    fn test_undo_manager_creation() {
-        let manager = UndoManager::new(10);
-        assert_eq!(manager.max_history, 10);
-        assert!(!manager.can_undo());
-        assert!(!manager.can_redo());
-    }
+        // Edit gaps_in, then delete gaps_out
+        items.get_mut(&FocusedPanel::General).unwrap()[0].value = "8".to_string();
+        history.push(ChangeRecord::edit(
+            FocusedPanel::General,
+            "gaps_in",
+            "5",
+            "8",
+        ));
+        let removed = items.get_mut(&FocusedPanel::General).unwrap().remove(1);
+        history.push(ChangeRecord::delete(FocusedPanel::General, 1, removed));
+        let edited = values(&items, FocusedPanel::General);
+
+        let undone = history.undo(&mut items).unwrap();
+        assert_eq!(undone.description(), "Delete gaps_out");
+        assert_eq!(
+            values(&items, FocusedPanel::General),
+            vec![
+                ("gaps_in".to_string(), "8".to_string()),
+                ("gaps_out".to_string(), "20".to_string())
+            ]
+        );
 
-    #[test]
-    fn test_take_snapshot() {
-        let mut manager = UndoManager::new(10);
-        let config_items = create_test_config_items();
-        let snapshot = ConfigSnapshot::new(config_items, Some("Test snapshot".to_string()));
+        let undone = history.undo(&mut items).unwrap();
+        assert_eq!(undone.undone_value(), Some("5"));
+        assert_eq!(values(&items, FocusedPanel::General), original);
+        assert!(history.undo(&mut items).is_none());
 
-        manager.take_snapshot(snapshot);
-        assert!(manager.current_snapshot.is_some());
-        assert!(!manager.can_undo());
-        assert!(!manager.can_redo());
+        history.redo(&mut items).unwrap();
+        history.redo(&mut items).unwrap();
+        assert_eq!(values(&items, FocusedPanel::General), edited);
+        assert!(history.redo(&mut items).is_none());
     }
 
     #[test]
-    fn test_undo_redo_cycle() {
-        let mut manager = UndoManager::new(10);
-
-        // Create first snapshot
-        let config1 = create_test_config_items();
-        let snapshot1 = ConfigSnapshot::new(config1, Some("First state".to_string()));
-        manager.take_snapshot(snapshot1);
-
-        // Create second snapshot
-        let mut config2 = create_test_config_items();
-        config2.get_mut(&FocusedPanel::General).unwrap()[0].value = "modified_value".to_string();
-        let snapshot2 = ConfigSnapshot::new(config2, Some("Second state".to_string()));
-        manager.take_snapshot(snapshot2);
-
-        // Should be able to undo now
-        assert!(manager.can_undo());
-        assert!(!manager.can_redo());
-
-        // Undo
-        let undone = manager.undo().unwrap();
-        assert_eq!(undone.description, Some("First state".to_string()));
-        assert!(!manager.can_undo());
-        assert!(manager.can_redo());
+    fn test_change_history_undo_add_removes_item() {
+        let mut items = HashMap::new();
+        items.insert(
+            FocusedPanel::Binds,
+            vec![item("bind_0", "SUPER + Q → exec [kitty]")],
+        );
+        let mut history = ChangeHistory::new(10);
 
-        // Redo
-        let redone = manager.redo().unwrap();
-        assert_eq!(redone.description, Some("Second state".to_string()));
-        assert!(manager.can_undo());
-        assert!(!manager.can_redo());
+        let added = item("bind_1", "SUPER + E → exec [thunar]");
+        items
+            .get_mut(&FocusedPanel::Binds)
+            .unwrap()
+            .push(added.clone());
+        history.push(ChangeRecord::add(FocusedPanel::Binds, 1, added));
+
+        history.undo(&mut items).unwrap();
+        assert_eq!(items[&FocusedPanel::Binds].len(), 1);
+        assert_eq!(items[&FocusedPanel::Binds][0].key, "bind_0");
+
+        history.redo(&mut items).unwrap();
+        assert_eq!(items[&FocusedPanel::Binds].len(), 2);
+        assert_eq!(items[&FocusedPanel::Binds][1].key, "bind_1");
+
+        // A new change after undoing drops the redo history
+        history.undo(&mut items).unwrap();
+        history.push(ChangeRecord::edit(FocusedPanel::Binds, "bind_0", "a", "b"));
+        assert!(!history.can_redo());
     }
 
     #[test]
-    fn test_max_history_limit() {
-        let mut manager = UndoManager::new(2);
-
-        // Add 3 snapshots
-        for i in 0..3 {
-            let config = create_test_config_items();
-            let snapshot = ConfigSnapshot::new(config, Some(format!("State {}", i)));
-            manager.take_snapshot(snapshot);
-        }
-
-        // Should only keep 2 in history (current + 2 in undo stack)
-        assert_eq!(manager.undo_count(), 2);
+    fn test_previous_values_of_an_item() {
+        let mut history = ChangeHistory::default();
+        history.push(ChangeRecord::edit(FocusedPanel::Binds, "bind_0", "a", "b"));
+        history.push(ChangeRecord::edit(FocusedPanel::Binds, "bind_1", "x", "y"));
+        history.push(ChangeRecord::edit(FocusedPanel::Binds, "bind_0", "b", "c"));
+        history.push(ChangeRecord::edit(FocusedPanel::General, "bind_0", "", "2"));
+        assert_eq!(
+            history.previous_values(FocusedPanel::Binds, "bind_0"),
+            vec!["a", "b"]
+        );
+        assert!(history
+            .previous_values(FocusedPanel::Binds, "bind_2")
+            .is_empty());
     }
 
     #[test]
-    fn test_clear_redo_on_new_changes() {
-        let mut manager = UndoManager::new(10);
-
-        // Create initial state
-        let config1 = create_test_config_items();
-        let snapshot1 = ConfigSnapshot::new(config1, Some("State 1".to_string()));
-        manager.take_snapshot(snapshot1);
-
-        // Create second state
-        let config2 = create_test_config_items();
-        let snapshot2 = ConfigSnapshot::new(config2, Some("State 2".to_string()));
-        manager.take_snapshot(snapshot2);
+    fn test_change_history_depth_is_capped() {
+        let mut history = ChangeHistory::new(3);
+        for i in 0..5 {
+            history.push(ChangeRecord::edit(
+                FocusedPanel::General,
+                "gaps_in",
+                &i.to_string(),
+                &(i + 1).to_string(),
+            ));
+        }
+        assert_eq!(history.undo_count(), 3);
 
-        // Undo to have redo available
-        manager.undo();
-        assert!(manager.can_redo());
+        history.set_max_depth(1);
+        assert_eq!(history.undo_count(), 1);
 
-        // Make new change - should clear redo stack
-        let config3 = create_test_config_items();
-        let snapshot3 = ConfigSnapshot::new(config3, Some("State 3".to_string()));
-        manager.take_snapshot(snapshot3);
+        let mut items = HashMap::new();
+        items.insert(FocusedPanel::General, vec![item("gaps_in", "5")]);
+        let undone = history.undo(&mut items).unwrap();
+        // Only the most recent edit survives the trim
+        assert_eq!(undone.undone_value(), Some("4"));
+        assert_eq!(items[&FocusedPanel::General][0].value, "4");
+    }
 
-        assert!(!manager.can_redo());
+    #[test]
+    fn test_deleted_items_are_restored_from_the_trash() {
+        let mut items = HashMap::new();
+        items.insert(
+            FocusedPanel::Binds,
+            vec![
+                item("bind_0", "a"),
+                item("bind_1", "b"),
+                item("bind_2", "c"),
+            ],
+        );
+        let mut history = ChangeHistory::new(10);
+        let binds = |items: &HashMap<FocusedPanel, Vec<ConfigItem>>| -> Vec<String> {
+            items[&FocusedPanel::Binds]
+                .iter()
+                .map(|i| i.key.clone())
+                .collect()
+        };
+
+        let removed = items.get_mut(&FocusedPanel::Binds).unwrap().remove(1);
+        history.push_deleted(ChangeRecord::delete(FocusedPanel::Binds, 1, removed));
+        let removed = items.get_mut(&FocusedPanel::Binds).unwrap().remove(0);
+        history.push_deleted(ChangeRecord::delete(FocusedPanel::Binds, 0, removed));
+        // A later edit doesn't stand in the way of restoring
+        history.push(ChangeRecord::edit(FocusedPanel::Binds, "bind_2", "c", "d"));
+
+        let restored = history.restore_deleted(&mut items).unwrap();
+        assert_eq!(restored.description(), "Add bind_0");
+        assert_eq!(binds(&items), vec!["bind_0", "bind_2"]);
+        history.restore_deleted(&mut items).unwrap();
+        assert_eq!(binds(&items), vec!["bind_0", "bind_1", "bind_2"]);
+        assert!(history.restore_deleted(&mut items).is_none());
+
+        // Restoring is itself undoable
+        history.undo(&mut items).unwrap();
+        assert_eq!(binds(&items), vec!["bind_0", "bind_2"]);
+
+        // Deletions already undone, or saved, aren't restored again
+        let removed = items.get_mut(&FocusedPanel::Binds).unwrap().remove(0);
+        history.push_deleted(ChangeRecord::delete(FocusedPanel::Binds, 0, removed));
+        history.undo(&mut items).unwrap();
+        assert!(history.restore_deleted(&mut items).is_none());
+        let removed = items.get_mut(&FocusedPanel::Binds).unwrap().remove(0);
+        history.push_deleted(ChangeRecord::delete(FocusedPanel::Binds, 0, removed));
+        history.empty_trash();
+        assert!(history.restore_deleted(&mut items).is_none());
+        assert!(history.can_undo());
     }
 }
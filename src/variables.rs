@@ -0,0 +1,217 @@
+// Config variables
+// A config can define `$name = value` and use `$name` in any later line,
+// like `$mainMod = SUPER` in `bind = $mainMod, Q, killactive`. This table
+// collects the definitions, so binds and rules can be shown and checked the
+// way Hyprland reads them while they are still edited and saved as written.
+
+/// How many variables deep a value is followed, which stops variables that
+/// refer to each other
+const MAX_DEPTH: usize = 10;
+
+/// The variables a config defines, in the order they are defined
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Variables {
+    definitions: Vec<(String, String)>,
+}
+
+impl Variables {
+    /// The name and value of a `$name = value` line
+    pub fn parse_definition(line: &str) -> Option<(String, String)> {
+        let (name, value) = line.trim().strip_prefix('$')?.split_once('=')?;
+        let name = name.trim();
+        let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        valid.then(|| (name.to_string(), value.trim().to_string()))
+    }
+
+    /// The names defined, without their `$`
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.definitions.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Define a variable. Defining a name again replaces its value.
+    pub fn define(&mut self, name: String, value: String) {
+        match self
+            .definitions
+            .iter_mut()
+            .find(|(known, _)| *known == name)
+        {
+            Some((_, old)) => *old = value,
+            None => self.definitions.push((name, value)),
+        }
+    }
+
+    /// `text` with the variables it uses replaced by their values, and the
+    /// variables those values use in turn. As in Hyprland, the longest
+    /// defined name after a `$` wins. Anything else after a `$`, like
+    /// `$HOME` or `$(date)` in a command, is left as it is.
+    pub fn resolve(&self, text: &str) -> String {
+        self.resolve_nested(text, 0)
+    }
+
+    fn resolve_nested(&self, text: &str, depth: usize) -> String {
+        if depth > MAX_DEPTH || self.definitions.is_empty() {
+            return text.to_string();
+        }
+        let mut resolved = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(index) = rest.find('$') {
+            resolved.push_str(&rest[..index]);
+            let after = &rest[index + 1..];
+            match self.variable_at(after) {
+                Some((name, value)) => {
+                    resolved.push_str(&self.resolve_nested(value, depth + 1));
+                    rest = &after[name.len()..];
+                }
+                None => {
+                    resolved.push('$');
+                    rest = after;
+                }
+            }
+        }
+        resolved.push_str(rest);
+        resolved
+    }
+
+    /// The names of the variables `text` uses itself, in the order it uses
+    /// them
+    pub fn used_names<'a>(&'a self, text: &str) -> Vec<&'a str> {
+        text.match_indices('$')
+            .filter_map(|(index, _)| self.variable_at(&text[index + 1..]))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// The variable whose name `text` starts with, the longest one if several do
+    fn variable_at(&self, text: &str) -> Option<&(String, String)> {
+        self.definitions
+            .iter()
+            .filter(|(name, _)| text.starts_with(name.as_str()))
+            .max_by_key(|(name, _)| name.len())
+    }
+}
+
+/// Whether a line is a `$name = value` definition
+pub fn is_variable_line(line: &str) -> bool {
+    Variables::parse_definition(line).is_some()
+}
+
+/// Check an edited definition. `taken` are the names the other definitions
+/// use, which it can't take as well.
+pub fn validate_line(line: &str, taken: &[String]) -> Result<(), String> {
+    let line = line.trim();
+    if !line.starts_with('$') {
+        return Err("Variable names start with $, like $mainMod = SUPER".to_string());
+    }
+    let Some((name, _)) = line[1..].split_once('=') else {
+        return Err("Give the variable a value after =, like $mainMod = SUPER".to_string());
+    };
+    let Some((name, _)) = Variables::parse_definition(line) else {
+        return Err(format!(
+            "'{}' isn't a variable name: use letters, digits and _",
+            name.trim()
+        ));
+    };
+    if taken.contains(&name) {
+        return Err(format!("${name} is already defined"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(lines: &[&str]) -> Variables {
+        let mut variables = Variables::default();
+        for line in lines {
+            let (name, value) = Variables::parse_definition(line).unwrap();
+            variables.define(name, value);
+        }
+        variables
+    }
+
+    #[test]
+    fn test_definitions_are_parsed() {
+        assert_eq!(
+            Variables::parse_definition("$mainMod = SUPER"),
+            Some(("mainMod".to_string(), "SUPER".to_string()))
+        );
+        assert_eq!(
+            Variables::parse_definition("  $term=kitty --single-instance"),
+            Some(("term".to_string(), "kitty --single-instance".to_string()))
+        );
+        assert_eq!(
+            Variables::parse_definition("bind = $mainMod, Q, killactive"),
+            None
+        );
+        assert_eq!(Variables::parse_definition("$ = SUPER"), None);
+        assert_eq!(Variables::parse_definition("$main mod = SUPER"), None);
+    }
+
+    #[test]
+    fn test_variables_are_resolved_nested_and_longest_first() {
+        let variables = variables(&[
+            "$mainMod = SUPER",
+            "$mainModShift = $mainMod SHIFT",
+            "$term = kitty",
+            "$launch = $term --class $class",
+            "$class = floating",
+        ]);
+        assert_eq!(
+            variables.resolve("bind = $mainMod, Return, exec, $term"),
+            "bind = SUPER, Return, exec, kitty"
+        );
+        assert_eq!(
+            variables.resolve("bind = $mainModShift, T, exec, $launch"),
+            "bind = SUPER SHIFT, T, exec, kitty --class floating"
+        );
+        assert_eq!(
+            variables.resolve("exec, notify-send $HOME $(date) costs $5"),
+            "exec, notify-send $HOME $(date) costs $5"
+        );
+
+        // A later definition replaces the earlier one
+        let mut redefined = variables.clone();
+        redefined.define("term".to_string(), "foot".to_string());
+        assert_eq!(redefined.resolve("$term"), "foot");
+    }
+
+    #[test]
+    fn test_used_names_take_the_longest_match() {
+        let variables = variables(&["$mainMod = SUPER", "$mainModShift = SUPER SHIFT"]);
+        assert_eq!(
+            variables.used_names("bind = $mainModShift, Q, exec, $HOME/$mainMod"),
+            ["mainModShift", "mainMod"]
+        );
+        assert!(variables
+            .used_names("bind = SUPER, Q, killactive")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_edited_definitions_are_validated() {
+        let taken = ["mainMod".to_string()];
+        assert_eq!(validate_line("$term = kitty", &taken), Ok(()));
+        assert!(validate_line("term = kitty", &taken)
+            .unwrap_err()
+            .contains("start with $"));
+        assert!(validate_line("$term", &taken)
+            .unwrap_err()
+            .contains("value"));
+        assert!(validate_line("$my term = kitty", &taken)
+            .unwrap_err()
+            .contains("'my term'"));
+        assert_eq!(
+            validate_line("$mainMod = ALT", &taken),
+            Err("$mainMod is already defined".to_string())
+        );
+    }
+
+    #[test]
+    fn test_variables_referring_to_each_other_stop() {
+        let variables = variables(&["$a = x $b", "$b = y $a"]);
+        let resolved = variables.resolve("$a");
+        assert!(resolved.starts_with("x y x y"));
+        assert!(resolved.ends_with("$a") || resolved.ends_with("$b"));
+    }
+}
@@ -0,0 +1,353 @@
+// Window rule patterns
+// Hyprland matches window rules against a window's class or title with a
+// regex, so picking a running window has to escape what it is called, and a
+// pattern that doesn't compile makes Hyprland skip the rule.
+
+use crate::hyprctl::HyprlandClient;
+use crate::ui::RuleType;
+
+/// Window rule selectors whose value is a regex. The others, like
+/// `floating:1` or `workspace:2`, are compared literally.
+const REGEX_SELECTORS: [&str; 4] = ["class", "title", "initialclass", "initialtitle"];
+
+/// Escape `text` so a Hyprland rule regex matches it literally
+pub fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// A pattern matching exactly the windows of `class`, like `class:^(kitty)$`
+pub fn class_pattern(class: &str) -> String {
+    format!("class:^({})$", escape_regex(class))
+}
+
+/// A pattern matching exactly the windows titled `title`
+pub fn title_pattern(title: &str) -> String {
+    format!("title:^({})$", escape_regex(title))
+}
+
+/// A pattern matching one particular window by its class and title, for
+/// writing a rule about a window picked on screen
+pub fn window_pattern(window: &HyprlandClient) -> String {
+    match (window.class.is_empty(), window.title.is_empty()) {
+        (false, false) => format!(
+            "{}, {}",
+            class_pattern(&window.class),
+            title_pattern(&window.title)
+        ),
+        (false, true) => class_pattern(&window.class),
+        _ => title_pattern(&window.title),
+    }
+}
+
+/// Check that the regexes in a window or layer rule pattern compile. A
+/// window rule's pattern is a list of `selector:value` pairs, or a bare
+/// class regex; a layer rule's is a namespace regex or an `address:`.
+pub fn validate_pattern(rule_type: &RuleType, pattern: &str) -> Result<(), String> {
+    let regexes: Vec<(&str, &str)> = match rule_type {
+        RuleType::Window => window_selectors(pattern)
+            .into_iter()
+            .filter(|(name, _)| REGEX_SELECTORS.contains(&name.to_ascii_lowercase().as_str()))
+            .collect(),
+        RuleType::Layer if pattern.trim_start().starts_with("address:") => Vec::new(),
+        RuleType::Layer => vec![("namespace", pattern.trim())],
+        RuleType::Workspace => Vec::new(),
+    };
+    for (name, regex) in regexes {
+        // `negative:` inverts the match and isn't part of the regex
+        let regex = regex.strip_prefix("negative:").unwrap_or(regex);
+        if let Err(e) = regex::Regex::new(regex) {
+            return Err(format!("{name} isn't a valid regex: {}", regex_error(&e)));
+        }
+    }
+    Ok(())
+}
+
+/// The `selector:value` pairs of a window rule pattern. One without any is
+/// a class regex, as in the old `windowrule` syntax.
+fn window_selectors(pattern: &str) -> Vec<(&str, &str)> {
+    if !pattern.contains(':') {
+        return vec![("class", pattern.trim())];
+    }
+    pattern
+        .split(',')
+        .filter_map(|selector| selector.trim().split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect()
+}
+
+/// A check of one selector against an open window
+type SelectorTest = Box<dyn Fn(&HyprlandClient) -> bool>;
+
+/// The open windows a window rule pattern applies to, and the selectors in
+/// it that open windows can't be checked against, such as `onworkspace`.
+/// As in Hyprland, a regex has to match the whole class or title.
+pub fn matching_clients<'a>(
+    pattern: &str,
+    clients: &'a [HyprlandClient],
+) -> Result<(Vec<&'a HyprlandClient>, Vec<String>), String> {
+    let mut tests: Vec<SelectorTest> = Vec::new();
+    let mut unchecked = Vec::new();
+    for (name, value) in window_selectors(pattern) {
+        let field = name.to_ascii_lowercase();
+        let test: SelectorTest = match field.as_str() {
+            "class" | "title" | "initialclass" | "initialtitle" => {
+                let (negative, regex) = match value.strip_prefix("negative:") {
+                    Some(regex) => (true, regex),
+                    None => (false, value),
+                };
+                let regex = regex::Regex::new(&format!("^(?:{regex})$"))
+                    .map_err(|e| format!("{name} isn't a valid regex: {}", regex_error(&e)))?;
+                Box::new(move |client| {
+                    let text = match field.as_str() {
+                        "class" => &client.class,
+                        "title" => &client.title,
+                        "initialclass" => &client.initial_class,
+                        _ => &client.initial_title,
+                    };
+                    regex.is_match(text) != negative
+                })
+            }
+            "floating" | "xwayland" | "pinned" => {
+                let on = matches!(value, "1" | "true");
+                Box::new(move |client| {
+                    let flag = match field.as_str() {
+                        "floating" => client.floating,
+                        "xwayland" => client.xwayland,
+                        _ => client.pinned,
+                    };
+                    flag == on
+                })
+            }
+            "workspace" => {
+                let value = value.to_string();
+                Box::new(move |client| match value.strip_prefix("name:") {
+                    Some(name) => client.workspace.name == name,
+                    None => client.workspace.id.to_string() == value,
+                })
+            }
+            _ => {
+                unchecked.push(name.to_string());
+                continue;
+            }
+        };
+        tests.push(test);
+    }
+
+    let matching = clients
+        .iter()
+        .filter(|client| tests.iter().all(|test| test(client)))
+        .collect();
+    Ok((matching, unchecked))
+}
+
+/// The one-line reason of a regex error, whose message otherwise points at
+/// the offending character over several lines
+fn regex_error(error: &regex::Error) -> String {
+    let message = error.to_string();
+    message
+        .lines()
+        .find_map(|line| line.strip_prefix("error: "))
+        .unwrap_or(&message)
+        .to_string()
+}
+
+/// The text typed into a pattern so far, without the `class:` prefix and the
+/// `^(...)$` anchors around it
+fn pattern_query(pattern: &str) -> String {
+    let text = pattern.trim();
+    let text = text
+        .strip_prefix("class:")
+        .or_else(|| text.strip_prefix("title:"))
+        .unwrap_or(text)
+        .trim();
+    let text = text.strip_prefix('^').unwrap_or(text);
+    let text = text.strip_prefix('(').unwrap_or(text);
+    let text = text.strip_suffix('$').unwrap_or(text);
+    let text = text.strip_suffix(')').unwrap_or(text);
+    text.replace('\\', "").to_lowercase()
+}
+
+/// Patterns for the open windows whose class or title contains what has been
+/// typed, classes first. Once the pattern starts with `title:` only titles
+/// are offered.
+pub fn client_completions(clients: &[HyprlandClient], pattern: &str) -> Vec<String> {
+    let query = pattern_query(pattern);
+    let matching = |name: &&String| !name.is_empty() && name.to_lowercase().contains(&query);
+
+    let mut completions: Vec<String> = Vec::new();
+    if !pattern.trim_start().starts_with("title:") {
+        for class in clients.iter().map(|client| &client.class).filter(matching) {
+            let completion = class_pattern(class);
+            if !completions.contains(&completion) {
+                completions.push(completion);
+            }
+        }
+    }
+    for title in clients.iter().map(|client| &client.title).filter(matching) {
+        let completion = title_pattern(title);
+        if !completions.contains(&completion) {
+            completions.push(completion);
+        }
+    }
+    completions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(class: &str, title: &str) -> HyprlandClient {
+        HyprlandClient {
+            class: class.to_string(),
+            title: title.to_string(),
+            address: "0x1".to_string(),
+            ..HyprlandClient::default()
+        }
+    }
+
+    #[test]
+    fn test_chosen_names_are_escaped() {
+        assert_eq!(class_pattern("kitty"), "class:^(kitty)$");
+        assert_eq!(
+            class_pattern("org.gnome.Nautilus"),
+            "class:^(org\\.gnome\\.Nautilus)$"
+        );
+        assert_eq!(
+            title_pattern("Picture-in-Picture (1)"),
+            "title:^(Picture-in-Picture \\(1\\))$"
+        );
+        assert_eq!(
+            escape_regex("a+b*c?[d]{e}|f^$\\"),
+            "a\\+b\\*c\\?\\[d\\]\\{e\\}\\|f\\^\\$\\\\"
+        );
+    }
+
+    #[test]
+    fn test_pattern_regexes_must_compile() {
+        let window = |pattern| validate_pattern(&RuleType::Window, pattern);
+        assert_eq!(window("class:^(kitty)$, title:^(.*htop.*)$"), Ok(()));
+        assert_eq!(window("^(mpv|vlc)$"), Ok(()));
+        assert_eq!(
+            window("initialTitle:negative:^(Steam)$, floating:1"),
+            Ok(())
+        );
+        // Literal selectors aren't regexes
+        assert_eq!(window("floating:1, workspace:(2"), Ok(()));
+        assert_eq!(window(""), Ok(()));
+
+        assert_eq!(
+            window("class:^(kitty"),
+            Err("class isn't a valid regex: unclosed group".to_string())
+        );
+        assert!(window("floating:1, title:[abc")
+            .unwrap_err()
+            .starts_with("title isn't a valid regex"));
+        assert!(window("^(kitty").is_err());
+        assert!(window("class:negative:(").is_err());
+
+        let layer = |pattern| validate_pattern(&RuleType::Layer, pattern);
+        assert_eq!(layer("^(waybar|rofi)$"), Ok(()));
+        assert_eq!(layer("address:0x1234"), Ok(()));
+        assert!(layer("waybar)").is_err());
+        assert_eq!(validate_pattern(&RuleType::Workspace, "(("), Ok(()));
+    }
+
+    #[test]
+    fn test_rules_are_matched_against_open_windows() {
+        let mut pip = client("firefox", "Picture-in-Picture");
+        pip.floating = true;
+        pip.workspace.id = 3;
+        let mut steam = client("steam", "Steam");
+        steam.initial_title = "Steam".to_string();
+        steam.workspace.name = "games".to_string();
+        let clients = vec![
+            client("kitty", "~/src"),
+            client("firefox", "Mozilla Firefox"),
+            pip,
+            steam,
+        ];
+        let matched = |pattern| {
+            let (matching, unchecked) = matching_clients(pattern, &clients).unwrap();
+            let titles: Vec<&str> = matching.iter().map(|c| c.title.as_str()).collect();
+            (titles, unchecked)
+        };
+
+        assert_eq!(matched("class:^(kitty)$").0, vec!["~/src"]);
+        // The whole class has to match, as in Hyprland
+        assert_eq!(matched("class:fire").0, Vec::<&str>::new());
+        assert_eq!(
+            matched("firefox").0,
+            vec!["Mozilla Firefox", "Picture-in-Picture"]
+        );
+        assert_eq!(
+            matched("class:^(firefox)$, floating:1").0,
+            vec!["Picture-in-Picture"]
+        );
+        assert_eq!(
+            matched("class:^(firefox)$, floating:0").0,
+            vec!["Mozilla Firefox"]
+        );
+        assert_eq!(matched("workspace:3").0, vec!["Picture-in-Picture"]);
+        assert_eq!(matched("workspace:name:games").0, vec!["Steam"]);
+        assert_eq!(matched("class:negative:^(firefox|steam)$").0, vec!["~/src"]);
+        assert_eq!(matched("initialTitle:Steam").0, vec!["Steam"]);
+        assert_eq!(matched("title:.*").0.len(), 4);
+        assert_eq!(
+            matched("class:^(kitty)$, onworkspace:1"),
+            (vec!["~/src"], vec!["onworkspace".to_string()])
+        );
+        assert!(matching_clients("class:^(kitty", &clients).is_err());
+    }
+
+    #[test]
+    fn test_grabbed_window_pattern() {
+        assert_eq!(
+            window_pattern(&client("firefox", "Mozilla Firefox")),
+            "class:^(firefox)$, title:^(Mozilla Firefox)$"
+        );
+        assert_eq!(window_pattern(&client("kitty", "")), "class:^(kitty)$");
+        assert_eq!(
+            window_pattern(&client("", "Picture-in-Picture")),
+            "title:^(Picture-in-Picture)$"
+        );
+    }
+
+    #[test]
+    fn test_completions_follow_the_typed_pattern() {
+        let clients = vec![
+            client("kitty", "~/src"),
+            client("firefox", "Mozilla Firefox"),
+            client("kitty", "htop"),
+            client("", "Picture-in-Picture"),
+        ];
+
+        assert_eq!(
+            client_completions(&clients, "^()$"),
+            vec![
+                "class:^(kitty)$",
+                "class:^(firefox)$",
+                "title:^(~/src)$",
+                "title:^(Mozilla Firefox)$",
+                "title:^(htop)$",
+                "title:^(Picture-in-Picture)$",
+            ]
+        );
+        assert_eq!(
+            client_completions(&clients, "class:^(Fire"),
+            vec!["class:^(firefox)$", "title:^(Mozilla Firefox)$"]
+        );
+        assert_eq!(
+            client_completions(&clients, "title:pic"),
+            vec!["title:^(Picture-in-Picture)$"]
+        );
+        assert!(client_completions(&clients, "steam").is_empty());
+        assert!(client_completions(&[], "").is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+// Workspace rules
+// Parses and formats `workspace = SELECTOR, key:value, ...` lines
+
+/// Fields of the workspace rule editor, in the order Tab visits them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkspaceField {
+    Selector,
+    Monitor,
+    Default,
+    GapsOut,
+    Persistent,
+    OnCreatedEmpty,
+}
+
+impl WorkspaceField {
+    pub const ALL: [WorkspaceField; 6] = [
+        WorkspaceField::Selector,
+        WorkspaceField::Monitor,
+        WorkspaceField::Default,
+        WorkspaceField::GapsOut,
+        WorkspaceField::Persistent,
+        WorkspaceField::OnCreatedEmpty,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let index = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WorkspaceField::Selector => "Workspace",
+            WorkspaceField::Monitor => "Monitor",
+            WorkspaceField::Default => "Default",
+            WorkspaceField::GapsOut => "Gaps out",
+            WorkspaceField::Persistent => "Persistent",
+            WorkspaceField::OnCreatedEmpty => "On created empty",
+        }
+    }
+
+    /// Whether the field is a true/false toggle rather than free text
+    pub fn is_toggle(self) -> bool {
+        matches!(self, WorkspaceField::Default | WorkspaceField::Persistent)
+    }
+}
+
+/// A single `workspace = ...` rule. Empty strings mean the rule is unset.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WorkspaceRule {
+    pub selector: String,
+    pub monitor: String,
+    pub default: bool,
+    pub gaps_out: String,
+    pub persistent: bool,
+    pub on_created_empty: String,
+    /// Rules the editor has no field for, kept verbatim
+    pub other: Vec<String>,
+}
+
+/// Whether a config line is a workspace rule, as opposed to an option such as
+/// `workspace_swipe = true` that merely starts with the same word
+pub fn is_workspace_rule_line(line: &str) -> bool {
+    line.trim()
+        .strip_prefix("workspace")
+        .is_some_and(|rest| rest.trim_start().starts_with('='))
+}
+
+fn parse_bool(value: &str) -> bool {
+    matches!(value.trim(), "true" | "1" | "yes" | "on")
+}
+
+impl WorkspaceRule {
+    /// Parse a rule, with or without the leading `workspace =`
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let body = if is_workspace_rule_line(line) {
+            line.split_once('=').map(|(_, body)| body)?
+        } else {
+            line
+        };
+
+        let mut parts = body.split(',').map(str::trim);
+        let selector = parts.next().filter(|s| !s.is_empty())?.to_string();
+        let mut rule = WorkspaceRule {
+            selector,
+            ..Default::default()
+        };
+
+        for part in parts.filter(|p| !p.is_empty()) {
+            match part.split_once(':') {
+                Some(("monitor", value)) => rule.monitor = value.trim().to_string(),
+                Some(("default", value)) => rule.default = parse_bool(value),
+                Some(("gapsout", value)) => rule.gaps_out = value.trim().to_string(),
+                Some(("persistent", value)) => rule.persistent = parse_bool(value),
+                Some(("on-created-empty", value)) => {
+                    rule.on_created_empty = value.trim().to_string()
+                }
+                _ => rule.other.push(part.to_string()),
+            }
+        }
+
+        Some(rule)
+    }
+
+    /// Format the rule as a config line
+    pub fn to_line(&self) -> String {
+        let mut parts = vec![self.selector.trim().to_string()];
+        if !self.monitor.trim().is_empty() {
+            parts.push(format!("monitor:{}", self.monitor.trim()));
+        }
+        if self.default {
+            parts.push("default:true".to_string());
+        }
+        if !self.gaps_out.trim().is_empty() {
+            parts.push(format!("gapsout:{}", self.gaps_out.trim()));
+        }
+        if self.persistent {
+            parts.push("persistent:true".to_string());
+        }
+        if !self.on_created_empty.trim().is_empty() {
+            parts.push(format!("on-created-empty:{}", self.on_created_empty.trim()));
+        }
+        parts.extend(self.other.iter().cloned());
+
+        format!("workspace = {}", parts.join(", "))
+    }
+
+    /// The text of a free-text field, or `None` for toggles
+    pub fn text_field_mut(&mut self, field: WorkspaceField) -> Option<&mut String> {
+        match field {
+            WorkspaceField::Selector => Some(&mut self.selector),
+            WorkspaceField::Monitor => Some(&mut self.monitor),
+            WorkspaceField::GapsOut => Some(&mut self.gaps_out),
+            WorkspaceField::OnCreatedEmpty => Some(&mut self.on_created_empty),
+            WorkspaceField::Default | WorkspaceField::Persistent => None,
+        }
+    }
+
+    /// Flip a toggle field; free-text fields are left alone
+    pub fn toggle(&mut self, field: WorkspaceField) {
+        match field {
+            WorkspaceField::Default => self.default = !self.default,
+            WorkspaceField::Persistent => self.persistent = !self.persistent,
+            _ => {}
+        }
+    }
+
+    /// Display value of a field for the editor
+    pub fn field_value(&self, field: WorkspaceField) -> String {
+        match field {
+            WorkspaceField::Selector => self.selector.clone(),
+            WorkspaceField::Monitor => self.monitor.clone(),
+            WorkspaceField::Default => self.default.to_string(),
+            WorkspaceField::GapsOut => self.gaps_out.clone(),
+            WorkspaceField::Persistent => self.persistent.to_string(),
+            WorkspaceField::OnCreatedEmpty => self.on_created_empty.clone(),
+        }
+    }
+}
+
+/// Check a workspace selector: a number, `name:NAME`, `special` or
+/// `special:NAME`, or a selector expression like `r[1-4]` or `m[DP-1]`
+pub fn validate_selector(selector: &str) -> Result<(), String> {
+    let selector = selector.trim();
+    let valid = if selector.is_empty() {
+        false
+    } else if let Some(name) = selector.strip_prefix("name:") {
+        !name.is_empty()
+    } else if let Some(name) = selector.strip_prefix("special:") {
+        !name.is_empty()
+    } else if selector == "special" {
+        true
+    } else if selector.contains('[') {
+        selector.ends_with(']')
+    } else {
+        selector.parse::<u32>().is_ok_and(|n| n > 0)
+    };
+
+    if valid && !selector.contains(',') {
+        Ok(())
+    } else {
+        Err("workspace must be a number, name:NAME or special:NAME".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_round_trip() {
+        let line = "workspace = 1, monitor:DP-1, default:true, gapsout:20, persistent:true";
+        let rule = WorkspaceRule::parse(line).unwrap();
+        assert_eq!(rule.selector, "1");
+        assert_eq!(rule.monitor, "DP-1");
+        assert!(rule.default && rule.persistent);
+        assert_eq!(rule.gaps_out, "20");
+        assert_eq!(rule.to_line(), line);
+    }
+
+    #[test]
+    fn test_unknown_rules_are_kept() {
+        let rule =
+            WorkspaceRule::parse("workspace = special:term, on-created-empty:kitty, border:false")
+                .unwrap();
+        assert_eq!(rule.on_created_empty, "kitty");
+        assert_eq!(rule.other, vec!["border:false".to_string()]);
+        assert_eq!(
+            rule.to_line(),
+            "workspace = special:term, on-created-empty:kitty, border:false"
+        );
+    }
+
+    #[test]
+    fn test_workspace_rule_lines_exclude_options() {
+        assert!(is_workspace_rule_line("workspace = 1, monitor:DP-1"));
+        assert!(is_workspace_rule_line("  workspace=2"));
+        assert!(!is_workspace_rule_line("workspace_swipe = true"));
+        assert!(!is_workspace_rule_line("workspace_swipe_fingers=3"));
+    }
+
+    #[test]
+    fn test_selector_validation() {
+        for ok in ["1", "name:coding", "special", "special:scratch", "r[1-4]"] {
+            assert!(validate_selector(ok).is_ok(), "{ok}");
+        }
+        for bad in ["", "0", "name:", "coding", "1,2"] {
+            assert!(validate_selector(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,468 @@
+// Keyboard layouts known to xkb
+// Hyprland ignores a kb_layout or kb_variant it doesn't know without saying
+// so, so both are checked against the system's xkb rules before saving.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Where distributions install the xkb rules, NixOS last
+const RULES_FILES: [&str; 2] = [
+    "/usr/share/X11/xkb/rules/evdev.lst",
+    "/run/current-system/sw/share/X11/xkb/rules/evdev.lst",
+];
+
+/// kb_options offered when the xkb rules can't be read
+const COMMON_OPTIONS: [(&str, &str); 6] = [
+    ("grp:alt_shift_toggle", "Alt+Shift"),
+    ("grp:win_space_toggle", "Win+Space"),
+    ("caps:escape", "Make Caps Lock an additional Esc"),
+    ("caps:swapescape", "Swap Esc and Caps Lock"),
+    ("ctrl:nocaps", "Make Caps Lock an additional Ctrl"),
+    ("compose:ralt", "Right Alt"),
+];
+
+/// Layouts, their variants and the layout options
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XkbLayouts {
+    /// Layout names with their descriptions, like `us` and `English (US)`
+    pub layouts: BTreeMap<String, String>,
+    /// Variant names of each layout
+    pub variants: BTreeMap<String, Vec<String>>,
+    /// kb_options flags with their descriptions, like `caps:escape`
+    pub options: BTreeMap<String, String>,
+    /// False when the layouts came from localectl, which doesn't list variants
+    variants_known: bool,
+}
+
+impl XkbLayouts {
+    /// Read the `! layout`, `! variant` and `! option` sections of an xkb
+    /// rules list such as `evdev.lst`. Variant lines name their layout before
+    /// the description: `  dvorak          us: English (Dvorak)`. Options
+    /// without a colon only head a group and can't be set.
+    pub fn parse_rules(text: &str) -> Self {
+        let mut xkb = Self {
+            variants_known: true,
+            ..Self::default()
+        };
+        let mut section = "";
+        for line in text.lines() {
+            if let Some(name) = line.strip_prefix('!') {
+                section = name.trim();
+                continue;
+            }
+            let Some((name, description)) = line.trim().split_once(char::is_whitespace) else {
+                continue;
+            };
+            let description = description.trim();
+            match section {
+                "layout" => {
+                    xkb.layouts
+                        .insert(name.to_string(), description.to_string());
+                }
+                "variant" => {
+                    if let Some((layout, _)) = description.split_once(':') {
+                        xkb.variants
+                            .entry(layout.to_string())
+                            .or_default()
+                            .push(name.to_string());
+                    }
+                }
+                "option" if name.contains(':') => {
+                    xkb.options
+                        .insert(name.to_string(), description.to_string());
+                }
+                _ => {}
+            }
+        }
+        xkb
+    }
+
+    /// Read the output of `localectl list-x11-keymap-layouts`, one name a line
+    pub fn parse_layout_list(text: &str) -> Self {
+        Self {
+            layouts: text
+                .lines()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| (name.to_string(), String::new()))
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// The system's layouts, from the xkb rules or else from localectl. None
+    /// when neither is available, in which case nothing is validated.
+    pub async fn load() -> Option<Self> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        if let Some(root) = std::env::var_os("XKB_CONFIG_ROOT") {
+            files.push(PathBuf::from(root).join("rules/evdev.lst"));
+        }
+        files.extend(RULES_FILES.iter().map(PathBuf::from));
+        for file in files {
+            if let Ok(text) = tokio::fs::read_to_string(&file).await {
+                let xkb = Self::parse_rules(&text);
+                if !xkb.layouts.is_empty() {
+                    return Some(xkb);
+                }
+            }
+        }
+
+        let output = tokio::process::Command::new("localectl")
+            .arg("list-x11-keymap-layouts")
+            .output()
+            .await
+            .ok()?;
+        let xkb = Self::parse_layout_list(&String::from_utf8_lossy(&output.stdout));
+        (output.status.success() && !xkb.layouts.is_empty()).then_some(xkb)
+    }
+
+    /// Check a comma-separated `kb_layout` and the `kb_variant` that goes with
+    /// it, where the n-th variant belongs to the n-th layout and may be empty
+    pub fn validate(&self, layout: &str, variant: &str) -> Result<(), String> {
+        let layouts: Vec<&str> = layout.split(',').map(str::trim).collect();
+        for name in &layouts {
+            if !name.is_empty() && !self.layouts.contains_key(*name) {
+                return Err(format!("'{name}' is not a keyboard layout known to xkb"));
+            }
+        }
+
+        if variant.trim().is_empty() {
+            return Ok(());
+        }
+        let variants: Vec<&str> = variant.split(',').map(str::trim).collect();
+        if variants.len() > layouts.len() {
+            return Err(format!(
+                "kb_variant lists {} variants but kb_layout only {} layouts",
+                variants.len(),
+                layouts.len()
+            ));
+        }
+        if !self.variants_known {
+            return Ok(());
+        }
+        for (name, layout) in variants.iter().zip(&layouts) {
+            let known = self
+                .variants
+                .get(*layout)
+                .is_some_and(|variants| variants.iter().any(|v| v == name));
+            if !name.is_empty() && !known {
+                return Err(format!("'{name}' is not a variant of layout '{layout}'"));
+            }
+        }
+        Ok(())
+    }
+
+    /// `kb_layout` values completing the last layout typed
+    pub fn layout_completions(&self, value: &str) -> Vec<String> {
+        complete_last(value, self.layouts.keys())
+    }
+
+    /// `kb_variant` values completing the last variant typed, from the
+    /// variants of the layout in the same position
+    pub fn variant_completions(&self, layout: &str, value: &str) -> Vec<String> {
+        let position = value.matches(',').count();
+        let Some(layout) = layout.split(',').map(str::trim).nth(position) else {
+            return Vec::new();
+        };
+        complete_last(value, self.variants.get(layout).into_iter().flatten())
+    }
+}
+
+/// The entries of a comma-separated option such as `kb_layout = us, de`, in
+/// order
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// A kb_layout being edited as a list, in switching order
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutList {
+    pub layouts: Vec<String>,
+    pub selected: usize,
+    /// A layout being typed, added with Tab
+    pub adding: String,
+}
+
+impl LayoutList {
+    pub fn parse(value: &str) -> Self {
+        Self {
+            layouts: split_list(value),
+            selected: 0,
+            adding: String::new(),
+        }
+    }
+
+    pub fn to_value(&self) -> String {
+        self.layouts.join(",")
+    }
+
+    /// Add a layout at the end, unless it is already in the list
+    pub fn add(&mut self, layout: &str) {
+        let layout = layout.trim();
+        if !layout.is_empty() && !self.layouts.iter().any(|l| l == layout) {
+            self.layouts.push(layout.to_string());
+            self.selected = self.layouts.len() - 1;
+        }
+    }
+
+    pub fn remove_selected(&mut self) {
+        if self.selected < self.layouts.len() {
+            self.layouts.remove(self.selected);
+            self.selected = self.selected.min(self.layouts.len().saturating_sub(1));
+        }
+    }
+
+    /// Swap the selected layout with its neighbour, which changes the order
+    /// the switch key cycles through
+    pub fn move_selected(&mut self, later: bool) {
+        let target = if later {
+            self.selected + 1
+        } else {
+            match self.selected.checked_sub(1) {
+                Some(target) => target,
+                None => return,
+            }
+        };
+        if target < self.layouts.len() {
+            self.layouts.swap(self.selected, target);
+            self.selected = target;
+        }
+    }
+}
+
+/// kb_options being edited as checkboxes
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionFlags {
+    /// Every flag that can be checked, with its description
+    pub available: Vec<(String, String)>,
+    /// Checked flags in the order they are written
+    pub checked: Vec<String>,
+    /// Index into the flags matching `filter`
+    pub selected: usize,
+    pub filter: String,
+}
+
+impl OptionFlags {
+    /// Flags from the xkb rules, or a few common ones without them. Flags
+    /// already set are always offered, even when xkb doesn't know them.
+    pub fn new(value: &str, xkb: Option<&XkbLayouts>) -> Self {
+        let checked = split_list(value);
+        let mut available: Vec<(String, String)> = match xkb {
+            Some(xkb) if !xkb.options.is_empty() => xkb
+                .options
+                .iter()
+                .map(|(name, description)| (name.clone(), description.clone()))
+                .collect(),
+            _ => COMMON_OPTIONS
+                .iter()
+                .map(|(name, description)| (name.to_string(), description.to_string()))
+                .collect(),
+        };
+        for name in checked.iter().rev() {
+            if !available.iter().any(|(known, _)| known == name) {
+                available.insert(0, (name.clone(), String::new()));
+            }
+        }
+        Self {
+            available,
+            checked,
+            selected: 0,
+            filter: String::new(),
+        }
+    }
+
+    /// Flags whose name or description contains the filter
+    pub fn visible(&self) -> Vec<&(String, String)> {
+        let filter = self.filter.to_lowercase();
+        self.available
+            .iter()
+            .filter(|(name, description)| {
+                name.contains(&filter) || description.to_lowercase().contains(&filter)
+            })
+            .collect()
+    }
+
+    pub fn is_checked(&self, name: &str) -> bool {
+        self.checked.iter().any(|checked| checked == name)
+    }
+
+    /// Check or uncheck the selected flag. Newly checked flags go last.
+    pub fn toggle_selected(&mut self) {
+        let Some((name, _)) = self
+            .visible()
+            .get(self.selected)
+            .map(|flag| (*flag).clone())
+        else {
+            return;
+        };
+        if self.is_checked(&name) {
+            self.checked.retain(|checked| *checked != name);
+        } else {
+            self.checked.push(name);
+        }
+    }
+
+    pub fn to_value(&self) -> String {
+        self.checked.join(",")
+    }
+}
+
+/// `value` with its last comma-separated entry replaced by each candidate
+/// that starts with it
+fn complete_last<'a>(value: &str, candidates: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let (done, last) = match value.rsplit_once(',') {
+        Some((done, last)) => (format!("{done},"), last.trim()),
+        None => (String::new(), value.trim()),
+    };
+    candidates
+        .filter(|name| name.starts_with(last))
+        .map(|name| format!("{done}{name}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = "! model
+  pc105           Generic 105-key PC
+
+! layout
+  us              English (US)
+  de              German
+  fr              French
+
+! variant
+  dvorak          us: English (Dvorak)
+  intl            us: English (US, intl., with dead keys)
+  nodeadkeys      de: German (no dead keys)
+
+! option
+  grp                  Switching to another layout
+  grp:alt_shift_toggle Alt+Shift
+  grp:win_space_toggle Win+Space
+  caps:escape          Make Caps Lock an additional Esc
+";
+
+    #[test]
+    fn test_rules_list() {
+        let xkb = XkbLayouts::parse_rules(RULES);
+        assert_eq!(
+            xkb.layouts.keys().collect::<Vec<_>>(),
+            vec!["de", "fr", "us"]
+        );
+        assert_eq!(xkb.layouts["de"], "German");
+        assert_eq!(xkb.variants["us"], vec!["dvorak", "intl"]);
+        assert_eq!(xkb.variants["de"], vec!["nodeadkeys"]);
+        assert!(!xkb.layouts.contains_key("pc105"));
+        assert!(!xkb.layouts.contains_key("grp"));
+        assert_eq!(
+            xkb.options.keys().collect::<Vec<_>>(),
+            vec![
+                "caps:escape",
+                "grp:alt_shift_toggle",
+                "grp:win_space_toggle"
+            ]
+        );
+        assert_eq!(xkb.options["grp:alt_shift_toggle"], "Alt+Shift");
+    }
+
+    #[test]
+    fn test_two_and_three_layouts_keep_their_order() {
+        let mut two = LayoutList::parse("us,de");
+        assert_eq!(two.layouts, vec!["us", "de"]);
+        assert_eq!(two.to_value(), "us,de");
+        two.move_selected(true);
+        assert_eq!(two.to_value(), "de,us");
+        assert_eq!(two.selected, 1);
+        two.move_selected(true);
+        assert_eq!(two.to_value(), "de,us");
+
+        let mut three = LayoutList::parse(" us, de ,fr ");
+        assert_eq!(three.to_value(), "us,de,fr");
+        three.selected = 2;
+        three.move_selected(false);
+        assert_eq!(three.to_value(), "us,fr,de");
+        three.add("fr");
+        assert_eq!(three.to_value(), "us,fr,de");
+        three.remove_selected();
+        assert_eq!(three.to_value(), "us,de");
+        three.add("fr");
+        assert_eq!(three.to_value(), "us,de,fr");
+        assert_eq!(three.selected, 2);
+    }
+
+    #[test]
+    fn test_option_flags_are_toggled_in_order() {
+        let xkb = XkbLayouts::parse_rules(RULES);
+        let mut flags = OptionFlags::new("caps:escape,grp:alt_shift_toggle", Some(&xkb));
+        assert_eq!(flags.available.len(), 3);
+        assert!(flags.is_checked("caps:escape"));
+
+        flags.filter = "win".to_string();
+        assert_eq!(flags.visible().len(), 1);
+        flags.toggle_selected();
+        assert_eq!(
+            flags.to_value(),
+            "caps:escape,grp:alt_shift_toggle,grp:win_space_toggle"
+        );
+
+        flags.filter = "esc".to_string();
+        flags.toggle_selected();
+        assert_eq!(
+            flags.to_value(),
+            "grp:alt_shift_toggle,grp:win_space_toggle"
+        );
+
+        // Flags xkb doesn't know are kept and offered first
+        let flags = OptionFlags::new("custom:thing", None);
+        assert_eq!(flags.available[0].0, "custom:thing");
+        assert_eq!(flags.to_value(), "custom:thing");
+        assert_eq!(OptionFlags::new("", None).to_value(), "");
+    }
+
+    #[test]
+    fn test_layouts_and_variants_are_validated() {
+        let xkb = XkbLayouts::parse_rules(RULES);
+        assert!(xkb.validate("us", "").is_ok());
+        assert!(xkb.validate("us,de", "dvorak,nodeadkeys").is_ok());
+        assert!(xkb.validate("us, de", ",nodeadkeys").is_ok());
+        assert_eq!(
+            xkb.validate("us,xx", "").unwrap_err(),
+            "'xx' is not a keyboard layout known to xkb"
+        );
+        assert_eq!(
+            xkb.validate("de", "dvorak").unwrap_err(),
+            "'dvorak' is not a variant of layout 'de'"
+        );
+        assert_eq!(
+            xkb.validate("us", "dvorak,intl").unwrap_err(),
+            "kb_variant lists 2 variants but kb_layout only 1 layouts"
+        );
+
+        // localectl knows the layouts but not their variants
+        let xkb = XkbLayouts::parse_layout_list("de\nfr\nus\n");
+        assert!(xkb.validate("us,de", "whatever,").is_ok());
+        assert!(xkb.validate("uk", "").is_err());
+    }
+
+    #[test]
+    fn test_completions_replace_the_last_entry() {
+        let xkb = XkbLayouts::parse_rules(RULES);
+        assert_eq!(xkb.layout_completions("d"), vec!["de"]);
+        assert_eq!(xkb.layout_completions("us,f"), vec!["us,fr"]);
+        assert_eq!(xkb.layout_completions("").len(), 3);
+        assert_eq!(xkb.variant_completions("us,de", "d"), vec!["dvorak"]);
+        assert_eq!(
+            xkb.variant_completions("us,de", "dvorak,"),
+            vec!["dvorak,nodeadkeys"]
+        );
+        assert!(xkb.variant_completions("us", "dvorak,").is_empty());
+        assert!(xkb.variant_completions("fr", "").is_empty());
+    }
+}